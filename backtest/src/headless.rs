@@ -0,0 +1,190 @@
+use common::structs::TradingSettings;
+use core::{
+    config::BenchmarkSettings, controller::Controller, monte_carlo::MonteCarloSettings,
+    replay::replay_benchmark,
+};
+use glow_error::GlowError;
+use serde::Deserialize;
+use serde_json::from_reader;
+use std::{collections::HashMap, fs::File, io::BufReader, time::Duration as StdDuration};
+use strategy::{params::ParamId, schemas::declarative::definition::StrategyDefinition, Strategy};
+use tokio::time::timeout;
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_BENCHMARK_FAILED: i32 = 1;
+pub const EXIT_INVALID_SPEC: i32 = 2;
+pub const EXIT_TIMEOUT: i32 = 3;
+
+/// Describes a non-interactive benchmark run, loaded from a JSON file.
+///
+/// ```json
+/// {
+///     "datetimes": ["2024-06-01T00:00:00Z", "2024-06-08T00:00:00Z"],
+///     "strategy_id": "SimpleTrend",
+///     "data_provider_id": "Binance",
+///     "trader_exchange_id": "Bybit",
+///     "params": { "FastSpan": 20, "SlowSpan": 100 },
+///     "timeout_secs": 600
+/// }
+/// ```
+///
+/// `trading_settings` follows the same format as `config/backtest/trading_settings.json`,
+/// and falls back to `TradingSettings::default()` when absent.
+///
+/// Setting `data_provider_id` to `"Offline"` runs the benchmark from local kline files only,
+/// read from `OFFLINE_DATA_DIR` (defaults to `data/ticks/binance`).
+///
+/// Setting `replay` to `true` replays the benchmark klines through the live trader over a paper exchange,
+/// printing the klines where it disagrees with the benchmark instead of its statistics.
+///
+/// Setting `monte_carlo` (e.g. `{ "iterations": 1000, "method": { "block_bootstrap": 5 }, "seed": 42 }`)
+/// resamples the benchmark trading sessions afterwards, printing the percentiles of the simulations
+/// and saving them as CSV next to the benchmark trades.
+#[derive(Deserialize)]
+pub struct RunSpec {
+    #[serde(flatten)]
+    pub benchmark_settings: BenchmarkSettings,
+    pub monte_carlo: Option<MonteCarloSettings>,
+    #[serde(default)]
+    pub params: HashMap<ParamId, f64>,
+    #[serde(default)]
+    pub replay: bool,
+    #[serde(default)]
+    pub trading_settings: TradingSettings,
+    /// Path to a `StrategyDefinition` file, which replaces `strategy_id` when present
+    pub strategy_definition: Option<String>,
+    pub timeout_secs: Option<u64>,
+}
+
+impl RunSpec {
+    pub fn load(path: &str) -> Result<Self, GlowError> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let spec: Self = from_reader(reader)?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    fn validate(&self) -> Result<(), GlowError> {
+        match self.benchmark_settings.datetimes {
+            (_, None) => Err(GlowError::new_str(
+                "Invalid run spec",
+                "datetimes must include a benchmark end",
+            )),
+            (Some(start), Some(end)) if start >= end => Err(GlowError::new_str(
+                "Invalid run spec",
+                "benchmark start must be before benchmark end",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn build_strategy(&self) -> Result<Strategy, GlowError> {
+        let mut strategy = match &self.strategy_definition {
+            Some(path) => Strategy::from_definition(
+                StrategyDefinition::load(path)?,
+                self.trading_settings.symbols_pair,
+            ),
+            None => Strategy::new(
+                self.benchmark_settings.strategy_id,
+                self.trading_settings.symbols_pair,
+            )?,
+        };
+        for (param_id, value) in &self.params {
+            strategy = strategy.patch_number_param(*param_id, *value)?;
+        }
+        Ok(strategy)
+    }
+}
+
+/// Runs a benchmark described by the spec at `spec_path` and returns the process exit code
+pub async fn run_headless(spec_path: &str) -> i32 {
+    let spec = match RunSpec::load(spec_path) {
+        Ok(spec) => spec,
+        Err(error) => {
+            eprintln!("Invalid run spec {}: {:?}", spec_path, error);
+            return EXIT_INVALID_SPEC;
+        }
+    };
+    let strategy = match spec.build_strategy() {
+        Ok(strategy) => strategy,
+        Err(error) => {
+            eprintln!("Invalid run spec params: {:?}", error);
+            return EXIT_INVALID_SPEC;
+        }
+    };
+
+    if spec.replay {
+        return run_headless_replay(&spec, &strategy).await;
+    }
+
+    let controller = match Controller::from_settings(
+        spec.benchmark_settings.clone(),
+        &spec.trading_settings,
+        &strategy,
+        true,
+    ) {
+        Ok(controller) => controller,
+        Err(error) => {
+            eprintln!("Invalid run spec strategy: {:?}", error);
+            return EXIT_INVALID_SPEC;
+        }
+    };
+
+    let result = match spec.timeout_secs {
+        Some(secs) => match timeout(StdDuration::from_secs(secs), controller.run_benchmark()).await
+        {
+            Ok(result) => result,
+            Err(_) => {
+                eprintln!("Benchmark timed out after {} secs", secs);
+                return EXIT_TIMEOUT;
+            }
+        },
+        None => controller.run_benchmark().await,
+    };
+
+    let statistics = match result {
+        Ok(statistics) => statistics,
+        Err(error) => {
+            eprintln!("Benchmark failed: {:?}", error);
+            return EXIT_BENCHMARK_FAILED;
+        }
+    };
+    println!("{}", statistics);
+
+    if let Some(monte_carlo_settings) = &spec.monte_carlo {
+        match controller.run_monte_carlo(monte_carlo_settings) {
+            Ok(report) => println!("{}", report),
+            Err(error) => {
+                eprintln!("Monte Carlo analysis failed: {:?}", error);
+                return EXIT_BENCHMARK_FAILED;
+            }
+        }
+    }
+    EXIT_SUCCESS
+}
+
+async fn run_headless_replay(spec: &RunSpec, strategy: &Strategy) -> i32 {
+    let replay = replay_benchmark(&spec.benchmark_settings, &spec.trading_settings, strategy);
+    let result = match spec.timeout_secs {
+        Some(secs) => match timeout(StdDuration::from_secs(secs), replay).await {
+            Ok(result) => result,
+            Err(_) => {
+                eprintln!("Replay timed out after {} secs", secs);
+                return EXIT_TIMEOUT;
+            }
+        },
+        None => replay.await,
+    };
+
+    match result {
+        Ok(report) => {
+            println!("{}", report);
+            EXIT_SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Replay failed: {:?}", error);
+            EXIT_BENCHMARK_FAILED
+        }
+    }
+}
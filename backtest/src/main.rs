@@ -1,11 +1,12 @@
 use cli::{change_benchmark_datetimes, change_symbols_pair, select_from_list};
 use common::functions::current_datetime;
 use common::traits::exchange::TraderHelper;
-use core::controller::Controller;
+use core::{controller::Controller, monte_carlo::MonteCarloSettings};
 use dialoguer::console::Term;
 use dotenv::dotenv;
-use std::time::Duration as StdDuration;
-use tokio::time::sleep;
+use headless::{run_headless, EXIT_INVALID_SPEC};
+use std::process::exit;
+mod headless;
 
 use std::env;
 
@@ -16,8 +17,27 @@ async fn main() {
     let max_rows = "40".to_string();
     env::set_var("POLARS_FMT_MAX_ROWS", max_rows);
 
+    // headless mode: `backtest --spec path/to/run_spec.json`
+    let args: Vec<String> = env::args().collect();
+    if let Some(flag_index) = args.iter().position(|arg| arg == "--spec") {
+        let exit_code = match args.get(flag_index + 1) {
+            Some(spec_path) => run_headless(spec_path).await,
+            None => {
+                eprintln!("Missing run spec path after --spec");
+                EXIT_INVALID_SPEC
+            }
+        };
+        exit(exit_code);
+    }
+
     let term = Term::stdout();
-    let mut controller = Controller::new(true);
+    let mut controller = match Controller::new(true) {
+        Ok(controller) => controller,
+        Err(error) => {
+            eprintln!("Controller setup failed {:?}", error);
+            exit(1);
+        }
+    };
     loop {
         // term.clear_screen().unwrap(); // comment this to debug
         let start_datetime = controller
@@ -62,11 +82,19 @@ async fn main() {
             0 => {
                 let current_strategy = controller.data_feed.strategy.clone();
                 let current_trade_exchange = controller.trader.trader_exchange.clone();
+                let minimum_klines_for_benchmarking =
+                    match current_strategy.get_minimum_klines_for_calculation() {
+                        Ok(minimum_klines) => minimum_klines,
+                        Err(error) => {
+                            println!("Invalid strategy params {:?}", error);
+                            continue;
+                        }
+                    };
                 let result = change_benchmark_datetimes(
                     start_datetime,
                     end_datetime,
                     &current_trade_exchange,
-                    current_strategy.get_minimum_klines_for_calculation(),
+                    minimum_klines_for_benchmarking,
                 );
                 println!("@@@@@@ RESULT {:?}", result);
                 if result.is_none() {
@@ -89,7 +117,9 @@ async fn main() {
                 let updated_symbols_pair = updated_symbols_pair.unwrap();
                 let updated_trading_settings =
                     current_trading_settings.patch_symbols_pair(updated_symbols_pair);
-                controller.patch_settings(&updated_trading_settings);
+                if let Err(error) = controller.patch_settings(&updated_trading_settings) {
+                    println!("Settings update failed {:?}", error);
+                }
             }
             2 => {
                 // CHANGE PROVIDER EXCHANGE
@@ -105,10 +135,19 @@ async fn main() {
             }
             6 => {
                 // RUN BENCHMARK
-                controller.init();
-                sleep(StdDuration::new(5, 0)).await;
-                let options = vec!["Press enter to run again"];
-                select_from_list("Benchmark is done", &options, Some(default_index));
+                if let Err(error) = controller.run_benchmark().await {
+                    println!("Benchmark failed {:?}", error);
+                }
+                let options = vec!["Press enter to run again", "🎲 Run Monte Carlo Analysis"];
+                let selection = select_from_list("Benchmark is done", &options, Some(0));
+                if selection == 1 {
+                    match controller.run_monte_carlo(&MonteCarloSettings::default()) {
+                        Ok(report) => println!("{}", report),
+                        Err(error) => println!("Monte Carlo analysis failed {:?}", error),
+                    }
+                    let options = vec!["Press enter to run again"];
+                    select_from_list("Monte Carlo analysis is done", &options, Some(0));
+                }
             }
             selection => {
                 println!("Invalid option {}", selection);
@@ -0,0 +1,65 @@
+use std::{fs, path::PathBuf, process::Command};
+
+const EXIT_INVALID_SPEC: i32 = 2;
+
+/// Definition with an unbounded `KLength`, so only its type range limits the values it takes
+const DEFINITION: &str = r#"{
+    "name": "unbounded_stochastic",
+    "params": { "KLength": { "default": 14 } },
+    "indicators": [
+        { "id": "stoch", "type": "Stochastic", "k_length": { "param": "KLength" }, "k_smoothing": 3, "d_smoothing": 3 }
+    ],
+    "signals": {
+        "long": { "lt": [{ "indicator": "stoch", "output": "k" }, 20] }
+    }
+}"#;
+
+fn write_spec(name: &str, k_length: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("glow_headless_{}", name));
+    fs::create_dir_all(&dir).unwrap();
+    let definition_path = dir.join("definition.json");
+    fs::write(&definition_path, DEFINITION).unwrap();
+    let spec_path = dir.join("run_spec.json");
+    let spec = format!(
+        r#"{{
+            "datetimes": ["2024-06-01T00:00:00Z", "2024-06-08T00:00:00Z"],
+            "strategy_id": "SimpleTrend",
+            "data_provider_id": "Binance",
+            "trader_exchange_id": "Bybit",
+            "strategy_definition": "{}",
+            "params": {{ "KLength": {} }}
+        }}"#,
+        definition_path.display(),
+        k_length
+    );
+    fs::write(&spec_path, spec).unwrap();
+    spec_path
+}
+
+fn run_spec(spec_path: &PathBuf) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_backtest"))
+        .arg("--spec")
+        .arg(spec_path)
+        .output()
+        .unwrap()
+        .status
+        .code()
+}
+
+#[test]
+fn test_rejects_params_out_of_their_type_range() {
+    for (name, k_length) in [
+        ("negative", "-1"),
+        ("fractional", "14.5"),
+        ("oversized", "5000000000"),
+    ] {
+        let spec_path = write_spec(name, k_length);
+
+        assert_eq!(
+            run_spec(&spec_path),
+            Some(EXIT_INVALID_SPEC),
+            "KLength of {}",
+            k_length
+        );
+    }
+}
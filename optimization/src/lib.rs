@@ -0,0 +1,3 @@
+pub mod spec;
+pub mod sweep;
+pub mod walk_forward;
@@ -0,0 +1,164 @@
+use common::{
+    enums::granularity::Granularity,
+    structs::{Statistics, TradingSettings},
+};
+use core::config::BenchmarkSettings;
+use glow_error::GlowError;
+use serde::Deserialize;
+use serde_json::from_reader;
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+use strategy::{params::ParamId, schemas::declarative::definition::StrategyDefinition, Strategy};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum RankBy {
+    #[default]
+    SharpeRatio,
+    SortinoRatio,
+    CalmarRatio,
+    FinalBalance,
+    PerTradeSharpeRatio,
+    PerTradeSortinoRatio,
+}
+
+impl RankBy {
+    pub fn get_value(&self, statistics: &Statistics) -> f64 {
+        match self {
+            RankBy::SharpeRatio => statistics.sharpe_ratio(),
+            RankBy::SortinoRatio => statistics.sortino_ratio(),
+            RankBy::CalmarRatio => statistics.calmar_ratio(),
+            RankBy::FinalBalance => statistics.current_balance(),
+            RankBy::PerTradeSharpeRatio => statistics.per_trade_sharpe_ratio(),
+            RankBy::PerTradeSortinoRatio => statistics.per_trade_sortino_ratio(),
+        }
+    }
+}
+
+/// Rolling windows: each in-sample window is followed by its out-of-sample window,
+/// and windows advance by the out-of-sample length
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct WalkForwardSettings {
+    pub in_sample_days: i64,
+    pub out_of_sample_days: i64,
+}
+
+fn default_output_path() -> String {
+    String::from("data/optimization")
+}
+
+fn default_returns_granularity() -> Granularity {
+    Granularity::d1
+}
+
+/// Describes a parameter sweep, loaded from a JSON file.
+///
+/// ```json
+/// {
+///     "datetimes": ["2024-06-01T00:00:00Z", "2024-06-08T00:00:00Z"],
+///     "strategy_id": "SimpleTrend",
+///     "data_provider_id": "Binance",
+///     "trader_exchange_id": "Bybit",
+///     "params": { "SlowSpan": 100 },
+///     "steps": { "FastSpan": 5 },
+///     "rank_by": "SortinoRatio"
+/// }
+/// ```
+///
+/// Every number param with min and max configured is swept, unless it is fixed at `params`.
+/// `steps` overrides the default step, which splits each range in `DEFAULT_SWEEP_STEPS`.
+///
+/// Adding `"walk_forward": { "in_sample_days": 30, "out_of_sample_days": 7 }` runs a
+/// walk-forward validation instead of a single sweep over the whole range.
+///
+/// Sharpe and Sortino ratios sample the balance daily, unless `"returns_granularity"` sets
+/// another period (e.g. `"h4"`). The former per trade ratios rank as `"PerTradeSharpeRatio"`.
+#[derive(Deserialize)]
+pub struct SweepSpec {
+    #[serde(flatten)]
+    pub benchmark_settings: BenchmarkSettings,
+    #[serde(default)]
+    pub params: HashMap<ParamId, f64>,
+    #[serde(default)]
+    pub steps: HashMap<ParamId, f64>,
+    #[serde(default)]
+    pub rank_by: RankBy,
+    #[serde(default = "default_output_path")]
+    pub output_path: String,
+    #[serde(default = "default_returns_granularity")]
+    pub returns_granularity: Granularity,
+    #[serde(default)]
+    pub trading_settings: TradingSettings,
+    /// Path to a `StrategyDefinition` file, which replaces `strategy_id` when present
+    pub strategy_definition: Option<String>,
+    pub walk_forward: Option<WalkForwardSettings>,
+}
+
+impl SweepSpec {
+    pub fn load(path: &str) -> Result<Self, GlowError> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let spec: Self = from_reader(reader)?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    fn validate(&self) -> Result<(), GlowError> {
+        match self.benchmark_settings.datetimes {
+            (_, None) => {
+                return Err(GlowError::new_str(
+                    "Invalid sweep spec",
+                    "datetimes must include a benchmark end",
+                ))
+            }
+            (Some(start), Some(end)) if start >= end => {
+                return Err(GlowError::new_str(
+                    "Invalid sweep spec",
+                    "benchmark start must be before benchmark end",
+                ))
+            }
+            _ => {}
+        }
+        if let Some((param_id, step)) = self.steps.iter().find(|(_, step)| **step <= 0.0) {
+            return Err(GlowError::new(
+                String::from("Invalid sweep spec"),
+                format!("step for {:?} must be positive, got {}", param_id, step),
+            ));
+        }
+        if let Some(walk_forward) = self.walk_forward {
+            if walk_forward.in_sample_days <= 0 || walk_forward.out_of_sample_days <= 0 {
+                return Err(GlowError::new_str(
+                    "Invalid sweep spec",
+                    "walk forward windows must last at least one day",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Names outputs after the definition file for declarative strategies
+    pub fn get_strategy_name(&self) -> String {
+        match &self.strategy_definition {
+            Some(path) => Path::new(path)
+                .file_stem()
+                .map_or(path.clone(), |stem| stem.to_string_lossy().to_string()),
+            None => format!("{:?}", self.benchmark_settings.strategy_id),
+        }
+    }
+
+    /// Builds the strategy with fixed params applied
+    pub fn build_base_strategy(&self) -> Result<Strategy, GlowError> {
+        let mut strategy = match &self.strategy_definition {
+            Some(path) => Strategy::from_definition(
+                StrategyDefinition::load(path)?,
+                self.trading_settings.symbols_pair,
+            ),
+            None => Strategy::new(
+                self.benchmark_settings.strategy_id,
+                self.trading_settings.symbols_pair,
+            )?,
+        };
+        for (param_id, value) in &self.params {
+            strategy = strategy.patch_number_param(*param_id, *value)?;
+        }
+        Ok(strategy)
+    }
+}
@@ -0,0 +1,276 @@
+use crate::spec::{RankBy, SweepSpec};
+use common::{
+    enums::granularity::Granularity,
+    structs::{Statistics, Symbol},
+};
+use core::{
+    benchmark::functions::compute_benchmark_positions,
+    controller::Controller,
+    performance::{calculate_trades, calculate_trading_sessions, calculate_trading_stats},
+    trader::Trader,
+};
+use glow_error::GlowError;
+use itertools::Itertools;
+use polars::prelude::*;
+use std::cmp::Ordering;
+use strategy::{params::ParamId, Strategy};
+
+/// Number of intervals each swept range is split into when no step is configured
+pub const DEFAULT_SWEEP_STEPS: f64 = 10.0;
+
+type StatisticsGetter = fn(&Statistics) -> f64;
+
+pub struct SweepResult {
+    pub values: Vec<f64>,
+    pub statistics: Statistics,
+}
+
+/// Param combination that couldn't be evaluated
+pub struct SweepFailure {
+    pub values: Vec<f64>,
+    pub error: GlowError,
+}
+
+/// Results sorted by rank, alongside the combinations that failed
+pub struct SweepOutcome {
+    pub param_ids: Vec<ParamId>,
+    pub results: Vec<SweepResult>,
+    pub failures: Vec<SweepFailure>,
+}
+
+/// Lists the values taken by each swept param, sorted by param id
+pub fn get_sweep_ranges(strategy: &Strategy, spec: &SweepSpec) -> Vec<(ParamId, Vec<f64>)> {
+    let mut ranges = vec![];
+    for (param_id, param) in strategy.params.iter().sorted_by_key(|(id, _)| **id) {
+        if spec.params.contains_key(param_id) {
+            continue;
+        }
+        let Some((min, max)) = param.number_bounds() else {
+            continue;
+        };
+        let mut step = match spec.steps.get(param_id) {
+            Some(step) => *step,
+            None => (max - min) / DEFAULT_SWEEP_STEPS,
+        };
+        if param.is_integer() {
+            step = step.round().max(1.0);
+        }
+        let intervals = if step > 0.0 {
+            ((max - min) / step + 1e-9).floor() as usize
+        } else {
+            0
+        };
+        let values = (0..=intervals)
+            .map(|index| min + index as f64 * step)
+            .collect();
+        ranges.push((*param_id, values));
+    }
+    ranges
+}
+
+/// Appends the strategy indicators and signals to already fetched klines
+pub fn compute_strategy_df(
+    strategy: &Strategy,
+    klines_df: &DataFrame,
+) -> Result<DataFrame, GlowError> {
+    let strategy_lf = strategy.append_indicators_to_lf(klines_df.clone().lazy())?;
+    let strategy_lf = strategy.append_signals_to_lf(strategy_lf)?;
+    Ok(strategy_lf.collect()?)
+}
+
+/// Runs the benchmark engine over strategy data, returning the benchmark positions and their statistics
+pub fn evaluate_strategy_df(
+    trader: &Trader,
+    strategy_df: DataFrame,
+    traded_symbol: &Symbol,
+    risk_free_returns: f64,
+    returns_granularity: Granularity,
+) -> Result<(DataFrame, Statistics), GlowError> {
+    let benchmark_df = compute_benchmark_positions(trader, strategy_df)?;
+
+    let trades_lf = calculate_trades(benchmark_df.clone().lazy())?;
+    let trading_df = calculate_trading_sessions(trades_lf, traded_symbol, None)?
+        .drop_nulls(None)
+        .collect()?;
+    let statistics = calculate_trading_stats(&trading_df, risk_free_returns, returns_granularity)?;
+    Ok((benchmark_df, statistics))
+}
+
+/// Sorts results by rank, best first. NaN ranks go last.
+pub fn sort_results(results: &mut [SweepResult], rank_by: RankBy) {
+    results.sort_by(|a, b| {
+        let a = rank_by.get_value(&a.statistics);
+        let b = rank_by.get_value(&b.statistics);
+        match (a.is_nan(), b.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+        }
+    });
+}
+
+/// Param combinations of a spec, sharing a controller and a single klines fetch
+pub struct Sweep {
+    pub base_strategy: Strategy,
+    pub combinations: Vec<Vec<f64>>,
+    pub controller: Controller,
+    pub klines_df: DataFrame,
+    pub param_ids: Vec<ParamId>,
+    pub returns_granularity: Granularity,
+}
+
+impl Sweep {
+    pub async fn new(spec: &SweepSpec) -> Result<Self, GlowError> {
+        let base_strategy = spec.build_base_strategy()?;
+        let ranges = get_sweep_ranges(&base_strategy, spec);
+        let controller = Controller::from_settings(
+            spec.benchmark_settings.clone(),
+            &spec.trading_settings,
+            &base_strategy,
+            true,
+        )?;
+        let klines_df = controller.data_feed.fetch_benchmark_klines().await?;
+
+        let param_ids: Vec<ParamId> = ranges.iter().map(|(param_id, _)| *param_id).collect();
+        let combinations: Vec<Vec<f64>> = if ranges.is_empty() {
+            vec![vec![]]
+        } else {
+            ranges
+                .into_iter()
+                .map(|(_, values)| values)
+                .multi_cartesian_product()
+                .collect()
+        };
+        println!(
+            "🔎 Sweeping {} combinations of {:?}",
+            combinations.len(),
+            param_ids
+        );
+
+        Ok(Self {
+            base_strategy,
+            combinations,
+            controller,
+            klines_df,
+            param_ids,
+            returns_granularity: spec.returns_granularity,
+        })
+    }
+
+    pub fn build_strategy(&self, values: &[f64]) -> Result<Strategy, GlowError> {
+        let mut strategy = self.base_strategy.clone();
+        for (param_id, value) in self.param_ids.iter().zip(values.iter()) {
+            strategy = strategy.patch_number_param(*param_id, *value)?;
+        }
+        Ok(strategy)
+    }
+
+    pub fn compute_strategy_df(&self, values: &[f64]) -> Result<DataFrame, GlowError> {
+        let strategy = self.build_strategy(values)?;
+        compute_strategy_df(&strategy, &self.klines_df)
+    }
+
+    pub fn evaluate(&self, strategy_df: DataFrame) -> Result<(DataFrame, Statistics), GlowError> {
+        evaluate_strategy_df(
+            &self.controller.trader,
+            strategy_df,
+            self.base_strategy.symbols_pair.traded,
+            0.0,
+            self.returns_granularity,
+        )
+    }
+}
+
+/// Evaluates every param combination over a single klines fetch.
+/// Results are sorted by the spec rank, best first, and combinations that fail are returned apart.
+pub async fn run_sweep(spec: &SweepSpec) -> Result<SweepOutcome, GlowError> {
+    let sweep = Sweep::new(spec).await?;
+
+    let mut results = vec![];
+    let mut failures = vec![];
+    for values in &sweep.combinations {
+        let evaluation = sweep
+            .compute_strategy_df(values)
+            .and_then(|strategy_df| sweep.evaluate(strategy_df));
+        match evaluation {
+            Ok((_, statistics)) => results.push(SweepResult {
+                values: values.clone(),
+                statistics,
+            }),
+            Err(error) => failures.push(SweepFailure {
+                values: values.clone(),
+                error,
+            }),
+        }
+    }
+    sort_results(&mut results, spec.rank_by);
+
+    Ok(SweepOutcome {
+        param_ids: sweep.param_ids,
+        results,
+        failures,
+    })
+}
+
+/// Builds one column per statistic, with names prefixed by `prefix`
+pub fn get_statistics_columns(prefix: &str, statistics: &[&Statistics]) -> Vec<Column> {
+    let getters: [(&str, StatisticsGetter); 11] = [
+        ("success_rate", Statistics::success_rate),
+        ("final_balance", Statistics::current_balance),
+        ("risk", Statistics::risk),
+        ("downside_deviation", Statistics::downside_deviation),
+        ("risk_adjusted_return", Statistics::risk_adjusted_return),
+        ("max_drawdown", Statistics::max_drawdown),
+        ("sharpe_ratio", Statistics::sharpe_ratio),
+        ("sortino_ratio", Statistics::sortino_ratio),
+        ("per_trade_sharpe_ratio", Statistics::per_trade_sharpe_ratio),
+        (
+            "per_trade_sortino_ratio",
+            Statistics::per_trade_sortino_ratio,
+        ),
+        ("calmar_ratio", Statistics::calmar_ratio),
+    ];
+    let mut columns = vec![];
+    for (name, getter) in getters {
+        let values: Vec<f64> = statistics.iter().map(|stats| getter(stats)).collect();
+        columns.push(Column::new(format!("{}{}", prefix, name).into(), values));
+    }
+    let max_drawdown_durations: Vec<i64> = statistics
+        .iter()
+        .map(|stats| stats.max_drawdown_duration().num_seconds())
+        .collect();
+    columns.push(Column::new(
+        format!("{}max_drawdown_duration_secs", prefix).into(),
+        max_drawdown_durations,
+    ));
+    columns
+}
+
+/// Builds one column per param, holding each row's value
+pub fn get_param_columns(param_ids: &[ParamId], values: &[&Vec<f64>]) -> Vec<Column> {
+    param_ids
+        .iter()
+        .enumerate()
+        .map(|(index, param_id)| {
+            let param_values: Vec<f64> = values.iter().map(|values| values[index]).collect();
+            Column::new(format!("{:?}", param_id).into(), param_values)
+        })
+        .collect()
+}
+
+pub fn build_leaderboard_df(
+    param_ids: &[ParamId],
+    results: &[SweepResult],
+) -> Result<DataFrame, GlowError> {
+    let mut columns = vec![Column::new(
+        "rank".into(),
+        (1..=results.len() as u32).collect::<Vec<u32>>(),
+    )];
+    let values: Vec<&Vec<f64>> = results.iter().map(|result| &result.values).collect();
+    columns.extend(get_param_columns(param_ids, &values));
+    let statistics: Vec<&Statistics> = results.iter().map(|result| &result.statistics).collect();
+    columns.extend(get_statistics_columns("", &statistics));
+
+    Ok(DataFrame::new(columns)?)
+}
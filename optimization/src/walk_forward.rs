@@ -0,0 +1,296 @@
+use crate::{
+    spec::{SweepSpec, WalkForwardSettings},
+    sweep::{
+        get_param_columns, get_statistics_columns, sort_results, Sweep, SweepFailure, SweepResult,
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use common::structs::Statistics;
+use glow_error::GlowError;
+use polars::prelude::*;
+use strategy::params::ParamId;
+
+#[derive(Clone, Copy, Debug)]
+pub struct WalkForwardWindow {
+    pub in_sample: (DateTime<Utc>, DateTime<Utc>), // (start, end)
+    pub out_of_sample: (DateTime<Utc>, DateTime<Utc>), // (start, end)
+}
+
+pub struct WalkForwardResult {
+    pub window: WalkForwardWindow,
+    pub values: Vec<f64>,
+    pub in_sample_statistics: Statistics,
+    pub out_of_sample_statistics: Statistics,
+    pub out_of_sample_df: DataFrame, // start_time, balance
+}
+
+/// Part of a window a combination is evaluated over
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WalkForwardStage {
+    InSample,
+    OutOfSample,
+}
+
+/// Combination that couldn't be evaluated over a window stage
+pub struct WalkForwardFailure {
+    pub window: WalkForwardWindow,
+    pub stage: WalkForwardStage,
+    pub failure: SweepFailure,
+}
+
+/// Results of the windows that could be evaluated, alongside the failures of every window
+pub struct WalkForwardOutcome {
+    pub param_ids: Vec<ParamId>,
+    pub results: Vec<WalkForwardResult>,
+    pub failures: Vec<WalkForwardFailure>,
+    /// Windows left without a result, as no combination could be evaluated in-sample or the winner failed out-of-sample
+    pub skipped_windows: Vec<WalkForwardWindow>,
+}
+
+/// Splits [start, end) into rolling windows. The last out-of-sample window is truncated at `end`.
+pub fn get_walk_forward_windows(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    settings: WalkForwardSettings,
+) -> Vec<WalkForwardWindow> {
+    let in_sample_duration = Duration::days(settings.in_sample_days);
+    let out_of_sample_duration = Duration::days(settings.out_of_sample_days);
+    let mut windows = vec![];
+    let mut window_start = start;
+    while window_start + in_sample_duration < end {
+        let out_of_sample_start = window_start + in_sample_duration;
+        let out_of_sample_end = (out_of_sample_start + out_of_sample_duration).min(end);
+        windows.push(WalkForwardWindow {
+            in_sample: (window_start, out_of_sample_start),
+            out_of_sample: (out_of_sample_start, out_of_sample_end),
+        });
+        window_start += out_of_sample_duration;
+    }
+    windows
+}
+
+fn filter_by_datetimes(
+    df: &DataFrame,
+    datetimes: (DateTime<Utc>, DateTime<Utc>),
+) -> Result<DataFrame, GlowError> {
+    let start_time = col("start_time").dt().timestamp(TimeUnit::Milliseconds);
+    let df = df
+        .clone()
+        .lazy()
+        .filter(
+            start_time
+                .clone()
+                .gt_eq(lit(datetimes.0.timestamp_millis()))
+                .and(start_time.lt(lit(datetimes.1.timestamp_millis()))),
+        )
+        .collect()?;
+    Ok(df)
+}
+
+/// Optimizes params on each in-sample window, then evaluates the winner on the following out-of-sample window.
+/// Strategy indicators are computed over the whole klines frame, so that every window starts warmed up,
+/// while windows are anchored at the benchmark start rather than at the warm-up klines.
+/// Combinations that fail are returned apart, alongside the windows they left without a result.
+pub async fn run_walk_forward(
+    spec: &SweepSpec,
+    settings: WalkForwardSettings,
+) -> Result<WalkForwardOutcome, GlowError> {
+    let sweep = Sweep::new(spec).await?;
+    let (start, end) = match spec.benchmark_settings.datetimes {
+        (Some(start), Some(end)) => (start, end),
+        _ => {
+            return Err(GlowError::new_str(
+                "Walk forward error",
+                "datetimes must include a benchmark start and end",
+            ))
+        }
+    };
+    let windows = get_walk_forward_windows(start, end, settings);
+    if windows.is_empty() {
+        return Err(GlowError::new(
+            String::from("Walk forward error"),
+            format!(
+                "benchmark range from {} to {} is shorter than the in-sample window",
+                start, end
+            ),
+        ));
+    }
+    eprintln!("🪟 Walk forward over {} windows", windows.len());
+
+    let mut in_sample_results: Vec<Vec<SweepResult>> = windows.iter().map(|_| vec![]).collect();
+    let mut failures = vec![];
+    for values in &sweep.combinations {
+        let strategy_df = match sweep.compute_strategy_df(values) {
+            Ok(strategy_df) => strategy_df,
+            Err(error) => {
+                // the strategy data is shared by every window, so the combination fails all of them
+                failures.extend(windows.iter().map(|window| WalkForwardFailure {
+                    window: *window,
+                    stage: WalkForwardStage::InSample,
+                    failure: SweepFailure {
+                        values: values.clone(),
+                        error: error.clone(),
+                    },
+                }));
+                continue;
+            }
+        };
+        for (window, results) in windows.iter().zip(in_sample_results.iter_mut()) {
+            let evaluation = filter_by_datetimes(&strategy_df, window.in_sample)
+                .and_then(|in_sample_df| sweep.evaluate(in_sample_df));
+            match evaluation {
+                Ok((_, statistics)) => results.push(SweepResult {
+                    values: values.clone(),
+                    statistics,
+                }),
+                Err(error) => failures.push(WalkForwardFailure {
+                    window: *window,
+                    stage: WalkForwardStage::InSample,
+                    failure: SweepFailure {
+                        values: values.clone(),
+                        error,
+                    },
+                }),
+            }
+        }
+    }
+
+    let mut walk_forward_results = vec![];
+    let mut skipped_windows = vec![];
+    for (window, mut results) in windows.into_iter().zip(in_sample_results) {
+        sort_results(&mut results, spec.rank_by);
+        let Some(winner) = results.into_iter().next() else {
+            eprintln!("no combination could be evaluated at window {:?}", window);
+            skipped_windows.push(window);
+            continue;
+        };
+        let evaluation = sweep
+            .compute_strategy_df(&winner.values)
+            .and_then(|strategy_df| filter_by_datetimes(&strategy_df, window.out_of_sample))
+            .and_then(|out_of_sample_df| sweep.evaluate(out_of_sample_df))
+            .and_then(|(benchmark_df, out_of_sample_statistics)| {
+                let out_of_sample_df = benchmark_df
+                    .lazy()
+                    .select([col("start_time"), col("balance").cast(DataType::Float64)])
+                    .collect()?;
+                Ok((out_of_sample_df, out_of_sample_statistics))
+            });
+        match evaluation {
+            Ok((out_of_sample_df, out_of_sample_statistics)) => {
+                walk_forward_results.push(WalkForwardResult {
+                    window,
+                    values: winner.values,
+                    in_sample_statistics: winner.statistics,
+                    out_of_sample_statistics,
+                    out_of_sample_df,
+                })
+            }
+            Err(error) => {
+                failures.push(WalkForwardFailure {
+                    window,
+                    stage: WalkForwardStage::OutOfSample,
+                    failure: SweepFailure {
+                        values: winner.values,
+                        error,
+                    },
+                });
+                skipped_windows.push(window);
+            }
+        }
+    }
+
+    Ok(WalkForwardOutcome {
+        param_ids: sweep.param_ids,
+        results: walk_forward_results,
+        failures,
+        skipped_windows,
+    })
+}
+
+fn get_datetimes_column(name: &str, datetimes: Vec<DateTime<Utc>>) -> Result<Column, GlowError> {
+    let timestamps: Vec<i64> = datetimes
+        .iter()
+        .map(|datetime| datetime.timestamp_millis())
+        .collect();
+    let column = Column::new(name.into(), timestamps)
+        .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?;
+    Ok(column)
+}
+
+/// One row per window, with the chosen params, in-sample stats prefixed by `is_` and out-of-sample stats by `oos_`
+pub fn build_windows_df(
+    param_ids: &[ParamId],
+    results: &[WalkForwardResult],
+) -> Result<DataFrame, GlowError> {
+    let mut columns = vec![
+        Column::new(
+            "window".into(),
+            (1..=results.len() as u32).collect::<Vec<u32>>(),
+        ),
+        get_datetimes_column(
+            "in_sample_start",
+            results.iter().map(|r| r.window.in_sample.0).collect(),
+        )?,
+        get_datetimes_column(
+            "out_of_sample_start",
+            results.iter().map(|r| r.window.out_of_sample.0).collect(),
+        )?,
+        get_datetimes_column(
+            "out_of_sample_end",
+            results.iter().map(|r| r.window.out_of_sample.1).collect(),
+        )?,
+    ];
+    let values: Vec<&Vec<f64>> = results.iter().map(|result| &result.values).collect();
+    columns.extend(get_param_columns(param_ids, &values));
+    let in_sample_statistics: Vec<&Statistics> = results
+        .iter()
+        .map(|result| &result.in_sample_statistics)
+        .collect();
+    columns.extend(get_statistics_columns("is_", &in_sample_statistics));
+    let out_of_sample_statistics: Vec<&Statistics> = results
+        .iter()
+        .map(|result| &result.out_of_sample_statistics)
+        .collect();
+    columns.extend(get_statistics_columns("oos_", &out_of_sample_statistics));
+
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Chains out-of-sample balances, so that each window starts from the previous window's final equity
+pub fn build_stitched_equity_df(results: &[WalkForwardResult]) -> Result<DataFrame, GlowError> {
+    let mut start_times: Vec<i64> = vec![];
+    let mut equities: Vec<f64> = vec![];
+    let mut windows: Vec<u32> = vec![];
+    let mut carried_equity: Option<f64> = None;
+
+    for (index, result) in results.iter().enumerate() {
+        let df = &result.out_of_sample_df;
+        let balances = df.column("balance")?.f64()?;
+        let Some(initial_balance) = balances.into_iter().flatten().next() else {
+            continue;
+        };
+        let factor = match carried_equity {
+            Some(equity) if initial_balance != 0.0 => equity / initial_balance,
+            _ => 1.0,
+        };
+        let timestamps = df.column("start_time")?.cast(&DataType::Int64)?;
+        for (timestamp, balance) in timestamps.i64()?.into_iter().zip(balances) {
+            let (Some(timestamp), Some(balance)) = (timestamp, balance) else {
+                continue;
+            };
+            let equity = balance * factor;
+            start_times.push(timestamp);
+            equities.push(equity);
+            windows.push(index as u32 + 1);
+            carried_equity = Some(equity);
+        }
+    }
+
+    let df = DataFrame::new(vec![
+        Column::new("start_time".into(), start_times)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?,
+        Column::new("window".into(), windows),
+        Column::new("equity".into(), equities),
+    ])?;
+    Ok(df)
+}
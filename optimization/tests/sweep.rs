@@ -0,0 +1,190 @@
+use chrono::Duration;
+use common::{
+    enums::symbol_id::SymbolId,
+    structs::{Statistics, SymbolsPair},
+};
+use optimization::{
+    spec::{RankBy, SweepSpec},
+    sweep::{build_leaderboard_df, get_sweep_ranges, sort_results, SweepResult},
+};
+use strategy::{
+    params::ParamId, schemas::declarative::definition::StrategyDefinition, Strategy, StrategyId,
+};
+
+fn get_symbols_pair() -> SymbolsPair {
+    SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin)
+}
+
+fn get_spec(params: &str, steps: &str) -> SweepSpec {
+    serde_json::from_str(&format!(
+        r#"{{
+            "datetimes": ["2024-06-01T00:00:00Z", "2024-06-08T00:00:00Z"],
+            "strategy_id": "SimpleTrend",
+            "data_provider_id": "Binance",
+            "trader_exchange_id": "Bybit",
+            "params": {},
+            "steps": {}
+        }}"#,
+        params, steps
+    ))
+    .unwrap()
+}
+
+fn get_statistics(sharpe_ratio: f64, current_balance: f64) -> Statistics {
+    Statistics::new(
+        0.5,
+        current_balance,
+        0.0,
+        0.0,
+        0.0,
+        0.1,
+        Duration::hours(1),
+        sharpe_ratio,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+    )
+}
+
+fn get_result(values: Vec<f64>, sharpe_ratio: f64) -> SweepResult {
+    SweepResult {
+        values,
+        statistics: get_statistics(sharpe_ratio, 100.0),
+    }
+}
+
+fn assert_values(values: &[f64], expected_values: &[f64]) {
+    assert_eq!(values.len(), expected_values.len(), "{:?}", values);
+    for (value, expected_value) in values.iter().zip(expected_values) {
+        assert!((value - expected_value).abs() < 1e-9, "{:?}", values);
+    }
+}
+
+#[test]
+fn test_default_steps_split_ranges_in_integer_steps() {
+    let strategy = Strategy::new(StrategyId::SimpleTrend, get_symbols_pair()).unwrap();
+
+    let ranges = get_sweep_ranges(&strategy, &get_spec("{}", "{}"));
+
+    // sorted by param id, SlowSpan in [50, 200] steps by 15, FastSpan in [1, 50] by 4.9 rounded to 5
+    let param_ids: Vec<ParamId> = ranges.iter().map(|(param_id, _)| *param_id).collect();
+    assert_eq!(param_ids, [ParamId::SlowSpan, ParamId::FastSpan]);
+    assert_eq!(ranges[0].1.len(), 11);
+    assert_eq!(ranges[0].1.last(), Some(&200.0));
+    assert_values(
+        &ranges[1].1,
+        &[1.0, 6.0, 11.0, 16.0, 21.0, 26.0, 31.0, 36.0, 41.0, 46.0],
+    );
+}
+
+#[test]
+fn test_fixed_params_are_skipped_and_steps_rounded() {
+    let strategy = Strategy::new(StrategyId::SimpleTrend, get_symbols_pair()).unwrap();
+
+    let ranges = get_sweep_ranges(
+        &strategy,
+        &get_spec(r#"{ "SlowSpan": 100 }"#, r#"{ "FastSpan": 12.4 }"#),
+    );
+    let tiny_step_ranges = get_sweep_ranges(&strategy, &get_spec("{}", r#"{ "FastSpan": 0.2 }"#));
+
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0].0, ParamId::FastSpan);
+    assert_values(&ranges[0].1, &[1.0, 13.0, 25.0, 37.0, 49.0]);
+    // integer steps are at least 1
+    assert_eq!(tiny_step_ranges[1].0, ParamId::FastSpan);
+    assert_eq!(tiny_step_ranges[1].1.len(), 50);
+}
+
+#[test]
+fn test_float_ranges_keep_their_last_step() {
+    let definition: StrategyDefinition = serde_json::from_str(
+        r#"{
+            "name": "float_threshold",
+            "params": {
+                "UpperThreshold": { "kind": "Float64", "default": 0.3, "min": 0.1, "max": 0.7 }
+            }
+        }"#,
+    )
+    .unwrap();
+    let strategy = Strategy::from_definition(definition, get_symbols_pair());
+
+    let ranges = get_sweep_ranges(&strategy, &get_spec("{}", r#"{ "UpperThreshold": 0.2 }"#));
+
+    // (0.7 - 0.1) / 0.2 is slightly below 3, which the floor would drop without its margin
+    assert_values(&ranges[0].1, &[0.1, 0.3, 0.5, 0.7]);
+}
+
+#[test]
+fn test_nan_ranks_sort_last() {
+    let mut results = vec![
+        get_result(vec![1.0], f64::NAN),
+        get_result(vec![2.0], 0.5),
+        get_result(vec![3.0], f64::NAN),
+        get_result(vec![4.0], 1.5),
+        get_result(vec![5.0], -1.0),
+    ];
+
+    sort_results(&mut results, RankBy::SharpeRatio);
+
+    let values: Vec<f64> = results.iter().map(|result| result.values[0]).collect();
+    assert_eq!(&values[..3], [4.0, 2.0, 5.0]);
+    assert!(results[3].statistics.sharpe_ratio().is_nan());
+    assert!(results[4].statistics.sharpe_ratio().is_nan());
+}
+
+#[test]
+fn test_leaderboard_columns() {
+    let results = vec![
+        get_result(vec![20.0, 100.0], 1.5),
+        get_result(vec![10.0, 150.0], 0.5),
+    ];
+
+    let leaderboard_df =
+        build_leaderboard_df(&[ParamId::FastSpan, ParamId::SlowSpan], &results).unwrap();
+
+    assert_eq!(
+        leaderboard_df.get_column_names_str(),
+        [
+            "rank",
+            "FastSpan",
+            "SlowSpan",
+            "success_rate",
+            "final_balance",
+            "risk",
+            "downside_deviation",
+            "risk_adjusted_return",
+            "max_drawdown",
+            "sharpe_ratio",
+            "sortino_ratio",
+            "per_trade_sharpe_ratio",
+            "per_trade_sortino_ratio",
+            "calmar_ratio",
+            "max_drawdown_duration_secs"
+        ]
+    );
+    let ranks: Vec<u32> = leaderboard_df
+        .column("rank")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(ranks, [1, 2]);
+    let slow_spans: Vec<f64> = leaderboard_df
+        .column("SlowSpan")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(slow_spans, [100.0, 150.0]);
+    let durations = leaderboard_df
+        .column("max_drawdown_duration_secs")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .get(0);
+    assert_eq!(durations, Some(3600));
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use common::structs::Statistics;
+use optimization::{
+    spec::WalkForwardSettings,
+    walk_forward::{build_stitched_equity_df, get_walk_forward_windows, WalkForwardResult},
+};
+use polars::prelude::*;
+
+fn get_datetime(day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap()
+}
+
+fn get_settings(in_sample_days: i64, out_of_sample_days: i64) -> WalkForwardSettings {
+    WalkForwardSettings {
+        in_sample_days,
+        out_of_sample_days,
+    }
+}
+
+fn get_statistics() -> Statistics {
+    Statistics::new(
+        0.5,
+        100.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        Duration::zero(),
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+    )
+}
+
+fn get_result(day: u32, balances: &[f64]) -> WalkForwardResult {
+    let start = get_datetime(day);
+    let timestamps: Vec<i64> = (0..balances.len() as i64)
+        .map(|hour| (start + Duration::hours(hour)).timestamp_millis())
+        .collect();
+    let out_of_sample_df = DataFrame::new(vec![
+        Column::new("start_time".into(), timestamps)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap(),
+        Column::new("balance".into(), balances.to_vec()),
+    ])
+    .unwrap();
+    WalkForwardResult {
+        window: get_walk_forward_windows(
+            start - Duration::days(7),
+            start + Duration::days(3),
+            get_settings(7, 3),
+        )[0],
+        values: vec![20.0],
+        in_sample_statistics: get_statistics(),
+        out_of_sample_statistics: get_statistics(),
+        out_of_sample_df,
+    }
+}
+
+#[test]
+fn test_windows_roll_by_out_of_sample_days() {
+    let windows = get_walk_forward_windows(get_datetime(1), get_datetime(20), get_settings(7, 3));
+
+    // in-sample windows start every 3 days from the benchmark start, until they reach its end
+    let in_sample_starts: Vec<DateTime<Utc>> =
+        windows.iter().map(|window| window.in_sample.0).collect();
+    assert_eq!(
+        in_sample_starts,
+        [
+            get_datetime(1),
+            get_datetime(4),
+            get_datetime(7),
+            get_datetime(10)
+        ]
+    );
+    for window in &windows {
+        assert_eq!(window.in_sample.1, window.out_of_sample.0);
+        assert_eq!(window.in_sample.1 - window.in_sample.0, Duration::days(7));
+    }
+    assert_eq!(
+        windows[3].out_of_sample,
+        (get_datetime(17), get_datetime(20))
+    );
+}
+
+#[test]
+fn test_last_out_of_sample_window_is_truncated() {
+    let windows = get_walk_forward_windows(get_datetime(1), get_datetime(19), get_settings(7, 3));
+    let short_windows =
+        get_walk_forward_windows(get_datetime(1), get_datetime(8), get_settings(7, 3));
+
+    assert_eq!(windows.len(), 4);
+    assert_eq!(
+        windows[3].out_of_sample,
+        (get_datetime(17), get_datetime(19))
+    );
+    // the in-sample window must end before the benchmark does
+    assert!(short_windows.is_empty());
+}
+
+#[test]
+fn test_stitched_equity_chains_windows() {
+    let results = vec![
+        get_result(8, &[100.0, 110.0]),
+        get_result(11, &[200.0, 180.0]),
+        get_result(14, &[]),
+        get_result(17, &[50.0, 55.0]),
+    ];
+
+    let stitched_equity_df = build_stitched_equity_df(&results).unwrap();
+
+    // each window scales its balances so that it starts from the previous window final equity
+    let equities: Vec<f64> = stitched_equity_df
+        .column("equity")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    let expected_equities = [100.0, 110.0, 110.0, 99.0, 99.0, 108.9];
+    assert_eq!(equities.len(), expected_equities.len());
+    for (equity, expected_equity) in equities.iter().zip(expected_equities) {
+        assert!((equity - expected_equity).abs() < 1e-9, "{:?}", equities);
+    }
+    let windows: Vec<u32> = stitched_equity_df
+        .column("window")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(windows, [1, 1, 2, 2, 4, 4]);
+    let first_start_time = stitched_equity_df
+        .column("start_time")
+        .unwrap()
+        .cast(&DataType::Int64)
+        .unwrap()
+        .i64()
+        .unwrap()
+        .get(0);
+    assert_eq!(first_start_time, Some(get_datetime(8).timestamp_millis()));
+}
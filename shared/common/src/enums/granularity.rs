@@ -35,7 +35,7 @@ impl Granularity {
             Self::m15 => 15 * 60,
             Self::m30 => 30 * 60,
             Self::h1 => 60 * 60,
-            Self::h2 => 2 * 60 * 20,
+            Self::h2 => 2 * 60 * 60,
             Self::h4 => 4 * 60 * 60,
             Self::h6 => 6 * 60 * 60,
             Self::h12 => 12 * 60 * 60,
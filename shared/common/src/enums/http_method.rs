@@ -2,4 +2,6 @@
 pub enum HttpMethod {
     Get,
     Post,
+    Put,
+    Delete,
 }
@@ -1,3 +1,4 @@
 pub mod leverage;
 pub mod position_lock;
 pub mod price_level;
+pub mod slippage;
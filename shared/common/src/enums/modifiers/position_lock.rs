@@ -11,3 +11,17 @@ pub enum PositionLock {
     #[serde(rename="loss")]
     Loss = 2, // least percentage for the trade to close
 }
+
+impl PositionLock {
+    /// Whether the lock keeps a signal from closing a position, given its price profit and loss before fees and the fees to open and close it.
+    /// Live traders and benchmarks share this estimate, whose profit and loss only discounts the close fee.
+    pub fn is_close_locked(&self, price_pnl: f64, open_fee: f64, close_fee: f64) -> bool {
+        let profit_and_loss = price_pnl - close_fee;
+        let total_fee = open_fee + close_fee;
+        match self {
+            PositionLock::None => false,
+            PositionLock::Fee => total_fee >= profit_and_loss.abs(),
+            PositionLock::Loss => profit_and_loss <= 0.0,
+        }
+    }
+}
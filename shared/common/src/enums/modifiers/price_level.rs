@@ -1,3 +1,4 @@
+use crate::enums::side::Side;
 use serde::{Deserialize, Serialize};
 
 // uses ROI
@@ -7,28 +8,71 @@ pub enum PriceLevel {
     StopLoss(f64), // 0 < f64 <= 75 in bybit
     #[serde(rename="tp")]
     TakeProfit(f64),
-    // #[serde(rename="tsp")]
-    // TrailingStopLoss(TrailingStopLoss),
+    #[serde(rename="tsp")]
+    TrailingStopLoss(TrailingStopLoss),
 }
 
-// TODO: implement this in the future
-// #[derive(Serialize, Deserialize, Debug, Clone)]
-// pub enum TrailingStopLoss {
-//     #[serde(rename="pcnt")]
-//     Percent(f64, f64),
-//     #[serde(rename="step")]
-//     Stepped(f64, f64),
-// }
+// uses ROI, the stop only starts trailing once the peak returns reach the activation returns
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TrailingStopLoss {
+    #[serde(rename="pcnt")]
+    Percent(f64, f64), // (distance from the peak returns, activation returns)
+    #[serde(rename="step")]
+    Stepped(f64, f64), // (step the peak returns are locked at, activation returns)
+}
+
+impl TrailingStopLoss {
+    pub fn get_activation_returns(&self) -> f64 {
+        match self {
+            TrailingStopLoss::Percent(_, activation_returns)
+            | TrailingStopLoss::Stepped(_, activation_returns) => *activation_returns,
+        }
+    }
+
+    /// Returns locked by the stop, if the peak returns already reached the activation returns
+    pub fn get_stop_returns(&self, peak_returns: f64) -> Option<f64> {
+        let activation_returns = self.get_activation_returns();
+        if peak_returns < activation_returns {
+            return None;
+        }
+        match self {
+            TrailingStopLoss::Percent(distance, _) => Some(peak_returns - distance),
+            TrailingStopLoss::Stepped(step, _) => {
+                let locked_returns = (peak_returns / step).floor() * step;
+                Some(locked_returns.max(activation_returns))
+            }
+        }
+    }
+
+    /// Stop price given the most favorable price reached since the position was opened at `entry_price`
+    pub fn get_stop_price(
+        &self,
+        side: Side,
+        entry_price: f64,
+        peak_price: f64,
+        leverage_factor: f64,
+    ) -> Option<f64> {
+        let (peak_returns, returns_sign) = match side {
+            Side::Buy => ((peak_price - entry_price) / entry_price * leverage_factor, 1.0),
+            Side::Sell => ((entry_price - peak_price) / entry_price * leverage_factor, -1.0),
+            Side::None => return None,
+        };
+        self.get_stop_returns(peak_returns)
+            .map(|stop_returns| {
+                entry_price * (leverage_factor + returns_sign * stop_returns) / leverage_factor
+            })
+    }
+}
 
 impl PriceLevel {
     pub fn get_percentage(&self) -> f64 {
         match &self {
             PriceLevel::StopLoss(percentage) => *percentage,
             PriceLevel::TakeProfit(percentage) => *percentage,
-            // PriceLevel::TrailingStopLoss(trailing_stop_loss) => match trailing_stop_loss {
-            //     TrailingStopLoss::Percent(percentage, _) => *percentage,
-            //     TrailingStopLoss::Stepped(percentage, _) => *percentage,
-            // },
+            PriceLevel::TrailingStopLoss(trailing_stop_loss) => match trailing_stop_loss {
+                TrailingStopLoss::Percent(percentage, _) => *percentage,
+                TrailingStopLoss::Stepped(percentage, _) => *percentage,
+            },
         }
     }
 
@@ -36,7 +80,7 @@ impl PriceLevel {
         match &self {
             PriceLevel::StopLoss(_) => "sl".to_string(),
             PriceLevel::TakeProfit(_) => "tp".to_string(),
-            // PriceLevel::TrailingStopLoss(_) => "tsp".to_string(),
+            PriceLevel::TrailingStopLoss(_) => "tsp".to_string(),
         }
     }
 }
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+// applied against the order side, to market fills only
+#[derive(Serialize, Deserialize, Default, Debug, PartialEq, Clone, Copy)]
+pub enum Slippage {
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "ticks")]
+    Ticks(u32), // fixed amount of ticks
+    #[serde(rename = "pcnt")]
+    Percentage(f64), // percentage of the fill price
+    #[serde(rename = "vol")]
+    Volatility(f64), // factor of the kline range (high - low)
+}
+
+impl Slippage {
+    /// Price amount a fill is moved against the order, given the contract tick size and the kline range
+    pub fn get_price_slippage(&self, price: f64, tick_size: f64, kline_range: f64) -> f64 {
+        match self {
+            Slippage::None => 0.0,
+            Slippage::Ticks(ticks) => *ticks as f64 * tick_size,
+            Slippage::Percentage(percentage) => price * percentage / 100.0,
+            Slippage::Volatility(factor) => kline_range * factor,
+        }
+    }
+}
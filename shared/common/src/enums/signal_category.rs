@@ -5,17 +5,19 @@ use super::side::Side;
 pub enum SignalCategory {
     GoShort,
     CloseShort,
-    // RevertShort,
+    RevertShort, // closes the short, opening a long
     GoLong,
     CloseLong,
-    // RevertLong,
+    RevertLong, // closes the long, opening a short
     KeepPosition,
     ClosePosition,
     // RevertPosition,
     StopLoss,
-    // TrailingStopLoss,
+    TrailingStopLoss,
     TakeProfit,
     LeverageBankrupcty,
+    UnfilledLong,
+    UnfilledShort,
 }
 
 impl Default for SignalCategory {
@@ -29,17 +31,19 @@ impl SignalCategory {
         match self {
             Self::GoShort => "short",
             Self::CloseShort => "short_close",
-            // Self::RevertShort => "short_revert",
+            Self::RevertShort => "short_revert",
             Self::GoLong => "long",
             Self::CloseLong => "long_close",
-            // Self::RevertLong => "long_revert",
+            Self::RevertLong => "long_revert",
             Self::KeepPosition => "position_keep",
             Self::ClosePosition => "position_close",
             // Self::RevertPosition => "position_revert",
             Self::StopLoss => "stop_loss",
-            // Self::TrailingStopLoss => "trailing_stop_loss",
+            Self::TrailingStopLoss => "trailing_stop_loss",
             Self::TakeProfit => "take_profit",
             Self::LeverageBankrupcty => "leverage_bankruptcy",
+            Self::UnfilledLong => "long_unfilled",
+            Self::UnfilledShort => "short_unfilled",
         }
     }
 }
@@ -49,14 +53,18 @@ impl From<SignalCategory> for Side {
         match value {
             SignalCategory::GoShort => Side::Sell,
             SignalCategory::CloseShort => Side::Buy,
+            SignalCategory::RevertShort => Side::Buy,
             SignalCategory::GoLong => Side::Buy,
             SignalCategory::CloseLong => Side::Sell,
+            SignalCategory::RevertLong => Side::Sell,
             SignalCategory::KeepPosition => Side::None,
             SignalCategory::ClosePosition => unreachable!(),
             SignalCategory::StopLoss => unreachable!(),
-            // SignalCategory::TrailingStopLoss => unreachable!(),
+            SignalCategory::TrailingStopLoss => unreachable!(),
             SignalCategory::TakeProfit => unreachable!(),
             SignalCategory::LeverageBankrupcty => unreachable!(),
+            SignalCategory::UnfilledLong => unreachable!(),
+            SignalCategory::UnfilledShort => unreachable!(),
         }
     }
 }
@@ -1,3 +1,4 @@
+use glow_error::GlowError;
 use polars::prelude::DataFrame;
 
 /// piping schema: https://app.clickup.com/9013233975/v/wb/8ckp29q-533
@@ -7,4 +8,6 @@ pub enum TradingDataUpdate {
     None,
     Initial(DataFrame),
     Market(DataFrame),
+    /// emitted downstream when a stage fails, so that subscribers waiting on the pipeline can stop
+    Failed(GlowError),
 }
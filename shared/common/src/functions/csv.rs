@@ -1,4 +1,10 @@
-use crate::{functions::get_days_between, structs::Symbol};
+use crate::{
+    functions::{
+        get_days_between,
+        parquet::{get_kline_store_dir, load_interval_kline_dataframe_from_dir},
+    },
+    structs::Symbol,
+};
 use chrono::{DateTime, NaiveDate, Utc};
 use glow_error::{assert_or_error, GlowError};
 use polars::prelude::*;
@@ -129,8 +135,14 @@ pub fn get_tick_data_csv_path(
     data_provider_exchange_name: &str,
     symbol_name: &str,
 ) -> PathBuf {
-    let mut path_buf = PathBuf::from("data/ticks");
-    path_buf.push(data_provider_exchange_name);
+    let mut dir = PathBuf::from("data/ticks");
+    dir.push(data_provider_exchange_name);
+    get_tick_data_csv_path_from_dir(&dir, date, symbol_name)
+}
+
+/// Same `{symbol}/{year}/{month}/{day}.csv` layout as `get_tick_data_csv_path`, rooted at `dir`
+pub fn get_tick_data_csv_path_from_dir(dir: &Path, date: NaiveDate, symbol_name: &str) -> PathBuf {
+    let mut path_buf = dir.to_path_buf();
     path_buf.push(symbol_name);
     path_buf.push(date.format("%Y").to_string());
     path_buf.push(date.format("%m").to_string());
@@ -138,7 +150,8 @@ pub fn get_tick_data_csv_path(
     path_buf
 }
 
-/// Tries to load ticks data between an interval. If a file is absent, push the NaiveDates of absent dates
+/// Tries to load ticks data between an interval from the parquet kline store, migrating days still cached as CSV.
+/// If a day is absent, push the NaiveDates of absent dates
 pub fn load_interval_tick_dataframe(
     start_datetime: DateTime<Utc>,
     end_datetime: DateTime<Utc>,
@@ -147,28 +160,8 @@ pub fn load_interval_tick_dataframe(
 ) -> Result<(Option<DataFrame>, Vec<NaiveDate>), GlowError> {
     assert_or_error!(start_datetime <= end_datetime);
     let days_between = get_days_between(start_datetime, end_datetime)?;
-    let mut dataframe = None;
-    let mut not_loaded_dates = vec![];
-    let symbol_tick_data_schema = symbol.derive_symbol_tick_data_schema();
-
-    for date in days_between {
-        let path = get_tick_data_csv_path(date, data_provider_exchange_name, &symbol.name);
-        match load_csv(path, &symbol_tick_data_schema) {
-            Ok(df) => {
-                if dataframe.is_none() {
-                    dataframe = Some(df);
-                } else {
-                    dataframe = Some(dataframe.unwrap().vstack(&df)?);
-                }
-            }
-            Err(error) => {
-                println!("LOAD CSV ERROR {:?}", error);
-                not_loaded_dates.push(date);
-            }
-        }
-    }
-
-    Ok((dataframe, not_loaded_dates))
+    let dir = get_kline_store_dir(data_provider_exchange_name);
+    load_interval_kline_dataframe_from_dir(&dir, &days_between, symbol)
 }
 
 pub fn load_csv<P: Into<PathBuf>>(path: P, schema: &Schema) -> Result<DataFrame, PolarsError> {
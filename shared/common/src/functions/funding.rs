@@ -0,0 +1,166 @@
+use super::parquet::{get_kline_store_dir, start_time_lit};
+use crate::structs::FundingRate;
+use chrono::{DateTime, Duration, Utc};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, File},
+    path::{Path, PathBuf},
+};
+
+/// Column holding the funding rate settled within each kline of the symbol, null when there is none
+pub fn get_funding_rate_col(symbol_name: &str) -> String {
+    format!("{}_funding_rate", symbol_name)
+}
+
+/// Symbol funding rates, stored next to its kline partitions at `{dir}/{symbol}/funding_rates.parquet`
+pub fn get_funding_rates_parquet_path_from_dir(dir: &Path, symbol_name: &str) -> PathBuf {
+    let mut path_buf = dir.to_path_buf();
+    path_buf.push(symbol_name);
+    path_buf.push("funding_rates.parquet");
+    path_buf
+}
+
+fn map_funding_rates_to_df(funding_rates: &[FundingRate]) -> Result<DataFrame, GlowError> {
+    let funding_times: Vec<i64> = funding_rates
+        .iter()
+        .map(|funding_rate| funding_rate.funding_time.timestamp_millis())
+        .collect();
+    let rates: Vec<f64> = funding_rates
+        .iter()
+        .map(|funding_rate| funding_rate.funding_rate)
+        .collect();
+    let df = DataFrame::new(vec![
+        Series::new("funding_time".into(), funding_times)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?
+            .into_column(),
+        Series::new("funding_rate".into(), rates).into_column(),
+    ])?;
+    Ok(df)
+}
+
+fn map_df_to_funding_rates(df: &DataFrame) -> Result<Vec<FundingRate>, GlowError> {
+    let funding_times = df.column("funding_time")?.datetime()?.clone();
+    let rates = df.column("funding_rate")?.f64()?.clone();
+    let funding_rates = funding_times
+        .into_no_null_iter()
+        .zip(rates.into_no_null_iter())
+        .filter_map(|(funding_time, rate)| {
+            DateTime::from_timestamp_millis(funding_time)
+                .map(|funding_time| FundingRate::new(funding_time, rate))
+        })
+        .collect();
+    Ok(funding_rates)
+}
+
+/// Loads the stored symbol funding rates within `[start_datetime, end_datetime]`, sorted by funding time
+pub fn load_funding_rates_from_dir(
+    dir: &Path,
+    symbol_name: &str,
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+) -> Result<Vec<FundingRate>, GlowError> {
+    let path = get_funding_rates_parquet_path_from_dir(dir, symbol_name);
+    if !path.is_file() {
+        return Ok(vec![]);
+    }
+    let df = LazyFrame::scan_parquet(&path, ScanArgsParquet::default())?
+        .filter(
+            col("funding_time")
+                .gt_eq(start_time_lit(start_datetime))
+                .and(col("funding_time").lt_eq(start_time_lit(end_datetime))),
+        )
+        .sort(["funding_time"], SortMultipleOptions::default())
+        .collect()?;
+    map_df_to_funding_rates(&df)
+}
+
+/// Merges funding rates into the symbol store, overriding the ones stored with the same funding time
+pub fn save_funding_rates_to_dir(
+    dir: &Path,
+    symbol_name: &str,
+    funding_rates: &[FundingRate],
+) -> Result<(), GlowError> {
+    if funding_rates.is_empty() {
+        return Ok(());
+    }
+    let path = get_funding_rates_parquet_path_from_dir(dir, symbol_name);
+    let funding_rates_lf = map_funding_rates_to_df(funding_rates)?.lazy();
+    let funding_rates_lf = if path.is_file() {
+        let stored_lf = LazyFrame::scan_parquet(&path, ScanArgsParquet::default())?;
+        concat([stored_lf, funding_rates_lf], UnionArgs::default())?
+    } else {
+        create_dir_all(path.parent().unwrap())?;
+        funding_rates_lf
+    };
+
+    let mut funding_rates_df = funding_rates_lf
+        .unique_stable(Some(vec!["funding_time".into()]), UniqueKeepStrategy::Last)
+        .sort(["funding_time"], SortMultipleOptions::default())
+        .collect()?;
+
+    ParquetWriter::new(File::create(&path)?).finish(&mut funding_rates_df)?;
+    Ok(())
+}
+
+pub fn load_funding_rates(
+    data_provider_exchange_name: &str,
+    symbol_name: &str,
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+) -> Result<Vec<FundingRate>, GlowError> {
+    let dir = get_kline_store_dir(data_provider_exchange_name);
+    load_funding_rates_from_dir(&dir, symbol_name, start_datetime, end_datetime)
+}
+
+pub fn save_funding_rates(
+    data_provider_exchange_name: &str,
+    symbol_name: &str,
+    funding_rates: &[FundingRate],
+) -> Result<(), GlowError> {
+    let dir = get_kline_store_dir(data_provider_exchange_name);
+    save_funding_rates_to_dir(&dir, symbol_name, funding_rates)
+}
+
+/// Sets the symbol funding rate column, assigning each funding rate to the kline its funding time falls within.
+/// The column keeps its position if the dataframe already has it (i.e. it was coerced to the trading data schema).
+pub fn append_funding_rates_to_df(
+    df: DataFrame,
+    symbol_name: &str,
+    funding_rates: &[FundingRate],
+    kline_duration: Duration,
+) -> Result<DataFrame, GlowError> {
+    let start_times: Vec<i64> = df
+        .column("start_time")?
+        .datetime()?
+        .into_no_null_iter()
+        .collect();
+    let kline_duration_ms = kline_duration.num_milliseconds();
+
+    let mut kline_funding_rates: BTreeMap<usize, f64> = BTreeMap::new();
+    for funding_rate in funding_rates {
+        let funding_timestamp = funding_rate.funding_time.timestamp_millis();
+        // start_times are sorted, so the kline is the last one starting before the funding time
+        let kline_index =
+            start_times.partition_point(|start_time| *start_time <= funding_timestamp);
+        if kline_index == 0 {
+            continue;
+        }
+        let kline_index = kline_index - 1;
+        if funding_timestamp >= start_times[kline_index] + kline_duration_ms {
+            continue;
+        }
+        *kline_funding_rates.entry(kline_index).or_insert(0.0) += funding_rate.funding_rate;
+    }
+
+    let funding_rate_values: Vec<Option<f64>> = (0..start_times.len())
+        .map(|index| kline_funding_rates.get(&index).copied())
+        .collect();
+    let mut df = df;
+    df.with_column(Series::new(
+        get_funding_rate_col(symbol_name).into(),
+        funding_rate_values,
+    ))?;
+    Ok(df)
+}
@@ -0,0 +1,85 @@
+use crate::{enums::granularity::Granularity, structs::Symbol};
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// (start time, high, low) of each 1 minute kline within a coarser kline
+pub type IntrabarPath = Vec<(i64, f64, f64)>;
+
+/// Columns holding the 1 minute (start times, highs, lows) within each kline of the symbol
+pub fn get_intrabar_cols(symbol_name: &str) -> (String, String, String) {
+    (
+        format!("{}_intrabar_start_times", symbol_name),
+        format!("{}_intrabar_highs", symbol_name),
+        format!("{}_intrabar_lows", symbol_name),
+    )
+}
+
+pub fn get_intrabar_fields(symbol_name: &str) -> Vec<Field> {
+    let (start_times_col, highs_col, lows_col) = get_intrabar_cols(symbol_name);
+    vec![
+        Field::new(
+            start_times_col.into(),
+            DataType::List(Box::new(DataType::Datetime(TimeUnit::Milliseconds, None))),
+        ),
+        Field::new(
+            highs_col.into(),
+            DataType::List(Box::new(DataType::Float64)),
+        ),
+        Field::new(lows_col.into(), DataType::List(Box::new(DataType::Float64))),
+    ]
+}
+
+/// Klines from 1 hour on are coarse enough for both price thresholds to be touched within them,
+/// so their 1 minute path is kept to resolve which one was hit first
+pub fn is_intrabar_granularity(granularity: &Granularity) -> bool {
+    granularity.get_granularity_in_secs() >= Granularity::h1.get_granularity_in_secs()
+}
+
+/// Aggregations collecting the 1 minute path of the symbol, when downsampling 1 minute klines.
+/// Rows of other symbols are skipped, as their prices are null.
+pub fn get_intrabar_agg_expressions(symbol: &Symbol) -> Vec<Expr> {
+    let (_, high_col, low_col, _) = symbol.get_ohlc_cols();
+    let (start_times_col, highs_col, lows_col) = get_intrabar_cols(symbol.name);
+    vec![
+        col("start_time")
+            .filter(col(high_col).is_not_null())
+            .alias(start_times_col),
+        col(high_col).drop_nulls().alias(highs_col),
+        col(low_col).drop_nulls().alias(lows_col),
+    ]
+}
+
+/// Returns the 1 minute path within each kline of the symbol, or None if the dataframe doesn't hold it
+pub fn get_intrabar_paths(
+    df: &DataFrame,
+    symbol_name: &str,
+) -> Result<Option<Vec<IntrabarPath>>, GlowError> {
+    let (start_times_col, highs_col, lows_col) = get_intrabar_cols(symbol_name);
+    let (Ok(start_times), Ok(highs), Ok(lows)) = (
+        df.column(&start_times_col),
+        df.column(&highs_col),
+        df.column(&lows_col),
+    ) else {
+        return Ok(None);
+    };
+    let mut paths = Vec::with_capacity(df.height());
+    for ((start_times, highs), lows) in start_times
+        .list()?
+        .into_iter()
+        .zip(highs.list()?)
+        .zip(lows.list()?)
+    {
+        let path = match (start_times, highs, lows) {
+            (Some(start_times), Some(highs), Some(lows)) => start_times
+                .datetime()?
+                .into_no_null_iter()
+                .zip(highs.f64()?.into_no_null_iter())
+                .zip(lows.f64()?.into_no_null_iter())
+                .map(|((start_time, high), low)| (start_time, high, low))
+                .collect(),
+            _ => vec![],
+        };
+        paths.push(path);
+    }
+    Ok(Some(paths))
+}
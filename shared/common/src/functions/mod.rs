@@ -13,11 +13,15 @@ use std::{
 };
 
 pub mod csv;
+pub mod funding;
+pub mod intrabar;
+pub mod parquet;
 pub mod performance;
 
 use crate::{
     constants::SECONDS_IN_MIN,
     enums::signal_category::SignalCategory,
+    functions::intrabar::{get_intrabar_agg_expressions, get_intrabar_cols},
     structs::{Symbol, TickData},
 };
 
@@ -288,10 +292,10 @@ pub fn downsample_tick_lf_to_kline_duration(
     for symbol in unique_symbols {
         let (o, h, l, c) = symbol.get_ohlc_cols();
         if schema_to_comply.is_some() {
-            tick_data_cols.insert(o);
-            tick_data_cols.insert(h);
-            tick_data_cols.insert(l);
-            tick_data_cols.insert(c);
+            tick_data_cols.insert(o.to_string());
+            tick_data_cols.insert(h.to_string());
+            tick_data_cols.insert(l.to_string());
+            tick_data_cols.insert(c.to_string());
         }
         // TODO: check if we can use keep_name() instead of alias
         let first_open = col(o).drop_nulls().first().alias(o);
@@ -302,6 +306,15 @@ pub fn downsample_tick_lf_to_kline_duration(
         agg_expressions.push(max_high);
         agg_expressions.push(min_low);
         agg_expressions.push(last_close);
+        if let Some(schema) = schema_to_comply {
+            let (start_times_col, highs_col, lows_col) = get_intrabar_cols(symbol.name);
+            if schema.contains(&highs_col) {
+                tick_data_cols.insert(start_times_col);
+                tick_data_cols.insert(highs_col);
+                tick_data_cols.insert(lows_col);
+                agg_expressions.extend(get_intrabar_agg_expressions(symbol));
+            }
+        }
     }
 
     if let Some(schema) = schema_to_comply {
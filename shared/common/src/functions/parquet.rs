@@ -0,0 +1,238 @@
+use super::csv::{get_tick_data_csv_path_from_dir, load_csv};
+use crate::structs::Symbol;
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::{
+    collections::BTreeSet,
+    fs::{create_dir_all, read_dir, File},
+    path::{Path, PathBuf},
+};
+
+/// Root of an exchange kline store, shared with the legacy daily CSV cache
+pub fn get_kline_store_dir(data_provider_exchange_name: &str) -> PathBuf {
+    let mut dir = PathBuf::from("data/ticks");
+    dir.push(data_provider_exchange_name);
+    dir
+}
+
+/// Month partition of a symbol klines, at `{dir}/{symbol}/{year}/{month}.parquet`
+pub fn get_kline_parquet_path_from_dir(dir: &Path, symbol_name: &str, month: NaiveDate) -> PathBuf {
+    let mut path_buf = dir.to_path_buf();
+    path_buf.push(symbol_name);
+    path_buf.push(month.format("%Y").to_string());
+    path_buf.push(format!("{}.parquet", month.format("%m")));
+    path_buf
+}
+
+fn get_month_start(date: NaiveDate) -> NaiveDate {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap()
+}
+
+fn get_months_between(start_date: NaiveDate, end_date: NaiveDate) -> Vec<NaiveDate> {
+    let mut months = vec![];
+    let mut month = get_month_start(start_date);
+    while month <= end_date {
+        months.push(month);
+        month = month + Months::new(1);
+    }
+    months
+}
+
+pub(crate) fn start_time_lit(datetime: DateTime<Utc>) -> Expr {
+    lit(datetime.timestamp_millis()).cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+}
+
+/// Lazily scans a symbol month partitions within `[start_datetime, end_datetime]`,
+/// pushing the `start_time` predicate down to the parquet reader.
+/// Returns `None` if no partition exists for the interval.
+pub fn scan_kline_parquet_from_dir(
+    dir: &Path,
+    symbol_name: &str,
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+) -> Result<Option<LazyFrame>, GlowError> {
+    let paths: Vec<PathBuf> =
+        get_months_between(start_datetime.date_naive(), end_datetime.date_naive())
+            .into_iter()
+            .map(|month| get_kline_parquet_path_from_dir(dir, symbol_name, month))
+            .filter(|path| path.is_file())
+            .collect();
+
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    let lf = LazyFrame::scan_parquet_files(paths.into(), ScanArgsParquet::default())?.filter(
+        col("start_time")
+            .gt_eq(start_time_lit(start_datetime))
+            .and(col("start_time").lt_eq(start_time_lit(end_datetime))),
+    );
+    Ok(Some(lf))
+}
+
+/// Merges klines into their month partitions, overriding already stored klines with the same `start_time`
+pub fn save_kline_df_to_parquet_dir(
+    dir: &Path,
+    df: &DataFrame,
+    symbol_name: &str,
+) -> Result<(), GlowError> {
+    // ticks dataframes don't have a stable column order, while partitions must share the same schema
+    let df = df.select([
+        "start_time".to_string(),
+        format!("{}_open", symbol_name),
+        format!("{}_high", symbol_name),
+        format!("{}_low", symbol_name),
+        format!("{}_close", symbol_name),
+    ])?;
+    let months: BTreeSet<NaiveDate> = df
+        .column("start_time")?
+        .datetime()?
+        .into_no_null_iter()
+        .filter_map(DateTime::from_timestamp_millis)
+        .map(|datetime| get_month_start(datetime.date_naive()))
+        .collect();
+
+    for month in months {
+        let month_start = month.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let next_month_start = month_start + Months::new(1);
+        let month_lf = df.clone().lazy().filter(
+            col("start_time")
+                .gt_eq(start_time_lit(month_start))
+                .and(col("start_time").lt(start_time_lit(next_month_start))),
+        );
+
+        let path = get_kline_parquet_path_from_dir(dir, symbol_name, month);
+        let month_lf = if path.is_file() {
+            let stored_lf = LazyFrame::scan_parquet(&path, ScanArgsParquet::default())?;
+            concat([stored_lf, month_lf], UnionArgs::default())?
+        } else {
+            create_dir_all(path.parent().unwrap())?;
+            month_lf
+        };
+
+        let mut month_df = month_lf
+            .unique_stable(Some(vec!["start_time".into()]), UniqueKeepStrategy::Last)
+            .sort(["start_time"], SortMultipleOptions::default())
+            .collect()?;
+
+        ParquetWriter::new(File::create(&path)?).finish(&mut month_df)?;
+    }
+
+    Ok(())
+}
+
+pub fn save_kline_df_to_parquet(
+    df: &DataFrame,
+    data_provider_exchange_name: &str,
+    symbol_name: &str,
+) -> Result<(), GlowError> {
+    let dir = get_kline_store_dir(data_provider_exchange_name);
+    save_kline_df_to_parquet_dir(&dir, df, symbol_name)
+}
+
+/// Copies every daily CSV cached for the symbol into the parquet store, returning the amount of migrated days.
+/// CSV files are kept, so the migration can be safely re-run.
+pub fn migrate_csv_cache_to_parquet(
+    data_provider_exchange_name: &str,
+    symbol: &Symbol,
+) -> Result<usize, GlowError> {
+    let dir = get_kline_store_dir(data_provider_exchange_name);
+    let symbol_tick_data_schema = symbol.derive_symbol_tick_data_schema();
+    let mut csv_paths = vec![];
+    let symbol_dir = dir.join(symbol.name);
+    if !symbol_dir.is_dir() {
+        return Ok(0);
+    }
+    for year_entry in read_dir(symbol_dir)? {
+        let year_path = year_entry?.path();
+        if !year_path.is_dir() {
+            continue;
+        }
+        for month_entry in read_dir(year_path)? {
+            let month_path = month_entry?.path();
+            if !month_path.is_dir() {
+                continue;
+            }
+            for day_entry in read_dir(month_path)? {
+                let day_path = day_entry?.path();
+                if day_path
+                    .extension()
+                    .is_some_and(|extension| extension == "csv")
+                {
+                    csv_paths.push(day_path);
+                }
+            }
+        }
+    }
+
+    let mut migrated_df = DataFrame::empty_with_schema(&symbol_tick_data_schema);
+    for path in &csv_paths {
+        migrated_df.vstack_mut(&load_csv(path, &symbol_tick_data_schema)?)?;
+    }
+    if migrated_df.height() > 0 {
+        migrated_df.align_chunks();
+        save_kline_df_to_parquet_dir(&dir, &migrated_df, symbol.name)?;
+    }
+
+    Ok(csv_paths.len())
+}
+
+/// Loads full days of symbol klines between `start_datetime` and `end_datetime` from the parquet store.
+/// Days absent from the store are migrated from the daily CSV cache if present, otherwise returned as not loaded.
+pub fn load_interval_kline_dataframe_from_dir(
+    dir: &Path,
+    days_between: &[NaiveDate],
+    symbol: &Symbol,
+) -> Result<(Option<DataFrame>, Vec<NaiveDate>), GlowError> {
+    let (Some(first_day), Some(last_day)) = (days_between.first(), days_between.last()) else {
+        return Ok((None, vec![]));
+    };
+    let symbol_tick_data_schema = symbol.derive_symbol_tick_data_schema();
+    let interval_start = first_day.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let interval_end = last_day
+        .and_hms_milli_opt(23, 59, 59, 999)
+        .unwrap()
+        .and_utc();
+
+    let stored_df =
+        match scan_kline_parquet_from_dir(dir, symbol.name, interval_start, interval_end)? {
+            Some(stored_lf) => stored_lf.collect()?,
+            None => DataFrame::empty_with_schema(&symbol_tick_data_schema),
+        };
+    let stored_dates: BTreeSet<NaiveDate> = stored_df
+        .column("start_time")?
+        .datetime()?
+        .into_no_null_iter()
+        .filter_map(DateTime::from_timestamp_millis)
+        .map(|datetime| datetime.date_naive())
+        .collect();
+
+    let mut migrated_df = DataFrame::empty_with_schema(&symbol_tick_data_schema);
+    let mut not_loaded_dates = vec![];
+    for date in days_between {
+        if stored_dates.contains(date) {
+            continue;
+        }
+        let csv_path = get_tick_data_csv_path_from_dir(dir, *date, symbol.name);
+        match load_csv(csv_path, &symbol_tick_data_schema) {
+            Ok(day_df) => migrated_df.vstack_mut(&day_df)?,
+            Err(_) => {
+                not_loaded_dates.push(*date);
+                continue;
+            }
+        };
+    }
+
+    if migrated_df.height() > 0 {
+        migrated_df.align_chunks();
+        save_kline_df_to_parquet_dir(dir, &migrated_df, symbol.name)?;
+    }
+
+    let loaded_df = stored_df.vstack(&migrated_df)?;
+    if loaded_df.height() == 0 {
+        return Ok((None, not_loaded_dates));
+    }
+    let loaded_df = loaded_df.sort(["start_time"], SortMultipleOptions::default())?;
+    Ok((Some(loaded_df), not_loaded_dates))
+}
@@ -1,4 +1,5 @@
 use super::round_down_nth_decimal;
+use crate::enums::granularity::Granularity;
 use chrono::Duration;
 use glow_error::GlowError;
 use polars::prelude::*;
@@ -34,7 +35,9 @@ pub fn calculate_risk_adjusted_returns(
     Ok(risk_adjusted_returns)
 }
 
-pub fn calculate_sharpe_ratio(
+/// Sum of each trade returns over its risk, offset by the risk free returns.
+/// Unlike `calculate_sharpe_ratio`, it grows with the number of trades and isn't annualized.
+pub fn calculate_per_trade_sharpe_ratio(
     returns_series: &Column,
     risk_series: &Column,
     risk_free_returns: f64,
@@ -52,7 +55,9 @@ pub fn calculate_sharpe_ratio(
     Ok(risk_adjusted_returns)
 }
 
-pub fn calculate_sortino_ratio(
+/// Sum of each trade returns over its downside risk, offset by the risk free returns.
+/// Unlike `calculate_sortino_ratio`, it grows with the number of trades and isn't annualized.
+pub fn calculate_per_trade_sortino_ratio(
     returns_series: &Column,
     downside_risk_series: &Column,
     risk_free_returns: f64,
@@ -70,6 +75,119 @@ pub fn calculate_sortino_ratio(
     Ok(risk_adjusted_returns)
 }
 
+/// Returns of the balance over consecutive periods of the sampling granularity, aligned to its boundaries
+/// (e.g. midnight UTC for daily periods). Each session balance counts from the session end on.
+/// A bust counts as a -100% period and ends the sampling, as nothing is left to trade afterwards.
+pub fn calculate_period_returns(
+    start_series: &Column,
+    end_series: &Column,
+    balance_series: &Column,
+    sampling_granularity: Granularity,
+) -> Result<Vec<f64>, GlowError> {
+    let period_start = start_series.datetime()?.min();
+    let period_end = end_series.datetime()?.max();
+    let initial_balance = balance_series.f64()?.get(0);
+    let (Some(period_start), Some(period_end), Some(initial_balance)) =
+        (period_start, period_end, initial_balance)
+    else {
+        return Ok(vec![]);
+    };
+    if initial_balance <= 0.0 {
+        return Ok(vec![]);
+    }
+    let period_in_ms = sampling_granularity
+        .get_chrono_duration()
+        .num_milliseconds();
+
+    let ends_ca = end_series.datetime()?;
+    let balances_ca = balance_series.f64()?;
+    let mut sessions = ends_ca
+        .into_no_null_iter()
+        .zip(balances_ca.into_no_null_iter())
+        .peekable();
+    let mut balance = initial_balance;
+    let mut sampled_balances = vec![];
+    let mut boundary = period_start.div_euclid(period_in_ms) * period_in_ms;
+    loop {
+        while let Some((_, session_balance)) = sessions.next_if(|(end, _)| *end <= boundary) {
+            balance = session_balance;
+        }
+        sampled_balances.push(balance);
+        if balance <= 0.0 || boundary >= period_end {
+            break;
+        }
+        boundary += period_in_ms;
+    }
+
+    let period_returns = sampled_balances
+        .windows(2)
+        .map(|balances| f64::max(-1.0, balances[1] / balances[0] - 1.0))
+        .collect();
+    Ok(period_returns)
+}
+
+/// Sampling periods in a year, as crypto markets trade every day
+pub fn get_periods_per_year(sampling_granularity: Granularity) -> f64 {
+    Duration::days(365).num_seconds() as f64 / sampling_granularity.get_granularity_in_secs() as f64
+}
+
+/// Mean excess return over the standard deviation of the period returns, annualized.
+/// `risk_free_returns` is the risk free rate of a single sampling period.
+pub fn calculate_sharpe_ratio(
+    period_returns: &[f64],
+    risk_free_returns: f64,
+    sampling_granularity: Granularity,
+) -> f64 {
+    let len = period_returns.len() as f64;
+    if len < 2.0 {
+        return 0.0;
+    }
+    let mean_returns = period_returns.iter().sum::<f64>() / len;
+    let variance = period_returns
+        .iter()
+        .map(|returns| (returns - mean_returns).powi(2))
+        .sum::<f64>()
+        / (len - 1.0);
+    let std = variance.sqrt();
+    if std < f64::EPSILON {
+        return 0.0;
+    }
+    (mean_returns - risk_free_returns) / std * get_periods_per_year(sampling_granularity).sqrt()
+}
+
+/// Mean excess return over the downside deviation of the period returns below the risk free rate, annualized.
+/// `risk_free_returns` is the risk free rate of a single sampling period.
+/// Positive excess returns without any downside give an infinite ratio.
+pub fn calculate_sortino_ratio(
+    period_returns: &[f64],
+    risk_free_returns: f64,
+    sampling_granularity: Granularity,
+) -> f64 {
+    let len = period_returns.len() as f64;
+    if len < 2.0 {
+        return 0.0;
+    }
+    let mean_excess_returns = period_returns
+        .iter()
+        .map(|returns| returns - risk_free_returns)
+        .sum::<f64>()
+        / len;
+    let downside_variance = period_returns
+        .iter()
+        .map(|returns| f64::min(0.0, returns - risk_free_returns).powi(2))
+        .sum::<f64>()
+        / len;
+    let downside_deviation = downside_variance.sqrt();
+    if downside_deviation < f64::EPSILON {
+        return if mean_excess_returns > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        };
+    }
+    mean_excess_returns / downside_deviation * get_periods_per_year(sampling_granularity).sqrt()
+}
+
 pub fn calculate_calmar_ratio(
     balance_series: &Column,
     max_drawdown: f64,
@@ -116,3 +234,155 @@ pub fn calculate_max_drawdown_and_duration(
         Ok((0.0, Duration::minutes(0)))
     }
 }
+
+/// Gross profit over gross loss of the trades, infinite when there were only winning trades
+pub fn calculate_profit_factor(profit_and_loss_series: &Column) -> Result<f64, GlowError> {
+    let (gross_profit, gross_loss) = profit_and_loss_series.f64()?.into_no_null_iter().fold(
+        (0.0, 0.0),
+        |(gross_profit, gross_loss), pnl| {
+            if pnl > 0.0 {
+                (gross_profit + pnl, gross_loss)
+            } else {
+                (gross_profit, gross_loss - pnl)
+            }
+        },
+    );
+    let profit_factor = if gross_loss != 0.0 {
+        gross_profit / gross_loss
+    } else if gross_profit != 0.0 {
+        f64::INFINITY
+    } else {
+        0.0
+    };
+    Ok(profit_factor)
+}
+
+/// Average profit of the winning trades and average loss (as a negative value) of the losing ones
+pub fn calculate_average_win_and_loss(
+    profit_and_loss_series: &Column,
+) -> Result<(f64, f64), GlowError> {
+    let pnl_ca = profit_and_loss_series.f64()?;
+    let mean = |pnls: Vec<f64>| {
+        if pnls.is_empty() {
+            0.0
+        } else {
+            pnls.iter().sum::<f64>() / pnls.len() as f64
+        }
+    };
+    let wins = pnl_ca
+        .into_no_null_iter()
+        .filter(|&pnl| pnl > 0.0)
+        .collect();
+    let losses = pnl_ca
+        .into_no_null_iter()
+        .filter(|&pnl| pnl < 0.0)
+        .collect();
+    Ok((mean(wins), mean(losses)))
+}
+
+/// Longest streaks of winning and losing trades, in that order
+pub fn calculate_max_consecutive_wins_and_losses(
+    profit_and_loss_series: &Column,
+) -> Result<(u32, u32), GlowError> {
+    let mut streaks = (0, 0);
+    let mut max_streaks = (0, 0);
+    for pnl in profit_and_loss_series.f64()?.into_no_null_iter() {
+        streaks = if pnl > 0.0 {
+            (streaks.0 + 1, 0)
+        } else if pnl < 0.0 {
+            (0, streaks.1 + 1)
+        } else {
+            (0, 0)
+        };
+        max_streaks = (max_streaks.0.max(streaks.0), max_streaks.1.max(streaks.1));
+    }
+    Ok(max_streaks)
+}
+
+/// Share of the period with at least one open trade, alongside the average trade duration.
+/// Overlapping trades (i.e. of different portfolio symbols) count once towards the exposure.
+pub fn calculate_exposure_and_average_duration(
+    start_series: &Column,
+    end_series: &Column,
+    period: Duration,
+) -> Result<(f64, Duration), GlowError> {
+    let mut trades = start_series
+        .datetime()?
+        .into_no_null_iter()
+        .zip(end_series.datetime()?.into_no_null_iter())
+        .collect::<Vec<(i64, i64)>>();
+    if trades.is_empty() {
+        return Ok((0.0, Duration::minutes(0)));
+    }
+    let total_duration: i64 = trades.iter().map(|(start, end)| end - start).sum();
+    let average_duration = Duration::milliseconds(total_duration / trades.len() as i64);
+
+    trades.sort_unstable();
+    let mut exposed_duration = 0;
+    let mut exposed_until = i64::MIN;
+    for (start, end) in trades {
+        let start = start.max(exposed_until);
+        if end > start {
+            exposed_duration += end - start;
+            exposed_until = end;
+        }
+    }
+    let period = period.num_milliseconds();
+    let exposure = if period > 0 {
+        f64::min(1.0, exposed_duration as f64 / period as f64)
+    } else {
+        0.0
+    };
+    Ok((exposure, average_duration))
+}
+
+/// Notional value traded when opening and closing each trade, at the start and end prices of its session
+pub fn calculate_turnover(
+    units_series: &Column,
+    start_price_series: &Column,
+    end_price_series: &Column,
+) -> Result<f64, GlowError> {
+    let turnover = units_series
+        .f64()?
+        .into_no_null_iter()
+        .zip(start_price_series.f64()?.into_no_null_iter())
+        .zip(end_price_series.f64()?.into_no_null_iter())
+        .map(|((units, start_price), end_price)| units * (start_price + end_price))
+        .sum();
+    Ok(turnover)
+}
+
+/// Compound annual growth rate of the balance over the period
+pub fn calculate_cagr(initial_balance: f64, final_balance: f64, period: Duration) -> f64 {
+    let years = period.num_milliseconds() as f64 / Duration::days(365).num_milliseconds() as f64;
+    if initial_balance <= 0.0 || years <= 0.0 {
+        return 0.0;
+    }
+    (final_balance / initial_balance).powf(1.0 / years) - 1.0
+}
+
+/// Root mean square of the drawdowns, which weighs both their depth and how long they last
+pub fn calculate_ulcer_index(drawdown_series: &Column) -> Result<f64, GlowError> {
+    let drawdown_ca = drawdown_series.f64()?;
+    let len = drawdown_ca.into_no_null_iter().len();
+    if len == 0 {
+        return Ok(0.0);
+    }
+    let squared_drawdowns_sum: f64 = drawdown_ca
+        .into_no_null_iter()
+        .map(|drawdown| drawdown.powi(2))
+        .sum();
+    Ok((squared_drawdowns_sum / len as f64).sqrt())
+}
+
+/// Net return over the max drawdown, i.e. how many times the worst drawdown was earned back
+pub fn calculate_recovery_factor(
+    initial_balance: f64,
+    final_balance: f64,
+    max_drawdown: f64,
+) -> f64 {
+    if initial_balance <= 0.0 || max_drawdown == 0.0 {
+        return 0.0;
+    }
+    (final_balance / initial_balance - 1.0) / max_drawdown
+}
@@ -1,16 +1,18 @@
 use std::sync::Arc;
-use tokio::sync::watch::{channel, Receiver, Ref, Sender};
-use tokio_stream::wrappers::WatchStream;
+use tokio::sync::watch::{channel, Receiver, Sender};
+use tokio_stream::{wrappers::WatchStream, Stream, StreamExt};
 
+/// Subject emitting its latest value to subscribers. Values are versioned by the number of emissions,
+/// so that subscribers can tell whether they're done with the latest one.
 #[derive(Clone)]
 pub struct BehaviorSubject<T> {
-    sender: Arc<Sender<T>>,
-    receiver: Receiver<T>,
+    sender: Arc<Sender<(u64, T)>>,
+    receiver: Receiver<(u64, T)>,
 }
 
 impl<T: 'static + Clone + Send + Sync> BehaviorSubject<T> {
     pub fn new(value: T) -> Self {
-        let (sender, receiver) = channel(value);
+        let (sender, receiver) = channel((0, value));
         Self {
             sender: Arc::new(sender),
             receiver,
@@ -18,18 +20,27 @@ impl<T: 'static + Clone + Send + Sync> BehaviorSubject<T> {
     }
 
     pub fn value(&self) -> T {
-        self.receiver.borrow().clone()
+        self.receiver.borrow().1.clone()
     }
 
-    pub fn ref_value(&self) -> Ref<'_, T> {
-        self.receiver.borrow()
+    /// Version of the latest value, 0 being the initial one
+    pub fn version(&self) -> u64 {
+        self.receiver.borrow().0
     }
 
     pub fn next(&self, value: T) {
-        _ = self.sender.send(value)
+        self.sender.send_modify(|(version, latest_value)| {
+            *version += 1;
+            *latest_value = value;
+        });
     }
 
-    pub fn subscribe(&self) -> WatchStream<T> {
+    pub fn subscribe(&self) -> impl Stream<Item = T> + Send + Unpin {
+        self.subscribe_versioned().map(|(_, value)| value)
+    }
+
+    /// Subscribes to the values along with their versions
+    pub fn subscribe_versioned(&self) -> impl Stream<Item = (u64, T)> + Send + Unpin {
         let rx = self.sender.subscribe();
         WatchStream::new(rx)
     }
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Timelike, Utc};
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc,
+};
+
+/// Time the trader and its exchange run at, which is the system time unless the clock is simulated.
+///
+/// Clones of a simulated clock share its time, so that replays can move it for every component they drive.
+#[derive(Clone, Debug, Default)]
+pub struct Clock {
+    simulated_timestamp_ms: Option<Arc<AtomicI64>>,
+}
+
+impl Clock {
+    pub fn simulated(timestamp_ms: i64) -> Self {
+        Self {
+            simulated_timestamp_ms: Some(Arc::new(AtomicI64::new(timestamp_ms))),
+        }
+    }
+
+    /// Moves a simulated clock to `timestamp_ms`, system clocks keep following the system time
+    pub fn set_timestamp_ms(&self, timestamp_ms: i64) {
+        if let Some(simulated_timestamp_ms) = &self.simulated_timestamp_ms {
+            simulated_timestamp_ms.store(timestamp_ms, Ordering::SeqCst);
+        }
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        match &self.simulated_timestamp_ms {
+            Some(simulated_timestamp_ms) => {
+                DateTime::from_timestamp_millis(simulated_timestamp_ms.load(Ordering::SeqCst))
+                    .expect("simulated timestamp to be in range")
+            }
+            None => Utc::now(),
+        }
+    }
+
+    /// gives result with full seconds
+    pub fn current_datetime(&self) -> DateTime<Utc> {
+        self.now().with_nanosecond(0).unwrap()
+    }
+
+    // gives current timestamp in milliseconds
+    pub fn current_timestamp_ms(&self) -> i64 {
+        self.current_datetime().timestamp_millis()
+    }
+}
@@ -1,4 +1,4 @@
-use super::Symbol;
+use super::{RiskLimit, Symbol};
 use chrono::{DateTime, Duration, NaiveTime, Utc};
 
 #[derive(Clone, Debug)]
@@ -6,10 +6,12 @@ pub struct Contract {
     pub available_since: DateTime<Utc>,
     _funding_interval: Duration,
     pub funding_rate: f64,
+    pub liquidation_fee_rate: f64, // charged over the position value when it gets liquidated
     pub max_leverage: f64,
     pub maximum_order_sizes: (f64, f64), // (market, limit) in units
     pub minimum_order_size: f64,         // in units
     pub next_funding: Option<NaiveTime>,
+    pub risk_limits: &'static [RiskLimit], // sorted by max position value
     pub symbol: &'static Symbol,
     pub tick_size: f64, // in USDT
 }
@@ -19,10 +21,12 @@ impl Contract {
         available_since: DateTime<Utc>,
         funding_interval: Duration,
         funding_rate: f64,
+        liquidation_fee_rate: f64,
         max_leverage: f64,
         maximum_order_sizes: (f64, f64),
         minimum_order_size: f64,
         next_funding: Option<NaiveTime>,
+        risk_limits: &'static [RiskLimit],
         symbol: &'static Symbol,
         tick_size: f64,
     ) -> Self {
@@ -30,10 +34,12 @@ impl Contract {
             available_since,
             _funding_interval: funding_interval,
             funding_rate,
+            liquidation_fee_rate,
             max_leverage,
             maximum_order_sizes,
             minimum_order_size,
             next_funding,
+            risk_limits,
             symbol,
             tick_size,
         }
@@ -44,6 +50,11 @@ impl Contract {
         // self.next_funding = Some(date_time);
     }
 
+    /// Returns the (maintenance margin rate, maintenance amount) for the given position value
+    pub fn get_maintenance_margin(&self, position_value: f64) -> (f64, f64) {
+        RiskLimit::get_maintenance_margin(self.risk_limits, position_value)
+    }
+
     pub fn tick_data_decimal_places(&self) -> usize {
         self.tick_size.to_string().split('.').last().unwrap().len()
     }
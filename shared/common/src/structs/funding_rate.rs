@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+
+/// Funding rate settled for a perpetual contract at `funding_time`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundingRate {
+    pub funding_time: DateTime<Utc>,
+    pub funding_rate: f64,
+}
+
+impl FundingRate {
+    pub fn new(funding_time: DateTime<Utc>, funding_rate: f64) -> Self {
+        Self {
+            funding_time,
+            funding_rate,
+        }
+    }
+}
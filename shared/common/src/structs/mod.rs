@@ -1,15 +1,24 @@
 mod behavior_subject;
 pub use behavior_subject::*;
 
+mod clock;
+pub use clock::*;
+
 mod contract;
 pub use contract::*;
 
 mod execution;
 pub use execution::*;
 
+mod funding_rate;
+pub use funding_rate::*;
+
 mod order;
 pub use order::*;
 
+mod risk_limit;
+pub use risk_limit::*;
+
 mod tick_data;
 pub use tick_data::*;
 
@@ -0,0 +1,36 @@
+/// Maintenance margin tier, applied to positions valued up to `max_position_value`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RiskLimit {
+    pub maintenance_margin_rate: f64,
+    pub max_position_value: f64, // in USDT
+}
+
+impl RiskLimit {
+    pub const fn new(maintenance_margin_rate: f64, max_position_value: f64) -> Self {
+        RiskLimit {
+            maintenance_margin_rate,
+            max_position_value,
+        }
+    }
+
+    /// Returns the (maintenance margin rate, maintenance amount) of the tier the position value falls in,
+    /// the maintenance amount deducting what lower tiers already cover at lower rates
+    pub fn get_maintenance_margin(risk_limits: &[RiskLimit], position_value: f64) -> (f64, f64) {
+        let mut maintenance_amount = 0.0;
+        let mut previous: Option<&RiskLimit> = None;
+        for risk_limit in risk_limits {
+            if let Some(previous) = previous {
+                maintenance_amount += previous.max_position_value
+                    * (risk_limit.maintenance_margin_rate - previous.maintenance_margin_rate);
+            }
+            if position_value <= risk_limit.max_position_value {
+                return (risk_limit.maintenance_margin_rate, maintenance_amount);
+            }
+            previous = Some(risk_limit);
+        }
+        match previous {
+            Some(risk_limit) => (risk_limit.maintenance_margin_rate, maintenance_amount),
+            None => (0.0, 0.0),
+        }
+    }
+}
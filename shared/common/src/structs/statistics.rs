@@ -12,7 +12,89 @@ pub struct Statistics {
     max_drawdown_duration: Duration,
     sharpe_ratio: f64,
     sortino_ratio: f64,
+    per_trade_sharpe_ratio: f64,
+    per_trade_sortino_ratio: f64,
     calmar_ratio: f64,
+    funding_fees: f64,
+    trading_metrics: TradingMetrics,
+}
+
+/// Trade and risk metrics computed from the trading sessions
+#[derive(Clone, Debug)]
+pub struct TradingMetrics {
+    pub average_loss: f64,
+    pub average_trade_duration: Duration,
+    pub average_win: f64,
+    pub cagr: f64,
+    pub expectancy: f64,    // average profit and loss per trade
+    pub exposure_time: f64, // share of the period with an open trade
+    pub max_consecutive_losses: u32,
+    pub max_consecutive_wins: u32,
+    pub payoff_ratio: f64,  // average win over average loss
+    pub profit_factor: f64, // gross profit over gross loss
+    pub recovery_factor: f64,
+    pub total_fees: f64,
+    pub turnover: f64,
+    pub ulcer_index: f64,
+}
+
+impl Display for TradingMetrics {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(
+            f,
+            r#"
+💹 Profit factor: {:.2}
+🎯 Expectancy (USDT): {:.4}
+✅ Average win (USDT): {:.4}
+❌ Average loss (USDT): {:.4}
+⚖️ Payoff ratio: {:.2}
+🔥 Max consecutive wins: {}
+🧊 Max consecutive losses: {}
+⏱️ Exposure time (%): {:.2}
+⌛ Average trade duration: {}h{}
+🔄 Turnover (USDT): {:.2}
+🧾 Fees paid (USDT): {:.4}
+📈 CAGR (%): {:.2}
+😰 Ulcer index (%): {:.4}
+🩹 Recovery factor: {:.2}"#,
+            self.profit_factor,
+            self.expectancy,
+            self.average_win,
+            self.average_loss,
+            self.payoff_ratio,
+            self.max_consecutive_wins,
+            self.max_consecutive_losses,
+            self.exposure_time * 100.0,
+            self.average_trade_duration.num_hours(),
+            self.average_trade_duration.num_minutes() % 60,
+            self.turnover,
+            self.total_fees,
+            self.cagr * 100.0,
+            self.ulcer_index * 100.0,
+            self.recovery_factor
+        )
+    }
+}
+
+impl Default for TradingMetrics {
+    fn default() -> Self {
+        Self {
+            average_loss: 0.0,
+            average_trade_duration: Duration::minutes(0),
+            average_win: 0.0,
+            cagr: 0.0,
+            expectancy: 0.0,
+            exposure_time: 0.0,
+            max_consecutive_losses: 0,
+            max_consecutive_wins: 0,
+            payoff_ratio: 0.0,
+            profit_factor: 0.0,
+            recovery_factor: 0.0,
+            total_fees: 0.0,
+            turnover: 0.0,
+            ulcer_index: 0.0,
+        }
+    }
 }
 
 impl Display for Statistics {
@@ -27,9 +109,12 @@ impl Display for Statistics {
 📏 Risk adjusted return: {:.6}
 📉 Max drawndown (%): {:.4}
 ⏳ Max drawdown duration: {}h{}
-📝 Sharpe: {:.2}
-📝 Sortino: {:.2}
-📝 Calmar: {:.2}"#,
+📝 Sharpe (annualized): {:.2}
+📝 Sortino (annualized): {:.2}
+📝 Sharpe per trade: {:.2}
+📝 Sortino per trade: {:.2}
+📝 Calmar: {:.2}
+💸 Funding fees (USDT): {:.4}{}"#,
             self.success_rate,
             self.current_balance,
             self.risk,
@@ -40,7 +125,11 @@ impl Display for Statistics {
             self.max_drawdown_duration.num_minutes() % 60,
             self.sharpe_ratio,
             self.sortino_ratio,
-            self.calmar_ratio
+            self.per_trade_sharpe_ratio,
+            self.per_trade_sortino_ratio,
+            self.calmar_ratio,
+            self.funding_fees,
+            self.trading_metrics
         )
     }
 }
@@ -56,7 +145,10 @@ impl Statistics {
         max_drawdown_duration: Duration,
         sharpe_ratio: f64,
         sortino_ratio: f64,
+        per_trade_sharpe_ratio: f64,
+        per_trade_sortino_ratio: f64,
         calmar_ratio: f64,
+        funding_fees: f64,
     ) -> Self {
         Statistics {
             success_rate,
@@ -68,9 +160,78 @@ impl Statistics {
             max_drawdown_duration,
             sharpe_ratio,
             sortino_ratio,
+            per_trade_sharpe_ratio,
+            per_trade_sortino_ratio,
             calmar_ratio,
+            funding_fees,
+            trading_metrics: TradingMetrics::default(),
         }
     }
+
+    pub fn patch_trading_metrics(&self, updated_trading_metrics: TradingMetrics) -> Self {
+        let mut result = self.clone();
+        result.trading_metrics = updated_trading_metrics;
+
+        result
+    }
+}
+
+impl Statistics {
+    pub fn success_rate(&self) -> f64 {
+        self.success_rate
+    }
+
+    pub fn current_balance(&self) -> f64 {
+        self.current_balance
+    }
+
+    pub fn risk(&self) -> f64 {
+        self.risk
+    }
+
+    pub fn downside_deviation(&self) -> f64 {
+        self.downside_deviation
+    }
+
+    pub fn risk_adjusted_return(&self) -> f64 {
+        self.risk_adjusted_return
+    }
+
+    pub fn max_drawdown(&self) -> f64 {
+        self.max_drawdown
+    }
+
+    pub fn max_drawdown_duration(&self) -> Duration {
+        self.max_drawdown_duration
+    }
+
+    pub fn sharpe_ratio(&self) -> f64 {
+        self.sharpe_ratio
+    }
+
+    pub fn sortino_ratio(&self) -> f64 {
+        self.sortino_ratio
+    }
+
+    pub fn per_trade_sharpe_ratio(&self) -> f64 {
+        self.per_trade_sharpe_ratio
+    }
+
+    pub fn per_trade_sortino_ratio(&self) -> f64 {
+        self.per_trade_sortino_ratio
+    }
+
+    pub fn calmar_ratio(&self) -> f64 {
+        self.calmar_ratio
+    }
+
+    pub fn funding_fees(&self) -> f64 {
+        self.funding_fees
+    }
+
+    pub fn trading_metrics(&self) -> &TradingMetrics {
+        &self.trading_metrics
+    }
 }
 
 impl Default for Statistics {
@@ -85,7 +246,11 @@ impl Default for Statistics {
             max_drawdown_duration: Duration::minutes(0),
             sharpe_ratio: 0.0,
             sortino_ratio: 0.0,
+            per_trade_sharpe_ratio: 0.0,
+            per_trade_sortino_ratio: 0.0,
             calmar_ratio: 0.0,
+            funding_fees: 0.0,
+            trading_metrics: TradingMetrics::default(),
         }
     }
 }
@@ -74,7 +74,7 @@ impl Trade {
                 OrderStatus::Closed
                 | OrderStatus::StoppedBR
                 | OrderStatus::StoppedSL
-                // | OrderStatus::StoppedTSL
+                | OrderStatus::StoppedTSL
                 | OrderStatus::StoppedTP => TradeStatus::Closed,
                 OrderStatus::PartiallyClosed => TradeStatus::PartiallyClosed,
                 OrderStatus::StandBy => TradeStatus::CloseOrderStandBy,
@@ -190,28 +190,38 @@ impl Trade {
         (realized_pnl, returns)
     }
 
-    pub fn calculate_unrealized_pnl_and_returns(&self, current_price: f64) -> (f64, f64) {
-        // short: Unrealized P&L = (Average Entry Price - Current Mark Price) × Position Size, ROI = [(Entry Price − Mark Price) × Position Size/ Initial Margin] × 100%
-        // long: Unrealized P&L = (Current Mark Price - Average Entry Price) × Position Size, ROI = [(Mark Price − Entry Price) × Position Size/ Initial Margin] × 100%
-        let avg_entry_price = self.open_order.get_executed_avg_price();
+    fn get_current_position_size(&self) -> f64 {
         let executed_qty = self.open_order.get_executed_quantity();
         let closed_qty = if let Some(close_order) = &self.close_order {
             close_order.get_closed_quanitity()
         } else {
             0.0
         };
-        let current_position_size = executed_qty - closed_qty;
+        executed_qty - closed_qty
+    }
+
+    /// Profit and loss of the open position size at the given price, before fees
+    pub fn calculate_price_pnl(&self, current_price: f64) -> f64 {
+        let avg_entry_price = self.open_order.get_executed_avg_price();
+        let current_position_size = self.get_current_position_size();
+        if self.open_order.side == Side::Sell {
+            (avg_entry_price - current_price) * current_position_size
+        } else if self.open_order.side == Side::Buy {
+            (current_price - avg_entry_price) * current_position_size
+        } else {
+            panic!("calculate_price_pnl -> open order position is different from -1 or 1");
+        }
+    }
+
+    pub fn calculate_unrealized_pnl_and_returns(&self, current_price: f64) -> (f64, f64) {
+        // short: Unrealized P&L = (Average Entry Price - Current Mark Price) × Position Size, ROI = [(Entry Price − Mark Price) × Position Size/ Initial Margin] × 100%
+        // long: Unrealized P&L = (Current Mark Price - Average Entry Price) × Position Size, ROI = [(Mark Price − Entry Price) × Position Size/ Initial Margin] × 100%
+        let current_position_size = self.get_current_position_size();
         let bankruptcy_price = self.open_order.get_bankruptcy_price().unwrap_or_default();
         let provisional_close_fee =
             current_position_size * bankruptcy_price * self.open_order.taker_fee_rate;
         // TODO: CHECK THIS =>  here, we don't subtract fees since their effects result in having less units
-        let unrealized_pnl = if self.open_order.side == Side::Sell {
-            (avg_entry_price - current_price) * current_position_size - provisional_close_fee
-        } else if self.open_order.side == Side::Buy {
-            (current_price - avg_entry_price) * current_position_size - provisional_close_fee
-        } else {
-            panic!("calculate_unrealized_profit -> open order position is different from -1 or 1");
-        };
+        let unrealized_pnl = self.calculate_price_pnl(current_price) - provisional_close_fee;
         let initial_margin = self.calculate_initial_margin();
         let unrealized_returns = if initial_margin != 0.0 {
             unrealized_pnl / initial_margin
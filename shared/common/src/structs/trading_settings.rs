@@ -1,7 +1,12 @@
 use super::{Symbol, SymbolsPair};
 use crate::enums::{
     granularity::Granularity,
-    modifiers::{leverage::Leverage, position_lock::PositionLock, price_level::PriceLevel},
+    modifiers::{
+        leverage::Leverage,
+        position_lock::PositionLock,
+        price_level::{PriceLevel, TrailingStopLoss},
+        slippage::Slippage,
+    },
     order_type::OrderType,
     symbol_id::SymbolId,
 };
@@ -16,6 +21,24 @@ use std::{
     io::{BufReader, Result as IoResult},
 };
 
+/// Limit orders simulation used by benchmarks when opening positions with `OrderType::Limit`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LimitOrderSettings {
+    /// Distance (%) from the signal price the order is placed at, below it for longs and above it for shorts
+    pub price_offset_percentage: f64,
+    /// Amount of klines the order is kept open before being cancelled if not filled
+    pub cancel_after_klines: u32,
+}
+
+impl Default for LimitOrderSettings {
+    fn default() -> Self {
+        Self {
+            price_offset_percentage: 0.0,
+            cancel_after_klines: 1,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 // #[serde(bound(deserialize = "'de: 'static"))]
 pub struct TradingSettings {
@@ -28,6 +51,10 @@ pub struct TradingSettings {
     pub symbols_pair: SymbolsPair,
     pub bechmark_minimum_days: u32,
     pub granularity: Granularity,
+    #[serde(default)]
+    pub limit_order_settings: LimitOrderSettings,
+    #[serde(default)]
+    pub slippage: Slippage,
 }
 
 impl TradingSettings {
@@ -54,6 +81,8 @@ impl TradingSettings {
             symbols_pair: SymbolsPair::new(&anchor_contract_symbol, &traded_contract_symbol),
             bechmark_minimum_days,
             granularity,
+            limit_order_settings: LimitOrderSettings::default(),
+            slippage: Slippage::default(),
         }
     }
 
@@ -112,6 +141,13 @@ impl TradingSettings {
         self.order_types.1
     }
 
+    pub fn get_trailing_stop_loss(&self) -> Option<TrailingStopLoss> {
+        match self.price_level_modifier_map.get("tsp") {
+            Some(PriceLevel::TrailingStopLoss(trailing_stop_loss)) => Some(*trailing_stop_loss),
+            _ => None,
+        }
+    }
+
     pub fn patch_symbols_pair(&self, updated_symbols_pair: SymbolsPair) -> Self {
         let mut result = self.clone();
         result.symbols_pair = updated_symbols_pair;
@@ -119,6 +155,23 @@ impl TradingSettings {
         result
     }
 
+    pub fn patch_limit_order_settings(
+        &self,
+        updated_limit_order_settings: LimitOrderSettings,
+    ) -> Self {
+        let mut result = self.clone();
+        result.limit_order_settings = updated_limit_order_settings;
+
+        result
+    }
+
+    pub fn patch_slippage(&self, updated_slippage: Slippage) -> Self {
+        let mut result = self.clone();
+        result.slippage = updated_slippage;
+
+        result
+    }
+
     pub fn fmt_price_level_modifiers(&self) -> String {
         let str = if self.price_level_modifier_map.len() == 0 {
             "No price modifiers".to_owned()
@@ -150,6 +203,8 @@ impl Default for TradingSettings {
             symbols_pair: SymbolsPair::default(),
             granularity: Granularity::default(),
             bechmark_minimum_days: 1,
+            limit_order_settings: LimitOrderSettings::default(),
+            slippage: Slippage::default(),
         }
     }
 }
@@ -164,6 +219,8 @@ impl Debug for TradingSettings {
             💰 Allocation Percentage (%): {:?}
             🎰 Leverage: {:?}
             📒 Order types: Buy {:?}, Sell {:?}
+            ⏳ Limit Orders: {:?}
+            📉 Slippage: {:?}
             🎭 Price Modifiers: {:?}
             🔒 Position Lock: {:?}
             🔁 Revert Opposite Signals {}
@@ -174,6 +231,8 @@ impl Debug for TradingSettings {
             self.leverage,
             self.order_types.0,
             self.order_types.1,
+            self.limit_order_settings,
+            self.slippage,
             self.fmt_price_level_modifiers(),
             self.position_lock_modifier,
             self.signals_revert_its_opposite,
@@ -150,6 +150,13 @@ pub trait TraderExchange: TraderHelper {
         trade: &Trade,
         est_price: f64,
     ) -> impl Future<Output = Result<Order, GlowError>> + Send;
+    /// Keeps the trade stop loss trailing the last price, as configured by the `tsp` price level modifier.
+    /// Returns whether the exchange stop loss was updated.
+    fn update_trailing_stop_loss(
+        &self,
+        trade: &Trade,
+        last_price: f64,
+    ) -> impl Future<Output = Result<bool, GlowError>> + Send;
     /// this function is meant to be run by trades with status TradeStatus::PartiallyOpen and TradeStatus::CloseOrderStandBy
     fn cancel_order(
         &self,
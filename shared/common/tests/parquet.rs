@@ -0,0 +1,161 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use common::{
+    functions::{
+        csv::get_tick_data_csv_path_from_dir,
+        map_ticks_data_to_df,
+        parquet::{
+            get_kline_parquet_path_from_dir, load_interval_kline_dataframe_from_dir,
+            save_kline_df_to_parquet_dir, scan_kline_parquet_from_dir,
+        },
+    },
+    r#static::SYMBOLS_MAP,
+    structs::{Symbol, TickData},
+};
+use polars::prelude::{CsvWriter, DataFrame, SerWriter};
+use std::{
+    fs::{create_dir_all, remove_dir_all, File},
+    path::{Path, PathBuf},
+};
+
+fn get_symbol() -> &'static Symbol {
+    SYMBOLS_MAP.get("BTCUSDT").unwrap()
+}
+
+fn new_store_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("glow_kline_store_{}", name));
+    let _ = remove_dir_all(&dir);
+    dir
+}
+
+fn get_ticks_df(start: DateTime<Utc>, minutes: i64, price: f64) -> DataFrame {
+    let symbol = get_symbol();
+    let ticks: Vec<TickData> = (0..minutes)
+        .map(|minute| {
+            TickData::new_from_string(
+                symbol.name,
+                start + Duration::minutes(minute),
+                price,
+                price + 1.0,
+                price + 0.5,
+                price - 1.0,
+            )
+        })
+        .collect();
+    map_ticks_data_to_df(&ticks).unwrap()
+}
+
+fn write_day_csv(dir: &Path, date: NaiveDate) {
+    let day_start = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+    let mut df = get_ticks_df(day_start, 1440, 100.0)
+        .select([
+            "start_time",
+            "BTCUSDT_open",
+            "BTCUSDT_high",
+            "BTCUSDT_low",
+            "BTCUSDT_close",
+        ])
+        .unwrap();
+    let path = get_tick_data_csv_path_from_dir(dir, date, get_symbol().name);
+    create_dir_all(path.parent().unwrap()).unwrap();
+    CsvWriter::new(File::create(path).unwrap())
+        .include_header(true)
+        .finish(&mut df)
+        .unwrap();
+}
+
+#[test]
+fn test_save_partitions_by_month_and_overrides_klines() {
+    let dir = new_store_dir("partitions");
+    let symbol = get_symbol();
+    // crosses from January into February
+    let start = Utc.with_ymd_and_hms(2024, 1, 31, 23, 0, 0).unwrap();
+    save_kline_df_to_parquet_dir(&dir, &get_ticks_df(start, 120, 100.0), symbol.name).unwrap();
+    // overlapping klines with updated prices
+    let overlap_start = start + Duration::minutes(90);
+    save_kline_df_to_parquet_dir(&dir, &get_ticks_df(overlap_start, 60, 200.0), symbol.name)
+        .unwrap();
+
+    let january = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let february = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+    assert!(get_kline_parquet_path_from_dir(&dir, symbol.name, january).is_file());
+    assert!(get_kline_parquet_path_from_dir(&dir, symbol.name, february).is_file());
+
+    let end = start + Duration::minutes(149);
+    let stored_df = scan_kline_parquet_from_dir(&dir, symbol.name, start, end)
+        .unwrap()
+        .expect("partitions to be scanned")
+        .collect()
+        .unwrap();
+    assert_eq!(stored_df.height(), 150);
+    let opens: Vec<f64> = stored_df
+        .column("BTCUSDT_open")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert_eq!(opens[89], 100.0);
+    assert_eq!(opens[90], 200.0);
+}
+
+#[test]
+fn test_scan_filters_start_time() {
+    let dir = new_store_dir("scan");
+    let symbol = get_symbol();
+    let start = Utc.with_ymd_and_hms(2024, 3, 10, 0, 0, 0).unwrap();
+    save_kline_df_to_parquet_dir(&dir, &get_ticks_df(start, 1440, 100.0), symbol.name).unwrap();
+
+    let scan_start = start + Duration::hours(2);
+    let scan_end = start + Duration::hours(3);
+    let scanned_df = scan_kline_parquet_from_dir(&dir, symbol.name, scan_start, scan_end)
+        .unwrap()
+        .unwrap()
+        .collect()
+        .unwrap();
+    assert_eq!(scanned_df.height(), 61);
+
+    let absent_month = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+    assert!(
+        scan_kline_parquet_from_dir(&dir, symbol.name, absent_month, absent_month)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_load_interval_migrates_csv_days() {
+    let dir = new_store_dir("migration");
+    let symbol = get_symbol();
+    let first_day = NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+    let second_day = NaiveDate::from_ymd_opt(2024, 4, 2).unwrap();
+    let third_day = NaiveDate::from_ymd_opt(2024, 4, 3).unwrap();
+    // first day already in the store, second one only cached as CSV, third one missing
+    let first_day_start = Utc.from_utc_datetime(&first_day.and_hms_opt(0, 0, 0).unwrap());
+    save_kline_df_to_parquet_dir(
+        &dir,
+        &get_ticks_df(first_day_start, 1440, 100.0),
+        symbol.name,
+    )
+    .unwrap();
+    write_day_csv(&dir, second_day);
+
+    let days = [first_day, second_day, third_day];
+    let (loaded_df, not_loaded_dates) =
+        load_interval_kline_dataframe_from_dir(&dir, &days, symbol).unwrap();
+    assert_eq!(loaded_df.unwrap().height(), 2880);
+    assert_eq!(not_loaded_dates, vec![third_day]);
+
+    // second day is now served from the parquet partition
+    let second_day_start = first_day_start + Duration::days(1);
+    let stored_df = scan_kline_parquet_from_dir(
+        &dir,
+        symbol.name,
+        second_day_start,
+        second_day_start + Duration::minutes(1439),
+    )
+    .unwrap()
+    .unwrap()
+    .collect()
+    .unwrap();
+    assert_eq!(stored_df.height(), 1440);
+}
@@ -0,0 +1,190 @@
+use chrono::Duration;
+use common::{
+    enums::granularity::Granularity,
+    functions::performance::{
+        calculate_average_win_and_loss, calculate_cagr, calculate_exposure_and_average_duration,
+        calculate_max_consecutive_wins_and_losses, calculate_per_trade_sharpe_ratio,
+        calculate_period_returns, calculate_profit_factor, calculate_recovery_factor,
+        calculate_sharpe_ratio, calculate_sortino_ratio, calculate_turnover, calculate_ulcer_index,
+    },
+};
+use polars::prelude::*;
+
+const HOUR_IN_MS: i64 = 3_600_000;
+
+fn get_f64_column(values: &[f64]) -> Column {
+    Column::new("values".into(), values.to_vec())
+}
+
+fn get_datetime_column(hours: &[i64]) -> Column {
+    Column::new(
+        "datetimes".into(),
+        hours
+            .iter()
+            .map(|hour| hour * HOUR_IN_MS)
+            .collect::<Vec<i64>>(),
+    )
+    .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+    .unwrap()
+}
+
+#[test]
+fn test_profit_factor() {
+    let profit_and_loss = get_f64_column(&[10.0, -5.0, 20.0, -10.0, 0.0]);
+    assert_eq!(calculate_profit_factor(&profit_and_loss).unwrap(), 2.0);
+
+    let winning_profit_and_loss = get_f64_column(&[10.0, 5.0]);
+    assert_eq!(
+        calculate_profit_factor(&winning_profit_and_loss).unwrap(),
+        f64::INFINITY
+    );
+    assert_eq!(calculate_profit_factor(&get_f64_column(&[])).unwrap(), 0.0);
+}
+
+#[test]
+fn test_average_win_and_loss() {
+    let profit_and_loss = get_f64_column(&[10.0, -5.0, 20.0, -10.0, 0.0]);
+
+    assert_eq!(
+        calculate_average_win_and_loss(&profit_and_loss).unwrap(),
+        (15.0, -7.5)
+    );
+    assert_eq!(
+        calculate_average_win_and_loss(&get_f64_column(&[1.0])).unwrap(),
+        (1.0, 0.0)
+    );
+}
+
+#[test]
+fn test_max_consecutive_wins_and_losses() {
+    let profit_and_loss = get_f64_column(&[1.0, 2.0, -1.0, 3.0, 4.0, 5.0, -1.0, -2.0, 0.0, -3.0]);
+
+    assert_eq!(
+        calculate_max_consecutive_wins_and_losses(&profit_and_loss).unwrap(),
+        (3, 2)
+    );
+}
+
+#[test]
+fn test_exposure_counts_overlapping_trades_once() {
+    let starts = get_datetime_column(&[0, 2, 6]);
+    let ends = get_datetime_column(&[4, 5, 7]);
+
+    let (exposure, average_duration) =
+        calculate_exposure_and_average_duration(&starts, &ends, Duration::hours(10)).unwrap();
+
+    // open from 0h to 5h and from 6h to 7h
+    assert!((exposure - 0.6).abs() < 1e-12);
+    assert_eq!(average_duration, Duration::hours(8) / 3);
+}
+
+#[test]
+fn test_turnover() {
+    let units = get_f64_column(&[2.0, 0.5]);
+    let start_prices = get_f64_column(&[100.0, 200.0]);
+    let end_prices = get_f64_column(&[110.0, 180.0]);
+
+    let turnover = calculate_turnover(&units, &start_prices, &end_prices).unwrap();
+
+    assert!((turnover - 610.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_cagr() {
+    let cagr = calculate_cagr(100.0, 121.0, Duration::days(730));
+    assert!((cagr - 0.1).abs() < 1e-12);
+
+    assert_eq!(calculate_cagr(100.0, 121.0, Duration::zero()), 0.0);
+}
+
+#[test]
+fn test_ulcer_index() {
+    let drawdowns = get_f64_column(&[0.0, 0.3, 0.4, 0.0]);
+
+    let ulcer_index = calculate_ulcer_index(&drawdowns).unwrap();
+
+    assert!((ulcer_index - 0.25).abs() < 1e-12);
+    assert_eq!(calculate_ulcer_index(&get_f64_column(&[])).unwrap(), 0.0);
+}
+
+#[test]
+fn test_recovery_factor() {
+    assert!((calculate_recovery_factor(100.0, 130.0, 0.1) - 3.0).abs() < 1e-12);
+    assert_eq!(calculate_recovery_factor(100.0, 130.0, 0.0), 0.0);
+}
+
+#[test]
+fn test_period_returns_sample_balance_at_period_boundaries() {
+    let starts = get_datetime_column(&[0, 10, 20, 40, 60]);
+    let ends = get_datetime_column(&[10, 20, 30, 50, 100]);
+    let balances = get_f64_column(&[100.0, 110.0, 121.0, 108.9, 121.0]);
+
+    let period_returns =
+        calculate_period_returns(&starts, &ends, &balances, Granularity::d1).unwrap();
+
+    // balances of 100, 110, 121, 108.9, 108.9 and 121 at each midnight up to 120h
+    let expected_returns = [0.1, 0.1, -0.1, 0.0, 121.0 / 108.9 - 1.0];
+    assert_eq!(period_returns.len(), expected_returns.len());
+    for (period_return, expected_return) in period_returns.iter().zip(expected_returns) {
+        assert!((period_return - expected_return).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_period_returns_stop_sampling_at_bust() {
+    let starts = get_datetime_column(&[0, 30, 60]);
+    let ends = get_datetime_column(&[30, 60, 100]);
+    let balances = get_f64_column(&[100.0, -5.0, 10.0]);
+
+    let period_returns =
+        calculate_period_returns(&starts, &ends, &balances, Granularity::d1).unwrap();
+
+    // balances of 100 at the first midnights, busted at 72h and never sampled again
+    assert_eq!(period_returns, vec![0.0, 0.0, -1.0]);
+}
+
+#[test]
+fn test_sharpe_ratio_is_annualized() {
+    let period_returns = [0.01, -0.01, 0.02, 0.0];
+    let daily_sharpe_ratio = 0.005 / (0.0005_f64 / 3.0).sqrt();
+
+    let sharpe_ratio = calculate_sharpe_ratio(&period_returns, 0.0, Granularity::d1);
+    let half_day_sharpe_ratio = calculate_sharpe_ratio(&period_returns, 0.0, Granularity::h12);
+
+    assert!((sharpe_ratio - daily_sharpe_ratio * 365_f64.sqrt()).abs() < 1e-9);
+    assert!((half_day_sharpe_ratio - daily_sharpe_ratio * 730_f64.sqrt()).abs() < 1e-9);
+    assert!(calculate_sharpe_ratio(&period_returns, 0.005, Granularity::d1).abs() < 1e-9);
+    assert_eq!(
+        calculate_sharpe_ratio(&[0.01; 4], 0.0, Granularity::d1),
+        0.0
+    );
+    assert_eq!(calculate_sharpe_ratio(&[0.01], 0.0, Granularity::d1), 0.0);
+}
+
+#[test]
+fn test_sortino_ratio_is_annualized() {
+    let period_returns = [0.01, -0.01, 0.02, 0.0];
+
+    let sortino_ratio = calculate_sortino_ratio(&period_returns, 0.0, Granularity::d1);
+
+    // downside deviation of sqrt(0.0001 / 4) = 0.005, as the mean return
+    assert!((sortino_ratio - 365_f64.sqrt()).abs() < 1e-9);
+    assert_eq!(
+        calculate_sortino_ratio(&[0.01, 0.02], 0.0, Granularity::d1),
+        f64::INFINITY
+    );
+    assert_eq!(
+        calculate_sortino_ratio(&[0.01, 0.01], 0.01, Granularity::d1),
+        0.0
+    );
+}
+
+#[test]
+fn test_per_trade_sharpe_ratio() {
+    let returns = get_f64_column(&[0.1, -0.05]);
+    let risks = get_f64_column(&[0.01, 0.01]);
+
+    let per_trade_sharpe_ratio = calculate_per_trade_sharpe_ratio(&returns, &risks, 1.0).unwrap();
+
+    assert!((per_trade_sharpe_ratio - 4.0).abs() < 1e-9);
+}
@@ -0,0 +1,30 @@
+use common::structs::RiskLimit;
+
+const RISK_LIMITS: [RiskLimit; 3] = [
+    RiskLimit::new(0.004, 50_000.0),
+    RiskLimit::new(0.005, 500_000.0),
+    RiskLimit::new(0.01, 8_000_000.0),
+];
+
+#[test]
+fn test_maintenance_amount_deducts_lower_tiers() {
+    assert_eq!(
+        RiskLimit::get_maintenance_margin(&RISK_LIMITS, 10_000.0),
+        (0.004, 0.0)
+    );
+    let (rate, amount) = RiskLimit::get_maintenance_margin(&RISK_LIMITS, 100_000.0);
+    assert_eq!(rate, 0.005);
+    assert!((amount - 50.0).abs() < 1e-6);
+    let (rate, amount) = RiskLimit::get_maintenance_margin(&RISK_LIMITS, 1_000_000.0);
+    assert_eq!(rate, 0.01);
+    assert!((amount - 2_550.0).abs() < 1e-6);
+    // positions beyond the last tier keep its rate
+    let (rate, amount) = RiskLimit::get_maintenance_margin(&RISK_LIMITS, 10_000_000.0);
+    assert_eq!(rate, 0.01);
+    assert!((amount - 2_550.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_no_risk_limits_have_no_maintenance_margin() {
+    assert_eq!(RiskLimit::get_maintenance_margin(&[], 10_000.0), (0.0, 0.0));
+}
@@ -0,0 +1,159 @@
+use chrono::NaiveDateTime;
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{leverage::Leverage, position_lock::PositionLock, price_level::PriceLevel},
+        order_type::OrderType,
+        signal_category::SignalCategory,
+        symbol_id::SymbolId,
+        trading_data_update::TradingDataUpdate,
+    },
+    structs::{BehaviorSubject, Clock, TradingSettings},
+};
+use core::{benchmark::functions::compute_benchmark_positions, trader::Trader};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use exchanges::{
+    binance::trader::BinanceTraderExchange,
+    enums::TraderExchangeWrapper,
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use polars::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Days of 1 minute klines benchmarked, built by repeating the `data/test` day
+static BENCHMARKED_DAYS: usize = 30;
+
+/// Loads the `data/test` day as BTCUSDT strategy data, repeated over `BENCHMARKED_DAYS`
+fn load_strategy_df() -> DataFrame {
+    let path = format!(
+        "{}/../../data/test/BTC_USDT_AGIX_USDT.csv",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let df = CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(None)
+        .try_into_reader_with_file_path(Some(path.into()))
+        .unwrap()
+        .finish()
+        .unwrap();
+    let mut columns = vec![];
+    for price_col in [
+        "BTCUSDT_open",
+        "BTCUSDT_high",
+        "BTCUSDT_low",
+        "BTCUSDT_close",
+    ] {
+        columns.push(
+            df.column(price_col)
+                .unwrap()
+                .cast(&DataType::Float64)
+                .unwrap(),
+        );
+    }
+    for signal_category in [
+        SignalCategory::GoShort,
+        SignalCategory::GoLong,
+        SignalCategory::CloseShort,
+        SignalCategory::CloseLong,
+    ] {
+        let signal_col = signal_category.get_column();
+        columns.push(
+            df.column(signal_col)
+                .unwrap()
+                .cast(&DataType::Int32)
+                .unwrap(),
+        );
+    }
+    let day_df = DataFrame::new(columns).unwrap();
+
+    let mut strategy_df = day_df.clone();
+    for _ in 1..BENCHMARKED_DAYS {
+        strategy_df.vstack_mut(&day_df).unwrap();
+    }
+    let first_start_time = df
+        .column("start_time")
+        .unwrap()
+        .str()
+        .unwrap()
+        .get(0)
+        .unwrap();
+    let first_start_time = NaiveDateTime::parse_from_str(first_start_time, "%Y-%m-%dT%H:%M:%S%.3f")
+        .unwrap()
+        .and_utc()
+        .timestamp_millis();
+    let start_times: Vec<i64> = (0..strategy_df.height() as i64)
+        .map(|minute| first_start_time + minute * 60_000)
+        .collect();
+    strategy_df
+        .with_column(
+            Series::new("start_time".into(), start_times)
+                .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+                .unwrap(),
+        )
+        .unwrap();
+    strategy_df.rechunk_mut();
+    strategy_df
+}
+
+fn new_trader() -> Trader {
+    let stop_loss = PriceLevel::StopLoss(0.03);
+    let take_profit = PriceLevel::TakeProfit(0.05);
+    let trading_settings = TradingSettings::new(
+        10.0,
+        Leverage::Isolated(10),
+        OrderType::Market,
+        OrderType::Market,
+        PositionLock::None,
+        HashMap::from([
+            (stop_loss.get_hash_key(), stop_loss),
+            (take_profit.get_hash_key(), take_profit),
+        ]),
+        true,
+        &SymbolId::Bitcoin,
+        &SymbolId::Bitcoin,
+        1,
+        Granularity::m1,
+    );
+    let config = ExchangeConfig {
+        credentials: ApiCredentials {
+            key: "bench-api-key",
+            secret: "bench-api-secret",
+        },
+        endpoints: ApiEndpoints {
+            http: "http://127.0.0.1:0",
+            ws: "ws://127.0.0.1:0",
+        },
+    };
+    let trader_exchange = TraderExchangeWrapper::Binance(BinanceTraderExchange::with_config(
+        &trading_settings,
+        config,
+    ));
+    Trader::new(
+        &BehaviorSubject::new(TradingDataUpdate::default()),
+        trader_exchange,
+        &Arc::new(Mutex::new(DataFrame::empty())),
+        &Arc::new(RwLock::new(0)),
+        &Clock::default(),
+    )
+}
+
+fn bench_benchmark_kernel(c: &mut Criterion) {
+    let strategy_df = load_strategy_df();
+    let trader = new_trader();
+
+    let mut group = c.benchmark_group("compute_benchmark_positions");
+    group.bench_function("engine", |b| {
+        b.iter_batched(
+            || strategy_df.clone(),
+            |strategy_df| compute_benchmark_positions(&trader, strategy_df).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_benchmark_kernel);
+criterion_main!(benches);
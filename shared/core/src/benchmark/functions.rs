@@ -1,48 +1,74 @@
-use super::{round_down_nth_decimal, round_nth_decimal, BenchmarkTradeError};
+use super::{
+    round_down_nth_decimal, round_nth_decimal, BenchmarkTradeError, ContractSteps, USDT_DECIMALS,
+};
 use crate::benchmark::{
-    count_decimal_places, new_benchmark_trade, BenchmarkTrade, NewBenchmarkTradeParams, PriceLock,
+    new_benchmark_trade, BenchmarkLimitOrder, BenchmarkMargin, BenchmarkSlippage, BenchmarkTrade,
+    NewBenchmarkTradeParams, PriceLock, PriceThreshold, ThresholdKind,
 };
 use crate::trader::Trader;
+use common::enums::modifiers::leverage::Leverage;
+use common::enums::modifiers::position_lock::PositionLock;
 use common::enums::order_type::OrderType;
 use common::enums::side::Side;
 use common::enums::signal_category::SignalCategory;
-use common::functions::{get_price_columns_f32, get_signal_col_values};
+use common::functions::{
+    funding::get_funding_rate_col,
+    intrabar::{get_intrabar_paths, IntrabarPath},
+};
+use common::structs::{LimitOrderSettings, Symbol};
 use common::traits::exchange::{BenchmarkExchange, TraderHelper};
 use glow_error::GlowError;
 use polars::prelude::*;
-use std::time::Instant;
+use std::borrow::Cow;
+
+/// Wallet balance every benchmark starts from
+pub const INITIAL_BENCHMARK_BALANCE: f64 = 100.0;
 
 #[derive(Clone, Copy, Debug)]
-enum IterationsError {
+pub enum IterationsError {
     ZeroUnits,
     InsufficientFunds,
+    /// Every kline was already stepped
+    EngineDone,
+}
+
+impl From<IterationsError> for GlowError {
+    fn from(error: IterationsError) -> Self {
+        Self::new(
+            String::from("Benchmark Iterations Error"),
+            format!("{:?}", error),
+        )
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 struct IterationData {
-    fee: f32,
-    units: f32,
-    pnl: f32,
-    roi: f32,
-    balance: f32,
-    funding: f32,
+    fee: f64,
+    slippage: f64,
+    units: f64,
+    pnl: f64,
+    roi: f64,
+    balance: f64,
+    funding: f64,
     position: i32,
-    action: String,
+    action: SignalCategory,
 }
 
 impl IterationData {
     pub fn new(
-        fee: f32,
-        units: f32,
-        pnl: f32,
-        roi: f32,
-        balance: f32,
-        funding: f32,
+        fee: f64,
+        slippage: f64,
+        units: f64,
+        pnl: f64,
+        roi: f64,
+        balance: f64,
+        funding: f64,
         position: i32,
-        action: String,
+        action: SignalCategory,
     ) -> Self {
         Self {
             fee,
+            slippage,
             units,
             pnl,
             roi,
@@ -54,433 +80,849 @@ impl IterationData {
     }
 }
 
-pub fn compute_benchmark_positions(
-    trader: &Trader,
-    initial_strategy_df: DataFrame,
-) -> Result<DataFrame, GlowError> {
-    // let data = data.to_owned();
-    // TODO: TRY TO IMPLEMENT THIS USING LAZYFRAMES
-    let perf_start = Instant::now();
-
-    let mut df = initial_strategy_df;
-    let df_height = df.height();
-
-    let traded_symbol = trader.trader_exchange.get_traded_symbol();
-    let traded_contract = trader.trader_exchange.get_traded_contract();
-    let (opens, highs, lows, closes) = get_price_columns_f32(&df, &traded_symbol)?;
-    let shorts = get_signal_col_values(&df, SignalCategory::GoShort)?;
-    let longs = get_signal_col_values(&df, SignalCategory::GoShort)?;
-    let close_shorts = get_signal_col_values(&df, SignalCategory::CloseShort)?;
-    let close_longs = get_signal_col_values(&df, SignalCategory::CloseLong)?;
-
-    let mut trade_fees = vec![0.0];
-    let mut units = vec![0.0];
-    let mut profit_and_loss = vec![0.0];
-    let mut returns = vec![0.0];
-    let mut balances = vec![100.0];
-    let mut fundings = vec![0_f32];
-    let mut positions = vec![0];
-    let mut actions = vec![SignalCategory::KeepPosition.get_column().to_owned()];
-    let trading_settings = trader.trader_exchange.get_trading_settings();
-    let leverage_factor = trading_settings.leverage.get_factor() as f32;
-    let has_leverage = leverage_factor > 1.0;
-
-    let price_level_modifier_map_binding = trading_settings.price_level_modifier_map.clone();
-    let stop_loss: Option<PriceLock> = price_level_modifier_map_binding
-        .get("sl")
-        .map_or(None, |sl| Some(sl.clone().into()));
-    let take_profit: Option<PriceLock> = price_level_modifier_map_binding
-        .get("tp")
-        .map_or(None, |tp| Some(tp.clone().into()));
-    let should_check_price_modifiers = has_leverage || stop_loss.is_some() || take_profit.is_some();
-
-    let maker_fee_rate = trader.trader_exchange.get_maker_fee() as f32;
-    let taker_fee_rate = trader.trader_exchange.get_taker_fee() as f32;
-    let open_order_fee_rate = if trading_settings.order_types.0 == OrderType::Market {
-        taker_fee_rate
-    } else {
-        maker_fee_rate
-    };
-    let close_order_fee_rate = if trading_settings.order_types.1 == OrderType::Market {
-        taker_fee_rate
-    } else {
-        maker_fee_rate
+/// Strategy data columns a benchmark runs over, borrowed from the strategy dataframe when they're contiguous
+struct BenchmarkInputs<'a> {
+    start_times: Cow<'a, [i64]>,
+    opens: Cow<'a, [f64]>,
+    highs: Cow<'a, [f64]>,
+    lows: Cow<'a, [f64]>,
+    closes: Cow<'a, [f64]>,
+    shorts: Cow<'a, [i32]>,
+    longs: Cow<'a, [i32]>,
+    close_shorts: Cow<'a, [i32]>,
+    close_longs: Cow<'a, [i32]>,
+    funding_rates: Option<Vec<Option<f64>>>,
+    intrabar_paths: Option<Vec<IntrabarPath>>,
+}
+
+fn get_f64_values<'a>(df: &'a DataFrame, column: &str) -> Result<Cow<'a, [f64]>, GlowError> {
+    let values = df.column(column)?.f64()?;
+    Ok(match values.cont_slice() {
+        Ok(values) => Cow::Borrowed(values),
+        Err(_) => Cow::Owned(values.into_no_null_iter().collect()),
+    })
+}
+
+fn get_signal_values<'a>(
+    df: &'a DataFrame,
+    signal_category: SignalCategory,
+) -> Result<Cow<'a, [i32]>, GlowError> {
+    let Ok(column) = df.column(signal_category.get_column()) else {
+        return Ok(Cow::Owned(vec![0; df.height()]));
     };
-    let order_sizes = (
-        traded_contract.minimum_order_size as f32,
-        if trading_settings.order_types.0 == OrderType::Market {
-            traded_contract.maximum_order_sizes.0 as f32
+    let values = column.i32()?;
+    Ok(match values.cont_slice() {
+        Ok(values) => Cow::Borrowed(values),
+        Err(_) => Cow::Owned(values.into_no_null_iter().collect()),
+    })
+}
+
+impl<'a> BenchmarkInputs<'a> {
+    fn new(df: &'a DataFrame, symbol: &Symbol) -> Result<Self, GlowError> {
+        let start_times = df.column("start_time")?.datetime()?.physical();
+        let start_times = match start_times.cont_slice() {
+            Ok(start_times) => Cow::Borrowed(start_times),
+            Err(_) => Cow::Owned(start_times.into_no_null_iter().collect()),
+        };
+        let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+        let funding_rates = match df.column(&get_funding_rate_col(symbol.name)) {
+            Ok(funding_rate_col) => Some(funding_rate_col.f64()?.into_iter().collect()),
+            Err(_) => None,
+        };
+        Ok(Self {
+            start_times,
+            opens: get_f64_values(df, open_col)?,
+            highs: get_f64_values(df, high_col)?,
+            lows: get_f64_values(df, low_col)?,
+            closes: get_f64_values(df, close_col)?,
+            shorts: get_signal_values(df, SignalCategory::GoShort)?,
+            longs: get_signal_values(df, SignalCategory::GoLong)?,
+            close_shorts: get_signal_values(df, SignalCategory::CloseShort)?,
+            close_longs: get_signal_values(df, SignalCategory::CloseLong)?,
+            funding_rates,
+            intrabar_paths: get_intrabar_paths(df, symbol.name)?,
+        })
+    }
+
+    fn get_funding_rate(&self, index: usize) -> Option<f64> {
+        self.funding_rates
+            .as_ref()
+            .and_then(|funding_rates| funding_rates[index])
+    }
+}
+
+/// Trading results of each kline of a benchmark
+#[derive(Clone, Debug, Default)]
+pub struct BenchmarkResult {
+    pub actions: Vec<SignalCategory>,
+    /// Balance left after each kline, without the margin of the open position nor the `fundings` set aside
+    pub balances: Vec<f64>,
+    /// Error that stopped the benchmark before its last kline, i.e. when funds ran out
+    pub error: Option<IterationsError>,
+    pub exit_times: Vec<Option<i64>>,
+    pub funding_fees: Vec<f64>,
+    /// Funds set aside when the balance exceeded the maximum order size, until a trade needs them
+    pub fundings: Vec<f64>,
+    pub positions: Vec<i32>,
+    pub profit_and_loss: Vec<f64>,
+    pub returns: Vec<f64>,
+    pub slippages: Vec<f64>,
+    pub trade_fees: Vec<f64>,
+    pub units: Vec<f64>,
+}
+
+impl BenchmarkResult {
+    /// Allocates the results of `height` klines, holding the first one, which never trades
+    fn new(height: usize) -> Self {
+        let mut result = Self {
+            actions: Vec::with_capacity(height),
+            balances: Vec::with_capacity(height),
+            error: None,
+            exit_times: Vec::with_capacity(height),
+            funding_fees: Vec::with_capacity(height),
+            fundings: Vec::with_capacity(height),
+            positions: Vec::with_capacity(height),
+            profit_and_loss: Vec::with_capacity(height),
+            returns: Vec::with_capacity(height),
+            slippages: Vec::with_capacity(height),
+            trade_fees: Vec::with_capacity(height),
+            units: Vec::with_capacity(height),
+        };
+        let first_kline = IterationData::new(
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            INITIAL_BENCHMARK_BALANCE,
+            0.0,
+            0,
+            SignalCategory::KeepPosition,
+        );
+        result.push(first_kline, 0.0, None);
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn push(&mut self, data: IterationData, funding_fee: f64, exit_time: Option<i64>) {
+        self.trade_fees.push(data.fee);
+        self.slippages.push(data.slippage);
+        self.funding_fees.push(funding_fee);
+        self.units.push(data.units);
+        self.profit_and_loss.push(data.pnl);
+        self.returns.push(data.roi);
+        self.balances.push(data.balance);
+        self.fundings.push(data.funding);
+        self.positions.push(data.position);
+        self.actions.push(data.action);
+        self.exit_times.push(exit_time);
+    }
+
+    /// Overrides the results of the kline at `index`
+    fn set(&mut self, index: usize, data: IterationData) {
+        self.trade_fees[index] = data.fee;
+        self.slippages[index] = data.slippage;
+        self.units[index] = data.units;
+        self.profit_and_loss[index] = data.pnl;
+        self.returns[index] = data.roi;
+        self.balances[index] = data.balance;
+        self.fundings[index] = data.funding;
+        self.positions[index] = data.position;
+    }
+
+    /// Fills the klines up to `height` with the last results
+    fn fill_up_to(&mut self, height: usize) {
+        let last_fee = *self.trade_fees.last().unwrap();
+        self.trade_fees.resize(height, last_fee);
+        let last_slippage = *self.slippages.last().unwrap();
+        self.slippages.resize(height, last_slippage);
+        let last_funding_fee = *self.funding_fees.last().unwrap();
+        self.funding_fees.resize(height, last_funding_fee);
+        let last_units = *self.units.last().unwrap();
+        self.units.resize(height, last_units);
+        let last_pnl = *self.profit_and_loss.last().unwrap();
+        self.profit_and_loss.resize(height, last_pnl);
+        let last_roi = *self.returns.last().unwrap();
+        self.returns.resize(height, last_roi);
+        let last_balance = *self.balances.last().unwrap();
+        self.balances.resize(height, last_balance);
+        let last_funding = *self.fundings.last().unwrap();
+        self.fundings.resize(height, last_funding);
+        let last_position = *self.positions.last().unwrap();
+        self.positions.resize(height, last_position);
+        let last_action = *self.actions.last().unwrap();
+        self.actions.resize(height, last_action);
+        self.exit_times.resize(height, None);
+    }
+
+    /// Discards the trades from `range_start` on, restoring the balance and fundings of `balance_index`
+    fn discard_from(&mut self, range_start: usize, balance_index: usize) {
+        let range = range_start..self.len();
+        self.trade_fees[range.clone()].fill(0.0);
+        self.slippages[range.clone()].fill(0.0);
+        self.funding_fees[range.clone()].fill(0.0);
+        self.units[range.clone()].fill(0.0);
+        self.profit_and_loss[range.clone()].fill(0.0);
+        self.returns[range.clone()].fill(0.0);
+        self.positions[range.clone()].fill(0);
+        self.actions[range.clone()].fill(SignalCategory::KeepPosition);
+        self.exit_times[range.clone()].fill(None);
+        let previous_balance = self.balances[balance_index];
+        self.balances[range.clone()].fill(previous_balance);
+        let previous_funding = self.fundings[balance_index];
+        self.fundings[range].fill(previous_funding);
+    }
+
+    /// Appends the trading columns to the strategy dataframe, whose `balance` includes the `fundings` set aside
+    pub fn append_to_df(self, df: DataFrame) -> Result<DataFrame, GlowError> {
+        let balance_series = Series::new(
+            "balance".into(),
+            self.balances
+                .iter()
+                .zip(self.fundings.iter())
+                .map(|(&balance, &funding)| balance + funding)
+                .collect::<Vec<f64>>(),
+        );
+        let action_series = Series::new(
+            "action".into(),
+            self.actions
+                .iter()
+                .map(|action| action.get_column())
+                .collect::<Vec<&str>>(),
+        );
+        let exit_time_series = Series::new("exit_time".into(), self.exit_times)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?;
+
+        let mut df = df;
+        df.with_column(Series::new("trade_fees".into(), self.trade_fees))?;
+        df.with_column(Series::new("slippage".into(), self.slippages))?;
+        df.with_column(Series::new("funding_fees".into(), self.funding_fees))?;
+        df.with_column(Series::new("units".into(), self.units))?;
+        df.with_column(Series::new("profit_and_loss".into(), self.profit_and_loss))?;
+        df.with_column(Series::new("returns".into(), self.returns))?;
+        df.with_column(balance_series)?;
+        df.with_column(Series::new("position".into(), self.positions))?;
+        df.with_column(action_series)?;
+        df.with_column(exit_time_series)?;
+        Ok(df)
+    }
+}
+
+/// Simulates the trades of a single traded symbol over its strategy data, one kline at a time.
+/// Klines are stepped by the caller, so that several engines can share a wallet (see `compute_portfolio_positions`).
+///
+/// The engine reads the strategy data columns in place and writes each kline results into buffers allocated upfront,
+/// so stepping a kline doesn't allocate.
+pub struct BenchmarkEngine<'a> {
+    inputs: BenchmarkInputs<'a>,
+
+    close_order_fee_rate: f64,
+    is_limit_open_order: bool,
+    is_market_close_order: bool,
+    limit_order_settings: LimitOrderSettings,
+    new_trade_params: NewBenchmarkTradeParams,
+    position_lock: PositionLock,
+    signals_revert_its_opposite: bool,
+    steps: ContractSteps,
+
+    current_trade: Option<BenchmarkTrade>,
+    current_min_price_threshold: Option<PriceThreshold>,
+    current_max_price_threshold: Option<PriceThreshold>,
+    pending_limit_order: Option<BenchmarkLimitOrder>,
+    // close results of the rows where a position was reverted, in case the opposite position has to be discarded
+    last_reverted_close: Option<(usize, IterationData)>,
+
+    result: BenchmarkResult,
+}
+
+impl<'a> BenchmarkEngine<'a> {
+    pub fn new(trader: &Trader, df: &'a DataFrame) -> Result<Self, GlowError> {
+        let traded_symbol = trader.trader_exchange.get_traded_symbol();
+        let traded_contract = trader.trader_exchange.get_traded_contract();
+        let inputs = BenchmarkInputs::new(df, traded_symbol)?;
+
+        let trading_settings = trader.trader_exchange.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let margin = BenchmarkMargin::new(
+            matches!(trading_settings.leverage, Leverage::Cross(_)),
+            traded_contract.liquidation_fee_rate,
+            traded_contract.risk_limits,
+        );
+
+        let price_level_modifier_map_binding = trading_settings.price_level_modifier_map.clone();
+        let stop_loss: Option<PriceLock> = price_level_modifier_map_binding
+            .get("sl")
+            .map(|sl| PriceLock::try_from(sl.clone()))
+            .transpose()?;
+        let take_profit: Option<PriceLock> = price_level_modifier_map_binding
+            .get("tp")
+            .map(|tp| PriceLock::try_from(tp.clone()))
+            .transpose()?;
+        let trailing_stop_loss = trading_settings.get_trailing_stop_loss();
+
+        let maker_fee_rate = trader.trader_exchange.get_maker_fee();
+        let taker_fee_rate = trader.trader_exchange.get_taker_fee();
+        let open_order_fee_rate = if trading_settings.order_types.0 == OrderType::Market {
+            taker_fee_rate
         } else {
-            traded_contract.maximum_order_sizes.1 as f32
-        },
-    );
-    let tick_size = traded_contract.tick_size;
-    let price_locks = (stop_loss, take_profit);
-    let minimum_notional_value = trader
-        .trader_exchange
-        .get_minimum_notional_value()
-        .map(|v| v as f32);
-
-    let mut current_trade: Option<BenchmarkTrade> = None;
-    // let mut current_peak_returns = 0.0;
-    let mut current_min_price_threshold = None;
-    let mut current_max_price_threshold = None;
-    let symbol_decimals = count_decimal_places(order_sizes.0);
-    let tick_decimals = count_decimal_places(tick_size as f32);
-    let allocation_pct = trading_settings.allocation_percentage as f32;
-
-    // need to be updated
-    // trade_fees, units, profit_and_loss, returns, balances, positions, actions
-    let mut index = 0;
-
-    while index < df_height {
-        if index == 0 {
-            index += 1;
-            continue;
+            maker_fee_rate
+        };
+        let close_order_fee_rate = if trading_settings.order_types.1 == OrderType::Market {
+            taker_fee_rate
+        } else {
+            maker_fee_rate
+        };
+        let order_sizes = (
+            traded_contract.minimum_order_size,
+            if trading_settings.order_types.0 == OrderType::Market {
+                traded_contract.maximum_order_sizes.0
+            } else {
+                traded_contract.maximum_order_sizes.1
+            },
+        );
+        let tick_size = traded_contract.tick_size;
+        let price_locks = (stop_loss, take_profit);
+        let slippage = BenchmarkSlippage::new(trading_settings.slippage, tick_size);
+        let minimum_notional_value = trader.trader_exchange.get_minimum_notional_value();
+        let steps = ContractSteps::from_f64(tick_size, order_sizes.0);
+        let allocation_pct = trading_settings.allocation_percentage;
+        // balance, price, side and kline range are set when opening each trade
+        let new_trade_params = NewBenchmarkTradeParams::new(
+            allocation_pct,
+            0.0,
+            leverage_factor,
+            margin,
+            minimum_notional_value,
+            open_order_fee_rate,
+            order_sizes,
+            0.0,
+            price_locks,
+            Side::None,
+            slippage,
+            None,
+            steps,
+            taker_fee_rate,
+            trailing_stop_loss,
+        );
+        let result = BenchmarkResult::new(df.height());
+
+        Ok(Self {
+            inputs,
+
+            close_order_fee_rate,
+            is_limit_open_order: trading_settings.order_types.0 == OrderType::Limit,
+            is_market_close_order: trading_settings.order_types.1 == OrderType::Market,
+            limit_order_settings: trading_settings.limit_order_settings,
+            new_trade_params,
+            position_lock: trading_settings.position_lock_modifier,
+            signals_revert_its_opposite: trading_settings.signals_revert_its_opposite,
+            steps,
+
+            current_trade: None,
+            current_min_price_threshold: None,
+            current_max_price_threshold: None,
+            pending_limit_order: None,
+            last_reverted_close: None,
+
+            result,
+        })
+    }
+
+    pub fn height(&self) -> usize {
+        self.inputs.start_times.len()
+    }
+
+    /// Index of the next kline to be stepped
+    pub fn index(&self) -> usize {
+        self.result.len()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.index() >= self.height()
+    }
+
+    /// Balance left after the last stepped kline, which doesn't include the margin of the open position
+    pub fn get_last_balance(&self) -> f64 {
+        *self.result.balances.last().unwrap()
+    }
+
+    /// Overrides the balance left after the last stepped kline
+    pub fn set_last_balance(&mut self, balance: f64) {
+        *self.result.balances.last_mut().unwrap() = balance;
+    }
+
+    fn get_new_trade_params(
+        &self,
+        current_balance: f64,
+        open_price: f64,
+        side: Side,
+        slippage_kline_range: Option<f64>,
+    ) -> NewBenchmarkTradeParams {
+        let mut new_trade_params = self.new_trade_params;
+        new_trade_params.current_balance = current_balance;
+        new_trade_params.price = open_price;
+        new_trade_params.side = side;
+        new_trade_params.slippage_kline_range = slippage_kline_range;
+        new_trade_params
+    }
+
+    fn open_trade(
+        &mut self,
+        new_trade_params: NewBenchmarkTradeParams,
+        trade_result_params: TradeResultParams,
+    ) -> Result<IterationData, IterationsError> {
+        open_trade(
+            new_trade_params,
+            trade_result_params,
+            &mut self.current_trade,
+            &mut self.current_min_price_threshold,
+            &mut self.current_max_price_threshold,
+        )
+    }
+
+    fn new_limit_order(&self, previous_close_price: f64, side: Side) -> BenchmarkLimitOrder {
+        BenchmarkLimitOrder::new(
+            previous_close_price,
+            self.limit_order_settings.price_offset_percentage,
+            side,
+            self.limit_order_settings.cancel_after_klines,
+            self.steps,
+        )
+    }
+
+    /// Simulates the next kline, trading from `current_balance`, and returns the balance left after it.
+    /// Fails if a trade can't be opened (i.e. funds ran out), after which no more klines can be stepped.
+    pub fn step(&mut self, current_balance: f64) -> Result<f64, IterationsError> {
+        let index = self.index();
+        if self.is_done() {
+            return Err(IterationsError::EngineDone);
         }
 
-        let current_position = positions[index - 1];
-        let current_units = units[index - 1];
-        let current_balance = balances[index - 1];
-        let current_funding = fundings[index - 1];
+        let previous_index = index - 1;
+        let start_time = self.inputs.start_times[index];
+        let open_price = self.inputs.opens[index];
+        let high_price = self.inputs.highs[index];
+        let low_price = self.inputs.lows[index];
+        let close_price = self.inputs.closes[index];
+        let previous_close_price = self.inputs.closes[previous_index];
+        let should_short = self.inputs.shorts[previous_index] == 1;
+        let should_long = self.inputs.longs[previous_index] == 1;
+        let should_close_short = self.inputs.close_shorts[previous_index] == 1;
+        let should_close_long = self.inputs.close_longs[previous_index] == 1;
+
+        let current_position = self.result.positions[previous_index];
+        let current_units = self.result.units[previous_index];
+        let current_funding = self.result.fundings[previous_index];
+        let mut funding_fee = 0_f64;
+        let mut exit_time = None;
 
         let default_results = IterationData::new(
-            0_f32,
+            0_f64,
+            0_f64,
             current_units,
-            0_f32,
-            0_f32,
+            0_f64,
+            0_f64,
             current_balance,
             current_funding,
             current_position,
-            SignalCategory::KeepPosition.get_column().to_owned(),
+            SignalCategory::KeepPosition,
         );
 
         let result: Result<IterationData, IterationsError> = if current_position == 0 {
-            let should_short = shorts[index - 1] == 1;
-            let should_long = longs[index - 1] == 1;
-            if should_short || should_long {
-                let open_price = opens[index];
-                let close_price = closes[index];
-                let new_benchmark_trade_params = NewBenchmarkTradeParams::new(
-                    allocation_pct,
+            let signal_side = if should_short {
+                Side::Sell
+            } else if should_long {
+                Side::Buy
+            } else {
+                Side::None
+            };
+            let mut unfilled_signal = None;
+            let mut is_replacing_order = false;
+            let open_order = if self.is_limit_open_order {
+                // mirrors the live trader, which cancels idle orders on close or opposite signals
+                if let Some(limit_order) = self.pending_limit_order {
+                    let was_cancelled = match limit_order.side {
+                        Side::Buy => should_close_long || signal_side == Side::Sell,
+                        Side::Sell => should_close_short || signal_side == Side::Buy,
+                        Side::None => unreachable!(),
+                    };
+                    if was_cancelled {
+                        unfilled_signal = Some(limit_order.get_unfilled_signal());
+                        self.pending_limit_order = None;
+                    }
+                }
+                if self.pending_limit_order.is_none() && signal_side != Side::None {
+                    self.pending_limit_order =
+                        Some(self.new_limit_order(previous_close_price, signal_side));
+                    is_replacing_order = unfilled_signal.is_some();
+                }
+                match self.pending_limit_order {
+                    // an order replacing a cancelled one rests from the next kline, so the cancellation is recorded
+                    Some(_) if is_replacing_order => None,
+                    Some(limit_order) if limit_order.is_filled(low_price, high_price) => {
+                        self.pending_limit_order = None;
+                        Some((limit_order.side, limit_order.price, None))
+                    }
+                    Some(mut limit_order) => {
+                        limit_order.remaining_klines -= 1;
+                        if limit_order.remaining_klines == 0 {
+                            unfilled_signal = Some(limit_order.get_unfilled_signal());
+                            self.pending_limit_order = None;
+                        } else {
+                            self.pending_limit_order = Some(limit_order);
+                        }
+                        None
+                    }
+                    None => None,
+                }
+            } else if signal_side != Side::None {
+                Some((signal_side, open_price, Some(high_price - low_price)))
+            } else {
+                None
+            };
+            if let Some((side, open_price, slippage_kline_range)) = open_order {
+                let new_benchmark_trade_params = self.get_new_trade_params(
                     current_balance,
-                    leverage_factor,
-                    minimum_notional_value,
-                    open_order_fee_rate,
-                    order_sizes,
                     open_price,
-                    price_locks,
-                    if should_short { Side::Sell } else { Side::Buy },
-                    symbol_decimals,
-                    taker_fee_rate,
-                    tick_decimals,
+                    side,
+                    slippage_kline_range,
                 );
                 let trade_result_params = TradeResultParams::new(
                     close_price,
-                    close_order_fee_rate,
+                    self.close_order_fee_rate,
                     current_balance,
                     current_funding,
-                    should_short,
+                    side == Side::Sell,
                 );
-                match new_benchmark_trade(new_benchmark_trade_params) {
-                    Ok(trade) => {
-                        let params = OnOpenTradeParams::new(trade_result_params, trade);
-                        let result = on_open_trade(
-                            params,
-                            &mut current_trade,
-                            &mut current_min_price_threshold,
-                            &mut current_max_price_threshold,
-                        );
-                        Ok(result)
-                    }
-                    Err(error) => {
-                        let result = on_open_trade_error(
-                            error,
-                            new_benchmark_trade_params,
-                            trade_result_params,
-                            &mut current_trade,
-                            &mut current_min_price_threshold,
-                            &mut current_max_price_threshold,
-                        );
-                        if let Err(error) = result {
-                            Err(error)
-                        } else {
-                            Ok(result.unwrap())
-                        }
-                    }
-                }
+                self.open_trade(new_benchmark_trade_params, trade_result_params)
+            } else if let Some(unfilled_signal) = unfilled_signal {
+                let mut result = default_results;
+                result.action = unfilled_signal;
+                Ok(result)
             } else {
                 Ok(default_results)
             }
         } else {
-            let trade = &current_trade.unwrap();
+            let close_order_fee_rate = self.close_order_fee_rate;
+            let mut trade = self.current_trade.unwrap();
             let current_side = trade.side;
-            let stopped_result = if should_check_price_modifiers {
-                let min_price = lows[index];
-                let max_price = highs[index];
-                let binds_on_min_price =
-                    min_price <= current_min_price_threshold.unwrap_or_default();
-                let binds_on_max_price = !binds_on_min_price
-                    && max_price >= current_max_price_threshold.unwrap_or_default();
-
-                if binds_on_min_price || binds_on_max_price {
-                    // let prev_close_price = closes[index - 1];
-                    // let prev_end_timestamp = end_timestamps[index - 1];
-                    let (pnl, roi, close_fee) = trade.get_pnl_returns_and_fees(
-                        if binds_on_min_price {
-                            current_min_price_threshold.unwrap()
-                        } else {
-                            current_max_price_threshold.unwrap()
-                        },
-                        close_order_fee_rate,
-                    );
-                    let action = match (current_side, binds_on_max_price, binds_on_min_price) {
-                        (Side::Buy, true, _) => SignalCategory::TakeProfit,
-                        (Side::Buy, _, true) => {
-                            if trade.prices.2.is_some() {
-                                SignalCategory::StopLoss
-                            } else {
-                                SignalCategory::TakeProfit
-                            }
-                        }
-                        (Side::Sell, true, _) => {
-                            if trade.prices.2.is_some() {
-                                SignalCategory::StopLoss
-                            } else {
-                                SignalCategory::TakeProfit
-                            }
-                        }
-                        (Side::Sell, _, true) => SignalCategory::TakeProfit,
-                        (_, _, _) => unreachable!(),
-                    };
-                    let result = IterationData::new(
-                        close_fee,
-                        0.0,
-                        pnl,
-                        roi,
-                        f32::max(
-                            0.0,
-                            round_nth_decimal(
-                                current_balance + trade.initial_margin + pnl,
-                                tick_decimals,
-                            ),
-                        ),
-                        current_funding,
-                        0,
-                        action.get_column().to_owned(),
-                    );
-                    (current_min_price_threshold, current_max_price_threshold) = (None, None);
-                    current_trade = None;
-                    Some(result)
+            let close_signal = match current_side {
+                Side::Sell if self.signals_revert_its_opposite && should_long => {
+                    Some(SignalCategory::RevertShort)
+                }
+                Side::Buy if self.signals_revert_its_opposite && should_short => {
+                    Some(SignalCategory::RevertLong)
+                }
+                Side::Sell if should_close_short => Some(SignalCategory::CloseShort),
+                Side::Buy if should_close_long => Some(SignalCategory::CloseLong),
+                _ => None,
+            };
+            let (exit_price, exit_slippage) =
+                if close_signal.is_some() && self.is_market_close_order {
+                    trade.get_exit_price_and_slippage(open_price, high_price - low_price)
                 } else {
-                    None
+                    (open_price, 0.0)
+                };
+            // locked positions ignore close signals, as the live trader does
+            let close_signal = close_signal.filter(|_| {
+                !trade.is_close_locked(self.position_lock, exit_price, close_order_fee_rate)
+            });
+            // positions still open at the kline open pay the funding settled within the kline
+            if let Some(funding_rate) = self.inputs.get_funding_rate(index) {
+                if close_signal.is_none() {
+                    funding_fee = trade.get_funding_fee(open_price, funding_rate);
+                    trade.funding_fees += funding_fee;
+                    self.current_trade = Some(trade);
                 }
+            }
+            let current_min_price_threshold = self.current_min_price_threshold;
+            let current_max_price_threshold = self.current_max_price_threshold;
+            let binds_on_threshold = |min_price: f64, max_price: f64| {
+                current_min_price_threshold.is_some_and(|threshold| min_price <= threshold.price)
+                    || current_max_price_threshold
+                        .is_some_and(|threshold| max_price >= threshold.price)
+            };
+            // coarse klines are walked through their 1 minute path, binding the threshold that was hit first
+            let (min_price, max_price, threshold_time) = self
+                .inputs
+                .intrabar_paths
+                .as_ref()
+                .and_then(|paths| {
+                    paths[index]
+                        .iter()
+                        .find(|(_, high, low)| binds_on_threshold(*low, *high))
+                })
+                .map_or(
+                    (low_price, high_price, start_time),
+                    |&(start_time, high, low)| (low, high, start_time),
+                );
+            let binds_on_min_price =
+                current_min_price_threshold.is_some_and(|threshold| min_price <= threshold.price);
+            let binds_on_max_price = !binds_on_min_price
+                && current_max_price_threshold
+                    .is_some_and(|threshold| max_price >= threshold.price);
+            let stopped_result = if binds_on_min_price || binds_on_max_price {
+                let threshold = if binds_on_min_price {
+                    current_min_price_threshold.unwrap()
+                } else {
+                    current_max_price_threshold.unwrap()
+                };
+                let threshold_price = threshold.price;
+                let (exit_slippage, pnl, roi, close_fee) = match threshold.kind {
+                    // liquidations are closed by the exchange at the liquidation price
+                    ThresholdKind::Liquidation => {
+                        let (pnl, roi, close_fee) =
+                            trade.get_liquidation_pnl_returns_and_fees(close_order_fee_rate);
+                        (0.0, pnl, roi, close_fee)
+                    }
+                    // stop triggers are filled as market orders
+                    ThresholdKind::StopLoss | ThresholdKind::TrailingStop => {
+                        let (exit_price, exit_slippage) = trade
+                            .get_exit_price_and_slippage(threshold_price, max_price - min_price);
+                        let (pnl, roi, close_fee) =
+                            trade.get_pnl_returns_and_fees(exit_price, close_order_fee_rate);
+                        (exit_slippage, pnl, roi, close_fee)
+                    }
+                    ThresholdKind::TakeProfit => {
+                        let (pnl, roi, close_fee) =
+                            trade.get_pnl_returns_and_fees(threshold_price, close_order_fee_rate);
+                        (0.0, pnl, roi, close_fee)
+                    }
+                };
+                let result = IterationData::new(
+                    close_fee,
+                    exit_slippage,
+                    0.0,
+                    pnl,
+                    roi,
+                    trade.get_close_balance(current_balance, pnl),
+                    current_funding,
+                    0,
+                    threshold.kind.into(),
+                );
+                (
+                    self.current_min_price_threshold,
+                    self.current_max_price_threshold,
+                ) = (None, None);
+                self.current_trade = None;
+                exit_time = Some(threshold_time);
+                Some(result)
             } else {
                 None
             };
 
             if let Some(stopped_result) = stopped_result {
                 Ok(stopped_result)
-            } else {
-                let open_price = opens[index];
+            } else if let Some(close_signal) = close_signal {
                 let (pnl, roi, close_fee) =
+                    trade.get_pnl_returns_and_fees(exit_price, close_order_fee_rate);
+                (
+                    self.current_min_price_threshold,
+                    self.current_max_price_threshold,
+                ) = (None, None);
+                self.current_trade = None;
+                exit_time = Some(start_time);
+                let close_result = IterationData::new(
+                    close_fee,
+                    exit_slippage,
+                    0_f64,
+                    pnl,
+                    roi,
+                    trade.get_close_balance(current_balance, pnl),
+                    current_funding,
+                    0,
+                    close_signal,
+                );
+                let revert_side: Side = close_signal.into();
+                if close_signal != SignalCategory::RevertShort
+                    && close_signal != SignalCategory::RevertLong
+                {
+                    Ok(close_result)
+                } else if self.is_limit_open_order {
+                    // the opposite limit order rests on the book from the next kline on
+                    self.pending_limit_order =
+                        Some(self.new_limit_order(previous_close_price, revert_side));
+                    Ok(close_result)
+                } else {
+                    // the opposite position is opened right after the reverted one is closed
+                    let new_benchmark_trade_params = self.get_new_trade_params(
+                        close_result.balance,
+                        open_price,
+                        revert_side,
+                        Some(high_price - low_price),
+                    );
+                    let trade_result_params = TradeResultParams::new(
+                        close_price,
+                        close_order_fee_rate,
+                        close_result.balance,
+                        current_funding,
+                        revert_side == Side::Sell,
+                    );
+                    match self.open_trade(new_benchmark_trade_params, trade_result_params) {
+                        // the row keeps the results of the closed trade, alongside the costs of both orders
+                        Ok(open_result) => {
+                            self.last_reverted_close = Some((index, close_result));
+                            Ok(IterationData::new(
+                                close_result.fee + open_result.fee,
+                                close_result.slippage + open_result.slippage,
+                                open_result.units,
+                                close_result.pnl,
+                                close_result.roi,
+                                open_result.balance,
+                                open_result.funding,
+                                open_result.position,
+                                close_result.action,
+                            ))
+                        }
+                        Err(_) => Ok(close_result),
+                    }
+                }
+            } else {
+                // the stop trails the kline extremes only once the kline is over, so it binds from the next one
+                if trade.trailing_stop_loss.is_some() {
+                    trade.update_trailing_stop_price(low_price, high_price);
+                    (
+                        self.current_min_price_threshold,
+                        self.current_max_price_threshold,
+                    ) = trade.get_threshold_prices();
+                    self.current_trade = Some(trade);
+                }
+                let (pnl, roi, _) =
                     trade.get_pnl_returns_and_fees(open_price, close_order_fee_rate);
-                let was_short_closed = close_shorts[index - 1] == 1 && current_side == Side::Sell;
-                let was_long_closed = close_longs[index - 1] == 1 && current_side == Side::Buy;
-
-                let (close_fee, units, balance, position, action) =
-                    if was_short_closed || was_long_closed {
-                        (current_min_price_threshold, current_max_price_threshold) = (None, None);
-                        current_trade = None;
-                        (
-                            close_fee,
-                            0_f32,
-                            f32::max(
-                                0.0,
-                                round_nth_decimal(
-                                    current_balance + trade.initial_margin + pnl,
-                                    tick_decimals,
-                                ),
-                            ),
-                            0,
-                            if was_short_closed {
-                                SignalCategory::CloseShort.get_column().to_owned()
-                            } else {
-                                SignalCategory::CloseLong.get_column().to_owned()
-                            },
-                        )
-                    } else {
-                        (
-                            0_f32,
-                            current_units,
-                            current_balance,
-                            current_position,
-                            default_results.action,
-                        )
-                    };
-
                 Ok(IterationData::new(
-                    close_fee,
-                    units,
+                    0_f64,
+                    0_f64,
+                    current_units,
                     pnl,
                     roi,
-                    balance,
+                    current_balance,
                     current_funding,
-                    position,
-                    action,
+                    current_position,
+                    default_results.action,
                 ))
             }
         };
 
-        if result.is_err() {
-            println!("result is error {:?}", result);
-            index += 1;
+        let result = result?;
+        self.result.push(result, funding_fee, exit_time);
+        Ok(result.balance)
+    }
 
-            break;
+    /// Steps the next kline without trading, i.e. when the trade it was meant to open couldn't be funded
+    pub fn skip(&mut self, current_balance: f64) -> Result<(), IterationsError> {
+        let index = self.index();
+        if self.is_done() {
+            return Err(IterationsError::EngineDone);
+        }
+        let skipped_kline = IterationData::new(
+            0.0,
+            0.0,
+            self.result.units[index - 1],
+            0.0,
+            0.0,
+            current_balance,
+            self.result.fundings[index - 1],
+            self.result.positions[index - 1],
+            SignalCategory::KeepPosition,
+        );
+        self.result.push(skipped_kline, 0.0, None);
+        Ok(())
+    }
+
+    /// Fills the klines that weren't stepped with the last results,
+    /// discarding the last position if it was kept open until the end.
+    fn settle(&mut self) {
+        let df_height = self.height();
+        if self.index() < df_height {
+            self.result.fill_up_to(df_height);
         }
 
-        let IterationData {
-            fee,
-            units: iteration_units,
-            pnl,
-            roi,
-            balance,
-            funding,
-            position,
-            action,
-        } = result.unwrap();
-
-        trade_fees.push(fee);
-        units.push(iteration_units);
-        profit_and_loss.push(pnl);
-        returns.push(roi);
-        balances.push(balance);
-        fundings.push(funding);
-        positions.push(position);
-        actions.push(action);
-        index += 1;
+        // if last position was taken
+        if self.result.positions.last().unwrap() != &0 {
+            // finds the last kline without a position
+            if let Some(before_last_order_index) = self
+                .result
+                .positions
+                .iter()
+                .rposition(|position| position == &0)
+            {
+                // a position opened by a revert is discarded from its following row, its revert row turning into a plain close
+                let (before_last_order_index, range_start) = match self.last_reverted_close.take() {
+                    Some((revert_index, close_result))
+                        if revert_index > before_last_order_index =>
+                    {
+                        self.result.set(revert_index, close_result);
+                        (revert_index, revert_index + 1)
+                    }
+                    _ => (before_last_order_index, before_last_order_index),
+                };
+                self.result
+                    .discard_from(range_start, before_last_order_index);
+            }
+        }
     }
 
-    if index < df_height {
-        let missing_data_no = df_height - index + 1;
-
-        let last_fee = trade_fees.last().unwrap().clone();
-        trade_fees.extend(vec![last_fee; missing_data_no]);
-        let last_units = units.last().unwrap().clone();
-        units.extend(vec![last_units; missing_data_no]);
-        let last_pnl = profit_and_loss.last().unwrap().clone();
-        profit_and_loss.extend(vec![last_pnl; missing_data_no]);
-        let last_roi = returns.last().unwrap().clone();
-        returns.extend(vec![last_roi; missing_data_no]);
-        let last_balance = balances.last().unwrap().clone();
-        balances.extend(vec![last_balance; missing_data_no]);
-        let last_funding = fundings.last().unwrap().clone();
-        fundings.extend(vec![last_funding; missing_data_no]);
-        let last_position = positions.last().unwrap().clone();
-        positions.extend(vec![last_position; missing_data_no]);
-        let last_action = actions.last().unwrap().clone();
-        actions.extend(vec![last_action; missing_data_no]);
+    /// Settles the stepped klines and returns the results of every kline
+    pub fn finish(mut self) -> BenchmarkResult {
+        self.settle();
+        self.result
     }
+}
 
-    // if last position was taken
-    if positions.last().unwrap() != &0 {
-        if let Some((before_last_order_index, _)) = positions // over positions vector
-            .iter() // iterate over
-            .enumerate() // an enumeration
-            .rev() // of reversed positions
-            .find(|(_, value)| value == &&0)
-        // until it finds where value is 0
-        {
-            // splices results vectors to values before opening the order
-            // note that even though the vector was reversed, before_last_order_index keeps being the original vector index. Thanks, Rust <3
-            let range = before_last_order_index..df_height;
-            let zeroed_float_patch: Vec<f32> = range.clone().map(|_| 0.0 as f32).collect();
-            let zeroed_integer_patch: Vec<i32> = range.clone().map(|_| 0 as i32).collect();
-            let keep_position_action_patch: Vec<String> = range
-                .clone()
-                .map(|_| SignalCategory::KeepPosition.get_column().to_owned())
-                .collect();
-
-            trade_fees.splice(range.clone(), zeroed_float_patch.clone());
-            units.splice(range.clone(), zeroed_float_patch.clone());
-            profit_and_loss.splice(range.clone(), zeroed_float_patch.clone());
-
-            positions.splice(range.clone(), zeroed_integer_patch);
-            actions.splice(range.clone(), keep_position_action_patch);
-
-            let previous_balance = balances[before_last_order_index];
-            let patch_balances: Vec<f32> = range.clone().map(|_| previous_balance as f32).collect();
-            balances.splice(range.clone(), patch_balances);
-            let previous_funding = fundings[before_last_order_index];
-            let patch_fundings: Vec<f32> = range.clone().map(|_| previous_funding as f32).collect();
-            fundings.splice(range.clone(), patch_fundings);
-            returns.splice(range.clone(), zeroed_float_patch);
+/// Benchmarks the strategy data of the trader symbol, returning the results of each kline
+pub fn compute_benchmark_result(
+    trader: &Trader,
+    strategy_df: &DataFrame,
+) -> Result<BenchmarkResult, GlowError> {
+    // there are no klines to trade
+    if strategy_df.height() == 0 {
+        return Ok(BenchmarkResult::default());
+    }
+    let mut engine = BenchmarkEngine::new(trader, strategy_df)?;
+    let mut error = None;
+    while !engine.is_done() {
+        let current_balance = engine.get_last_balance();
+        if let Err(step_error) = engine.step(current_balance) {
+            error = Some(step_error);
+            break;
         }
     }
+    let mut result = engine.finish();
+    result.error = error;
+    Ok(result)
+}
 
-    let elapsed_time = perf_start.elapsed();
-    let elapsed_millis = elapsed_time.as_nanos();
-    println!(
-        "compute_benchmark_positions => Elapsed time (ms): {}",
-        elapsed_millis as f64 / 1_000_000_f64
-    );
-
-    let trade_fee_series = Series::new(
-        "trade_fees".into(),
-        trade_fees.iter().map(|&f| f as f64).collect::<Vec<f64>>(),
-    );
-    let units_series = Series::new(
-        "units".into(),
-        units.iter().map(|&u| u as f64).collect::<Vec<f64>>(),
-    );
-    let profit_and_loss_series = Series::new(
-        "profit_and_loss".into(),
-        profit_and_loss
-            .iter()
-            .map(|&p| p as f64)
-            .collect::<Vec<f64>>(),
-    );
-    let returns_series = Series::new(
-        "returns".into(),
-        returns.iter().map(|&r| r as f64).collect::<Vec<f64>>(),
-    );
-    let balance_series = Series::new(
-        "balance".into(),
-        balances
-            .iter()
-            .zip(fundings.iter())
-            .map(|(&a, &b)| (a + b) as f64)
-            .collect::<Vec<f64>>(),
-    );
-    let position_series = Series::new("position".into(), positions);
-    let action_series = Series::new("action".into(), actions);
-
-    let df = df.with_column(trade_fee_series)?;
-    let df = df.with_column(units_series)?;
-    let df = df.with_column(profit_and_loss_series)?;
-    let df = df.with_column(returns_series)?;
-    let df = df.with_column(balance_series)?;
-    let df = df.with_column(position_series)?;
-    let df = df.with_column(action_series)?;
-
-    Ok(df.clone())
+/// Benchmarks the strategy data of the trader symbol, appending the trading columns of each kline to it
+pub fn compute_benchmark_positions(
+    trader: &Trader,
+    initial_strategy_df: DataFrame,
+) -> Result<DataFrame, GlowError> {
+    let result = compute_benchmark_result(trader, &initial_strategy_df)?;
+    result.append_to_df(initial_strategy_df)
 }
 
 #[derive(Clone, Copy)]
 struct TradeResultParams {
-    pub close_price: f32,
-    pub close_order_fee_rate: f32,
-    pub current_balance: f32,
-    pub current_funding: f32,
+    pub close_price: f64,
+    pub close_order_fee_rate: f64,
+    pub current_balance: f64,
+    pub current_funding: f64,
     pub should_short: bool,
 }
 
 impl TradeResultParams {
     pub fn new(
-        close_price: f32,
-        close_order_fee_rate: f32,
-        current_balance: f32,
-        current_funding: f32,
+        close_price: f64,
+        close_order_fee_rate: f64,
+        current_balance: f64,
+        current_funding: f64,
         should_short: bool,
     ) -> Self {
         Self {
@@ -494,13 +936,12 @@ impl TradeResultParams {
 }
 #[derive(Clone, Copy)]
 struct OnOpenTradeParams {
-    pub close_price: f32,
-    pub close_order_fee_rate: f32,
-    pub current_balance: f32,
-    pub current_funding: f32,
+    pub close_price: f64,
+    pub close_order_fee_rate: f64,
+    pub current_balance: f64,
+    pub current_funding: f64,
     pub should_short: bool,
     pub trade: BenchmarkTrade,
-    pub tick_decimals: i32,
 }
 
 impl OnOpenTradeParams {
@@ -520,7 +961,6 @@ impl OnOpenTradeParams {
             // remainder: success_params.1,
             should_short,
             trade,
-            tick_decimals: trade.tick_decimals,
         }
     }
 }
@@ -528,8 +968,8 @@ impl OnOpenTradeParams {
 fn on_open_trade(
     params: OnOpenTradeParams,
     current_trade: &mut Option<BenchmarkTrade>,
-    current_min_price_threshold: &mut Option<f32>,
-    current_max_price_threshold: &mut Option<f32>,
+    current_min_price_threshold: &mut Option<PriceThreshold>,
+    current_max_price_threshold: &mut Option<PriceThreshold>,
 ) -> IterationData {
     let OnOpenTradeParams {
         close_price,
@@ -539,45 +979,73 @@ fn on_open_trade(
         // remainder,
         should_short,
         trade,
-        tick_decimals,
     } = params;
     (*current_min_price_threshold, *current_max_price_threshold) = trade.get_threshold_prices();
     let side = trade.side.into();
     let open_fee = trade.open_fee;
+    let open_slippage = trade.open_slippage;
     let units = trade.units;
     let (pnl, roi, _) = trade.get_pnl_returns_and_fees(close_price, close_order_fee_rate);
     *current_trade = Some(trade);
     IterationData::new(
         open_fee,
+        open_slippage,
         units,
         pnl,
         roi,
-        f32::max(
+        f64::max(
             0.0,
             round_nth_decimal(
                 current_balance - trade.initial_margin - open_fee,
-                tick_decimals,
+                USDT_DECIMALS,
             ),
         ),
         current_funding,
         side,
-        (if should_short {
+        if should_short {
             SignalCategory::GoShort
         } else {
             SignalCategory::GoLong
-        })
-        .get_column()
-        .to_owned(),
+        },
     )
 }
 
+fn open_trade(
+    new_trade_params: NewBenchmarkTradeParams,
+    trade_result_params: TradeResultParams,
+    current_trade: &mut Option<BenchmarkTrade>,
+    current_min_price_threshold: &mut Option<PriceThreshold>,
+    current_max_price_threshold: &mut Option<PriceThreshold>,
+) -> Result<IterationData, IterationsError> {
+    match new_benchmark_trade(new_trade_params) {
+        Ok(trade) => {
+            let params = OnOpenTradeParams::new(trade_result_params, trade);
+            let result = on_open_trade(
+                params,
+                current_trade,
+                current_min_price_threshold,
+                current_max_price_threshold,
+            );
+            Ok(result)
+        }
+        Err(error) => on_open_trade_error(
+            error,
+            new_trade_params,
+            trade_result_params,
+            current_trade,
+            current_min_price_threshold,
+            current_max_price_threshold,
+        ),
+    }
+}
+
 fn on_open_trade_error(
     error: BenchmarkTradeError,
     new_trade_params: NewBenchmarkTradeParams,
     trade_result_params: TradeResultParams,
     current_trade: &mut Option<BenchmarkTrade>,
-    current_min_price_threshold: &mut Option<f32>,
-    current_max_price_threshold: &mut Option<f32>,
+    current_min_price_threshold: &mut Option<PriceThreshold>,
+    current_max_price_threshold: &mut Option<PriceThreshold>,
 ) -> Result<IterationData, IterationsError> {
     let mut current_balance = trade_result_params.current_balance;
     let mut current_funding = trade_result_params.current_funding;
@@ -592,26 +1060,18 @@ fn on_open_trade_error(
             max_expenditure,
             expenditure,
         } => {
-            let suspend_amount = round_down_nth_decimal(
-                expenditure - max_expenditure,
-                new_trade_params.tick_decimals,
-            );
-            let updated_balance = round_down_nth_decimal(
-                current_balance - suspend_amount,
-                new_trade_params.tick_decimals,
-            );
-            let updated_funding = round_down_nth_decimal(
-                current_funding + suspend_amount,
-                new_trade_params.tick_decimals,
-            );
+            let suspend_amount =
+                round_down_nth_decimal(expenditure - max_expenditure, USDT_DECIMALS);
+            let updated_balance =
+                round_down_nth_decimal(current_balance - suspend_amount, USDT_DECIMALS);
+            let updated_funding =
+                round_down_nth_decimal(current_funding + suspend_amount, USDT_DECIMALS);
             (updated_balance, updated_funding)
         }
         BenchmarkTradeError::UnitsLessThanMinSize { min_expenditure }
         | BenchmarkTradeError::ValueLessThanNotionalMin { min_expenditure } => {
-            let total_funds = round_down_nth_decimal(
-                current_funding + current_balance,
-                new_trade_params.tick_decimals,
-            );
+            let total_funds =
+                round_down_nth_decimal(current_funding + current_balance, USDT_DECIMALS);
             if current_funding == 0.0 || total_funds < min_expenditure {
                 return Err(IterationsError::InsufficientFunds);
             }
@@ -624,24 +1084,11 @@ fn on_open_trade_error(
     trade_result_params.current_balance = current_balance;
     trade_result_params.current_funding = current_funding;
 
-    match new_benchmark_trade(new_trade_params) {
-        Ok(trade) => {
-            let on_open_order_params = OnOpenTradeParams::new(trade_result_params, trade);
-            let result = on_open_trade(
-                on_open_order_params,
-                current_trade,
-                current_min_price_threshold,
-                current_max_price_threshold,
-            );
-            Ok(result)
-        }
-        Err(error) => on_open_trade_error(
-            error,
-            new_trade_params,
-            trade_result_params,
-            current_trade,
-            current_min_price_threshold,
-            current_max_price_threshold,
-        ),
-    }
+    open_trade(
+        new_trade_params,
+        trade_result_params,
+        current_trade,
+        current_min_price_threshold,
+        current_max_price_threshold,
+    )
 }
@@ -1,27 +1,250 @@
-use common::enums::{modifiers::price_level::PriceLevel, side::Side};
+use common::{
+    enums::{
+        modifiers::{
+            position_lock::PositionLock,
+            price_level::{PriceLevel, TrailingStopLoss},
+            slippage::Slippage,
+        },
+        side::Side,
+        signal_category::SignalCategory,
+    },
+    structs::RiskLimit,
+};
+use glow_error::GlowError;
 pub mod functions;
+pub mod portfolio;
+
+/// Decimals USDT amounts (balances, margins, fees and profits) are settled with
+pub const USDT_DECIMALS: i32 = 8;
+
+/// Most decimals a contract step is scaled to
+pub const MAX_STEP_DECIMALS: i32 = 12;
+
+/// Contract step held as an integer amount of its last decimal, i.e. a 0.05 tick is 5 at 2 decimals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScaledStep {
+    pub decimals: i32,
+    pub value: i64,
+}
+
+impl ScaledStep {
+    pub const fn new(value: i64, decimals: i32) -> Self {
+        Self { decimals, value }
+    }
+
+    /// Scales the step to the fewest decimals that hold it, ignoring the float error of its binary representation
+    pub fn from_f64(step: f64) -> Self {
+        let is_whole = |scaled: f64| (scaled - scaled.round()).abs() <= scaled.abs() * 1e-9;
+        let decimals = (0..MAX_STEP_DECIMALS)
+            .find(|&decimals| is_whole(step * 10.0_f64.powi(decimals)))
+            .unwrap_or(MAX_STEP_DECIMALS);
+        Self::new((step * 10.0_f64.powi(decimals)).round() as i64, decimals)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.value as f64 / 10.0_f64.powi(self.decimals)
+    }
+}
+
+/// Price tick and order quantity step of the traded contract, which exchanges round prices and order sizes to
+#[derive(Clone, Copy, Debug)]
+pub struct ContractSteps {
+    pub qty_decimals: i32,
+    pub qty_step: f64,
+    pub tick_decimals: i32,
+    pub tick_size: f64,
+}
+
+impl ContractSteps {
+    pub fn new(tick: ScaledStep, qty: ScaledStep) -> Self {
+        Self {
+            qty_decimals: qty.decimals,
+            qty_step: qty.to_f64(),
+            tick_decimals: tick.decimals,
+            tick_size: tick.to_f64(),
+        }
+    }
+
+    /// Builds the steps from the tick size and quantity step exchanges report for the contract
+    pub fn from_f64(tick_size: f64, qty_step: f64) -> Self {
+        Self::new(
+            ScaledStep::from_f64(tick_size),
+            ScaledStep::from_f64(qty_step),
+        )
+    }
+
+    /// Rounds the price to the closest tick
+    pub fn round_price(&self, price: f64) -> f64 {
+        round_to_step(price, self.tick_size, self.tick_decimals)
+    }
+
+    /// Rounds the units down to the quantity step, as orders can't hold a fraction of it
+    pub fn round_down_units(&self, units: f64) -> f64 {
+        round_down_to_step(units, self.qty_step, self.qty_decimals)
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct BenchmarkTrade {
-    pub initial_margin: f32,
-    pub leverage_factor: f32,
-    pub open_fee: f32,
-    pub prices: (f32, Option<f32>, Option<f32>, Option<f32>), // (price, bankruptcy_price, stop_loss_price, take_profit_price)
+    pub funding_fees: f64, // accrued while open, negative when funding was received
+    pub initial_margin: f64,
+    pub leverage_factor: f64,
+    pub liquidation_fee_rate: f64,
+    pub margin_balance: f64, // initial margin when isolated, whole wallet balance when cross
+    pub open_fee: f64,
+    pub open_slippage: f64,
+    pub peak_price: f64, // most favorable price reached while open
+    pub prices: (f64, Option<f64>, Option<f64>, Option<f64>), // (price, liquidation_price, stop_loss_price, take_profit_price)
     pub side: Side,
-    pub symbol_decimals: i32,
-    pub tick_decimals: i32,
-    pub units: f32,
+    pub slippage: BenchmarkSlippage,
+    pub steps: ContractSteps,
+    pub trailing_stop_loss: Option<TrailingStopLoss>,
+    pub trailing_stop_price: Option<f64>,
+    pub units: f64,
+}
+
+/// Open order resting on the book until its price is crossed or it gets cancelled
+#[derive(Clone, Copy, Debug)]
+pub struct BenchmarkLimitOrder {
+    pub price: f64,
+    pub remaining_klines: u32,
+    pub side: Side,
+}
+
+impl BenchmarkLimitOrder {
+    pub fn new(
+        signal_price: f64,
+        price_offset_pct: f64,
+        side: Side,
+        cancel_after_klines: u32,
+        steps: ContractSteps,
+    ) -> Self {
+        let price_mod = match side {
+            Side::Buy => 1.0 - price_offset_pct / 100.0,
+            Side::Sell => 1.0 + price_offset_pct / 100.0,
+            Side::None => unreachable!(),
+        };
+        Self {
+            price: steps.round_price(signal_price * price_mod),
+            remaining_klines: cancel_after_klines.max(1),
+            side,
+        }
+    }
+
+    /// Buy orders are filled once the low reaches the order price, sell orders once the high does
+    pub fn is_filled(&self, min_price: f64, max_price: f64) -> bool {
+        match self.side {
+            Side::Buy => min_price <= self.price,
+            Side::Sell => max_price >= self.price,
+            Side::None => unreachable!(),
+        }
+    }
+
+    pub fn get_unfilled_signal(&self) -> SignalCategory {
+        match self.side {
+            Side::Buy => SignalCategory::UnfilledLong,
+            Side::Sell => SignalCategory::UnfilledShort,
+            Side::None => unreachable!(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BenchmarkSlippage {
+    pub model: Slippage,
+    pub tick_size: f64,
+}
+
+impl BenchmarkSlippage {
+    pub fn new(model: Slippage, tick_size: f64) -> Self {
+        Self { model, tick_size }
+    }
+
+    /// Moves the price against the order side: buys are filled higher, sells lower
+    pub fn get_slipped_price(
+        &self,
+        price: f64,
+        side: Side,
+        kline_range: f64,
+        steps: ContractSteps,
+    ) -> f64 {
+        let price_slippage = self
+            .model
+            .get_price_slippage(price, self.tick_size, kline_range);
+        let slipped_price = match side {
+            Side::Buy => price + price_slippage,
+            Side::Sell => f64::max(0.0, price - price_slippage),
+            Side::None => unreachable!(),
+        };
+        steps.round_price(slipped_price)
+    }
+}
+
+/// Margin backing a trade, defining at which price it gets liquidated
+#[derive(Clone, Copy, Debug)]
+pub struct BenchmarkMargin {
+    pub is_cross: bool,
+    pub liquidation_fee_rate: f64,
+    pub risk_limits: &'static [RiskLimit],
+}
+
+impl BenchmarkMargin {
+    pub fn new(
+        is_cross: bool,
+        liquidation_fee_rate: f64,
+        risk_limits: &'static [RiskLimit],
+    ) -> Self {
+        Self {
+            is_cross,
+            liquidation_fee_rate,
+            risk_limits,
+        }
+    }
+
+    /// Price at which the margin balance drops to the maintenance margin of the position,
+    /// None when the margin balance covers any adverse move
+    pub fn get_liquidation_price(
+        &self,
+        margin_balance: f64,
+        price: f64,
+        side: Side,
+        units: f64,
+    ) -> Option<f64> {
+        let position_value = price * units;
+        let (maintenance_margin_rate, maintenance_amount) =
+            RiskLimit::get_maintenance_margin(self.risk_limits, position_value);
+        let liquidation_price = match side {
+            Side::Buy => {
+                (position_value - margin_balance - maintenance_amount)
+                    / (units * (1.0 - maintenance_margin_rate))
+            }
+            Side::Sell => {
+                (position_value + margin_balance + maintenance_amount)
+                    / (units * (1.0 + maintenance_margin_rate))
+            }
+            Side::None => unreachable!(),
+        };
+        if liquidation_price <= 0.0 {
+            return None;
+        }
+        Some(liquidation_price)
+    }
 }
 
 #[derive(Clone, Copy)]
-pub struct PriceLock(pub f32);
+pub struct PriceLock(pub f64);
+
+/// Only stop losses and take profits lock a fixed price, trailing stop losses follow the peak price
+impl TryFrom<PriceLevel> for PriceLock {
+    type Error = GlowError;
 
-impl From<PriceLevel> for PriceLock {
-    fn from(value: PriceLevel) -> Self {
+    fn try_from(value: PriceLevel) -> Result<Self, Self::Error> {
         match value {
-            PriceLevel::StopLoss(factor) | PriceLevel::TakeProfit(factor) => {
-                PriceLock(factor as f32)
-            }
+            PriceLevel::StopLoss(factor) | PriceLevel::TakeProfit(factor) => Ok(PriceLock(factor)),
+            PriceLevel::TrailingStopLoss(_) => Err(GlowError::new(
+                String::from("Invalid Price Lock"),
+                format!("{:?} doesn't lock a fixed price", value),
+            )),
         }
     }
 }
@@ -31,7 +254,7 @@ pub enum LockType {
 }
 
 impl LockType {
-    fn get_price_mod(&self, side: Side, pct: f32) -> f32 {
+    fn get_price_mod(&self, side: Side, pct: f64) -> f64 {
         match (self, side) {
             (Self::StopLoss, Side::Buy) | (Self::TakeProfit, Side::Sell) => -1.0 * pct,
             (Self::TakeProfit, Side::Buy) | (Self::StopLoss, Side::Sell) => 1.0 * pct,
@@ -42,63 +265,66 @@ impl LockType {
 
 impl BenchmarkTrade {
     pub fn new(
-        initial_margin: f32,
-        leverage_factor: f32,
-        open_order_fee_rate: f32,
-        price: f32,
+        initial_margin: f64,
+        leverage_factor: f64,
+        margin: BenchmarkMargin,
+        margin_balance: f64,
+        open_order_fee_rate: f64,
+        open_slippage: f64,
+        price: f64,
         price_locks: (Option<PriceLock>, Option<PriceLock>), // (stop_loss, take_profit)
         side: Side,
-        symbol_decimals: i32,
-        units: f32,
-        tick_decimals: i32,
+        slippage: BenchmarkSlippage,
+        steps: ContractSteps,
+        trailing_stop_loss: Option<TrailingStopLoss>,
+        units: f64,
     ) -> Self {
-        let mut bankruptcy_price = None;
-        if leverage_factor != 1.0 {
-            bankruptcy_price = Some(round_down_nth_decimal(
-                price * (leverage_factor + if side == Side::Sell { 1.0 } else { -1.0 })
-                    / leverage_factor,
-                tick_decimals,
-            ))
-        }
+        let liquidation_price = margin
+            .get_liquidation_price(margin_balance, price, side, units)
+            .map(|liquidation_price| steps.round_price(liquidation_price));
         let mut stop_loss_price = None;
         if let Some(lock) = price_locks.0 {
             let pct = lock.0;
             let position_mod = leverage_factor + LockType::StopLoss.get_price_mod(side, pct);
-            let sl_price = round_nth_decimal(price * position_mod / leverage_factor, tick_decimals);
+            let sl_price = steps.round_price(price * position_mod / leverage_factor);
             stop_loss_price = Some(sl_price);
         }
         let mut take_profit_price = None;
         if let Some(lock) = price_locks.1 {
             let pct = lock.0;
             let position_mod = leverage_factor + LockType::TakeProfit.get_price_mod(side, pct);
-            let tp_price = round_nth_decimal(price * position_mod / leverage_factor, tick_decimals);
+            let tp_price = steps.round_price(price * position_mod / leverage_factor);
             take_profit_price = Some(tp_price);
         }
-        let open_fee = round_nth_decimal(units * open_order_fee_rate * price, tick_decimals);
+        let open_fee = round_nth_decimal(units * open_order_fee_rate * price, USDT_DECIMALS);
         Self {
+            funding_fees: 0.0,
             initial_margin,
             leverage_factor,
+            liquidation_fee_rate: margin.liquidation_fee_rate,
+            margin_balance,
             open_fee,
-            prices: (price, bankruptcy_price, stop_loss_price, take_profit_price),
+            open_slippage,
+            peak_price: price,
+            prices: (price, liquidation_price, stop_loss_price, take_profit_price),
             side,
-            symbol_decimals,
+            slippage,
+            steps,
+            trailing_stop_loss,
+            trailing_stop_price: None,
             units,
-            tick_decimals,
         }
     }
 
     pub fn get_pnl_returns_and_fees(
         &self,
-        price: f32,
-        close_order_fee_rate: f32,
-    ) -> (f32, f32, f32) {
+        price: f64,
+        close_order_fee_rate: f64,
+    ) -> (f64, f64, f64) {
         // short: Unrealized P&L = (Average Entry Price - Current Mark Price) × Position Size, ROI = [(Entry Price − Mark Price) × Position Size/ Initial Margin] × 100%
         // long: Unrealized P&L = (Current Mark Price - Average Entry Price) × Position Size, ROI = [(Mark Price − Entry Price) × Position Size/ Initial Margin] × 100%
         // TODO: CHECK THIS =>  here, we don't subtract fees since their effects result in having less units
-        let close_fee = round_nth_decimal(
-            self.units * price * close_order_fee_rate,
-            self.symbol_decimals,
-        );
+        let close_fee = round_nth_decimal(self.units * price * close_order_fee_rate, USDT_DECIMALS);
         let price = if self.side == Side::Sell {
             self.prices.0 - price
         } else {
@@ -106,8 +332,8 @@ impl BenchmarkTrade {
         };
         // TODO: (self.prices.0 - price) * self.units - (close_fee + self.open_fee) make sure close_fee is not double-counted
         let pnl = round_nth_decimal(
-            price * self.units - (self.open_fee + close_fee),
-            self.symbol_decimals,
+            price * self.units - (self.open_fee + close_fee + self.funding_fees),
+            USDT_DECIMALS,
         );
         let roi = if self.initial_margin != 0.0 {
             pnl / self.initial_margin
@@ -117,98 +343,278 @@ impl BenchmarkTrade {
         (pnl, roi, close_fee)
     }
 
-    pub fn get_threshold_prices(&self) -> (Option<f32>, Option<f32>) {
+    /// Returns (pnl, returns, fees) of closing the trade at its liquidation price, fees including the liquidation fee,
+    /// which is capped by what is left of the margin balance
+    pub fn get_liquidation_pnl_returns_and_fees(
+        &self,
+        close_order_fee_rate: f64,
+    ) -> (f64, f64, f64) {
+        let liquidation_price = self.prices.1.expect("trade without liquidation price");
+        let (pnl, _, close_fee) =
+            self.get_pnl_returns_and_fees(liquidation_price, close_order_fee_rate);
+        let liquidation_fee = f64::min(
+            self.units * liquidation_price * self.liquidation_fee_rate,
+            f64::max(0.0, self.margin_balance + pnl),
+        );
+        let liquidation_fee = round_nth_decimal(liquidation_fee, USDT_DECIMALS);
+        let pnl = round_nth_decimal(pnl - liquidation_fee, USDT_DECIMALS);
+        let roi = if self.initial_margin != 0.0 {
+            pnl / self.initial_margin
+        } else {
+            0.0
+        };
+        (pnl, roi, close_fee + liquidation_fee)
+    }
+
+    /// Whether the position lock keeps a signal from closing the trade at the given price, out of the same estimate live traders use
+    pub fn is_close_locked(
+        &self,
+        position_lock: PositionLock,
+        price: f64,
+        close_order_fee_rate: f64,
+    ) -> bool {
+        let close_fee = round_nth_decimal(self.units * price * close_order_fee_rate, USDT_DECIMALS);
+        let price_pnl = if self.side == Side::Sell {
+            (self.prices.0 - price) * self.units
+        } else {
+            (price - self.prices.0) * self.units
+        };
+        position_lock.is_close_locked(price_pnl, self.open_fee, close_fee)
+    }
+
+    /// Balance once the trade is closed with the given profit and loss, out of the balance left after opening it.
+    /// The profit and loss discounts the open fee, which that balance already paid.
+    pub fn get_close_balance(&self, balance: f64, pnl: f64) -> f64 {
+        f64::max(
+            0.0,
+            round_nth_decimal(
+                balance + self.initial_margin + self.open_fee + pnl,
+                USDT_DECIMALS,
+            ),
+        )
+    }
+
+    /// Funding paid by the position at the given funding rate, longs paying shorts when it is positive
+    pub fn get_funding_fee(&self, price: f64, funding_rate: f64) -> f64 {
+        let funding_fee = self.units * price * funding_rate;
+        let funding_fee = if self.side == Side::Sell {
+            -funding_fee
+        } else {
+            funding_fee
+        };
+        round_nth_decimal(funding_fee, USDT_DECIMALS)
+    }
+
+    /// Fill price of a market order closing the trade, alongside the slippage cost
+    pub fn get_exit_price_and_slippage(&self, price: f64, kline_range: f64) -> (f64, f64) {
+        let close_side = if self.side == Side::Sell {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+        let exit_price =
+            self.slippage
+                .get_slipped_price(price, close_side, kline_range, self.steps);
+        let exit_slippage =
+            round_nth_decimal((exit_price - price).abs() * self.units, USDT_DECIMALS);
+        (exit_price, exit_slippage)
+    }
+
+    /// Ratchets the trailing stop price from the kline extremes, it never moves against the position
+    pub fn update_trailing_stop_price(&mut self, min_price: f64, max_price: f64) {
+        let Some(trailing_stop_loss) = self.trailing_stop_loss else {
+            return;
+        };
+        self.peak_price = match self.side {
+            Side::Buy => f64::max(self.peak_price, max_price),
+            Side::Sell => f64::min(self.peak_price, min_price),
+            Side::None => unreachable!(),
+        };
+        let stop_price = trailing_stop_loss.get_stop_price(
+            self.side,
+            self.prices.0,
+            self.peak_price,
+            self.leverage_factor,
+        );
+        let Some(stop_price) = stop_price.map(|price| self.steps.round_price(price)) else {
+            return;
+        };
+        self.trailing_stop_price = match (self.side, self.trailing_stop_price) {
+            (Side::Buy, Some(current_price)) => Some(f64::max(current_price, stop_price)),
+            (Side::Sell, Some(current_price)) => Some(f64::min(current_price, stop_price)),
+            (_, None) => Some(stop_price),
+            (Side::None, _) => unreachable!(),
+        };
+    }
+
+    /// Lower and upper thresholds closing the trade, of which stops are below longs and above shorts
+    pub fn get_threshold_prices(&self) -> (Option<PriceThreshold>, Option<PriceThreshold>) {
+        let take_profit = self
+            .prices
+            .3
+            .map(|price| PriceThreshold::new(price, ThresholdKind::TakeProfit));
+        // the closest of the stop loss, trailing stop and liquidation prices binds first.
+        // Stop orders are triggered before the exchange takes over the position, so they bind first at the same price
+        let stops = [
+            (self.prices.2, ThresholdKind::StopLoss),
+            (self.trailing_stop_price, ThresholdKind::TrailingStop),
+            (self.prices.1, ThresholdKind::Liquidation),
+        ]
+        .into_iter()
+        .filter_map(|(price, kind)| price.map(|price| PriceThreshold::new(price, kind)));
         match self.side {
-            Side::Sell => (self.prices.3, self.prices.2.or_else(|| self.prices.1)),
-            Side::Buy => (self.prices.2.or_else(|| self.prices.1), self.prices.3),
+            Side::Sell => {
+                let stop = stops.reduce(|closest, stop| {
+                    if stop.price < closest.price {
+                        stop
+                    } else {
+                        closest
+                    }
+                });
+                (take_profit, stop)
+            }
+            Side::Buy => {
+                let stop = stops.reduce(|closest, stop| {
+                    if stop.price > closest.price {
+                        stop
+                    } else {
+                        closest
+                    }
+                });
+                (stop, take_profit)
+            }
             Side::None => unreachable!(),
         }
     }
 }
 
+/// Price level that closes a trade once reached
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThresholdKind {
+    Liquidation,
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+}
+
+impl From<ThresholdKind> for SignalCategory {
+    fn from(kind: ThresholdKind) -> Self {
+        match kind {
+            ThresholdKind::Liquidation => SignalCategory::LeverageBankrupcty,
+            ThresholdKind::StopLoss => SignalCategory::StopLoss,
+            ThresholdKind::TakeProfit => SignalCategory::TakeProfit,
+            ThresholdKind::TrailingStop => SignalCategory::TrailingStopLoss,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceThreshold {
+    pub kind: ThresholdKind,
+    pub price: f64,
+}
+
+impl PriceThreshold {
+    pub fn new(price: f64, kind: ThresholdKind) -> Self {
+        Self { kind, price }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BenchmarkTradeError {
     UnitsLessThanMinSize {
-        min_expenditure: f32,
+        min_expenditure: f64,
     },
     UnitsMoreThanMaxSize {
-        max_expenditure: f32,
-        expenditure: f32,
+        max_expenditure: f64,
+        expenditure: f64,
     },
     ValueLessThanNotionalMin {
-        min_expenditure: f32,
+        min_expenditure: f64,
     },
     ZeroUnits,
 }
 
-pub fn calculate_remainder(dividend: f32, divisor: f32) -> f32 {
+pub fn calculate_remainder(dividend: f64, divisor: f64) -> f64 {
     dividend.rem_euclid(divisor)
 }
 
-pub fn count_decimal_places(value: f32) -> i32 {
-    let decimal_str = value.to_string();
+pub fn round_down_nth_decimal(n: f64, decimals: i32) -> f64 {
+    let multiplier = 10.0_f64.powi(decimals);
+    (n * multiplier).floor() / multiplier
+}
 
-    if let Some(dot_idx) = decimal_str.find('.') {
-        (decimal_str.len() - dot_idx - 1) as i32
-    } else {
-        0
-    }
+pub fn round_nth_decimal(n: f64, decimals: i32) -> f64 {
+    let multiplier = 10.0_f64.powi(decimals);
+    (n * multiplier).round() / multiplier
 }
 
-pub fn round_down_nth_decimal(n: f32, decimals: i32) -> f32 {
-    let multiplier = 10.0_f32.powi(decimals);
-    (n * multiplier).floor() / multiplier
+/// Rounds to the closest multiple of `step`, whose decimals clean the float error of the multiplication
+pub fn round_to_step(n: f64, step: f64, step_decimals: i32) -> f64 {
+    round_nth_decimal((n / step).round() * step, step_decimals)
 }
 
-pub fn round_nth_decimal(n: f32, decimals: i32) -> f32 {
-    let multiplier = 10.0_f32.powi(decimals);
-    (n * multiplier).round() / multiplier
+/// Rounds down to a multiple of `step`, values already at a multiple staying there despite float errors
+pub fn round_down_to_step(n: f64, step: f64, step_decimals: i32) -> f64 {
+    let steps = n / step;
+    // the division may land a few ulps short of a whole number of steps
+    let steps = (steps + steps.abs() * 4.0 * f64::EPSILON).floor();
+    round_nth_decimal(steps * step, step_decimals)
 }
 
 #[derive(Clone, Copy)]
 pub struct NewBenchmarkTradeParams {
-    pub allocation_pct: f32,
-    pub current_balance: f32,
-    pub leverage_factor: f32,
-    pub minimum_notional_value: Option<f32>,
-    pub open_order_fee_rate: f32,
-    pub order_sizes: (f32, f32), // (min,max)
-    pub price: f32,
+    pub allocation_pct: f64,
+    pub current_balance: f64,
+    pub leverage_factor: f64,
+    pub margin: BenchmarkMargin,
+    pub minimum_notional_value: Option<f64>,
+    pub open_order_fee_rate: f64,
+    pub order_sizes: (f64, f64), // (min,max)
+    pub price: f64,
     pub price_locks: (Option<PriceLock>, Option<PriceLock>), // (stop_loss, take_profit)
     pub side: Side,
-    pub symbol_decimals: i32,
-    pub taker_fee_rate: f32, // usually taker fee
-    pub tick_decimals: i32,
+    pub slippage: BenchmarkSlippage,
+    pub slippage_kline_range: Option<f64>, // None for orders resting on the book, filled without slippage
+    pub steps: ContractSteps,
+    pub taker_fee_rate: f64, // usually taker fee
+    pub trailing_stop_loss: Option<TrailingStopLoss>,
 }
 
 impl NewBenchmarkTradeParams {
     pub fn new(
-        allocation_pct: f32,
-        current_balance: f32,
-        leverage_factor: f32,
-        minimum_notional_value: Option<f32>,
-        open_order_fee_rate: f32,
-        order_sizes: (f32, f32), // (min,max)
-        price: f32,
+        allocation_pct: f64,
+        current_balance: f64,
+        leverage_factor: f64,
+        margin: BenchmarkMargin,
+        minimum_notional_value: Option<f64>,
+        open_order_fee_rate: f64,
+        order_sizes: (f64, f64), // (min,max)
+        price: f64,
         price_locks: (Option<PriceLock>, Option<PriceLock>), // (stop_loss, take_profit)
         side: Side,
-        symbol_decimals: i32,
-        taker_fee_rate: f32,
-        tick_decimals: i32,
+        slippage: BenchmarkSlippage,
+        slippage_kline_range: Option<f64>,
+        steps: ContractSteps,
+        taker_fee_rate: f64,
+        trailing_stop_loss: Option<TrailingStopLoss>,
     ) -> Self {
         Self {
             allocation_pct,
             current_balance,
             leverage_factor,
+            margin,
             minimum_notional_value,
             open_order_fee_rate,
             order_sizes,
             price,
             price_locks,
             side,
-            symbol_decimals,
+            slippage,
+            slippage_kline_range,
+            steps,
             taker_fee_rate,
-            tick_decimals,
+            trailing_stop_loss,
         }
     }
 }
@@ -220,16 +626,24 @@ pub fn new_benchmark_trade(
         allocation_pct,
         current_balance,
         leverage_factor,
+        margin,
         minimum_notional_value,
         open_order_fee_rate,
         order_sizes,
         price,
         price_locks,
         side,
-        symbol_decimals,
+        slippage,
+        slippage_kline_range,
+        steps,
         taker_fee_rate,
-        tick_decimals,
+        trailing_stop_loss,
     } = params;
+    let order_price = price;
+    let price = match slippage_kline_range {
+        Some(kline_range) => slippage.get_slipped_price(price, side, kline_range, steps),
+        None => price,
+    };
     let price_lock_modifier = if side == Side::Sell {
         taker_fee_rate
     } else if side == Side::Buy {
@@ -238,11 +652,10 @@ pub fn new_benchmark_trade(
         unreachable!();
     };
     let expenditure =
-        round_down_nth_decimal(allocation_pct * current_balance / 100_f32, tick_decimals);
-    let units = round_down_nth_decimal(
+        round_down_nth_decimal(allocation_pct * current_balance / 100_f64, USDT_DECIMALS);
+    let units = steps.round_down_units(
         expenditure * leverage_factor
             / (price * (((2.0 * taker_fee_rate) * leverage_factor) + (1.0 + price_lock_modifier))),
-        symbol_decimals,
     );
 
     if units == 0.0 {
@@ -255,7 +668,7 @@ pub fn new_benchmark_trade(
                 * (price
                     * (((2.0 * taker_fee_rate) * leverage_factor) + (1.0 + price_lock_modifier)))
                 / leverage_factor,
-            tick_decimals,
+            USDT_DECIMALS,
         );
         return Err(BenchmarkTradeError::UnitsLessThanMinSize { min_expenditure });
     }
@@ -266,7 +679,7 @@ pub fn new_benchmark_trade(
                 * (price
                     * (((2.0 * taker_fee_rate) * leverage_factor) + (1.0 + price_lock_modifier)))
                 / leverage_factor,
-            tick_decimals,
+            USDT_DECIMALS,
         );
         return Err(BenchmarkTradeError::UnitsMoreThanMaxSize {
             max_expenditure,
@@ -274,7 +687,7 @@ pub fn new_benchmark_trade(
         });
     }
 
-    let order_value = round_nth_decimal(units * price, tick_decimals);
+    let order_value = round_nth_decimal(units * price, USDT_DECIMALS);
     if let Some(minimum_notional_value) = minimum_notional_value {
         if order_value < minimum_notional_value {
             return Err(BenchmarkTradeError::ValueLessThanNotionalMin {
@@ -282,19 +695,30 @@ pub fn new_benchmark_trade(
             });
         }
     }
-    let initial_margin = round_nth_decimal(order_value / leverage_factor, tick_decimals);
+    let initial_margin = round_nth_decimal(order_value / leverage_factor, USDT_DECIMALS);
+    let margin_balance = if margin.is_cross {
+        current_balance
+    } else {
+        initial_margin
+    };
     // let balance_remainder = round_down_nth_decimal(expenditure - initial_margin, tick_decimals);
 
+    let open_slippage = round_nth_decimal((price - order_price).abs() * units, USDT_DECIMALS);
+
     let trade = BenchmarkTrade::new(
         initial_margin,
         leverage_factor,
+        margin,
+        margin_balance,
         open_order_fee_rate,
+        open_slippage,
         price,
         price_locks,
         side,
-        symbol_decimals,
+        slippage,
+        steps,
+        trailing_stop_loss,
         units,
-        tick_decimals,
     );
     Ok(trade)
 }
@@ -0,0 +1,157 @@
+use super::functions::{BenchmarkEngine, BenchmarkResult, INITIAL_BENCHMARK_BALANCE};
+use crate::trader::Trader;
+use common::structs::Symbol;
+use common::traits::exchange::TraderHelper;
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::collections::HashSet;
+
+/// Traded symbol of a portfolio, benchmarked over its own strategy data.
+/// Its trader settings set how the symbol trades, its `allocation_percentage` being taken from the shared wallet.
+pub struct PortfolioLeg<'a> {
+    pub strategy_df: DataFrame,
+    pub trader: &'a Trader,
+}
+
+impl<'a> PortfolioLeg<'a> {
+    pub fn new(strategy_df: DataFrame, trader: &'a Trader) -> Self {
+        Self {
+            strategy_df,
+            trader,
+        }
+    }
+}
+
+/// Benchmark results of several symbols traded from a shared wallet
+pub struct PortfolioBenchmark {
+    /// Benchmark data of each traded symbol, in legs order.
+    /// Balances only hold the results of the symbol trades, starting from the initial wallet balance.
+    pub benchmark_dfs: Vec<(&'static Symbol, DataFrame)>,
+    /// Combined equity curve, with the realized balance (`{symbol}_balance`) and position (`{symbol}_position`)
+    /// of each symbol, alongside the realized wallet `balance`
+    pub equity_df: DataFrame,
+}
+
+pub fn get_portfolio_balance_col(symbol_name: &str) -> String {
+    format!("{}_balance", symbol_name)
+}
+
+pub fn get_portfolio_position_col(symbol_name: &str) -> String {
+    format!("{}_position", symbol_name)
+}
+
+/// Returns the balance of each kline, valuing open positions at the balance they were opened from
+fn get_realized_balances(benchmark_df: &DataFrame) -> Result<Vec<f64>, GlowError> {
+    let balances = benchmark_df.column("balance")?.f64()?;
+    let positions = benchmark_df.column("position")?.i32()?;
+    let mut realized_balance = INITIAL_BENCHMARK_BALANCE;
+    let realized_balances = balances
+        .into_iter()
+        .zip(positions)
+        .map(|(balance, position)| {
+            if position == Some(0) {
+                realized_balance = balance.unwrap_or(realized_balance);
+            }
+            realized_balance
+        })
+        .collect();
+    Ok(realized_balances)
+}
+
+fn validate_legs(legs: &[PortfolioLeg]) -> Result<(), GlowError> {
+    let Some(first_leg) = legs.first() else {
+        return Err(GlowError::new_str(
+            "Invalid portfolio",
+            "Portfolio must have at least one traded symbol",
+        ));
+    };
+    let start_times = first_leg.strategy_df.column("start_time")?;
+    let mut traded_symbols = HashSet::new();
+    for leg in legs {
+        let traded_symbol = leg.trader.trader_exchange.get_traded_symbol();
+        if !traded_symbols.insert(traded_symbol.name) {
+            return Err(GlowError::new(
+                "Invalid portfolio".to_owned(),
+                format!("{} is traded more than once", traded_symbol.name),
+            ));
+        }
+        if !leg.strategy_df.column("start_time")?.equals(start_times) {
+            return Err(GlowError::new(
+                "Invalid portfolio".to_owned(),
+                format!(
+                    "{} klines start times differ from the ones of {}",
+                    traded_symbol.name,
+                    first_leg.trader.trader_exchange.get_traded_symbol().name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Benchmarks several traded symbols sharing a wallet, whose klines must share their start times.
+/// On each kline, symbols are stepped in legs order, so that earlier legs get to open positions first.
+/// Positions that can't be opened with the wallet funds left are skipped.
+pub fn compute_portfolio_positions(
+    legs: Vec<PortfolioLeg>,
+) -> Result<PortfolioBenchmark, GlowError> {
+    validate_legs(&legs)?;
+
+    let mut engines = legs
+        .iter()
+        .map(|leg| BenchmarkEngine::new(leg.trader, &leg.strategy_df))
+        .collect::<Result<Vec<BenchmarkEngine>, GlowError>>()?;
+    let mut wallet_balance = INITIAL_BENCHMARK_BALANCE;
+    let height = legs[0].strategy_df.height();
+
+    for _ in 1..height {
+        for engine in engines.iter_mut() {
+            // engines trade from the wallet, while keeping only their own results
+            let leg_balance = engine.get_last_balance();
+            match engine.step(wallet_balance) {
+                Ok(updated_wallet_balance) => {
+                    engine.set_last_balance(leg_balance + updated_wallet_balance - wallet_balance);
+                    wallet_balance = updated_wallet_balance;
+                }
+                Err(_) => {
+                    engine.skip(wallet_balance)?;
+                    engine.set_last_balance(leg_balance);
+                }
+            }
+        }
+    }
+
+    let results: Vec<BenchmarkResult> = engines.into_iter().map(|engine| engine.finish()).collect();
+    let start_time_col = legs[0].strategy_df.column("start_time")?.clone();
+    let mut wallet_balances = vec![INITIAL_BENCHMARK_BALANCE; height];
+    let mut equity_cols = vec![start_time_col];
+    let mut benchmark_dfs = vec![];
+    for (leg, result) in legs.into_iter().zip(results) {
+        let traded_symbol = leg.trader.trader_exchange.get_traded_symbol();
+        let benchmark_df = result.append_to_df(leg.strategy_df)?;
+        let realized_balances = get_realized_balances(&benchmark_df)?;
+        wallet_balances
+            .iter_mut()
+            .zip(realized_balances.iter())
+            .for_each(|(wallet_balance, realized_balance)| {
+                *wallet_balance += realized_balance - INITIAL_BENCHMARK_BALANCE
+            });
+        equity_cols.push(Column::new(
+            get_portfolio_balance_col(traded_symbol.name).into(),
+            realized_balances,
+        ));
+        equity_cols.push(
+            benchmark_df
+                .column("position")?
+                .clone()
+                .with_name(get_portfolio_position_col(traded_symbol.name).into()),
+        );
+        benchmark_dfs.push((traded_symbol, benchmark_df));
+    }
+    equity_cols.push(Column::new("balance".into(), wallet_balances));
+
+    Ok(PortfolioBenchmark {
+        benchmark_dfs,
+        equity_df: DataFrame::new(equity_cols)?,
+    })
+}
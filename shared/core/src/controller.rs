@@ -1,11 +1,18 @@
-use crate::{config::BenchmarkSettings, data_feed::DataFeed, trader::Trader};
+use crate::{
+    config::BenchmarkSettings,
+    data_feed::DataFeed,
+    monte_carlo::{MonteCarloReport, MonteCarloSettings},
+    trader::Trader,
+};
 
 use super::performance::Performance;
 use chrono::{DateTime, Duration, Utc};
-use common::structs::TradingSettings;
+use common::structs::{Clock, Statistics, TradingSettings};
 use common::traits::exchange::TraderHelper;
 use exchanges::enums::{DataProviderExchangeWrapper, TraderExchangeWrapper};
+use glow_error::GlowError;
 use strategy::{Strategy, StrategyId};
+use tokio_stream::StreamExt;
 
 #[derive(Clone)]
 pub struct Controller {
@@ -16,52 +23,72 @@ pub struct Controller {
 }
 
 impl Controller {
-    pub fn new(run_benchmark_only: bool) -> Self {
+    pub fn new(run_benchmark_only: bool) -> Result<Self, GlowError> {
         let benchmark_settings = BenchmarkSettings::load_or_default();
+        let trading_settings = TradingSettings::load_or_default();
+        let strategy = Strategy::new(
+            benchmark_settings.strategy_id,
+            trading_settings.symbols_pair,
+        )?;
+
+        Self::from_settings(
+            benchmark_settings,
+            &trading_settings,
+            &strategy,
+            run_benchmark_only,
+        )
+    }
+
+    /// Builds a controller without reading or writing config files
+    pub fn from_settings(
+        benchmark_settings: BenchmarkSettings,
+        trading_settings: &TradingSettings,
+        strategy: &Strategy,
+        run_benchmark_only: bool,
+    ) -> Result<Self, GlowError> {
         let BenchmarkSettings {
             datetimes,
-            strategy_id,
             data_provider_id,
             trader_exchange_id,
+            ..
         } = benchmark_settings;
-        let trading_settings = TradingSettings::load_or_default();
-        let strategy = Strategy::new(strategy_id, trading_settings.symbols_pair);
 
         let default_data_provider_exchange =
-            DataProviderExchangeWrapper::new(data_provider_id, &strategy, &trading_settings);
+            DataProviderExchangeWrapper::new(data_provider_id, strategy, trading_settings)?;
 
         let data_feed = DataFeed::new(
             datetimes,
             default_data_provider_exchange,
             run_benchmark_only,
-            &strategy,
-            &trading_settings,
-        );
+            strategy,
+            trading_settings,
+        )?;
 
         let default_trader_exchange =
-            TraderExchangeWrapper::new(trader_exchange_id, &trading_settings);
+            TraderExchangeWrapper::new(trader_exchange_id, trading_settings);
 
         let trader = Trader::new(
             &data_feed.strategy_data_emitter,
             default_trader_exchange,
             &data_feed.trading_data,
             &data_feed.minimum_klines_for_benchmarking,
+            &Clock::default(),
         );
 
         let initial_datetime = datetimes.1.unwrap() + Duration::days(1);
 
         let performance = Performance::new(
             initial_datetime,
-            &trading_settings,
+            trading_settings,
             &trader.performance_data_emitter,
         );
 
-        Self {
+        Ok(Self {
             benchmark_settings,
             data_feed,
             performance,
             trader,
-        }
+        })
     }
 
     pub fn patch_benchmark_datetimes(
@@ -77,23 +104,25 @@ impl Controller {
             .patch_benchmark_datetimes(benchmark_start, benchmark_end);
     }
 
-    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
-        self.data_feed.patch_trading_settings(trading_settings);
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) -> Result<(), GlowError> {
+        self.data_feed.patch_trading_settings(trading_settings)?;
         self.trader.patch_settings(trading_settings);
         self.performance.patch_settings(trading_settings);
         let _ = trading_settings.save_config();
+        Ok(())
     }
 
-    pub fn patch_strategy_id(&mut self, strategy_id: StrategyId) {
-        self.benchmark_settings.strategy_id = strategy_id;
-        let _ = self.benchmark_settings.save_config();
+    pub fn patch_strategy_id(&mut self, strategy_id: StrategyId) -> Result<(), GlowError> {
         let symbols_pair = self
             .trader
             .trader_exchange
             .get_trading_settings()
             .symbols_pair;
-        let updated_strategy = Strategy::new(strategy_id, symbols_pair);
-        self.data_feed.patch_strategy(&updated_strategy);
+        let updated_strategy = Strategy::new(strategy_id, symbols_pair)?;
+        self.data_feed.patch_strategy(&updated_strategy)?;
+        self.benchmark_settings.strategy_id = strategy_id;
+        let _ = self.benchmark_settings.save_config();
+        Ok(())
     }
 
     pub fn init(&self) {
@@ -101,4 +130,29 @@ impl Controller {
         self.trader.init();
         self.data_feed.init();
     }
+
+    /// Starts the pipeline and waits until Performance emits the benchmark statistics,
+    /// or until any stage reports a failure.
+    pub async fn run_benchmark(&self) -> Result<Statistics, GlowError> {
+        self.performance.benchmark_stats_emitter.next(None);
+        let mut subscription = self.performance.benchmark_stats_emitter.subscribe();
+        self.init();
+        while let Some(result) = subscription.next().await {
+            if let Some(result) = result {
+                return result;
+            }
+        }
+        Err(GlowError::new_str(
+            "Benchmark Error",
+            "Benchmark stats emitter closed before benchmark completion",
+        ))
+    }
+
+    /// Runs a Monte Carlo analysis over the trading sessions of the last benchmark
+    pub fn run_monte_carlo(
+        &self,
+        settings: &MonteCarloSettings,
+    ) -> Result<MonteCarloReport, GlowError> {
+        self.performance.run_benchmark_monte_carlo(settings)
+    }
 }
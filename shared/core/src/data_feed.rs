@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
 use common::enums::trading_data_update::TradingDataUpdate;
+use common::functions::funding::get_funding_rate_col;
+use common::functions::intrabar::{get_intrabar_fields, is_intrabar_granularity};
 use common::structs::{Symbol, TradingSettings};
 use common::{structs::BehaviorSubject, traits::exchange::DataProviderExchange};
 use exchanges::enums::DataProviderExchangeWrapper;
@@ -14,6 +16,7 @@ use tokio_stream::StreamExt;
 pub struct DataFeed {
     benchmark_datetimes: (Option<DateTime<Utc>>, Option<DateTime<Utc>>), // (start, end)
     data_provider_exchange: DataProviderExchangeWrapper,
+    intrabar_symbol: Option<&'static Symbol>,
     kline_data_listener: BehaviorSubject<TradingDataUpdate>,
     run_benchmark_only: bool, // TODO check if this is really necessary
     pub minimum_klines_for_benchmarking: Arc<RwLock<u32>>,
@@ -32,16 +35,30 @@ impl DataFeed {
             schema_fields.push(Field::new(high_col.into(), DataType::Float64));
             schema_fields.push(Field::new(low_col.into(), DataType::Float64));
             schema_fields.push(Field::new(close_col.into(), DataType::Float64));
+            schema_fields.push(Field::new(
+                get_funding_rate_col(symbol.name).into(),
+                DataType::Float64,
+            ));
         }
     }
 
-    fn insert_indicators_fields(schema_fields: &mut Vec<Field>, strategy: &Strategy) {
-        let columns = strategy.get_indicators_columns();
+    fn insert_intrabar_fields(schema_fields: &mut Vec<Field>, intrabar_symbol: Option<&Symbol>) {
+        if let Some(symbol) = intrabar_symbol {
+            schema_fields.extend(get_intrabar_fields(symbol.name));
+        }
+    }
+
+    fn insert_indicators_fields(
+        schema_fields: &mut Vec<Field>,
+        strategy: &Strategy,
+    ) -> Result<(), GlowError> {
+        let columns = strategy.get_indicators_columns()?;
 
         for (name, dtype) in columns {
             let field = Field::new(name.as_str().into(), dtype.clone());
             schema_fields.push(field);
         }
+        Ok(())
     }
 
     fn insert_signals_fields(schema_fields: &mut Vec<Field>, strategy: &Strategy) {
@@ -53,12 +70,18 @@ impl DataFeed {
 
     fn insert_trading_fields(schema_fields: &mut Vec<Field>) -> Schema {
         schema_fields.push(Field::new("trade_fees".into(), DataType::Float64));
+        schema_fields.push(Field::new("slippage".into(), DataType::Float64));
+        schema_fields.push(Field::new("funding_fees".into(), DataType::Float64));
         schema_fields.push(Field::new("units".into(), DataType::Float64));
         schema_fields.push(Field::new("profit_and_loss".into(), DataType::Float64));
         schema_fields.push(Field::new("returns".into(), DataType::Float64));
         schema_fields.push(Field::new("balance".into(), DataType::Float64));
         schema_fields.push(Field::new("position".into(), DataType::Int32));
         schema_fields.push(Field::new("action".into(), DataType::String));
+        schema_fields.push(Field::new(
+            "exit_time".into(),
+            DataType::Datetime(TimeUnit::Milliseconds, None),
+        ));
         Schema::from_iter(schema_fields.clone())
     }
 
@@ -75,22 +98,36 @@ impl DataFeed {
         *lock = value;
     }
 
-    fn set_schema(strategy: &Strategy, unique_symbols: &Vec<&Symbol>) -> (Schema, DataFrame, u32) {
+    /// Traded symbol whose 1 minute path is kept within coarse klines, for the benchmark to resolve price thresholds
+    fn get_intrabar_symbol(trading_settings: &TradingSettings) -> Option<&'static Symbol> {
+        if is_intrabar_granularity(&trading_settings.granularity) {
+            Some(trading_settings.symbols_pair.traded)
+        } else {
+            None
+        }
+    }
+
+    fn set_schema(
+        strategy: &Strategy,
+        unique_symbols: &Vec<&Symbol>,
+        intrabar_symbol: Option<&Symbol>,
+    ) -> Result<(Schema, DataFrame, u32), GlowError> {
         let mut schema_fields = vec![Field::new(
             "start_time".into(),
             DataType::Datetime(TimeUnit::Milliseconds, None),
         )];
         Self::insert_kline_fields(&mut schema_fields, &unique_symbols);
-        Self::insert_indicators_fields(&mut schema_fields, &strategy);
+        Self::insert_intrabar_fields(&mut schema_fields, intrabar_symbol);
+        Self::insert_indicators_fields(&mut schema_fields, &strategy)?;
         Self::insert_signals_fields(&mut schema_fields, &strategy);
-        let minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation();
+        let minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation()?;
         let trading_data_schema = Self::insert_trading_fields(&mut schema_fields);
         let trading_data_df = DataFrame::empty_with_schema(&trading_data_schema);
-        (
+        Ok((
             trading_data_schema,
             trading_data_df,
             minimum_klines_for_benchmarking,
-        )
+        ))
     }
 
     pub fn new(
@@ -99,7 +136,7 @@ impl DataFeed {
         run_benchmark_only: bool,
         strategy: &Strategy,
         trading_settings: &TradingSettings,
-    ) -> DataFeed {
+    ) -> Result<DataFeed, GlowError> {
         if let (Some(benchmark_start), Some(benchmark_end)) = benchmark_datetimes {
             assert!(
                 benchmark_start < benchmark_end,
@@ -109,15 +146,17 @@ impl DataFeed {
 
         let trading_data = Arc::new(Mutex::new(DataFrame::empty()));
         let unique_symbols = &trading_settings.get_unique_symbols();
+        let intrabar_symbol = Self::get_intrabar_symbol(trading_settings);
         let (trading_data_schema, trading_data_df, minimum_klines_for_benchmarking) =
-            Self::set_schema(strategy, unique_symbols);
+            Self::set_schema(strategy, unique_symbols, intrabar_symbol)?;
         Self::update_trading_data_df(&trading_data, &trading_data_df);
         let strategy_data_emitter = BehaviorSubject::new(TradingDataUpdate::default());
         let kline_data_listener = data_provider_exchange.get_kline_data_emitter().clone();
 
-        DataFeed {
+        Ok(DataFeed {
             benchmark_datetimes,
             data_provider_exchange,
+            intrabar_symbol,
             run_benchmark_only,
             kline_data_listener,
             minimum_klines_for_benchmarking: Arc::new(RwLock::new(minimum_klines_for_benchmarking)),
@@ -126,7 +165,7 @@ impl DataFeed {
             trading_data,
             trading_data_schema,
             unique_symbols: unique_symbols.clone(),
-        }
+        })
     }
 
     /// this must be run before init
@@ -138,29 +177,58 @@ impl DataFeed {
         self.benchmark_datetimes = (benchmark_start, benchmark_end)
     }
 
-    pub fn patch_trading_settings(&mut self, trading_settings: &TradingSettings) {
+    pub fn patch_trading_settings(
+        &mut self,
+        trading_settings: &TradingSettings,
+    ) -> Result<(), GlowError> {
         self.data_provider_exchange.patch_settings(trading_settings);
         let unique_symbols = trading_settings.symbols_pair.get_unique_symbols();
+        let intrabar_symbol = Self::get_intrabar_symbol(trading_settings);
         let (trading_data_schema, trading_data_df, minimum_klines_for_benchmarking) =
-            Self::set_schema(&self.strategy, &unique_symbols);
+            Self::set_schema(&self.strategy, &unique_symbols, intrabar_symbol)?;
         Self::update_trading_data_df(&self.trading_data, &trading_data_df);
         Self::update_minimum_klines_for_benchmarking(
             &self.minimum_klines_for_benchmarking,
             minimum_klines_for_benchmarking,
         );
         self.trading_data_schema = trading_data_schema;
+        self.intrabar_symbol = intrabar_symbol;
+        Ok(())
     }
 
-    pub fn patch_strategy(&mut self, strategy: &Strategy) {
-        self.data_provider_exchange.patch_strategy(strategy);
+    pub fn patch_strategy(&mut self, strategy: &Strategy) -> Result<(), GlowError> {
+        self.data_provider_exchange.patch_strategy(strategy)?;
         let (trading_data_schema, trading_data_df, minimum_klines_for_benchmarking) =
-            Self::set_schema(strategy, &self.unique_symbols);
+            Self::set_schema(strategy, &self.unique_symbols, self.intrabar_symbol)?;
         Self::update_trading_data_df(&self.trading_data, &trading_data_df);
         Self::update_minimum_klines_for_benchmarking(
             &self.minimum_klines_for_benchmarking,
             minimum_klines_for_benchmarking,
         );
         self.trading_data_schema = trading_data_schema;
+        Ok(())
+    }
+
+    /// Loads the benchmark klines through the data provider, without starting the feed handlers.
+    /// Allows evaluating several strategies over the same kline frame.
+    pub async fn fetch_benchmark_klines(&self) -> Result<DataFrame, GlowError> {
+        let mut data_provider_binding = self.data_provider_exchange.clone();
+        data_provider_binding
+            .init(
+                self.benchmark_datetimes.0,
+                self.benchmark_datetimes.1,
+                true,
+                self.trading_data_schema.clone(),
+            )
+            .await?;
+        match data_provider_binding.get_kline_data_emitter().value() {
+            TradingDataUpdate::Initial(klines_df) => Ok(klines_df),
+            TradingDataUpdate::Failed(error) => Err(error),
+            _ => Err(GlowError::new_str(
+                "Missing klines",
+                "Data provider didn't emit initial klines",
+            )),
+        }
     }
 
     fn set_initial_strategy_data(
@@ -173,7 +241,10 @@ impl DataFeed {
         Ok(initial_strategy_lf)
     }
 
-    fn handle_initial_klines(&self, initial_klines_df: DataFrame) -> Result<DataFrame, GlowError> {
+    pub(crate) fn handle_initial_klines(
+        &self,
+        initial_klines_df: DataFrame,
+    ) -> Result<DataFrame, GlowError> {
         let initial_klines_lf = initial_klines_df.lazy();
         let initial_klines_lf = self.set_initial_strategy_data(initial_klines_lf)?;
         let initial_strategy_data = initial_klines_lf.collect()?;
@@ -215,6 +286,8 @@ impl DataFeed {
                             }
                             Err(error) => {
                                 println!("handle_initial_klines error {:?}", error);
+                                let payload = TradingDataUpdate::Failed(error);
+                                data_feed.strategy_data_emitter.next(payload);
                             }
                         }
                     }
@@ -229,6 +302,10 @@ impl DataFeed {
                             }
                         }
                     }
+                    TradingDataUpdate::Failed(error) => {
+                        let payload = TradingDataUpdate::Failed(error);
+                        data_feed.strategy_data_emitter.next(payload);
+                    }
                     _ => {}
                 }
             }
@@ -241,9 +318,10 @@ impl DataFeed {
         let trading_data_schema = self.trading_data_schema.clone();
         let benchmark_start = self.benchmark_datetimes.0;
         let benchmark_end = self.benchmark_datetimes.1;
+        let strategy_data_emitter = self.strategy_data_emitter.clone();
 
         spawn(async move {
-            let _ = data_provider_binding
+            let result = data_provider_binding
                 .init(
                     benchmark_start,
                     benchmark_end,
@@ -251,6 +329,10 @@ impl DataFeed {
                     trading_data_schema,
                 )
                 .await;
+            if let Err(error) = result {
+                println!("data provider init error {:?}", error);
+                strategy_data_emitter.next(TradingDataUpdate::Failed(error));
+            }
         })
     }
 
@@ -258,4 +340,14 @@ impl DataFeed {
         self.init_kline_data_handler();
         self.init_data_provider_handler();
     }
+
+    /// Handles klines emitted through [`Self::emit_klines`], without initializing the data provider
+    pub fn init_replay(&self) {
+        self.init_kline_data_handler();
+    }
+
+    /// Emits klines as if they were sent by the data provider
+    pub fn emit_klines(&self, klines_data: TradingDataUpdate) {
+        self.kline_data_listener.next(klines_data);
+    }
 }
@@ -3,4 +3,6 @@ pub mod data_feed;
 pub mod performance;
 pub mod trader;
 pub mod config;
-pub mod benchmark;
\ No newline at end of file
+pub mod benchmark;
+pub mod replay;
+pub mod monte_carlo;
\ No newline at end of file
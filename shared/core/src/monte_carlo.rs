@@ -0,0 +1,328 @@
+use common::functions::csv::save_csv;
+use glow_error::GlowError;
+use polars::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Percentiles reported for each simulated metric
+pub static MONTE_CARLO_PERCENTILES: [f64; 5] = [5.0, 25.0, 50.0, 75.0, 95.0];
+
+/// How the trading sessions returns are drawn for each simulation
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub enum ResamplingMethod {
+    /// Reorders the returns without replacement, so only the path (e.g. drawdowns) changes
+    #[serde(rename = "shuffle")]
+    Shuffle,
+    /// Draws each return with replacement
+    #[serde(rename = "bootstrap")]
+    Bootstrap,
+    /// Draws runs of consecutive returns with replacement (wrapping around the last one),
+    /// which keeps streaks of wins and losses of up to the given length
+    #[serde(rename = "block_bootstrap")]
+    BlockBootstrap(usize),
+}
+
+impl Display for ResamplingMethod {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Shuffle => write!(f, "shuffle"),
+            Self::Bootstrap => write!(f, "bootstrap"),
+            Self::BlockBootstrap(block_size) => write!(f, "block bootstrap ({})", block_size),
+        }
+    }
+}
+
+impl ResamplingMethod {
+    pub fn resample(&self, returns: &[f64], rng: &mut StdRng) -> Vec<f64> {
+        let len = returns.len();
+        match self {
+            Self::Shuffle => {
+                let mut resampled = returns.to_vec();
+                // Fisher-Yates
+                for index in (1..len).rev() {
+                    resampled.swap(index, rng.gen_range(0..=index));
+                }
+                resampled
+            }
+            Self::Bootstrap => (0..len).map(|_| returns[rng.gen_range(0..len)]).collect(),
+            Self::BlockBootstrap(block_size) => {
+                let block_size = (*block_size).max(1);
+                let mut resampled = Vec::with_capacity(len + block_size);
+                while resampled.len() < len {
+                    let block_start = rng.gen_range(0..len);
+                    resampled.extend(
+                        (block_start..block_start + block_size).map(|index| returns[index % len]),
+                    );
+                }
+                resampled.truncate(len);
+                resampled
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MonteCarloSettings {
+    pub iterations: usize,
+    pub method: ResamplingMethod,
+    /// Share of the initial balance that counts as ruin once lost, i.e. 0.5 for losing half of it
+    pub ruin_drawdown: f64,
+    /// Makes the simulations reproducible, they are drawn from entropy otherwise
+    pub seed: Option<u64>,
+}
+
+impl Default for MonteCarloSettings {
+    fn default() -> Self {
+        Self {
+            iterations: 1000,
+            method: ResamplingMethod::Bootstrap,
+            ruin_drawdown: 0.5,
+            seed: None,
+        }
+    }
+}
+
+/// Outcome of trading one resampled sequence of sessions
+#[derive(Clone, Copy, Debug)]
+pub struct MonteCarloSimulation {
+    pub final_balance: f64,
+    pub is_ruined: bool,
+    pub max_drawdown: f64,
+    pub sharpe_ratio: f64, // per session, mean return over its standard deviation
+}
+
+impl MonteCarloSimulation {
+    pub fn new(initial_balance: f64, returns: &[f64], ruin_drawdown: f64) -> Self {
+        let ruin_balance = initial_balance * (1.0 - ruin_drawdown);
+        let mut balance = initial_balance;
+        let mut peak_balance = initial_balance;
+        let mut max_drawdown = 0.0_f64;
+        let mut is_ruined = false;
+        for session_return in returns {
+            balance = f64::max(0.0, balance * (1.0 + session_return));
+            peak_balance = f64::max(peak_balance, balance);
+            if peak_balance > 0.0 {
+                max_drawdown = f64::max(max_drawdown, (peak_balance - balance) / peak_balance);
+            }
+            is_ruined |= balance <= ruin_balance;
+        }
+
+        let len = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / len;
+        let std = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / len).sqrt();
+        let sharpe_ratio = if std != 0.0 { mean / std } else { 0.0 };
+
+        Self {
+            final_balance: balance,
+            is_ruined,
+            max_drawdown,
+            sharpe_ratio,
+        }
+    }
+}
+
+pub struct MonteCarloReport {
+    pub initial_balance: f64,
+    pub sessions: usize,
+    pub settings: MonteCarloSettings,
+    pub simulations: Vec<MonteCarloSimulation>,
+}
+
+impl MonteCarloReport {
+    /// Values of the metric at `MONTE_CARLO_PERCENTILES`, linearly interpolated between simulations
+    pub fn get_percentiles(&self, metric: impl Fn(&MonteCarloSimulation) -> f64) -> Vec<f64> {
+        let mut values: Vec<f64> = self.simulations.iter().map(metric).collect();
+        values.sort_by(|a, b| a.total_cmp(b));
+        MONTE_CARLO_PERCENTILES
+            .iter()
+            .map(|percentile| get_percentile(&values, *percentile))
+            .collect()
+    }
+
+    /// Share of simulations that lost `ruin_drawdown` of the initial balance at some point
+    pub fn get_risk_of_ruin(&self) -> f64 {
+        let ruined_count = self
+            .simulations
+            .iter()
+            .filter(|simulation| simulation.is_ruined)
+            .count();
+        ruined_count as f64 / self.simulations.len() as f64
+    }
+
+    pub fn get_simulations_df(&self) -> Result<DataFrame, GlowError> {
+        let get_column = |name: &str, metric: fn(&MonteCarloSimulation) -> f64| {
+            Column::new(
+                name.into(),
+                self.simulations.iter().map(metric).collect::<Vec<f64>>(),
+            )
+        };
+        let df = DataFrame::new(vec![
+            Column::new(
+                "simulation".into(),
+                (0..self.simulations.len() as u32).collect::<Vec<u32>>(),
+            ),
+            get_column("final_balance", |simulation| simulation.final_balance),
+            get_column("max_drawdown", |simulation| simulation.max_drawdown),
+            get_column("sharpe_ratio", |simulation| simulation.sharpe_ratio),
+            Column::new(
+                "is_ruined".into(),
+                self.simulations
+                    .iter()
+                    .map(|simulation| simulation.is_ruined)
+                    .collect::<Vec<bool>>(),
+            ),
+        ])?;
+        Ok(df)
+    }
+
+    /// One row per percentile, alongside the risk of ruin
+    pub fn get_percentiles_df(&self) -> Result<DataFrame, GlowError> {
+        let risk_of_ruin = self.get_risk_of_ruin();
+        let df = DataFrame::new(vec![
+            Column::new("percentile".into(), MONTE_CARLO_PERCENTILES.to_vec()),
+            Column::new(
+                "final_balance".into(),
+                self.get_percentiles(|simulation| simulation.final_balance),
+            ),
+            Column::new(
+                "max_drawdown".into(),
+                self.get_percentiles(|simulation| simulation.max_drawdown),
+            ),
+            Column::new(
+                "sharpe_ratio".into(),
+                self.get_percentiles(|simulation| simulation.sharpe_ratio),
+            ),
+            Column::new(
+                "risk_of_ruin".into(),
+                vec![risk_of_ruin; MONTE_CARLO_PERCENTILES.len()],
+            ),
+        ])?;
+        Ok(df)
+    }
+
+    /// Saves the simulations and their percentiles as `{identifier}_monte_carlo_simulations.csv`
+    /// and `{identifier}_monte_carlo_percentiles.csv`
+    pub fn save_csv(&self, path: String, identifier: &str) -> Result<(), GlowError> {
+        let file_name = format!("{}_monte_carlo_simulations.csv", identifier);
+        save_csv(path.clone(), file_name, &self.get_simulations_df()?, true)?;
+        let file_name = format!("{}_monte_carlo_percentiles.csv", identifier);
+        save_csv(path, file_name, &self.get_percentiles_df()?, true)?;
+        Ok(())
+    }
+}
+
+impl Display for MonteCarloReport {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let format_row = |values: Vec<f64>, decimals: usize| {
+            values
+                .iter()
+                .map(|value| format!("{:>12.*}", decimals, value))
+                .collect::<String>()
+        };
+        let header = MONTE_CARLO_PERCENTILES
+            .iter()
+            .map(|percentile| format!("{:>12}", format!("P{}", percentile)))
+            .collect::<String>();
+        let final_balances = self.get_percentiles(|simulation| simulation.final_balance);
+        let max_drawdowns = self
+            .get_percentiles(|simulation| simulation.max_drawdown)
+            .into_iter()
+            .map(|max_drawdown| max_drawdown * 100.0)
+            .collect();
+        let sharpe_ratios = self.get_percentiles(|simulation| simulation.sharpe_ratio);
+        write!(
+            f,
+            r#"
+🎲 Monte Carlo: {} {} simulations of {} trading sessions
+{:<28}{}
+💰 Final balance (USDT):    {}
+📉 Max drawdown (%):        {}
+📝 Sharpe (per session):    {}
+☠️ Risk of ruin (%): {:.2}, losing {:.0}% of {:.4} USDT"#,
+            self.simulations.len(),
+            self.settings.method,
+            self.sessions,
+            "",
+            header,
+            format_row(final_balances, 4),
+            format_row(max_drawdowns, 2),
+            format_row(sharpe_ratios, 4),
+            self.get_risk_of_ruin() * 100.0,
+            self.settings.ruin_drawdown * 100.0,
+            self.initial_balance,
+        )
+    }
+}
+
+/// Returns of the balance over each trading session of a frame built by `calculate_trading_sessions`,
+/// alongside the balance the first session started from
+pub fn get_session_returns(sessions_df: &DataFrame) -> Result<(f64, Vec<f64>), GlowError> {
+    let balances: Vec<f64> = sessions_df
+        .column("balance")?
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+    let positions: Vec<i32> = sessions_df
+        .column("position")?
+        .i32()?
+        .into_no_null_iter()
+        .collect();
+    let Some(initial_balance) = balances.first().copied() else {
+        return Err(GlowError::new_str(
+            "Monte Carlo Error",
+            "Trading sessions are empty",
+        ));
+    };
+    let returns = balances
+        .windows(2)
+        .zip(positions.iter().skip(1))
+        .filter(|(balances, position)| **position != 0 && balances[0] != 0.0)
+        .map(|(balances, _)| balances[1] / balances[0] - 1.0)
+        .collect();
+    Ok((initial_balance, returns))
+}
+
+/// Trades resampled sequences of the trading sessions returns, starting each one from the initial balance
+pub fn run_monte_carlo(
+    sessions_df: &DataFrame,
+    settings: &MonteCarloSettings,
+) -> Result<MonteCarloReport, GlowError> {
+    let (initial_balance, returns) = get_session_returns(sessions_df)?;
+    if returns.is_empty() {
+        return Err(GlowError::new_str(
+            "Monte Carlo Error",
+            "No trading sessions to resample",
+        ));
+    }
+    if settings.iterations == 0 {
+        return Err(GlowError::new_str(
+            "Monte Carlo Error",
+            "At least one iteration is required",
+        ));
+    }
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let simulations = (0..settings.iterations)
+        .map(|_| {
+            let resampled_returns = settings.method.resample(&returns, &mut rng);
+            MonteCarloSimulation::new(initial_balance, &resampled_returns, settings.ruin_drawdown)
+        })
+        .collect();
+    Ok(MonteCarloReport {
+        initial_balance,
+        sessions: returns.len(),
+        settings: *settings,
+        simulations,
+    })
+}
+
+fn get_percentile(sorted_values: &[f64], percentile: f64) -> f64 {
+    let rank = percentile / 100.0 * (sorted_values.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    let weight = rank - lower as f64;
+    sorted_values[lower] * (1.0 - weight) + sorted_values[upper] * weight
+}
@@ -1,17 +1,25 @@
+use crate::{
+    benchmark::portfolio::PortfolioBenchmark,
+    monte_carlo::{run_monte_carlo, MonteCarloReport, MonteCarloSettings},
+};
 use chrono::{DateTime, Duration, Utc};
 use common::{
     constants::DAY_IN_MS,
-    enums::trading_data_update::TradingDataUpdate,
+    enums::{granularity::Granularity, trading_data_update::TradingDataUpdate},
     functions::{
         csv::{get_current_env_log_path, save_csv},
         performance::{
-            calculate_calmar_ratio, calculate_max_drawdown_and_duration,
-            calculate_risk_adjusted_returns, calculate_sharpe_ratio, calculate_sortino_ratio,
-            calculate_success_rate,
+            calculate_average_win_and_loss, calculate_cagr, calculate_calmar_ratio,
+            calculate_exposure_and_average_duration, calculate_max_consecutive_wins_and_losses,
+            calculate_max_drawdown_and_duration, calculate_per_trade_sharpe_ratio,
+            calculate_per_trade_sortino_ratio, calculate_period_returns, calculate_profit_factor,
+            calculate_recovery_factor, calculate_risk_adjusted_returns, calculate_sharpe_ratio,
+            calculate_sortino_ratio, calculate_success_rate, calculate_turnover,
+            calculate_ulcer_index,
         },
         round_down_nth_decimal,
     },
-    structs::{BehaviorSubject, Statistics, Symbol, SymbolsPair, TradingSettings},
+    structs::{BehaviorSubject, Statistics, Symbol, SymbolsPair, TradingMetrics, TradingSettings},
 };
 use glow_error::GlowError;
 use polars::prelude::*;
@@ -22,10 +30,13 @@ use tokio_stream::StreamExt;
 
 #[derive(Clone)]
 pub struct Performance {
+    benchmark_sessions: Arc<Mutex<DataFrame>>,
     benchmark_stats: Arc<Mutex<Statistics>>,
+    pub benchmark_stats_emitter: BehaviorSubject<Option<Result<Statistics, GlowError>>>,
     _http: Client,
     risk_free_returns: f64,
     initial_datetime: DateTime<Utc>,
+    returns_granularity: Granularity,
     symbols: SymbolsPair,
     traded_data_listener: BehaviorSubject<TradingDataUpdate>,
     trading_stats: Arc<Mutex<Statistics>>,
@@ -39,10 +50,13 @@ impl Performance {
     ) -> Self {
         let symbols = trading_settings.symbols_pair;
         Self {
+            benchmark_sessions: Arc::new(Mutex::new(DataFrame::empty())),
             benchmark_stats: Arc::new(Mutex::new(Statistics::default())),
+            benchmark_stats_emitter: BehaviorSubject::new(None),
             _http: Client::new(),
             risk_free_returns: 0.0,
             initial_datetime,
+            returns_granularity: Granularity::d1,
             symbols,
             traded_data_listener: traded_data_listener.clone(),
             trading_stats: Arc::new(Mutex::new(Statistics::default())),
@@ -63,6 +77,12 @@ impl Performance {
     pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
         self.symbols = trading_settings.symbols_pair;
     }
+
+    /// Sets the period the balance is sampled at for the Sharpe and Sortino ratios, daily by default.
+    /// The risk free returns are taken as the ones of that period.
+    pub fn patch_returns_granularity(&mut self, returns_granularity: Granularity) {
+        self.returns_granularity = returns_granularity;
+    }
 }
 
 // risk-adjusted-return = reward / risk = mean returns / std of returns
@@ -77,14 +97,18 @@ impl Performance {
         self.risk_free_returns = returns;
     }
 
-    fn set_benchmark_stats(&self, benchmark_trading_df: DataFrame) -> Result<(), GlowError> {
+    fn get_trading_journey_identifier(&self) -> String {
         let journey_formmated_datetime_start =
             self.initial_datetime.format("%H:%M-%d-%m-%Y").to_string();
 
-        let trading_journey_identifier = format!(
+        format!(
             "{}_{}_{}",
             journey_formmated_datetime_start, self.symbols.anchor.name, self.symbols.traded.name
-        );
+        )
+    }
+
+    fn set_benchmark_stats(&self, benchmark_trading_df: DataFrame) -> Result<Statistics, GlowError> {
+        let trading_journey_identifier = self.get_trading_journey_identifier();
 
         let path = get_current_env_log_path();
         let file_name = format!("{}_benchmark_data.csv", trading_journey_identifier);
@@ -94,36 +118,34 @@ impl Performance {
         let (benchmark_data, benchmark_stats) = calculate_benchmark_data(
             benchmark_trading_lf,
             self.risk_free_returns,
+            self.returns_granularity,
             self.symbols.traded,
         )?;
         {
             let mut lock = self.benchmark_stats.lock().unwrap();
-            *lock = benchmark_stats;
+            *lock = benchmark_stats.clone();
+        };
+        {
+            let mut lock = self.benchmark_sessions.lock().unwrap();
+            *lock = benchmark_data.clone();
         };
 
         let file_name = format!("{}_benchmark_trades.csv", trading_journey_identifier);
         save_csv(path, file_name, &benchmark_data, true)?;
 
-        Ok(())
+        Ok(benchmark_stats)
     }
 
     fn handle_initial_traded_data(&self, benchmark_trading_df: DataFrame) {
-        match self.set_benchmark_stats(benchmark_trading_df) {
-            Ok(()) => {}
-            Err(error) => {
-                println!("set_benchmark_stats error {:?}", error);
-            }
+        let result = self.set_benchmark_stats(benchmark_trading_df);
+        if let Err(error) = &result {
+            println!("set_benchmark_stats error {:?}", error);
         }
+        self.benchmark_stats_emitter.next(Some(result));
     }
 
     fn update_trading_stats(&self, traded_data: DataFrame) -> Result<(), GlowError> {
-        let journey_formmated_datetime_start =
-            self.initial_datetime.format("%H:%M-%d-%m-%Y").to_string();
-
-        let trading_journey_identifier = format!(
-            "{}_{}_{}",
-            journey_formmated_datetime_start, self.symbols.anchor.name, self.symbols.traded.name
-        );
+        let trading_journey_identifier = self.get_trading_journey_identifier();
         let trading_journey_start = self.initial_datetime.timestamp_millis();
         let filter_mask = traded_data
             .column("start_time")?
@@ -142,6 +164,7 @@ impl Performance {
         let (trading_data, trading_stats) = update_trading_data(
             &trading_data,
             self.risk_free_returns,
+            self.returns_granularity,
             self.symbols.traded,
             Some(self.initial_datetime),
         )?;
@@ -176,6 +199,9 @@ impl Performance {
                     TradingDataUpdate::Market(traded_data) => {
                         performance.handle_market_traded_data(traded_data)
                     }
+                    TradingDataUpdate::Failed(error) => {
+                        performance.benchmark_stats_emitter.next(Some(Err(error)))
+                    }
                     _ => {}
                 }
             }
@@ -185,12 +211,33 @@ impl Performance {
     pub fn init(&self) {
         self.init_traded_data_handler();
     }
+
+    /// Resamples the trading sessions of the last benchmark, saving the report next to its trades
+    pub fn run_benchmark_monte_carlo(
+        &self,
+        settings: &MonteCarloSettings,
+    ) -> Result<MonteCarloReport, GlowError> {
+        let benchmark_sessions = self.benchmark_sessions.lock().unwrap().clone();
+        if benchmark_sessions.is_empty() {
+            return Err(GlowError::new_str(
+                "Monte Carlo Error",
+                "A benchmark has to be run first",
+            ));
+        }
+        let report = run_monte_carlo(&benchmark_sessions, settings)?;
+        report.save_csv(
+            get_current_env_log_path(),
+            &self.get_trading_journey_identifier(),
+        )?;
+        Ok(report)
+    }
 }
 
 // TODO: move this inside performance impl
 pub fn calculate_benchmark_data(
     benchmark_trading_data: LazyFrame,
     risk_free_returns: f64,
+    returns_granularity: Granularity,
     traded_symbol: &Symbol,
 ) -> Result<(DataFrame, Statistics), GlowError> {
     let trades_lf = calculate_trades(benchmark_trading_data)?;
@@ -200,7 +247,7 @@ pub fn calculate_benchmark_data(
 
     let df = trading_lf.collect()?;
 
-    let benchmark_stats = calculate_trading_stats(&df, risk_free_returns)?;
+    let benchmark_stats = calculate_trading_stats(&df, risk_free_returns, returns_granularity)?;
 
     println!("\n📋 Benchmark stats \n{}", benchmark_stats);
 
@@ -210,6 +257,7 @@ pub fn calculate_benchmark_data(
 pub fn update_trading_data(
     trading_data: &DataFrame,
     risk_free_returns: f64,
+    returns_granularity: Granularity,
     traded_symbol: &Symbol,
     log_from_timestamp_on: Option<DateTime<Utc>>,
 ) -> Result<(DataFrame, Statistics), GlowError> {
@@ -222,7 +270,7 @@ pub fn update_trading_data(
 
     let df = trading_lf.collect()?;
 
-    let trading_stats = calculate_trading_stats(&df, risk_free_returns)?;
+    let trading_stats = calculate_trading_stats(&df, risk_free_returns, returns_granularity)?;
 
     Ok((df, trading_stats))
 }
@@ -305,6 +353,8 @@ pub fn calculate_trading_sessions(
         col("balance").last().alias("balance").name().keep(),
         col("returns").std(0).alias("risk"),
         col("trade_fees").sum().alias("trade_fees").name().keep(),
+        col("slippage").sum().alias("slippage").name().keep(),
+        col("funding_fees").sum().alias("funding_fees").name().keep(),
         col("returns")
             .apply_many(
                 |series| {
@@ -354,7 +404,7 @@ pub fn calculate_trading_sessions(
         .sort(["start"], SortMultipleOptions::default())
         .filter(col("position").neq(0).or(col("session").eq(0)))
         // TODO: add new column for calculating drawdown
-        .with_column(((col("balance").cum_max(false) - col("balance")).abs()/ col("balance").cum_max(false)).alias("drawdown"))
+        .with_column(get_drawdown_expr())
         // .with_columns(vec![
         //     ((col("end_price") - col("start_price")) / col("start_price")).alias("relative_return"),
         //     // ().alias("")
@@ -392,9 +442,64 @@ pub fn calculate_trading_sessions(
     Ok(lf)
 }
 
+fn get_drawdown_expr() -> Expr {
+    ((col("balance").cum_max(false) - col("balance")).abs() / col("balance").cum_max(false))
+        .alias("drawdown")
+}
+
+/// Statistics of each traded symbol
+pub type SymbolsStatistics = Vec<(&'static Symbol, Statistics)>;
+
+/// Computes the statistics of each portfolio symbol, followed by the ones of the whole portfolio.
+/// The latter merge the trading sessions of every symbol, taking their balances from the wallet equity curve.
+pub fn calculate_portfolio_stats(
+    portfolio: &PortfolioBenchmark,
+    risk_free_returns: f64,
+    returns_granularity: Granularity,
+) -> Result<(SymbolsStatistics, Statistics), GlowError> {
+    let mut symbols_stats = vec![];
+    let mut sessions_lfs = vec![];
+    for (symbol, benchmark_df) in &portfolio.benchmark_dfs {
+        let (sessions_df, stats) = update_trading_data(
+            benchmark_df,
+            risk_free_returns,
+            returns_granularity,
+            symbol,
+            None,
+        )?;
+        symbols_stats.push((*symbol, stats));
+        sessions_lfs.push(sessions_df.lazy());
+    }
+
+    let wallet_balances_lf = portfolio
+        .equity_df
+        .clone()
+        .lazy()
+        .select([col("start_time").alias("end"), col("balance")]);
+    let sessions_df = concat(sessions_lfs, UnionArgs::default())?
+        .drop(["balance", "drawdown"])
+        .join(
+            wallet_balances_lf,
+            [col("end")],
+            [col("end")],
+            JoinArgs::new(JoinType::Left),
+        )
+        .sort(["end"], SortMultipleOptions::default())
+        .with_column(get_drawdown_expr())
+        .collect()?;
+    let portfolio_stats =
+        calculate_trading_stats(&sessions_df, risk_free_returns, returns_granularity)?;
+
+    Ok((symbols_stats, portfolio_stats))
+}
+
+/// Computes the statistics of the trading sessions.
+/// The Sharpe and Sortino ratios sample the balance at `returns_granularity` periods,
+/// whose risk free rate is `risk_free_returns`.
 pub fn calculate_trading_stats(
     trading_data: &DataFrame,
     risk_free_returns: f64,
+    returns_granularity: Granularity,
 ) -> Result<Statistics, GlowError> {
     let initial_data_filter_mask =
         trading_data
@@ -413,7 +518,13 @@ pub fn calculate_trading_stats(
     let downside_risk_series = df.column("downside_risk")?;
     let drawdown_series = df.column("drawdown")?;
     let balance_series = df.column("balance")?;
-    let current_balance = balance_series.f64()?.tail(Some(1)).to_vec()[0].unwrap();
+    // trading sessions always keep session 0, so this falls back to the initial balance when no trades were made
+    let current_balance = trading_data
+        .column("balance")?
+        .f64()?
+        .tail(Some(1))
+        .get(0)
+        .unwrap_or_default();
     let current_balance = round_down_nth_decimal(current_balance, 6);
 
     let success_rate = calculate_success_rate(returns_series)?;
@@ -424,11 +535,24 @@ pub fn calculate_trading_stats(
     let (max_drawdown, max_drawdown_duration) =
         calculate_max_drawdown_and_duration(start_series, end_series, drawdown_series)?;
     let risk_adjusted_return = calculate_risk_adjusted_returns(returns_series, risk_series)?;
-    let sharpe_ratio = calculate_sharpe_ratio(returns_series, risk_series, risk_free_returns)?;
-
+    let period_returns = calculate_period_returns(
+        trading_data.column("start")?,
+        trading_data.column("end")?,
+        trading_data.column("balance")?,
+        returns_granularity,
+    )?;
+    let sharpe_ratio =
+        calculate_sharpe_ratio(&period_returns, risk_free_returns, returns_granularity);
     let sortino_ratio =
-        calculate_sortino_ratio(returns_series, downside_risk_series, risk_free_returns)?;
+        calculate_sortino_ratio(&period_returns, risk_free_returns, returns_granularity);
+    let per_trade_sharpe_ratio =
+        calculate_per_trade_sharpe_ratio(returns_series, risk_series, risk_free_returns)?;
+    let per_trade_sortino_ratio =
+        calculate_per_trade_sortino_ratio(returns_series, downside_risk_series, risk_free_returns)?;
     let calmar_ratio = calculate_calmar_ratio(balance_series, max_drawdown)?;
+    let funding_fees = df.column("funding_fees")?.f64()?.sum().unwrap_or_default();
+
+    let trading_metrics = calculate_trading_metrics(trading_data, &df, max_drawdown)?;
 
     Ok(Statistics::new(
         success_rate,
@@ -440,8 +564,77 @@ pub fn calculate_trading_stats(
         max_drawdown_duration,
         sharpe_ratio,
         sortino_ratio,
+        per_trade_sharpe_ratio,
+        per_trade_sortino_ratio,
         calmar_ratio,
-    ))
+        funding_fees,
+    )
+    .patch_trading_metrics(trading_metrics))
+}
+
+/// Computes the trade and risk metrics of the trading sessions, out of the ones that held a position (`trades_df`).
+/// The whole trading data sets the period and the initial balance, kept by its first session.
+fn calculate_trading_metrics(
+    trading_data: &DataFrame,
+    trades_df: &DataFrame,
+    max_drawdown: f64,
+) -> Result<TradingMetrics, GlowError> {
+    let balances = trading_data.column("balance")?.f64()?;
+    let initial_balance = balances.get(0).unwrap_or_default();
+    let final_balance = balances.tail(Some(1)).get(0).unwrap_or_default();
+    let period_start = trading_data.column("start")?.datetime()?.min();
+    let period_end = trading_data.column("end")?.datetime()?.max();
+    let period = match (period_start, period_end) {
+        (Some(period_start), Some(period_end)) => Duration::milliseconds(period_end - period_start),
+        _ => Duration::zero(),
+    };
+
+    let start_series = trades_df.column("start")?;
+    let end_series = trades_df.column("end")?;
+    let profit_and_loss_series = trades_df.column("profit_and_loss")?;
+
+    let profit_factor = calculate_profit_factor(profit_and_loss_series)?;
+    let expectancy = profit_and_loss_series.f64()?.mean().unwrap_or_default();
+    let (average_win, average_loss) = calculate_average_win_and_loss(profit_and_loss_series)?;
+    let payoff_ratio = if average_loss != 0.0 {
+        average_win / average_loss.abs()
+    } else {
+        0.0
+    };
+    let (max_consecutive_wins, max_consecutive_losses) =
+        calculate_max_consecutive_wins_and_losses(profit_and_loss_series)?;
+    let (exposure_time, average_trade_duration) =
+        calculate_exposure_and_average_duration(start_series, end_series, period)?;
+    let turnover = calculate_turnover(
+        trades_df.column("units")?,
+        trades_df.column("start_price")?,
+        trades_df.column("end_price")?,
+    )?;
+    let total_fees = trades_df
+        .column("trade_fees")?
+        .f64()?
+        .sum()
+        .unwrap_or_default();
+    let cagr = calculate_cagr(initial_balance, final_balance, period);
+    let ulcer_index = calculate_ulcer_index(trades_df.column("drawdown")?)?;
+    let recovery_factor = calculate_recovery_factor(initial_balance, final_balance, max_drawdown);
+
+    Ok(TradingMetrics {
+        average_loss,
+        average_trade_duration,
+        average_win,
+        cagr,
+        expectancy,
+        exposure_time,
+        max_consecutive_losses,
+        max_consecutive_wins,
+        payoff_ratio,
+        profit_factor,
+        recovery_factor,
+        total_fees,
+        turnover,
+        ulcer_index,
+    })
 }
 
 // #[allow(dead_code)]
@@ -0,0 +1,297 @@
+use crate::{
+    benchmark::functions::{compute_benchmark_positions, INITIAL_BENCHMARK_BALANCE},
+    config::BenchmarkSettings,
+    data_feed::DataFeed,
+    trader::Trader,
+};
+use chrono::{DateTime, Utc};
+use common::{
+    enums::trading_data_update::TradingDataUpdate,
+    structs::{Clock, TradingSettings},
+};
+use exchanges::{
+    enums::{DataProviderExchangeWrapper, TraderExchangeWrapper},
+    paper::PaperTraderExchange,
+};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    time::Duration as StdDuration,
+};
+use strategy::Strategy;
+use tokio::time::{timeout, Instant};
+use tokio_stream::{Stream, StreamExt};
+
+/// Absolute balance difference, in USDT, from which replay and benchmark balances disagree
+pub const REPLAY_BALANCE_TOLERANCE: f64 = 0.01;
+
+/// Time the data feed and the trader have to handle each replayed kline
+static KLINE_TIMEOUT: StdDuration = StdDuration::from_secs(30);
+
+/// Kline where the replayed trader and the benchmark hold different positions or balances
+#[derive(Clone, Debug)]
+pub struct ReplayDisagreement {
+    pub benchmark_balance: f64,
+    pub benchmark_position: i32,
+    pub replay_balance: f64,
+    pub replay_position: i32,
+    pub start_time: DateTime<Utc>,
+}
+
+pub struct ReplayReport {
+    /// Benchmark data over the replayed klines
+    pub benchmark_df: DataFrame,
+    pub disagreements: Vec<ReplayDisagreement>,
+    /// Trading data left by the trader after handling each replayed kline
+    pub replay_df: DataFrame,
+}
+
+impl Display for ReplayReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(
+            f,
+            "Replayed {} klines, {} disagreements with the benchmark",
+            self.replay_df.height(),
+            self.disagreements.len()
+        )?;
+        for disagreement in &self.disagreements {
+            writeln!(
+                f,
+                "{} | position benchmark = {}, replay = {} | balance benchmark = {:.4}, replay = {:.4}",
+                disagreement.start_time,
+                disagreement.benchmark_position,
+                disagreement.replay_position,
+                disagreement.benchmark_balance,
+                disagreement.replay_balance
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn get_start_times(df: &DataFrame) -> Result<Vec<i64>, GlowError> {
+    Ok(df
+        .column("start_time")?
+        .datetime()?
+        .into_no_null_iter()
+        .collect())
+}
+
+fn get_positions_and_balances(df: &DataFrame) -> Result<(Vec<i32>, Vec<f64>), GlowError> {
+    let positions = df
+        .column("position")?
+        .i32()?
+        .into_iter()
+        .map(|position| position.unwrap_or_default())
+        .collect();
+    let balances = df
+        .column("balance")?
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .map(|balance| balance.unwrap_or_default())
+        .collect();
+    Ok((positions, balances))
+}
+
+/// Lists the klines where positions differ, or where balances differ while both engines are flat
+fn find_disagreements(
+    benchmark_df: &DataFrame,
+    replay_df: &DataFrame,
+) -> Result<Vec<ReplayDisagreement>, GlowError> {
+    let start_times = get_start_times(replay_df)?;
+    if get_start_times(benchmark_df)? != start_times {
+        return Err(GlowError::new_str(
+            "Replay Error",
+            "benchmark and replay klines differ",
+        ));
+    }
+    let (benchmark_positions, benchmark_balances) = get_positions_and_balances(benchmark_df)?;
+    let (replay_positions, replay_balances) = get_positions_and_balances(replay_df)?;
+
+    let disagreements = (0..start_times.len())
+        .filter(|&index| {
+            let positions_differ = benchmark_positions[index] != replay_positions[index];
+            let balances_differ = benchmark_positions[index] == 0
+                && (benchmark_balances[index] - replay_balances[index]).abs()
+                    > REPLAY_BALANCE_TOLERANCE;
+            positions_differ || balances_differ
+        })
+        .map(|index| ReplayDisagreement {
+            benchmark_balance: benchmark_balances[index],
+            benchmark_position: benchmark_positions[index],
+            replay_balance: replay_balances[index],
+            replay_position: replay_positions[index],
+            start_time: DateTime::from_timestamp_millis(start_times[index]).unwrap(),
+        })
+        .collect();
+    Ok(disagreements)
+}
+
+/// Waits until the trader acknowledges it's done with the kline starting at `start_time`
+async fn wait_for_kline(
+    trader: &Trader,
+    handled_klines: &mut (impl Stream<Item = Option<i64>> + Unpin),
+    start_time: i64,
+) -> Result<(), GlowError> {
+    let wait = async {
+        while let Some(handled_start_time) = handled_klines.next().await {
+            if let TradingDataUpdate::Failed(error) = trader.performance_data_emitter.value() {
+                return Err(error);
+            }
+            if handled_start_time == Some(start_time) {
+                return Ok(());
+            }
+        }
+        Err(GlowError::new_str(
+            "Replay Error",
+            "trader stopped acknowledging klines",
+        ))
+    };
+    timeout(KLINE_TIMEOUT, wait).await.map_err(|_| {
+        let start_time = DateTime::from_timestamp_millis(start_time).unwrap();
+        GlowError::new(
+            String::from("Replay Error"),
+            format!("kline {} wasn't handled by the trader", start_time),
+        )
+    })?
+}
+
+async fn replay_through_trader(
+    data_feed: &DataFeed,
+    trader: &Trader,
+    clock: &Clock,
+    kline_duration_ms: i64,
+    klines_df: &DataFrame,
+    warm_up_klines: usize,
+) -> Result<DataFrame, GlowError> {
+    let start_times = get_start_times(klines_df)?;
+    let mut handled_klines = trader.handled_kline_emitter.subscribe();
+
+    let warm_up_end = start_times[warm_up_klines - 1];
+    data_feed.emit_klines(TradingDataUpdate::Initial(
+        klines_df.head(Some(warm_up_klines)),
+    ));
+    wait_for_kline(trader, &mut handled_klines, warm_up_end).await?;
+
+    let mut replay_df: Option<DataFrame> = None;
+    for (index, start_time) in start_times.into_iter().enumerate().skip(warm_up_klines) {
+        // klines are handled once closed
+        clock.set_timestamp_ms(start_time + kline_duration_ms);
+        data_feed.emit_klines(TradingDataUpdate::Market(klines_df.slice(index as i64, 1)));
+        wait_for_kline(trader, &mut handled_klines, start_time).await?;
+
+        let last_trading_data = data_feed
+            .trading_data
+            .lock()
+            .expect("trading data deadlock")
+            .tail(Some(1));
+        match replay_df.as_mut() {
+            Some(replay_df) => {
+                replay_df.vstack_mut(&last_trading_data)?;
+            }
+            None => replay_df = Some(last_trading_data),
+        }
+    }
+    replay_df.ok_or(GlowError::new_str(
+        "Replay Error",
+        "no klines were left to replay after the warm up",
+    ))
+}
+
+/// Replays `klines_df` one kline at a time through the data feed and a trader over the paper exchange,
+/// setting their simulated clock to the close of each kline, so that the production trading code is what gets benchmarked.
+/// The same klines are then benchmarked, in order to report where both engines disagree.
+///
+/// The first `minimum_klines_for_benchmarking` klines are emitted as the initial data, for indicators to warm up.
+/// `data_feed` must not be initialized, as the replay takes its trading data over.
+pub async fn replay_klines(
+    data_feed: &DataFeed,
+    trading_settings: &TradingSettings,
+    klines_df: DataFrame,
+) -> Result<ReplayReport, GlowError> {
+    let warm_up_klines = *data_feed
+        .minimum_klines_for_benchmarking
+        .read()
+        .expect("minimum klines for benchmarking deadlock") as usize;
+    if klines_df.height() <= warm_up_klines.max(1) {
+        let error = format!(
+            "{} klines were provided, at least {} are needed for the strategy to warm up",
+            klines_df.height(),
+            warm_up_klines.max(1) + 1
+        );
+        return Err(GlowError::new(String::from("Replay Error"), error));
+    }
+    let warm_up_klines = warm_up_klines.max(1);
+
+    // the warm up klines are handled once the last one closes
+    let kline_duration_ms = trading_settings
+        .granularity
+        .get_chrono_duration()
+        .num_milliseconds();
+    let warm_up_end = get_start_times(&klines_df)?[warm_up_klines - 1];
+    let clock = Clock::simulated(warm_up_end + kline_duration_ms);
+    let paper_exchange =
+        PaperTraderExchange::with_balance(trading_settings, INITIAL_BENCHMARK_BALANCE, &clock);
+    let trader = Trader::new(
+        &data_feed.strategy_data_emitter,
+        TraderExchangeWrapper::Paper(paper_exchange),
+        &data_feed.trading_data,
+        &data_feed.minimum_klines_for_benchmarking,
+        &clock,
+    );
+    trader.init();
+    data_feed.init_replay();
+
+    let replay_start = Instant::now();
+    let replay_df = replay_through_trader(
+        data_feed,
+        &trader,
+        &clock,
+        kline_duration_ms,
+        &klines_df,
+        warm_up_klines,
+    )
+    .await?;
+    println!(
+        "Replayed {} klines in {:?}",
+        replay_df.height(),
+        replay_start.elapsed()
+    );
+
+    let strategy_df = data_feed.handle_initial_klines(klines_df)?;
+    let benchmark_df = compute_benchmark_positions(
+        &trader,
+        strategy_df.slice(warm_up_klines as i64, replay_df.height()),
+    )?;
+    let disagreements = find_disagreements(&benchmark_df, &replay_df)?;
+
+    Ok(ReplayReport {
+        benchmark_df,
+        disagreements,
+        replay_df,
+    })
+}
+
+/// Fetches the benchmark klines of the settings data provider and replays them, see [`replay_klines`]
+pub async fn replay_benchmark(
+    benchmark_settings: &BenchmarkSettings,
+    trading_settings: &TradingSettings,
+    strategy: &Strategy,
+) -> Result<ReplayReport, GlowError> {
+    let data_provider_exchange = DataProviderExchangeWrapper::new(
+        benchmark_settings.data_provider_id,
+        strategy,
+        trading_settings,
+    )?;
+    let data_feed = DataFeed::new(
+        benchmark_settings.datetimes,
+        data_provider_exchange,
+        true,
+        strategy,
+        trading_settings,
+    )?;
+    let klines_df = data_feed.fetch_benchmark_klines().await?;
+    replay_klines(&data_feed, trading_settings, klines_df).await
+}
@@ -3,28 +3,56 @@ use common::{
         balance::Balance, order_action::OrderAction, side::Side, signal_category::SignalCategory,
         trade_status::TradeStatus, trading_data_update::TradingDataUpdate,
     },
-    functions::{
-        check_last_index_for_signal, current_datetime, current_timestamp_ms,
-        get_trading_columns_values,
-    },
-    structs::{BehaviorSubject, Execution, Order, Trade, TradingSettings},
+    functions::{check_last_index_for_signal, get_trading_columns_values},
+    structs::{BehaviorSubject, Clock, Execution, Order, Trade, TradingSettings},
     traits::exchange::{TraderExchange, TraderHelper},
 };
 use exchanges::enums::TraderExchangeWrapper;
 use glow_error::GlowError;
 use polars::prelude::*;
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex, RwLock,
+};
 use tokio::{spawn, task::JoinHandle};
 use tokio_stream::StreamExt;
 
 use crate::benchmark::functions::compute_benchmark_positions;
 
+/// Versions of the updates each handler is done with, the trader being idle once they're all up to date
+#[derive(Default)]
+struct HandledVersions {
+    executions_update: AtomicU64,
+    order_update: AtomicU64,
+    signal: AtomicU64,
+    strategy_data: AtomicU64,
+    trade_update: AtomicU64,
+}
+
+/// Records the version of the update being handled once dropped, however its handling ends
+struct HandledUpdate<'a> {
+    handled_version: &'a AtomicU64,
+    trader: &'a Trader,
+    version: u64,
+}
+
+impl Drop for HandledUpdate<'_> {
+    fn drop(&mut self) {
+        self.trader.set_handled_version(self.handled_version, self.version);
+    }
+}
+
 #[derive(Clone)]
 pub struct Trader {
+    clock: Clock,
     current_balance_listener: BehaviorSubject<Balance>,
     current_trade_listener: BehaviorSubject<Option<Trade>>,
     executions_update_listener: BehaviorSubject<Vec<Execution>>,
+    /// Start time of the last kline in the trading data, emitted every time the trader is done handling updates
+    pub handled_kline_emitter: BehaviorSubject<Option<i64>>,
+    handled_versions: Arc<HandledVersions>,
     order_update_listener: BehaviorSubject<OrderAction>,
+    pending_revert_signal: Arc<Mutex<Option<SignalCategory>>>, // opens the opposite position once the reverted one is closed
     pub performance_data_emitter: BehaviorSubject<TradingDataUpdate>,
     signal_listener: BehaviorSubject<SignalCategory>,
     strategy_data_listener: BehaviorSubject<TradingDataUpdate>,
@@ -61,6 +89,7 @@ impl Trader {
         trader_exchange: TraderExchangeWrapper,
         trading_data: &Arc<Mutex<DataFrame>>,
         trading_data_klines_limit: &Arc<RwLock<u32>>,
+        clock: &Clock,
     ) -> Trader {
         let performance_data_emitter = BehaviorSubject::new(TradingDataUpdate::default());
         let (
@@ -70,10 +99,14 @@ impl Trader {
             current_trade_listener,
         ) = Self::get_listeners(&trader_exchange);
         Trader {
+            clock: clock.clone(),
             current_balance_listener: current_balance_listener.clone(),
             current_trade_listener: current_trade_listener.clone(),
             executions_update_listener: executions_update_listener.clone(),
+            handled_kline_emitter: BehaviorSubject::new(None),
+            handled_versions: Arc::new(HandledVersions::default()),
             order_update_listener: order_update_listener.clone(),
+            pending_revert_signal: Arc::new(Mutex::new(None)),
             performance_data_emitter: performance_data_emitter.clone(),
             signal_listener: BehaviorSubject::new(SignalCategory::default()),
             temp_executions: Arc::new(Mutex::new(Vec::new())),
@@ -97,6 +130,46 @@ impl Trader {
         Ok(trading_data)
     }
 
+    fn get_last_start_time(&self) -> Option<i64> {
+        let trading_data = self.trading_data.lock().expect("trading data deadlock");
+        let start_times = trading_data.column("start_time").ok()?.datetime().ok()?;
+        start_times.get(start_times.len().checked_sub(1)?)
+    }
+
+    /// Whether every update emitted so far was handled, including the ones emitted while handling others
+    fn is_idle(&self) -> bool {
+        let handled_versions = &self.handled_versions;
+        [
+            (
+                &handled_versions.executions_update,
+                self.executions_update_listener.version(),
+            ),
+            (
+                &handled_versions.order_update,
+                self.order_update_listener.version(),
+            ),
+            (&handled_versions.signal, self.signal_listener.version()),
+            (
+                &handled_versions.strategy_data,
+                self.strategy_data_listener.version(),
+            ),
+            (
+                &handled_versions.trade_update,
+                self.current_trade_listener.version(),
+            ),
+        ]
+        .iter()
+        .all(|(handled_version, version)| handled_version.load(Ordering::SeqCst) == *version)
+    }
+
+    /// Acknowledges the last kline once the trader is done with every update it led to
+    fn set_handled_version(&self, handled_version: &AtomicU64, version: u64) {
+        handled_version.store(version, Ordering::SeqCst);
+        if self.is_idle() {
+            self.handled_kline_emitter.next(self.get_last_start_time());
+        }
+    }
+
     fn update_trading_data(&self, payload: DataFrame) -> Result<(), GlowError> {
         {
             let mut lock = self
@@ -133,18 +206,140 @@ impl Trader {
         Ok(())
     }
 
-    async fn process_last_signal(&self, signal: SignalCategory) -> Result<(), GlowError> {
-        let current_trade = self.current_trade_listener.value();
+    fn get_last_price(&self) -> Result<f64, GlowError> {
         let traded_symbol = self.trader_exchange.get_traded_symbol();
         let close_col = traded_symbol.get_close_col();
         let trading_data = self.get_trading_data()?;
-        // TODO: check if this can be received via param
         let last_price = trading_data
             .column(&close_col)?
             .f64()?
             .into_no_null_iter()
             .last()
-            .expect("process_last_signal -> SignalCategory::GoLong -> missing last price");
+            .expect("get_last_price -> missing last price");
+        Ok(last_price)
+    }
+
+    async fn update_trailing_stop_loss(&self) -> Result<(), GlowError> {
+        let current_trade = self.current_trade_listener.value();
+        if current_trade.is_none() {
+            return Ok(());
+        }
+        let current_trade = current_trade.unwrap();
+        match current_trade.status() {
+            TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder => {
+                let last_price = self.get_last_price()?;
+                self.trader_exchange
+                    .update_trailing_stop_loss(&current_trade, last_price)
+                    .await?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn close_current_trade(
+        &self,
+        mut current_trade: Trade,
+        last_price: f64,
+    ) -> Result<(), GlowError> {
+        let current_trade_status = &current_trade.status();
+        if current_trade_status == &TradeStatus::PartiallyOpen {
+            let mut open_order = current_trade.open_order.clone();
+            let left_units = open_order.get_executed_quantity() - open_order.units;
+            let updated_units = Some(left_units);
+            let updated_price = None;
+            let updated_stop_loss_price = None;
+            let updated_take_profit_price = None;
+            let amend_result = self
+                .trader_exchange
+                .amend_order(
+                    current_trade.open_order.id.clone(),
+                    updated_units,
+                    updated_price,
+                    updated_stop_loss_price,
+                    updated_take_profit_price,
+                )
+                .await;
+            match amend_result {
+                Ok(amended) => {
+                    if amended {
+                        open_order.update_units(left_units);
+                        current_trade = current_trade.update_trade(open_order)?;
+                    } else {
+                        let error =
+                            "TradeStatus::PartiallyOpen -> amend order returned false".to_string();
+                        let error = GlowError::new(String::from("Amend Order Error"), error);
+                        return Err(error);
+                    }
+                }
+                Err(error) => {
+                    let error = format!(
+                        "TradeStatus::PartiallyOpen -> amend result failed! {:?}",
+                        error
+                    );
+                    let error = GlowError::new(String::from("Amend Order Error"), error);
+                    return Err(error);
+                }
+            }
+        }
+
+        match self
+            .trader_exchange
+            .try_close_position(&current_trade, last_price)
+            .await
+        {
+            Ok(close_order) => {
+                println!("TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder -> try_close_position result {:?}", close_order);
+                Ok(())
+            }
+            Err(error) => {
+                let error = format!(
+                    "TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder -> try close position result failed! {:?}",
+                    error
+                );
+                let error = GlowError::new(String::from("Try Close Position Error"), error);
+                Err(error)
+            }
+        }
+    }
+
+    async fn open_reverted_position(&self, signal: SignalCategory) -> Result<(), GlowError> {
+        let last_price = self.get_last_price()?;
+        let available_to_withdraw = self.current_balance_listener.value().available_to_withdraw;
+        println!(
+            "\n{:?} | 🔄 Opening {:?} position, as its opposite was reverted.",
+            self.clock.current_datetime(),
+            Side::from(signal)
+        );
+        open_order(
+            &self.trader_exchange,
+            signal.into(),
+            available_to_withdraw,
+            last_price,
+        )
+        .await
+    }
+
+    fn take_pending_revert_signal(&self) -> Option<SignalCategory> {
+        let mut lock = self
+            .pending_revert_signal
+            .lock()
+            .expect("pending_revert_signal deadlock");
+        lock.take()
+    }
+
+    fn set_pending_revert_signal(&self, payload: Option<SignalCategory>) {
+        let mut lock = self
+            .pending_revert_signal
+            .lock()
+            .expect("update pending_revert_signal deadlock");
+        *lock = payload;
+    }
+
+    async fn process_last_signal(&self, signal: SignalCategory) -> Result<(), GlowError> {
+        let current_trade = self.current_trade_listener.value();
+        // TODO: check if this can be received via param
+        let last_price = self.get_last_price()?;
 
         if current_trade.is_none() {
             let available_to_withdraw = self.current_balance_listener.value().available_to_withdraw;
@@ -156,7 +351,7 @@ impl Trader {
             )
             .await?);
         }
-        let mut current_trade = current_trade.unwrap();
+        let current_trade = current_trade.unwrap();
         let current_trade_status = &current_trade.status();
         let open_order_side = current_trade.open_order.side;
         match (current_trade_status, signal, open_order_side) {
@@ -177,14 +372,14 @@ impl Trader {
                                     // simple close signal received
                                     println!(
                                         "\n{:?} | ⚠️ Current order {:?} position, without executions, will be cancelled as it received a close signal.",
-                                        current_datetime(),
+                                        self.clock.current_datetime(),
                                         current_trade.open_order.side
                                     );
                                     return Ok(())
                                 }
                                 println!(
                                     "\n{:?} | ⚠️ Current idle order {:?} position, without executions, will be cancelled as it received an opposite side open signal.",
-                                    current_datetime(),
+                                    self.clock.current_datetime(),
                                     current_trade.open_order.side
                                 );
 
@@ -201,7 +396,7 @@ impl Trader {
                                     Ok(()) => {
                                         println!(
                                             "\n{:?} | ♻️ Current idle order, {:?} position, will be recycled as it received an opposite side open signal.",
-                                            current_datetime(),
+                                            self.clock.current_datetime(),
                                             current_trade.open_order.side
                                         );
                                         Ok(())
@@ -228,64 +423,26 @@ impl Trader {
             (TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder, SignalCategory::CloseLong, Side::Buy) |
             (TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder, SignalCategory::CloseShort, Side::Sell) |
             (TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder, SignalCategory::ClosePosition, _)
-             => {
-                if current_trade_status == &TradeStatus::PartiallyOpen {
-                    let mut open_order = current_trade.open_order.clone();
-                    let left_units = open_order.get_executed_quantity() - open_order.units;
-                    let updated_units = Some(left_units);
-                    let updated_price = None;
-                    let updated_stop_loss_price = None;
-                    let updated_take_profit_price = None;
-                    let amend_result = self.trader_exchange
-                        .amend_order(
-                            current_trade.open_order.id.clone(),
-                            updated_units,
-                            updated_price,
-                            updated_stop_loss_price,
-                            updated_take_profit_price,
-                        )
-                        .await;
-                    match amend_result {
-                        Ok(amended) => {
-                            if amended {
-                                open_order.update_units(left_units);
-                                current_trade = current_trade.update_trade(open_order)?;
-                            } else {
-                                let error = "TradeStatus::PartiallyOpen -> amend order returned false".to_string();
-                                let error = GlowError::new(String::from("Amend Order Error"), error);
-                                return Err(error);
-                            }
-                        }
-                        Err(error) => {
-                            let error = format!(
-                                "TradeStatus::PartiallyOpen -> amend result failed! {:?}",
-                                error
-                            );
-                            let error = GlowError::new(String::from("Amend Order Error"), error);
-                            return Err(error);
-                        }
-                    }
-                }
-
-                match self.trader_exchange
-                    .try_close_position(
-                        &current_trade,
-                        last_price,
-                    )
-                    .await
-                {
-                    Ok(close_order) => {
-                        println!("TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder -> try_close_position result {:?}", close_order);
-                        Ok(())},
-                    Err(error) => {
-                        let error = format!(
-                            "TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder -> try close position result failed! {:?}",
-                            error
-                        );
-                        let error = GlowError::new(String::from("Try Close Position Error"), error);
-                        Err(error)
-                    }
+             => self.close_current_trade(current_trade, last_price).await,
+            (TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder, SignalCategory::GoLong, Side::Sell) |
+            (TradeStatus::PartiallyOpen | TradeStatus::PendingCloseOrder, SignalCategory::GoShort, Side::Buy)
+                if self.trader_exchange.get_trading_settings().signals_revert_its_opposite =>
+            {
+                // the opposite position is opened once the close order gets filled, which may happen before the close call returns
+                self.set_pending_revert_signal(Some(signal));
+                if let Err(error) = self.close_current_trade(current_trade, last_price).await {
+                    self.set_pending_revert_signal(None);
+                    return Err(error);
                 }
+                Ok(())
+            }
+            (TradeStatus::CloseOrderStandBy | TradeStatus::PartiallyClosed, SignalCategory::GoLong, Side::Sell) |
+            (TradeStatus::CloseOrderStandBy | TradeStatus::PartiallyClosed, SignalCategory::GoShort, Side::Buy)
+                if self.trader_exchange.get_trading_settings().signals_revert_its_opposite =>
+            {
+                // a close order is already placed, the opposite position is opened once it gets filled
+                self.set_pending_revert_signal(Some(signal));
+                Ok(())
             }
             (current_trade_status, signal, open_order_side) => {
                 println!("process_last_signal NOOP current_trade_status = {:?}, signal = {:?}, open_order_side = {:?}", current_trade_status, signal, open_order_side);
@@ -297,8 +454,16 @@ impl Trader {
     fn init_signal_handler(&self) -> JoinHandle<()> {
         let trader = self.clone();
         spawn(async move {
-            let mut subscription = trader.signal_listener.subscribe();
-            while let Some(signal) = subscription.next().await {
+            let mut subscription = trader.signal_listener.subscribe_versioned();
+            while let Some((version, signal)) = subscription.next().await {
+                let _handled_update = HandledUpdate {
+                    handled_version: &trader.handled_versions.signal,
+                    trader: &trader,
+                    version,
+                };
+                if let Err(error) = trader.update_trailing_stop_loss().await {
+                    println!("update_trailing_stop_loss error {:?}", error);
+                }
                 if signal == SignalCategory::KeepPosition {
                     continue;
                 }
@@ -364,8 +529,13 @@ impl Trader {
     fn init_order_update_handler(&self) -> JoinHandle<()> {
         let trader = self.clone();
         spawn(async move {
-            let mut subscription = trader.order_update_listener.subscribe();
-            while let Some(order_action) = subscription.next().await {
+            let mut subscription = trader.order_update_listener.subscribe_versioned();
+            while let Some((version, order_action)) = subscription.next().await {
+                let _handled_update = HandledUpdate {
+                    handled_version: &trader.handled_versions.order_update,
+                    trader: &trader,
+                    version,
+                };
                 let current_trade = trader.current_trade_listener.value();
                 match order_action.clone() {
                     OrderAction::Update(mut updated_order)
@@ -384,7 +554,7 @@ impl Trader {
                             }
                             println!(
                                 "\n{:?} | 📖 Opened {:?} order ({:?} units)",
-                                current_datetime(),
+                                trader.clock.current_datetime(),
                                 updated_order.side,
                                 &updated_order.units,
                             );
@@ -398,7 +568,7 @@ impl Trader {
                                 // println!("match trade, updated {:?}", &updated_trade);
                                 if let OrderAction::Stop(_) = order_action {
                                     let (pnl, returns) = updated_trade.calculate_pnl_and_returns();
-                                    println!("\n{:?} | {} Position {:?} was stopped. Profit and loss = {}, returns = {}",  current_datetime(),
+                                    println!("\n{:?} | {} Position {:?} was stopped. Profit and loss = {}, returns = {}",  trader.clock.current_datetime(),
                                     if pnl > 0.0 { "📈" } else { "📉" },
                                     updated_trade.open_order.side,
                                     pnl,
@@ -449,8 +619,13 @@ impl Trader {
     fn init_executions_update_handler(&self) -> JoinHandle<()> {
         let trader = self.clone();
         spawn(async move {
-            let mut subscription = trader.executions_update_listener.subscribe();
-            while let Some(latest_executions) = subscription.next().await {
+            let mut subscription = trader.executions_update_listener.subscribe_versioned();
+            while let Some((version, latest_executions)) = subscription.next().await {
+                let _handled_update = HandledUpdate {
+                    handled_version: &trader.handled_versions.executions_update,
+                    trader: &trader,
+                    version,
+                };
                 if latest_executions.is_empty() {
                     continue;
                 }
@@ -496,6 +671,12 @@ impl Trader {
             mut actions,
         ) = get_trading_columns_values(&trading_data)?;
 
+        let mut exit_times: Vec<Option<i64>> = trading_data
+            .column("exit_time")?
+            .datetime()?
+            .into_iter()
+            .collect();
+
         let index = start_times.len() - 1;
         let balance = self.current_balance_listener.value();
         let signal = self.signal_listener.value();
@@ -506,10 +687,11 @@ impl Trader {
             let start_timestamp = start_times[index].expect(
                 "on_close_trade_update_trading_data -> TradeStatus::Closed arm -> interval_start_timestamp unwrap",
             );
-            let end_timestamp = current_timestamp_ms();
+            let end_timestamp = self.clock.current_timestamp_ms();
             let (pnl, returns) = current_trade.calculate_pnl_and_returns();
             let fees =
                 current_trade.get_executed_fees_between_interval(start_timestamp, end_timestamp);
+            exit_times[index] = Some(end_timestamp);
             (fees, pnl, returns)
         };
         fees_col[index] = Some(fees);
@@ -531,6 +713,11 @@ impl Trader {
         trading_data.replace("balance", Series::new("balance".into(), balances))?;
         trading_data.replace("position", Series::new("position".into(), positions))?;
         trading_data.replace("action", Series::new("action".into(), actions))?;
+        trading_data.replace(
+            "exit_time",
+            Series::new("exit_time".into(), exit_times)
+                .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?,
+        )?;
 
         self.update_trading_data(trading_data)?;
 
@@ -542,8 +729,13 @@ impl Trader {
     fn init_trade_update_handler(&self) -> JoinHandle<()> {
         let trader = self.clone();
         spawn(async move {
-            let mut subscription = trader.current_trade_listener.subscribe();
-            while let Some(current_trade) = subscription.next().await {
+            let mut subscription = trader.current_trade_listener.subscribe_versioned();
+            while let Some((version, current_trade)) = subscription.next().await {
+                let _handled_update = HandledUpdate {
+                    handled_version: &trader.handled_versions.trade_update,
+                    trader: &trader,
+                    version,
+                };
                 if current_trade.is_none() {
                     continue;
                 }
@@ -559,7 +751,7 @@ impl Trader {
                     let (pnl, returns) = current_trade.calculate_pnl_and_returns();
                     println!(
                             "\n{:?} | 📕 Closed Order {:?} side ({:?} units), profit/loss: {}, returns: {}",
-                            current_datetime(),
+                            trader.clock.current_datetime(),
                             current_trade.open_order.side,
                             &close_order.units,
                             pnl,
@@ -568,7 +760,7 @@ impl Trader {
                 } else {
                     println!(
                         "\n{:?} | ❌ Current Order side {:?} cancelled successfully!",
-                        current_datetime(),
+                        trader.clock.current_datetime(),
                         current_trade.open_order.side,
                     );
                 }
@@ -577,6 +769,16 @@ impl Trader {
                     Ok(_) => {}
                     Err(error) => println!("on_close_trade_update_trading_data error {:?}", error),
                 }
+
+                let Some(revert_signal) = trader.take_pending_revert_signal() else {
+                    continue;
+                };
+                if trade_status != TradeStatus::Closed {
+                    continue;
+                }
+                if let Err(error) = trader.open_reverted_position(revert_signal).await {
+                    println!("open_reverted_position error {:?}", error);
+                }
             }
         })
     }
@@ -675,8 +877,28 @@ impl Trader {
             }
         }
 
+        // live fills already embed slippage in their executed prices
+        let mut slippages: Vec<Option<f64>> = updated_strategy_df
+            .column("slippage")?
+            .f64()?
+            .into_iter()
+            .collect();
+        slippages[index] = Some(0.0);
+        // funding settlements aren't tracked by live trades yet
+        let mut funding_fees: Vec<Option<f64>> = updated_strategy_df
+            .column("funding_fees")?
+            .f64()?
+            .into_iter()
+            .collect();
+        funding_fees[index] = Some(0.0);
+
         let mut updated_strategy_df = updated_strategy_df.clone();
         updated_strategy_df.replace("trade_fees", Series::new("trade_fees".into(), trades_fees))?;
+        updated_strategy_df.replace("slippage", Series::new("slippage".into(), slippages))?;
+        updated_strategy_df.replace(
+            "funding_fees",
+            Series::new("funding_fees".into(), funding_fees),
+        )?;
         updated_strategy_df.replace("units", Series::new("units".into(), units))?;
         updated_strategy_df.replace(
             "profit_and_loss",
@@ -708,7 +930,22 @@ impl Trader {
             let trade_status = current_trade.status();
             if trade_status != TradeStatus::Cancelled && trade_status != TradeStatus::Closed {
                 let current_trade_side = current_trade.open_order.side;
-                if current_trade_side == Side::Buy
+                let signals_revert_its_opposite = self
+                    .trader_exchange
+                    .get_trading_settings()
+                    .signals_revert_its_opposite;
+                // opposite signals take precedence over close signals, as in benchmarks
+                if signals_revert_its_opposite
+                    && current_trade_side == Side::Buy
+                    && check_last_index_for_signal(trading_data_df, SignalCategory::GoShort)?
+                {
+                    emitted_signal = SignalCategory::GoShort;
+                } else if signals_revert_its_opposite
+                    && current_trade_side == Side::Sell
+                    && check_last_index_for_signal(trading_data_df, SignalCategory::GoLong)?
+                {
+                    emitted_signal = SignalCategory::GoLong;
+                } else if current_trade_side == Side::Buy
                     && check_last_index_for_signal(trading_data_df, SignalCategory::CloseLong)?
                 {
                     emitted_signal = SignalCategory::CloseLong;
@@ -793,17 +1030,41 @@ impl Trader {
         Ok(())
     }
 
+    /// Fills paper trading orders against the latest klines, before their signals are processed
+    fn fill_paper_orders(&self, updated_strategy_df: &DataFrame) -> Result<(), GlowError> {
+        match &self.trader_exchange {
+            TraderExchangeWrapper::Paper(paper_exchange) => {
+                paper_exchange.process_kline_data(updated_strategy_df)
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn init_strategy_data_handler(&self) -> JoinHandle<()> {
         let trader = self.clone();
         spawn(async move {
-            let mut subscription = trader.strategy_data_listener.subscribe();
-            while let Some(strategy_data_update) = subscription.next().await {
+            let mut subscription = trader.strategy_data_listener.subscribe_versioned();
+            while let Some((version, strategy_data_update)) = subscription.next().await {
+                let _handled_update = HandledUpdate {
+                    handled_version: &trader.handled_versions.strategy_data,
+                    trader: &trader,
+                    version,
+                };
                 let result = match strategy_data_update {
-                    TradingDataUpdate::Initial(initial_strategy_df) => {
-                        trader.handle_initial_strategy_data(initial_strategy_df)
-                    }
-                    TradingDataUpdate::Market(updated_strategy_df) => {
-                        trader.handle_updated_strategy_data(updated_strategy_df)
+                    TradingDataUpdate::Initial(initial_strategy_df) => trader
+                        .handle_initial_strategy_data(initial_strategy_df)
+                        .map_err(|error| {
+                            let payload = TradingDataUpdate::Failed(error.clone());
+                            trader.performance_data_emitter.next(payload);
+                            error
+                        }),
+                    TradingDataUpdate::Market(updated_strategy_df) => trader
+                        .fill_paper_orders(&updated_strategy_df)
+                        .and_then(|_| trader.handle_updated_strategy_data(updated_strategy_df)),
+                    TradingDataUpdate::Failed(error) => {
+                        let payload = TradingDataUpdate::Failed(error);
+                        trader.performance_data_emitter.next(payload);
+                        Ok(())
                     }
                     _ => Ok(()),
                 };
@@ -0,0 +1,606 @@
+use chrono::{Duration, TimeZone, Utc};
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{
+            leverage::Leverage,
+            position_lock::PositionLock,
+            price_level::{PriceLevel, TrailingStopLoss},
+            slippage::Slippage,
+        },
+        order_type::OrderType,
+        signal_category::SignalCategory,
+        symbol_id::SymbolId,
+        trading_data_update::TradingDataUpdate,
+    },
+    functions::{funding::get_funding_rate_col, intrabar::get_intrabar_cols},
+    structs::{BehaviorSubject, Clock, LimitOrderSettings, TradingSettings},
+};
+use core::{
+    benchmark::functions::{
+        compute_benchmark_positions, compute_benchmark_result, BenchmarkEngine, IterationsError,
+        INITIAL_BENCHMARK_BALANCE,
+    },
+    trader::Trader,
+};
+use exchanges::{
+    binance::trader::BinanceTraderExchange,
+    enums::TraderExchangeWrapper,
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use polars::prelude::{DataFrame, DataType, IntoColumn, NamedFrom, Series, TimeUnit};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+fn get_trading_settings(open_order_type: OrderType, cancel_after_klines: u32) -> TradingSettings {
+    get_trading_settings_with_price_levels(open_order_type, cancel_after_klines, HashMap::new())
+}
+
+fn get_trading_settings_with_price_levels(
+    open_order_type: OrderType,
+    cancel_after_klines: u32,
+    price_level_modifier_map: HashMap<String, PriceLevel>,
+) -> TradingSettings {
+    TradingSettings::new(
+        10.0,
+        Leverage::Isolated(10),
+        open_order_type,
+        OrderType::Market,
+        PositionLock::None,
+        price_level_modifier_map,
+        false,
+        &SymbolId::Bitcoin,
+        &SymbolId::Bitcoin,
+        1,
+        Granularity::m1,
+    )
+    .patch_limit_order_settings(LimitOrderSettings {
+        price_offset_percentage: 1.0,
+        cancel_after_klines,
+    })
+}
+
+fn new_trader(trading_settings: &TradingSettings) -> Trader {
+    let config = ExchangeConfig {
+        credentials: ApiCredentials {
+            key: "test-api-key",
+            secret: "test-api-secret",
+        },
+        endpoints: ApiEndpoints {
+            http: "http://127.0.0.1:0",
+            ws: "ws://127.0.0.1:0",
+        },
+    };
+    let trader_exchange = TraderExchangeWrapper::Binance(BinanceTraderExchange::with_config(
+        trading_settings,
+        config,
+    ));
+    Trader::new(
+        &BehaviorSubject::new(TradingDataUpdate::default()),
+        trader_exchange,
+        &Arc::new(Mutex::new(DataFrame::empty())),
+        &Arc::new(RwLock::new(0)),
+        &Clock::default(),
+    )
+}
+
+/// Builds a strategy dataframe with flat 100.0 prices, except for the given `highs`, shorting on the first kline
+fn get_strategy_df(highs: &[f64], short_closes: &[i32]) -> DataFrame {
+    let height = highs.len();
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let start_times: Vec<i64> = (0..height)
+        .map(|minute| (start + Duration::minutes(minute as i64)).timestamp_millis())
+        .collect();
+    let mut shorts = vec![0; height];
+    shorts[0] = 1;
+    DataFrame::new(vec![
+        Series::new("start_time".into(), start_times)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap()
+            .into_column(),
+        Series::new("BTCUSDT_open".into(), vec![100.0; height]).into_column(),
+        Series::new("BTCUSDT_high".into(), highs.to_vec()).into_column(),
+        Series::new("BTCUSDT_low".into(), vec![99.0; height]).into_column(),
+        Series::new("BTCUSDT_close".into(), vec![100.0; height]).into_column(),
+        Series::new(SignalCategory::GoShort.get_column().into(), shorts).into_column(),
+        Series::new(
+            SignalCategory::CloseShort.get_column().into(),
+            short_closes.to_vec(),
+        )
+        .into_column(),
+    ])
+    .unwrap()
+}
+
+fn with_signal_column(df: DataFrame, signal_category: SignalCategory, values: &[i32]) -> DataFrame {
+    let mut df = df;
+    df.with_column(Series::new(
+        signal_category.get_column().into(),
+        values.to_vec(),
+    ))
+    .unwrap();
+    df
+}
+
+/// Sets the 1 minute (start time, high, low) path within each kline, as coarse klines carry it
+fn with_intrabar_paths(df: DataFrame, paths: &[Vec<(i64, f64, f64)>]) -> DataFrame {
+    let (start_times_col, highs_col, lows_col) = get_intrabar_cols("BTCUSDT");
+    let get_list_series = |name: String, values: Vec<Series>| Series::new(name.into(), values);
+    let start_times = paths
+        .iter()
+        .map(|path| {
+            Series::new("".into(), path.iter().map(|m| m.0).collect::<Vec<i64>>())
+                .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+                .unwrap()
+        })
+        .collect();
+    let highs = paths
+        .iter()
+        .map(|path| Series::new("".into(), path.iter().map(|m| m.1).collect::<Vec<f64>>()))
+        .collect();
+    let lows = paths
+        .iter()
+        .map(|path| Series::new("".into(), path.iter().map(|m| m.2).collect::<Vec<f64>>()))
+        .collect();
+    let mut df = df;
+    df.with_column(get_list_series(start_times_col, start_times))
+        .unwrap();
+    df.with_column(get_list_series(highs_col, highs)).unwrap();
+    df.with_column(get_list_series(lows_col, lows)).unwrap();
+    df
+}
+
+fn get_actions(df: &DataFrame) -> Vec<String> {
+    df.column("action")
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_no_null_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn get_f64_values(df: &DataFrame, column: &str) -> Vec<f64> {
+    df.column(column)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+fn get_start_times(df: &DataFrame) -> Vec<i64> {
+    df.column("start_time")
+        .unwrap()
+        .datetime()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+fn get_exit_times(df: &DataFrame) -> Vec<Option<i64>> {
+    df.column("exit_time")
+        .unwrap()
+        .datetime()
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+fn get_positions(df: &DataFrame) -> Vec<i32> {
+    df.column("position")
+        .unwrap()
+        .i32()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+#[test]
+fn test_limit_order_fills_once_price_is_crossed() {
+    let trading_settings = get_trading_settings(OrderType::Limit, 3);
+    let trader = new_trader(&trading_settings);
+    // short limit order is placed at 101.0, only reached by the third kline
+    let strategy_df = get_strategy_df(&[100.0, 100.5, 101.5, 100.0, 100.0], &[0, 0, 0, 1, 0]);
+
+    let benchmark_df = compute_benchmark_positions(&trader, strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[1], SignalCategory::KeepPosition.get_column());
+    assert_eq!(actions[2], SignalCategory::GoShort.get_column());
+    assert_eq!(get_positions(&benchmark_df)[1..], [0, -1, -1, 0]);
+    // filled at the limit price, so closing the position at 100.0 is profitable
+    let profit_and_loss = benchmark_df
+        .column("profit_and_loss")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .get(4)
+        .unwrap();
+    assert!(profit_and_loss > 0.0);
+}
+
+#[test]
+fn test_limit_order_is_cancelled_after_klines() {
+    let trading_settings = get_trading_settings(OrderType::Limit, 2);
+    let trader = new_trader(&trading_settings);
+    let strategy_df = get_strategy_df(&[100.0, 100.5, 100.5, 101.5], &[0, 0, 0, 0]);
+
+    let benchmark_df = compute_benchmark_positions(&trader, strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[2], SignalCategory::UnfilledShort.get_column());
+    assert_eq!(actions[3], SignalCategory::KeepPosition.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0; 4]);
+}
+
+#[test]
+fn test_limit_order_is_cancelled_on_close_signal() {
+    let trading_settings = get_trading_settings(OrderType::Limit, 5);
+    let trader = new_trader(&trading_settings);
+    let strategy_df = get_strategy_df(&[100.0, 100.5, 101.5, 101.5], &[0, 1, 0, 0]);
+
+    let benchmark_df = compute_benchmark_positions(&trader, strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[2], SignalCategory::UnfilledShort.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0; 4]);
+}
+
+#[test]
+fn test_replacing_limit_order_fills_after_cancelling_kline() {
+    let trading_settings = get_trading_settings(OrderType::Limit, 5);
+    let trader = new_trader(&trading_settings);
+    // short limit order at 101.0 is never reached, the long one at 99.0 is reached by every kline
+    let strategy_df = get_strategy_df(&[100.0; 5], &[0; 5]);
+    let strategy_df = with_signal_column(strategy_df, SignalCategory::GoLong, &[0, 1, 0, 0, 0]);
+    let strategy_df = with_signal_column(strategy_df, SignalCategory::CloseLong, &[0, 0, 0, 1, 0]);
+
+    let benchmark_df = compute_benchmark_positions(&trader, strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[2], SignalCategory::UnfilledShort.get_column());
+    assert_eq!(actions[3], SignalCategory::GoLong.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, 0, 0, 1, 0]);
+}
+
+#[test]
+fn test_market_order_fills_at_next_open() {
+    let trading_settings = get_trading_settings(OrderType::Market, 1);
+    let trader = new_trader(&trading_settings);
+    let strategy_df = get_strategy_df(&[100.0, 100.5, 100.5, 100.5], &[0, 0, 1, 0]);
+
+    let benchmark_df = compute_benchmark_positions(&trader, strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[1], SignalCategory::GoShort.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, -1, -1, 0]);
+}
+
+#[test]
+fn test_slippage_is_applied_to_market_entries_and_exits() {
+    let strategy_df = get_strategy_df(&[100.0, 100.5, 100.5, 100.5], &[0, 0, 1, 0]);
+    let trading_settings = get_trading_settings(OrderType::Market, 1);
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df.clone()).unwrap();
+    let slipped_trading_settings = trading_settings.patch_slippage(Slippage::Percentage(0.5));
+    let slipped_benchmark_df =
+        compute_benchmark_positions(&new_trader(&slipped_trading_settings), strategy_df).unwrap();
+
+    assert_eq!(get_f64_values(&benchmark_df, "slippage"), vec![0.0; 4]);
+    let slippages = get_f64_values(&slipped_benchmark_df, "slippage");
+    assert!(slippages[1] > 0.0);
+    assert_eq!(slippages[2], 0.0);
+    assert!(slippages[3] > 0.0);
+    // short is opened at 99.5 and closed at 100.5 instead of both at 100.0
+    let profit_and_loss = get_f64_values(&benchmark_df, "profit_and_loss")[3];
+    let slipped_profit_and_loss = get_f64_values(&slipped_benchmark_df, "profit_and_loss")[3];
+    assert!(slipped_profit_and_loss < profit_and_loss);
+}
+
+#[test]
+fn test_limit_entries_are_filled_without_slippage() {
+    let trading_settings =
+        get_trading_settings(OrderType::Limit, 3).patch_slippage(Slippage::Volatility(0.5));
+    let strategy_df = get_strategy_df(&[100.0, 101.5, 100.5, 100.5], &[0, 0, 1, 0]);
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df).unwrap();
+
+    let slippages = get_f64_values(&benchmark_df, "slippage");
+    assert_eq!(slippages[1], 0.0);
+    // market exit slips half of the kline range, from 100.0 to 100.75
+    assert!(slippages[3] > 0.0);
+}
+
+#[test]
+fn test_funding_is_charged_on_open_positions() {
+    let trading_settings = get_trading_settings(OrderType::Market, 1);
+    let strategy_df = get_strategy_df(&[100.0, 100.5, 100.5, 100.5, 100.5], &[0, 0, 0, 1, 0]);
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df.clone()).unwrap();
+    let mut funded_strategy_df = strategy_df;
+    // funding is settled within the second and fifth klines, the latter after the short is closed
+    funded_strategy_df
+        .with_column(Series::new(
+            get_funding_rate_col("BTCUSDT").into(),
+            vec![None, None, Some(0.01), None, Some(0.01)],
+        ))
+        .unwrap();
+    let funded_benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), funded_strategy_df).unwrap();
+
+    assert_eq!(get_f64_values(&benchmark_df, "funding_fees"), vec![0.0; 5]);
+    let funding_fees = get_f64_values(&funded_benchmark_df, "funding_fees");
+    // shorts receive positive funding rates
+    assert!(funding_fees[2] < 0.0);
+    assert_eq!(funding_fees[4], 0.0);
+    let profit_and_loss = get_f64_values(&benchmark_df, "profit_and_loss")[4];
+    let funded_profit_and_loss = get_f64_values(&funded_benchmark_df, "profit_and_loss")[4];
+    assert!((funded_profit_and_loss - profit_and_loss + funding_fees[2]).abs() < 1e-4);
+}
+
+#[test]
+fn test_trailing_stop_loss_closes_short_after_reverting() {
+    let trailing_stop_loss = PriceLevel::TrailingStopLoss(TrailingStopLoss::Percent(0.05, 0.05));
+    let trading_settings = get_trading_settings_with_price_levels(
+        OrderType::Market,
+        1,
+        HashMap::from([(trailing_stop_loss.get_hash_key(), trailing_stop_loss)]),
+    );
+    // the low of the first kline after the fill locks 5% out of 10% ROI, trailing the stop at 99.5
+    let strategy_df = get_strategy_df(&[100.0; 5], &[0; 5]);
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[3], SignalCategory::TrailingStopLoss.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, -1, -1, 0, 0]);
+    assert!(get_f64_values(&benchmark_df, "profit_and_loss")[3] > 0.0);
+}
+
+#[test]
+fn test_isolated_short_is_liquidated_before_bankruptcy() {
+    let trading_settings = get_trading_settings(OrderType::Market, 1);
+    // 10x bankruptcy price is 110.0, the maintenance margin brings liquidation to ~109.56
+    let strategy_df = get_strategy_df(&[100.0, 100.0, 109.7, 100.0], &[0; 4]);
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[2], SignalCategory::LeverageBankrupcty.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, -1, 0, 0]);
+    // the whole initial margin is lost
+    assert!(get_f64_values(&benchmark_df, "returns")[2] <= -1.0);
+}
+
+#[test]
+fn test_stop_loss_past_liquidation_price_is_liquidated() {
+    // 15% of the margin at 10x sets the stop loss at ~101.5, 150% sets it past the ~109.56 liquidation price
+    let near_stop_loss = PriceLevel::StopLoss(0.15);
+    let far_stop_loss = PriceLevel::StopLoss(1.5);
+    let strategy_df = get_strategy_df(&[100.0, 100.0, 109.7, 100.0], &[0; 4]);
+
+    let get_benchmark_actions = |stop_loss: PriceLevel| {
+        let trading_settings = get_trading_settings_with_price_levels(
+            OrderType::Market,
+            1,
+            HashMap::from([(stop_loss.get_hash_key(), stop_loss)]),
+        );
+        let benchmark_df =
+            compute_benchmark_positions(&new_trader(&trading_settings), strategy_df.clone())
+                .unwrap();
+        get_actions(&benchmark_df)
+    };
+
+    assert_eq!(
+        get_benchmark_actions(near_stop_loss)[2],
+        SignalCategory::StopLoss.get_column()
+    );
+    assert_eq!(
+        get_benchmark_actions(far_stop_loss)[2],
+        SignalCategory::LeverageBankrupcty.get_column()
+    );
+}
+
+#[test]
+fn test_cross_short_is_backed_by_wallet_balance() {
+    let mut trading_settings = get_trading_settings(OrderType::Market, 1);
+    trading_settings.leverage = Leverage::Cross(10);
+    let strategy_df = get_strategy_df(&[100.0, 100.0, 109.7, 100.0, 100.0], &[0, 0, 0, 1, 0]);
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[2], SignalCategory::KeepPosition.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, -1, -1, -1, 0]);
+}
+
+#[test]
+fn test_opposite_signal_reverts_position() {
+    let mut trading_settings = get_trading_settings(OrderType::Market, 1);
+    trading_settings.signals_revert_its_opposite = true;
+    let strategy_df = get_strategy_df(&[100.0; 6], &[0; 6]);
+    let strategy_df = with_signal_column(strategy_df, SignalCategory::GoLong, &[0, 0, 1, 0, 0, 0]);
+    let strategy_df =
+        with_signal_column(strategy_df, SignalCategory::CloseLong, &[0, 0, 0, 0, 1, 0]);
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df.clone()).unwrap();
+    let ignored_benchmark_df = compute_benchmark_positions(
+        &new_trader(&get_trading_settings(OrderType::Market, 1)),
+        strategy_df,
+    )
+    .unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[3], SignalCategory::RevertShort.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, -1, -1, 1, 1, 0]);
+    // without reverting, the short is kept open until the end and gets discarded
+    assert_eq!(get_positions(&ignored_benchmark_df), vec![0; 6]);
+}
+
+#[test]
+fn test_unclosed_reverted_position_keeps_revert_close() {
+    let mut trading_settings = get_trading_settings(OrderType::Market, 1);
+    trading_settings.signals_revert_its_opposite = true;
+    let strategy_df = get_strategy_df(&[100.0; 5], &[0; 5]);
+    let strategy_df = with_signal_column(strategy_df, SignalCategory::GoLong, &[0, 0, 1, 0, 0]);
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[3], SignalCategory::RevertShort.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, -1, -1, 0, 0]);
+    let balances = get_f64_values(&benchmark_df, "balance");
+    assert_eq!(balances[3], balances[4]);
+}
+
+#[test]
+fn test_loss_lock_blocks_unprofitable_close() {
+    let mut trading_settings = get_trading_settings(OrderType::Market, 1);
+    trading_settings.position_lock_modifier = PositionLock::Loss;
+    // the short is opened at 100.0, reopening at 100.0 only covers fees, at 99.0 it's profitable
+    let strategy_df = get_strategy_df(&[100.0; 5], &[0, 1, 0, 1, 0]);
+    let mut strategy_df = strategy_df;
+    strategy_df
+        .with_column(Series::new(
+            "BTCUSDT_open".into(),
+            vec![100.0, 100.0, 100.0, 100.0, 99.0],
+        ))
+        .unwrap();
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df).unwrap();
+
+    let actions = get_actions(&benchmark_df);
+    assert_eq!(actions[2], SignalCategory::KeepPosition.get_column());
+    assert_eq!(actions[4], SignalCategory::CloseShort.get_column());
+    assert_eq!(get_positions(&benchmark_df), vec![0, -1, -1, -1, 0]);
+}
+
+#[test]
+fn test_trailing_stop_loss_keyed_as_stop_loss_is_rejected() {
+    let trailing_stop_loss = PriceLevel::TrailingStopLoss(TrailingStopLoss::Percent(0.02, 0.03));
+    let trading_settings = get_trading_settings_with_price_levels(
+        OrderType::Market,
+        1,
+        HashMap::from([(String::from("sl"), trailing_stop_loss)]),
+    );
+    let strategy_df = get_strategy_df(&[100.0; 3], &[0; 3]);
+
+    let result = compute_benchmark_result(&new_trader(&trading_settings), &strategy_df);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_intrabar_path_binds_first_hit_threshold() {
+    let stop_loss = PriceLevel::StopLoss(0.05);
+    let take_profit = PriceLevel::TakeProfit(0.05);
+    let trading_settings = get_trading_settings_with_price_levels(
+        OrderType::Market,
+        1,
+        HashMap::from([
+            (stop_loss.get_hash_key(), stop_loss),
+            (take_profit.get_hash_key(), take_profit),
+        ]),
+    );
+    // the third kline touches both the ~100.5 stop loss and the ~99.5 take profit
+    let strategy_df = get_strategy_df(&[100.0, 100.0, 101.0, 100.0, 100.0], &[0; 5]);
+    let start_times = get_start_times(&strategy_df);
+    // within it, the high is hit a minute before the low
+    let paths: Vec<Vec<(i64, f64, f64)>> = start_times
+        .iter()
+        .enumerate()
+        .map(|(index, &start_time)| match index {
+            2 => vec![
+                (start_time, 100.2, 99.8),
+                (start_time + 60_000, 101.0, 99.9),
+                (start_time + 120_000, 100.0, 99.0),
+            ],
+            _ => vec![(start_time, 100.0, 99.0)],
+        })
+        .collect();
+    let resolved_strategy_df = with_intrabar_paths(strategy_df.clone(), &paths);
+
+    let benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), strategy_df).unwrap();
+    let resolved_benchmark_df =
+        compute_benchmark_positions(&new_trader(&trading_settings), resolved_strategy_df).unwrap();
+
+    // without the path, the low is assumed to bind first
+    assert_eq!(
+        get_actions(&benchmark_df)[2],
+        SignalCategory::TakeProfit.get_column()
+    );
+    assert_eq!(get_exit_times(&benchmark_df)[2], Some(start_times[2]));
+    assert_eq!(
+        get_actions(&resolved_benchmark_df)[2],
+        SignalCategory::StopLoss.get_column()
+    );
+    assert_eq!(get_positions(&resolved_benchmark_df), vec![0, -1, 0, 0, 0]);
+    let exit_times = get_exit_times(&resolved_benchmark_df);
+    assert_eq!(exit_times[2], Some(start_times[2] + 60_000));
+    assert_eq!(exit_times.iter().flatten().count(), 1);
+}
+
+#[test]
+fn test_empty_strategy_df_has_empty_benchmark() {
+    let trader = new_trader(&get_trading_settings(OrderType::Market, 1));
+    let strategy_df = get_strategy_df(&[100.0], &[0]).clear();
+
+    let result = compute_benchmark_result(&trader, &strategy_df).unwrap();
+    let benchmark_df = compute_benchmark_positions(&trader, strategy_df).unwrap();
+
+    assert!(result.is_empty());
+    assert!(result.error.is_none());
+    assert_eq!(benchmark_df.height(), 0);
+    assert!(get_actions(&benchmark_df).is_empty());
+}
+
+#[test]
+fn test_unfunded_trade_stops_benchmark_with_error() {
+    let trader = new_trader(&get_trading_settings(OrderType::Market, 1));
+    // at 10 million, the leveraged balance doesn't buy a single order size step
+    let mut strategy_df = get_strategy_df(&[100.0; 3], &[0; 3]);
+    for column in ["open", "high", "low", "close"] {
+        let prices = strategy_df
+            .column(&format!("BTCUSDT_{}", column))
+            .unwrap()
+            .as_materialized_series()
+            * 100_000.0;
+        strategy_df.with_column(prices).unwrap();
+    }
+
+    let result = compute_benchmark_result(&trader, &strategy_df).unwrap();
+
+    assert!(matches!(result.error, Some(IterationsError::ZeroUnits)));
+    assert_eq!(result.len(), strategy_df.height());
+    assert_eq!(result.positions, vec![0; 3]);
+}
+
+#[test]
+fn test_stepping_done_engine_returns_error() {
+    let trader = new_trader(&get_trading_settings(OrderType::Market, 1));
+    let strategy_df = get_strategy_df(&[100.0; 2], &[0; 2]);
+    let mut engine = BenchmarkEngine::new(&trader, &strategy_df).unwrap();
+
+    engine.step(INITIAL_BENCHMARK_BALANCE).unwrap();
+
+    assert!(engine.is_done());
+    assert!(matches!(
+        engine.step(INITIAL_BENCHMARK_BALANCE),
+        Err(IterationsError::EngineDone)
+    ));
+    assert!(matches!(
+        engine.skip(INITIAL_BENCHMARK_BALANCE),
+        Err(IterationsError::EngineDone)
+    ));
+}
@@ -0,0 +1,337 @@
+use chrono::NaiveDateTime;
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{
+            leverage::Leverage,
+            position_lock::PositionLock,
+            price_level::{PriceLevel, TrailingStopLoss},
+            slippage::Slippage,
+        },
+        order_type::OrderType,
+        signal_category::SignalCategory,
+        symbol_id::SymbolId,
+        trading_data_update::TradingDataUpdate,
+    },
+    functions::funding::get_funding_rate_col,
+    structs::{BehaviorSubject, Clock, LimitOrderSettings, TradingSettings},
+};
+use core::{
+    benchmark::functions::{compute_benchmark_positions, compute_benchmark_result},
+    trader::Trader,
+};
+use exchanges::{
+    binance::trader::BinanceTraderExchange,
+    enums::TraderExchangeWrapper,
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use polars::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+static TEST_CSV_FILES: [&str; 2] = ["BTC_USDT_AGIX_USDT.csv", "calc 1BTC_USDT_AGIX_USDT.csv"];
+
+/// Benchmark outputs the engine is checked against, regenerated by `generate_benchmark_outputs_csv`:
+///
+/// `cargo test -p core --test benchmark_engine -- --ignored`
+static BENCHMARK_OUTPUTS_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../data/test/benchmark_outputs.csv"
+);
+
+static OUTPUT_COLUMNS: [&str; 10] = [
+    "trade_fees",
+    "slippage",
+    "funding_fees",
+    "units",
+    "profit_and_loss",
+    "returns",
+    "balance",
+    "position",
+    "action",
+    "exit_time",
+];
+
+/// Reads the signal column of a test file, whose booleans are either lowercase or uppercase
+fn get_signal_column(df: &DataFrame, csv_columns: &[&str], height: usize) -> Vec<i32> {
+    let Some(column) = csv_columns
+        .iter()
+        .find_map(|csv_column| df.column(csv_column).ok())
+    else {
+        return vec![0; height];
+    };
+    column
+        .cast(&DataType::String)
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_iter()
+        .map(|value| value.is_some_and(|value| value.eq_ignore_ascii_case("true")) as i32)
+        .collect()
+}
+
+/// Loads a `data/test` file as BTCUSDT strategy data
+fn load_strategy_df(file_name: &str) -> DataFrame {
+    let path = format!(
+        "{}/../../data/test/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        file_name
+    );
+    let df = CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(None)
+        .try_into_reader_with_file_path(Some(path.into()))
+        .unwrap()
+        .finish()
+        .unwrap();
+    let height = df.height();
+    let start_times: Vec<i64> = df
+        .column("start_time")
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_no_null_iter()
+        .map(|start_time| {
+            NaiveDateTime::parse_from_str(start_time, "%Y-%m-%dT%H:%M:%S%.3f")
+                .unwrap()
+                .and_utc()
+                .timestamp_millis()
+        })
+        .collect();
+    let mut columns = vec![Series::new("start_time".into(), start_times)
+        .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+        .unwrap()
+        .into_column()];
+    for price_col in [
+        "BTCUSDT_open",
+        "BTCUSDT_high",
+        "BTCUSDT_low",
+        "BTCUSDT_close",
+    ] {
+        columns.push(
+            df.column(price_col)
+                .unwrap()
+                .cast(&DataType::Float64)
+                .unwrap(),
+        );
+    }
+    for (signal_category, csv_columns) in [
+        (SignalCategory::GoShort, vec!["short"]),
+        (SignalCategory::GoLong, vec!["long"]),
+        (
+            SignalCategory::CloseShort,
+            vec!["short_close", "close_short"],
+        ),
+        (SignalCategory::CloseLong, vec!["long_close", "close_long"]),
+    ] {
+        columns.push(Column::new(
+            signal_category.get_column().into(),
+            get_signal_column(&df, &csv_columns, height),
+        ));
+    }
+    DataFrame::new(columns).unwrap()
+}
+
+fn get_trading_settings() -> TradingSettings {
+    TradingSettings::new(
+        10.0,
+        Leverage::Isolated(10),
+        OrderType::Market,
+        OrderType::Market,
+        PositionLock::None,
+        HashMap::new(),
+        false,
+        &SymbolId::Bitcoin,
+        &SymbolId::Bitcoin,
+        1,
+        Granularity::m1,
+    )
+}
+
+fn new_trader(trading_settings: &TradingSettings) -> Trader {
+    let config = ExchangeConfig {
+        credentials: ApiCredentials {
+            key: "test-api-key",
+            secret: "test-api-secret",
+        },
+        endpoints: ApiEndpoints {
+            http: "http://127.0.0.1:0",
+            ws: "ws://127.0.0.1:0",
+        },
+    };
+    let trader_exchange = TraderExchangeWrapper::Binance(BinanceTraderExchange::with_config(
+        trading_settings,
+        config,
+    ));
+    Trader::new(
+        &BehaviorSubject::new(TradingDataUpdate::default()),
+        trader_exchange,
+        &Arc::new(Mutex::new(DataFrame::empty())),
+        &Arc::new(RwLock::new(0)),
+        &Clock::default(),
+    )
+}
+
+/// Settings covering every path of the benchmark engine
+fn get_trading_settings_cases() -> Vec<(&'static str, TradingSettings)> {
+    let stop_loss = PriceLevel::StopLoss(0.03);
+    let take_profit = PriceLevel::TakeProfit(0.05);
+    let trailing_stop_loss = PriceLevel::TrailingStopLoss(TrailingStopLoss::Percent(0.02, 0.03));
+    let price_levels = HashMap::from([
+        (stop_loss.get_hash_key(), stop_loss),
+        (take_profit.get_hash_key(), take_profit),
+    ]);
+
+    let mut limit = get_trading_settings();
+    limit.order_types.0 = OrderType::Limit;
+    let limit = limit.patch_limit_order_settings(LimitOrderSettings {
+        price_offset_percentage: 0.1,
+        cancel_after_klines: 3,
+    });
+    let mut price_locked = get_trading_settings();
+    price_locked.price_level_modifier_map = price_levels;
+    let mut trailing = get_trading_settings();
+    trailing.price_level_modifier_map =
+        HashMap::from([(trailing_stop_loss.get_hash_key(), trailing_stop_loss)]);
+    let mut reverting = get_trading_settings().patch_slippage(Slippage::Percentage(0.05));
+    reverting.signals_revert_its_opposite = true;
+    reverting.position_lock_modifier = PositionLock::Fee;
+    let mut reverting_limit = limit.clone();
+    reverting_limit.signals_revert_its_opposite = true;
+    let mut cross = get_trading_settings();
+    cross.leverage = Leverage::Cross(50);
+    cross.allocation_percentage = 100.0;
+    let mut isolated = get_trading_settings();
+    isolated.leverage = Leverage::Isolated(200);
+
+    vec![
+        ("market", get_trading_settings()),
+        ("limit", limit),
+        ("price levels", price_locked),
+        ("trailing stop loss", trailing),
+        ("reverting", reverting),
+        ("reverting limit", reverting_limit),
+        ("cross", cross),
+        ("isolated", isolated),
+    ]
+}
+
+/// Sets funding rates every 8 hours, as they're settled
+fn with_funding_rates(strategy_df: &DataFrame) -> DataFrame {
+    let funding_rates: Vec<Option<f64>> = (0..strategy_df.height())
+        .map(|index| (index % 480 == 0).then_some(0.0001))
+        .collect();
+    let mut funded_strategy_df = strategy_df.clone();
+    funded_strategy_df
+        .with_column(Series::new(
+            get_funding_rate_col("BTCUSDT").into(),
+            funding_rates,
+        ))
+        .unwrap();
+    funded_strategy_df
+}
+
+/// Benchmarks every settings case over the test files, with and without funding.
+/// Only the klines whose outputs changed from the previous one are kept, which still tells any two benchmarks apart.
+fn get_benchmark_outputs() -> DataFrame {
+    let mut kept_columns = vec![col("case"), col("start_time")];
+    kept_columns.extend(OUTPUT_COLUMNS.iter().map(|column| col(*column)));
+    let changed_outputs = OUTPUT_COLUMNS
+        .iter()
+        .map(|column| col(*column).neq_missing(col(*column).shift(lit(1))))
+        .collect::<Vec<Expr>>();
+
+    let mut benchmark_outputs: Option<DataFrame> = None;
+    for file_name in TEST_CSV_FILES {
+        let strategy_df = load_strategy_df(file_name);
+        let funded_strategy_df = with_funding_rates(&strategy_df);
+        for (case, trading_settings) in get_trading_settings_cases() {
+            for (funding, strategy_df) in
+                [("unfunded", &strategy_df), ("funded", &funded_strategy_df)]
+            {
+                let trader = new_trader(&trading_settings);
+                let benchmark_df =
+                    compute_benchmark_positions(&trader, strategy_df.clone()).unwrap();
+                let case = format!("{} | {} | {}", file_name, case, funding);
+                let case_outputs = benchmark_df
+                    .lazy()
+                    .filter(any_horizontal(changed_outputs.clone()).unwrap())
+                    .with_column(lit(case).alias("case"))
+                    .select(kept_columns.clone())
+                    .collect()
+                    .unwrap();
+                match benchmark_outputs.as_mut() {
+                    Some(benchmark_outputs) => {
+                        benchmark_outputs.vstack_mut(&case_outputs).unwrap();
+                    }
+                    None => benchmark_outputs = Some(case_outputs),
+                }
+            }
+        }
+    }
+    benchmark_outputs.unwrap()
+}
+
+#[test]
+fn test_engine_matches_benchmark_outputs() {
+    let benchmark_outputs = get_benchmark_outputs();
+    let stored_benchmark_outputs = CsvReadOptions::default()
+        .with_has_header(true)
+        .with_schema(Some(benchmark_outputs.schema().clone()))
+        .try_into_reader_with_file_path(Some(BENCHMARK_OUTPUTS_CSV.into()))
+        .expect("benchmark outputs csv to exist")
+        .finish()
+        .expect("benchmark outputs csv to be parsed");
+
+    assert_eq!(
+        benchmark_outputs.height(),
+        stored_benchmark_outputs.height()
+    );
+    for case_outputs in benchmark_outputs
+        .partition_by_stable(["case"], true)
+        .unwrap()
+    {
+        let case = case_outputs
+            .column("case")
+            .unwrap()
+            .str()
+            .unwrap()
+            .get(0)
+            .unwrap();
+        let stored_case_outputs = stored_benchmark_outputs
+            .clone()
+            .lazy()
+            .filter(col("case").eq(lit(case)))
+            .collect()
+            .unwrap();
+        assert!(
+            case_outputs.equals_missing(&stored_case_outputs),
+            "{} benchmark differs from the stored one",
+            case
+        );
+    }
+}
+
+#[test]
+#[ignore = "regenerates the benchmark outputs csv"]
+fn generate_benchmark_outputs_csv() {
+    let mut benchmark_outputs = get_benchmark_outputs();
+    let mut file = std::fs::File::create(BENCHMARK_OUTPUTS_CSV).unwrap();
+    CsvWriter::new(&mut file)
+        .finish(&mut benchmark_outputs)
+        .unwrap();
+}
+
+#[test]
+fn test_engine_trades_test_data() {
+    let strategy_df = load_strategy_df(TEST_CSV_FILES[0]);
+    let trader = new_trader(&get_trading_settings());
+
+    let result = compute_benchmark_result(&trader, &strategy_df).unwrap();
+
+    assert_eq!(result.len(), strategy_df.height());
+    assert!(result.positions.contains(&1) && result.positions.contains(&-1));
+    assert!(result.actions.contains(&SignalCategory::CloseShort));
+}
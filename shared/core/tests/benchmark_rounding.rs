@@ -0,0 +1,291 @@
+use common::enums::{modifiers::slippage::Slippage, side::Side};
+use core::benchmark::{
+    new_benchmark_trade, round_nth_decimal, BenchmarkMargin, BenchmarkSlippage, BenchmarkTrade,
+    ContractSteps, NewBenchmarkTradeParams, PriceLock, ScaledStep, USDT_DECIMALS,
+};
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestCaseError, TestRunner},
+};
+
+/// (tick size, quantity step) pairs of actual contracts
+static CONTRACT_STEPS: [(ScaledStep, ScaledStep); 4] = [
+    (ScaledStep::new(1, 1), ScaledStep::new(1, 3)),
+    (ScaledStep::new(1, 2), ScaledStep::new(1, 2)),
+    (ScaledStep::new(1, 4), ScaledStep::new(1, 0)),
+    (ScaledStep::new(5, 1), ScaledStep::new(1, 1)),
+];
+
+/// Amounts are rounded to `USDT_DECIMALS` at every step, so each cycle drifts by a few units of that decimal at most,
+/// unless the balance grows so large that its float error is coarser than that decimal
+fn get_cycle_tolerance(balance: f64) -> f64 {
+    f64::max(1e-7, balance.abs() * 8.0 * f64::EPSILON)
+}
+
+fn new_trade_params(
+    balance: f64,
+    price: f64,
+    side: Side,
+    steps: ContractSteps,
+    fee_rate: f64,
+    leverage_factor: f64,
+) -> NewBenchmarkTradeParams {
+    NewBenchmarkTradeParams::new(
+        100.0,
+        balance,
+        leverage_factor,
+        BenchmarkMargin::new(false, 0.0, &[]),
+        None,
+        fee_rate,
+        (steps.qty_step, f64::MAX),
+        price,
+        (Some(PriceLock(0.5)), Some(PriceLock(0.5))),
+        side,
+        BenchmarkSlippage::new(Slippage::None, steps.tick_size),
+        None,
+        steps,
+        fee_rate,
+        None,
+    )
+}
+
+/// Opens a trade with the whole balance, returning it alongside the balance left after paying its margin and fee
+fn open_trade(
+    balance: f64,
+    price: f64,
+    side: Side,
+    steps: ContractSteps,
+    fee_rate: f64,
+    leverage_factor: f64,
+) -> Option<(BenchmarkTrade, f64)> {
+    let params = new_trade_params(balance, price, side, steps, fee_rate, leverage_factor);
+    let trade = new_benchmark_trade(params).ok()?;
+    let open_balance = round_nth_decimal(
+        balance - trade.initial_margin - trade.open_fee,
+        USDT_DECIMALS,
+    );
+    Some((trade, open_balance))
+}
+
+/// Allows for the float error of the division, which grows with the number of steps
+fn is_step_multiple(value: f64, step: f64) -> bool {
+    let steps = value / step;
+    (steps - steps.round()).abs() < 1e-4
+}
+
+/// Runner without failure persistence, as it needs the source file the `proptest!` macro passes
+fn new_test_runner() -> TestRunner {
+    TestRunner::new(Config {
+        failure_persistence: None,
+        ..Config::default()
+    })
+}
+
+/// Fails the case with the message unless the condition holds.
+/// `prop_assert!` can't be used, as its expansion refers to the std `core` this crate shadows.
+fn check(condition: bool, message: impl FnOnce() -> String) -> Result<(), TestCaseError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(TestCaseError::fail(message()))
+    }
+}
+
+fn contract_steps() -> impl Strategy<Value = ContractSteps> {
+    prop::sample::select(&CONTRACT_STEPS[..]).prop_map(|(tick, qty)| ContractSteps::new(tick, qty))
+}
+
+fn side() -> impl Strategy<Value = Side> {
+    prop::bool::ANY.prop_map(|is_long| if is_long { Side::Buy } else { Side::Sell })
+}
+
+#[test]
+fn test_round_trip_without_fees_keeps_balance() {
+    let strategy = (
+        1_000_u32..10_000_000,
+        1.0_f64..100_000.0,
+        side(),
+        contract_steps(),
+        1_u32..100,
+    );
+    new_test_runner()
+        .run(
+            &strategy,
+            |(balance_cents, price, side, steps, leverage_factor)| {
+                let balance = balance_cents as f64 / 100.0;
+                let price = steps.round_price(price);
+                let Some((trade, open_balance)) =
+                    open_trade(balance, price, side, steps, 0.0, leverage_factor as f64)
+                else {
+                    return Ok(());
+                };
+                let (pnl, _, close_fee) = trade.get_pnl_returns_and_fees(price, 0.0);
+                let close_balance = trade.get_close_balance(open_balance, pnl);
+
+                check(close_fee == 0.0, || {
+                    format!("charged {} to close", close_fee)
+                })?;
+                check(close_balance == balance, || {
+                    format!("closed with {} out of {}", close_balance, balance)
+                })
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_open_close_cycles_conserve_balance() {
+    let strategy = (
+        1_000_u32..10_000_000,
+        prop::collection::vec((side(), 1.0_f64..100_000.0, -0.2_f64..0.2), 1..20),
+        contract_steps(),
+        0.0_f64..0.001,
+        1_u32..20,
+    );
+    new_test_runner()
+        .run(
+            &strategy,
+            |(balance_cents, cycles, steps, fee_rate, leverage_factor)| {
+                let mut balance = balance_cents as f64 / 100.0;
+                for (side, open_price, price_change) in cycles {
+                    let open_price = steps.round_price(open_price);
+                    let close_price = steps.round_price(open_price * (1.0 + price_change));
+                    let Some((trade, open_balance)) = open_trade(
+                        balance,
+                        open_price,
+                        side,
+                        steps,
+                        fee_rate,
+                        leverage_factor as f64,
+                    ) else {
+                        continue;
+                    };
+                    let (pnl, _, close_fee) = trade.get_pnl_returns_and_fees(close_price, fee_rate);
+                    let close_balance = trade.get_close_balance(open_balance, pnl);
+                    let price_delta = if side == Side::Buy {
+                        close_price - open_price
+                    } else {
+                        open_price - close_price
+                    };
+                    let expected_balance = f64::max(
+                        0.0,
+                        balance + price_delta * trade.units - trade.open_fee - close_fee,
+                    );
+
+                    check(
+                        (close_balance - expected_balance).abs()
+                            < get_cycle_tolerance(expected_balance),
+                        || {
+                            format!(
+                                "closed with {} instead of {}",
+                                close_balance, expected_balance
+                            )
+                        },
+                    )?;
+                    balance = close_balance;
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_trades_follow_contract_steps() {
+    let strategy = (
+        1_000_u32..10_000_000,
+        1.0_f64..100_000.0,
+        side(),
+        contract_steps(),
+        0.0_f64..0.001,
+        1_u32..100,
+    );
+    new_test_runner()
+        .run(
+            &strategy,
+            |(balance_cents, price, side, steps, fee_rate, leverage_factor)| {
+                let balance = balance_cents as f64 / 100.0;
+                let Some((trade, open_balance)) = open_trade(
+                    balance,
+                    price,
+                    side,
+                    steps,
+                    fee_rate,
+                    leverage_factor as f64,
+                ) else {
+                    return Ok(());
+                };
+                let (_, _, stop_loss_price, take_profit_price) = trade.prices;
+
+                check(
+                    trade.units >= steps.qty_step && is_step_multiple(trade.units, steps.qty_step),
+                    || {
+                        format!(
+                            "opened {} units with a {} step",
+                            trade.units, steps.qty_step
+                        )
+                    },
+                )?;
+                for price in [stop_loss_price.unwrap(), take_profit_price.unwrap()] {
+                    check(is_step_multiple(price, steps.tick_size), || {
+                        format!("{} is off the {} tick", price, steps.tick_size)
+                    })?;
+                }
+                check(
+                    open_balance >= 0.0
+                        && open_balance == round_nth_decimal(open_balance, USDT_DECIMALS),
+                    || format!("left a balance of {} after opening", open_balance),
+                )
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_step_rounding_is_idempotent() {
+    let strategy = (0.0_f64..1_000_000.0, contract_steps());
+    new_test_runner()
+        .run(&strategy, |(value, steps)| {
+            let price = steps.round_price(value);
+            let units = steps.round_down_units(value);
+
+            check(
+                is_step_multiple(price, steps.tick_size)
+                    && (price - value).abs() <= steps.tick_size / 2.0 + 1e-9
+                    && steps.round_price(price) == price,
+                || format!("rounded {} to the {} price", value, price),
+            )?;
+            check(
+                is_step_multiple(units, steps.qty_step)
+                    && units <= value + 1e-9
+                    && value - units < steps.qty_step
+                    && steps.round_down_units(units) == units,
+                || format!("rounded {} down to {} units", value, units),
+            )
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_float_steps_are_scaled_to_their_decimals() {
+    for (step, scaled_step) in [
+        (0.1, ScaledStep::new(1, 1)),
+        (1e-8, ScaledStep::new(1, 8)),
+        (0.00001, ScaledStep::new(1, 5)),
+        (0.05, ScaledStep::new(5, 2)),
+        (10.0, ScaledStep::new(10, 0)),
+    ] {
+        assert_eq!(ScaledStep::from_f64(step), scaled_step);
+        assert_eq!(scaled_step.to_f64(), step);
+
+        let steps = ContractSteps::from_f64(step, step);
+        assert_eq!(steps.tick_decimals, scaled_step.decimals);
+        assert_eq!(steps.qty_decimals, scaled_step.decimals);
+        // the rounded price holds no float error, unlike `step * 3.0`
+        let three_steps = ScaledStep::new(scaled_step.value * 3, scaled_step.decimals).to_f64();
+        assert_eq!(steps.round_price(step * 3.4), three_steps);
+    }
+    let steps = ContractSteps::from_f64(1e-8, 0.00001);
+    assert_eq!(steps.round_price(0.123456789), 0.12345679);
+    assert_eq!(steps.round_down_units(0.123456789), 0.12345);
+}
@@ -0,0 +1,143 @@
+use core::monte_carlo::{
+    get_session_returns, run_monte_carlo, MonteCarloSettings, ResamplingMethod,
+    MONTE_CARLO_PERCENTILES,
+};
+use polars::prelude::{Column, DataFrame};
+
+/// Trading sessions frame, whose first session holds no position and keeps the initial balance
+fn get_sessions_df(balances: &[f64]) -> DataFrame {
+    let positions: Vec<i32> = (0..balances.len())
+        .map(|index| match index {
+            0 => 0,
+            index if index % 2 == 0 => 1,
+            _ => -1,
+        })
+        .collect();
+    DataFrame::new(vec![
+        Column::new("position".into(), positions),
+        Column::new("balance".into(), balances.to_vec()),
+    ])
+    .unwrap()
+}
+
+fn get_settings(method: ResamplingMethod) -> MonteCarloSettings {
+    MonteCarloSettings {
+        iterations: 500,
+        method,
+        seed: Some(7),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_session_returns_follow_balances() {
+    let sessions_df = get_sessions_df(&[100.0, 110.0, 99.0, 108.9]);
+
+    let (initial_balance, returns) = get_session_returns(&sessions_df).unwrap();
+
+    assert_eq!(initial_balance, 100.0);
+    assert_eq!(returns.len(), 3);
+    for (session_return, expected_return) in returns.iter().zip([0.1, -0.1, 0.1]) {
+        assert!((session_return - expected_return).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_shuffle_keeps_final_balance() {
+    let sessions_df = get_sessions_df(&[100.0, 110.0, 99.0, 108.9, 87.12, 95.832, 105.4152]);
+
+    let report = run_monte_carlo(&sessions_df, &get_settings(ResamplingMethod::Shuffle)).unwrap();
+
+    assert_eq!(report.sessions, 6);
+    assert_eq!(report.simulations.len(), 500);
+    for simulation in &report.simulations {
+        assert!((simulation.final_balance - 105.4152).abs() < 1e-9);
+    }
+    let max_drawdowns = report.get_percentiles(|simulation| simulation.max_drawdown);
+    assert!(max_drawdowns.first() < max_drawdowns.last());
+}
+
+#[test]
+fn test_whole_block_bootstrap_keeps_final_balance() {
+    let sessions_df = get_sessions_df(&[100.0, 120.0, 90.0, 99.0, 79.2]);
+
+    let report = run_monte_carlo(
+        &sessions_df,
+        &get_settings(ResamplingMethod::BlockBootstrap(4)),
+    )
+    .unwrap();
+
+    // a block as long as the sessions is a rotation of them
+    for simulation in &report.simulations {
+        assert!((simulation.final_balance - 79.2).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_bootstrap_is_reproducible_and_sorted() {
+    let sessions_df = get_sessions_df(&[100.0, 105.0, 94.5, 103.95, 98.7525, 108.62775, 97.764975]);
+    let settings = get_settings(ResamplingMethod::Bootstrap);
+
+    let report = run_monte_carlo(&sessions_df, &settings).unwrap();
+    let same_seed_report = run_monte_carlo(&sessions_df, &settings).unwrap();
+
+    let final_balances = report.get_percentiles(|simulation| simulation.final_balance);
+    assert_eq!(
+        final_balances,
+        same_seed_report.get_percentiles(|simulation| simulation.final_balance)
+    );
+    assert!(final_balances.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(final_balances.first() < final_balances.last());
+    let sharpe_ratios = report.get_percentiles(|simulation| simulation.sharpe_ratio);
+    assert!(sharpe_ratios.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn test_risk_of_ruin() {
+    let losing_balances: Vec<f64> = (0..10).map(|index| 100.0 * 0.9_f64.powi(index)).collect();
+    let winning_balances: Vec<f64> = (0..10).map(|index| 100.0 * 1.1_f64.powi(index)).collect();
+    let settings = get_settings(ResamplingMethod::Bootstrap);
+
+    let losing_report = run_monte_carlo(&get_sessions_df(&losing_balances), &settings).unwrap();
+    let winning_report = run_monte_carlo(&get_sessions_df(&winning_balances), &settings).unwrap();
+
+    // nine sessions losing 10% leave 38.7% of the balance
+    assert_eq!(losing_report.get_risk_of_ruin(), 1.0);
+    assert_eq!(winning_report.get_risk_of_ruin(), 0.0);
+    assert!(winning_report
+        .simulations
+        .iter()
+        .all(|simulation| simulation.max_drawdown == 0.0));
+}
+
+#[test]
+fn test_report_frames() {
+    let sessions_df = get_sessions_df(&[100.0, 110.0, 99.0, 108.9]);
+
+    let report = run_monte_carlo(&sessions_df, &get_settings(ResamplingMethod::Bootstrap)).unwrap();
+    let simulations_df = report.get_simulations_df().unwrap();
+    let percentiles_df = report.get_percentiles_df().unwrap();
+
+    assert_eq!(simulations_df.height(), 500);
+    assert_eq!(
+        simulations_df.get_column_names_str(),
+        [
+            "simulation",
+            "final_balance",
+            "max_drawdown",
+            "sharpe_ratio",
+            "is_ruined"
+        ]
+    );
+    assert_eq!(percentiles_df.height(), MONTE_CARLO_PERCENTILES.len());
+    assert!(report.to_string().contains("Risk of ruin"));
+}
+
+#[test]
+fn test_fails_without_trading_sessions() {
+    let sessions_df = get_sessions_df(&[100.0]);
+
+    let result = run_monte_carlo(&sessions_df, &MonteCarloSettings::default());
+
+    assert!(result.is_err());
+}
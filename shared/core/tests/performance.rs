@@ -0,0 +1,103 @@
+use chrono::Duration;
+use common::enums::granularity::Granularity;
+use core::performance::calculate_trading_stats;
+use polars::prelude::{Column, DataFrame, DataType, TimeUnit};
+
+const HOUR_IN_MS: i64 = 3_600_000;
+
+fn get_datetime_column(name: &str, hours: &[i64]) -> Column {
+    Column::new(
+        name.into(),
+        hours
+            .iter()
+            .map(|hour| hour * HOUR_IN_MS)
+            .collect::<Vec<i64>>(),
+    )
+    .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+    .unwrap()
+}
+
+/// Trading sessions over 100 hours, whose first session holds no position and keeps the initial balance,
+/// followed by two winning trades, a losing one and a last winning one
+fn get_sessions_df() -> DataFrame {
+    DataFrame::new(vec![
+        Column::new("session".into(), vec![0_u32, 1, 2, 3, 4]),
+        get_datetime_column("start", &[0, 10, 20, 40, 60]),
+        get_datetime_column("end", &[10, 20, 30, 50, 100]),
+        Column::new(
+            "start_price".into(),
+            vec![100.0, 100.0, 110.0, 121.0, 108.9],
+        ),
+        Column::new("end_price".into(), vec![100.0, 110.0, 121.0, 108.9, 121.0]),
+        Column::new("position".into(), vec![0_i32, 1, 1, 1, 1]),
+        Column::new("returns".into(), vec![0.0, 0.1, 0.1, -0.1, 0.111]),
+        Column::new("units".into(), vec![0.0, 1.0, 1.0, 1.0, 1.0]),
+        Column::new("profit_and_loss".into(), vec![0.0, 10.0, 11.0, -12.1, 12.1]),
+        Column::new("balance".into(), vec![100.0, 110.0, 121.0, 108.9, 121.0]),
+        Column::new("risk".into(), vec![0.0, 0.01, 0.01, 0.01, 0.01]),
+        Column::new("trade_fees".into(), vec![0.0, 1.0, 1.0, 1.0, 1.0]),
+        Column::new("slippage".into(), vec![0.0; 5]),
+        Column::new("funding_fees".into(), vec![0.0; 5]),
+        Column::new("downside_risk".into(), vec![0.0, 0.0, 0.0, 0.01, 0.0]),
+        Column::new("drawdown".into(), vec![0.0, 0.0, 0.0, 0.1, 0.0]),
+    ])
+    .unwrap()
+}
+
+#[test]
+fn test_trading_metrics() {
+    let stats = calculate_trading_stats(&get_sessions_df(), 0.0, Granularity::d1).unwrap();
+    let trading_metrics = stats.trading_metrics();
+
+    assert!((trading_metrics.profit_factor - 33.1 / 12.1).abs() < 1e-9);
+    assert!((trading_metrics.average_win - 33.1 / 3.0).abs() < 1e-9);
+    assert!((trading_metrics.average_loss + 12.1).abs() < 1e-9);
+    assert!((trading_metrics.payoff_ratio - 33.1 / 3.0 / 12.1).abs() < 1e-9);
+    assert!((trading_metrics.expectancy - 21.0 / 4.0).abs() < 1e-9);
+    assert_eq!(trading_metrics.max_consecutive_wins, 2);
+    assert_eq!(trading_metrics.max_consecutive_losses, 1);
+    // open for 70 out of 100 hours
+    assert!((trading_metrics.exposure_time - 0.7).abs() < 1e-9);
+    assert_eq!(
+        trading_metrics.average_trade_duration,
+        Duration::minutes(17 * 60 + 30)
+    );
+    assert!((trading_metrics.turnover - 900.8).abs() < 1e-9);
+    assert!((trading_metrics.total_fees - 4.0).abs() < 1e-9);
+    assert!((trading_metrics.recovery_factor - 2.1).abs() < 1e-9);
+    assert!((trading_metrics.ulcer_index - 0.05).abs() < 1e-9);
+    let years = 100.0 / (365.0 * 24.0);
+    assert!((trading_metrics.cagr - (1.21_f64.powf(1.0 / years) - 1.0)).abs() < 1e-9);
+    assert!(stats.to_string().contains("Profit factor"));
+}
+
+#[test]
+fn test_sharpe_ratio_samples_balance_per_period() {
+    let sessions_df = get_sessions_df();
+
+    let daily_stats = calculate_trading_stats(&sessions_df, 0.0, Granularity::d1).unwrap();
+    let hourly_stats = calculate_trading_stats(&sessions_df, 0.0, Granularity::h1).unwrap();
+
+    // the per trade ratios sum each trade returns over its risk, whatever the sampling
+    for stats in [&daily_stats, &hourly_stats] {
+        assert!((stats.per_trade_sharpe_ratio() - 21.1).abs() < 1e-9);
+        assert!((stats.per_trade_sortino_ratio() + 10.0).abs() < 1e-9);
+    }
+    assert!(daily_stats.sharpe_ratio() > 0.0);
+    assert!(hourly_stats.sharpe_ratio() > 0.0);
+    assert!(daily_stats.sortino_ratio() > daily_stats.sharpe_ratio());
+    assert!(daily_stats.sharpe_ratio() < daily_stats.per_trade_sharpe_ratio());
+}
+
+#[test]
+fn test_trading_metrics_without_trades() {
+    let sessions_df = get_sessions_df().head(Some(1));
+
+    let stats = calculate_trading_stats(&sessions_df, 0.0, Granularity::d1).unwrap();
+    let trading_metrics = stats.trading_metrics();
+
+    assert_eq!(trading_metrics.profit_factor, 0.0);
+    assert_eq!(trading_metrics.exposure_time, 0.0);
+    assert_eq!(trading_metrics.cagr, 0.0);
+    assert_eq!(trading_metrics.max_consecutive_wins, 0);
+}
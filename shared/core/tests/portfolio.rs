@@ -0,0 +1,185 @@
+use chrono::{Duration, TimeZone, Utc};
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{leverage::Leverage, position_lock::PositionLock},
+        order_type::OrderType,
+        signal_category::SignalCategory,
+        symbol_id::SymbolId,
+        trading_data_update::TradingDataUpdate,
+    },
+    structs::{BehaviorSubject, Clock, TradingSettings},
+};
+use core::{
+    benchmark::portfolio::{
+        compute_portfolio_positions, get_portfolio_balance_col, get_portfolio_position_col,
+        PortfolioLeg,
+    },
+    performance::calculate_portfolio_stats,
+    trader::Trader,
+};
+use exchanges::{
+    binance::trader::BinanceTraderExchange,
+    enums::TraderExchangeWrapper,
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use polars::prelude::{DataFrame, DataType, IntoColumn, NamedFrom, Series, TimeUnit};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+fn new_trader(symbol_id: SymbolId, allocation_percentage: f64) -> Trader {
+    let trading_settings = TradingSettings::new(
+        allocation_percentage,
+        Leverage::Isolated(10),
+        OrderType::Market,
+        OrderType::Market,
+        PositionLock::None,
+        HashMap::new(),
+        false,
+        &symbol_id,
+        &symbol_id,
+        1,
+        Granularity::m1,
+    );
+    let config = ExchangeConfig {
+        credentials: ApiCredentials {
+            key: "test-api-key",
+            secret: "test-api-secret",
+        },
+        endpoints: ApiEndpoints {
+            http: "http://127.0.0.1:0",
+            ws: "ws://127.0.0.1:0",
+        },
+    };
+    let trader_exchange = TraderExchangeWrapper::Binance(BinanceTraderExchange::with_config(
+        &trading_settings,
+        config,
+    ));
+    Trader::new(
+        &BehaviorSubject::new(TradingDataUpdate::default()),
+        trader_exchange,
+        &Arc::new(Mutex::new(DataFrame::empty())),
+        &Arc::new(RwLock::new(0)),
+        &Clock::default(),
+    )
+}
+
+/// Builds a strategy dataframe with flat 100.0 prices, shorting on the first kline and closing as given
+fn get_strategy_df(symbol_name: &str, first_minute: i64, short_closes: &[i32]) -> DataFrame {
+    let height = short_closes.len();
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let start_times: Vec<i64> = (first_minute..first_minute + height as i64)
+        .map(|minute| (start + Duration::minutes(minute)).timestamp_millis())
+        .collect();
+    let mut shorts = vec![0; height];
+    shorts[0] = 1;
+    DataFrame::new(vec![
+        Series::new("start_time".into(), start_times)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap()
+            .into_column(),
+        Series::new(format!("{}_open", symbol_name).into(), vec![100.0; height]).into_column(),
+        Series::new(format!("{}_high", symbol_name).into(), vec![100.0; height]).into_column(),
+        Series::new(format!("{}_low", symbol_name).into(), vec![99.0; height]).into_column(),
+        Series::new(format!("{}_close", symbol_name).into(), vec![100.0; height]).into_column(),
+        Series::new(SignalCategory::GoShort.get_column().into(), shorts).into_column(),
+        Series::new(
+            SignalCategory::CloseShort.get_column().into(),
+            short_closes.to_vec(),
+        )
+        .into_column(),
+    ])
+    .unwrap()
+}
+
+fn get_f64_values(df: &DataFrame, column: &str) -> Vec<f64> {
+    df.column(column)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+fn get_i32_values(df: &DataFrame, column: &str) -> Vec<i32> {
+    df.column(column)
+        .unwrap()
+        .i32()
+        .unwrap()
+        .into_no_null_iter()
+        .collect()
+}
+
+#[test]
+fn test_symbols_share_wallet_with_concurrent_positions() {
+    let btc_trader = new_trader(SymbolId::Bitcoin, 50.0);
+    let eth_trader = new_trader(SymbolId::Ethereum, 50.0);
+    let legs = vec![
+        PortfolioLeg::new(get_strategy_df("BTCUSDT", 0, &[0, 0, 1, 0, 0]), &btc_trader),
+        PortfolioLeg::new(get_strategy_df("ETHUSDT", 0, &[0, 0, 0, 1, 0]), &eth_trader),
+    ];
+
+    let portfolio = compute_portfolio_positions(legs).unwrap();
+
+    let equity_df = &portfolio.equity_df;
+    assert_eq!(
+        get_i32_values(equity_df, &get_portfolio_position_col("BTCUSDT")),
+        vec![0, -1, -1, 0, 0]
+    );
+    assert_eq!(
+        get_i32_values(equity_df, &get_portfolio_position_col("ETHUSDT")),
+        vec![0, -1, -1, -1, 0]
+    );
+    // ETH is opened with half of what BTC left in the wallet
+    let btc_units = get_f64_values(&portfolio.benchmark_dfs[0].1, "units")[1];
+    let eth_units = get_f64_values(&portfolio.benchmark_dfs[1].1, "units")[1];
+    assert!(eth_units > 0.0 && eth_units < btc_units * 0.6);
+    // the wallet only changes by the results of both symbols
+    let btc_balance = *get_f64_values(equity_df, &get_portfolio_balance_col("BTCUSDT"))
+        .last()
+        .unwrap();
+    let eth_balance = *get_f64_values(equity_df, &get_portfolio_balance_col("ETHUSDT"))
+        .last()
+        .unwrap();
+    let wallet_balance = *get_f64_values(equity_df, "balance").last().unwrap();
+    assert!(btc_balance < 100.0 && eth_balance < 100.0);
+    assert!((wallet_balance - (btc_balance + eth_balance - 100.0)).abs() < 1e-6);
+}
+
+#[test]
+fn test_portfolio_stats_are_computed_per_symbol_and_combined() {
+    let btc_trader = new_trader(SymbolId::Bitcoin, 50.0);
+    let eth_trader = new_trader(SymbolId::Ethereum, 50.0);
+    let legs = vec![
+        PortfolioLeg::new(get_strategy_df("BTCUSDT", 0, &[0, 0, 1, 0, 0]), &btc_trader),
+        PortfolioLeg::new(get_strategy_df("ETHUSDT", 0, &[0, 0, 0, 1, 0]), &eth_trader),
+    ];
+    let portfolio = compute_portfolio_positions(legs).unwrap();
+
+    let (symbols_stats, portfolio_stats) =
+        calculate_portfolio_stats(&portfolio, 0.0, Granularity::d1).unwrap();
+
+    let symbol_names: Vec<&str> = symbols_stats
+        .iter()
+        .map(|(symbol, _)| symbol.name)
+        .collect();
+    assert_eq!(symbol_names, vec!["BTCUSDT", "ETHUSDT"]);
+    let wallet_balance = *get_f64_values(&portfolio.equity_df, "balance")
+        .last()
+        .unwrap();
+    assert!((portfolio_stats.current_balance() - wallet_balance).abs() < 1e-4);
+}
+
+#[test]
+fn test_portfolio_klines_must_share_start_times() {
+    let btc_trader = new_trader(SymbolId::Bitcoin, 50.0);
+    let eth_trader = new_trader(SymbolId::Ethereum, 50.0);
+    let legs = vec![
+        PortfolioLeg::new(get_strategy_df("BTCUSDT", 0, &[0, 0, 1]), &btc_trader),
+        PortfolioLeg::new(get_strategy_df("ETHUSDT", 1, &[0, 0, 1]), &eth_trader),
+    ];
+
+    assert!(compute_portfolio_positions(legs).is_err());
+}
@@ -0,0 +1,217 @@
+use chrono::{Duration, TimeZone, Utc};
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{leverage::Leverage, position_lock::PositionLock},
+        order_type::OrderType,
+        signal_category::SignalCategory,
+        symbol_id::SymbolId,
+    },
+    functions::coerce_df_to_schema,
+    structs::TradingSettings,
+};
+use core::{data_feed::DataFeed, replay::replay_klines};
+use exchanges::enums::{DataProviderExchangeId, DataProviderExchangeWrapper};
+use polars::prelude::{DataFrame, DataType, IntoColumn, NamedFrom, Series, TimeUnit};
+use std::collections::HashMap;
+use strategy::{params::ParamId, Strategy, StrategyId};
+use tokio::runtime::Builder;
+
+fn get_trading_settings() -> TradingSettings {
+    TradingSettings::new(
+        50.0,
+        Leverage::Isolated(10),
+        OrderType::Market,
+        OrderType::Market,
+        PositionLock::None,
+        HashMap::new(),
+        true,
+        &SymbolId::Bitcoin,
+        &SymbolId::Bitcoin,
+        1,
+        Granularity::m1,
+    )
+}
+
+/// Builds BTCUSDT klines starting on 2024-01-01, one per close price
+fn get_klines_df(data_feed: &DataFeed, closes: Vec<f64>) -> DataFrame {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let start_times: Vec<i64> = (0..closes.len())
+        .map(|minute| (start + Duration::minutes(minute as i64)).timestamp_millis())
+        .collect();
+    let opens: Vec<f64> = (0..closes.len())
+        .map(|index| {
+            if index == 0 {
+                closes[0]
+            } else {
+                closes[index - 1]
+            }
+        })
+        .collect();
+    let highs: Vec<f64> = opens
+        .iter()
+        .zip(&closes)
+        .map(|(open, close)| open.max(*close) + 5.0)
+        .collect();
+    let lows: Vec<f64> = opens
+        .iter()
+        .zip(&closes)
+        .map(|(open, close)| open.min(*close) - 5.0)
+        .collect();
+    let klines_df = DataFrame::new(vec![
+        Series::new("start_time".into(), start_times)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap()
+            .into_column(),
+        Series::new("BTCUSDT_open".into(), opens).into_column(),
+        Series::new("BTCUSDT_high".into(), highs).into_column(),
+        Series::new("BTCUSDT_low".into(), lows).into_column(),
+        Series::new("BTCUSDT_close".into(), closes).into_column(),
+    ])
+    .unwrap();
+    coerce_df_to_schema(klines_df, &data_feed.trading_data_schema).unwrap()
+}
+
+fn get_data_feed(trading_settings: &TradingSettings) -> DataFeed {
+    let strategy = Strategy::new(StrategyId::SimpleTrend, trading_settings.symbols_pair)
+        .unwrap()
+        .patch_number_param(ParamId::FastSpan, 10.0)
+        .unwrap()
+        .patch_number_param(ParamId::SlowSpan, 50.0)
+        .unwrap();
+    let data_provider_exchange = DataProviderExchangeWrapper::new(
+        DataProviderExchangeId::Offline,
+        &strategy,
+        trading_settings,
+    )
+    .unwrap();
+    DataFeed::new(
+        (None, None),
+        data_provider_exchange,
+        true,
+        &strategy,
+        trading_settings,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_replays_klines_through_trader_with_simulated_clock() {
+    let trading_settings = get_trading_settings();
+    let data_feed = get_data_feed(&trading_settings);
+    // close prices oscillate, so that fast and slow EMAs keep crossing
+    let closes = (0..130)
+        .map(|index| 30000.0 + 600.0 * (index as f64 / 8.0).sin())
+        .collect();
+    let klines_df = get_klines_df(&data_feed, closes);
+
+    // `core` shadows the std crate the tokio test macro expands to, so the runtime is built here
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    let report = runtime
+        .block_on(replay_klines(&data_feed, &trading_settings, klines_df))
+        .unwrap();
+    println!("{}", report);
+
+    assert_eq!(report.replay_df.height(), 80);
+    assert_eq!(report.benchmark_df.height(), 80);
+    let replay_positions: Vec<i32> = report
+        .replay_df
+        .column("position")
+        .unwrap()
+        .i32()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    assert!(replay_positions.contains(&1) && replay_positions.contains(&-1));
+    // trades are timestamped by the simulated clock, at the close of the klines they happened on
+    let exit_times: Vec<i64> = report
+        .replay_df
+        .column("exit_time")
+        .unwrap()
+        .cast(&DataType::Int64)
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    let last_close = Utc
+        .with_ymd_and_hms(2024, 1, 1, 2, 10, 0)
+        .unwrap()
+        .timestamp_millis();
+    assert!(!exit_times.is_empty());
+    assert!(exit_times
+        .iter()
+        .all(|exit_time| exit_time % 60_000 == 0 && *exit_time <= last_close));
+}
+
+#[test]
+fn test_replayed_reverts_match_benchmark() {
+    let mut trading_settings = get_trading_settings();
+    trading_settings.position_lock_modifier = PositionLock::Loss;
+    let data_feed = get_data_feed(&trading_settings);
+    // prices step, so that live and benchmark EMAs cross on the same kline. A long opens on the first step,
+    // whose revert the lock keeps on the following drop, as it's at a loss, and lets happen on the last drop, with profit
+    let closes = (0..60)
+        .map(|index| 30060.0 - index as f64)
+        .chain(
+            [(20, 30300.0), (20, 30000.0), (40, 31500.0), (30, 30900.0)]
+                .into_iter()
+                .flat_map(|(klines, close)| vec![close; klines]),
+        )
+        .collect();
+    let klines_df = get_klines_df(&data_feed, closes);
+
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    let report = runtime
+        .block_on(replay_klines(&data_feed, &trading_settings, klines_df))
+        .unwrap();
+    println!("{}", report);
+
+    let get_i32_values = |df: &DataFrame, column: &str| -> Vec<i32> {
+        df.column(column)
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    };
+    // both drops signal shorts, only the second one reverting the long
+    let shorts = get_i32_values(&report.benchmark_df, "short");
+    assert_eq!(shorts.iter().sum::<i32>(), 2);
+    let actions: Vec<&str> = report
+        .benchmark_df
+        .column("action")
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+    let revert_indexes: Vec<usize> = (0..actions.len())
+        .filter(|&index| actions[index] == SignalCategory::RevertLong.get_column())
+        .collect();
+    assert_eq!(revert_indexes.len(), 1);
+    let revert_index = revert_indexes[0];
+
+    // the trader reverts on the kline the benchmark does, though it records closes on the kline that signalled them
+    let replay_positions = get_i32_values(&report.replay_df, "position");
+    assert_eq!(replay_positions[revert_index - 2], 1);
+    assert_eq!(replay_positions[revert_index - 1], 0);
+    assert_eq!(replay_positions[revert_index], -1);
+    // benchmarks discard the position opened by the last revert, as it's still open once klines are over,
+    // so both engines only disagree from its close on
+    let revert_time = report
+        .benchmark_df
+        .column("start_time")
+        .unwrap()
+        .cast(&DataType::Int64)
+        .unwrap()
+        .i64()
+        .unwrap()
+        .get(revert_index - 1)
+        .unwrap();
+    assert!(report
+        .disagreements
+        .iter()
+        .all(|disagreement| disagreement.start_time.timestamp_millis() >= revert_time));
+}
@@ -1,6 +1,323 @@
 pub mod http {
+    pub mod request {
+        use crate::{
+            binance::enums::{BinanceOrderType, BinanceSide, BinanceTimeInForce},
+            shared::serializers::option_f64_as_string,
+        };
+        use common::{enums::order_type::OrderType, structs::Order};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Clone, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct CreateOrderDto {
+            pub symbol: String,
+            pub side: BinanceSide,
+            #[serde(rename = "type")]
+            pub order_type: BinanceOrderType,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub time_in_force: Option<BinanceTimeInForce>,
+            #[serde(
+                rename = "quantity",
+                serialize_with = "option_f64_as_string",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub units: Option<f64>,
+            #[serde(
+                serialize_with = "option_f64_as_string",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub price: Option<f64>,
+            #[serde(
+                serialize_with = "option_f64_as_string",
+                skip_serializing_if = "Option::is_none"
+            )]
+            pub stop_price: Option<f64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub reduce_only: Option<bool>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub close_position: Option<bool>,
+            #[serde(rename = "newClientOrderId")]
+            pub id: String,
+        }
+
+        impl CreateOrderDto {
+            /// Market trigger order closing the whole position once `stop_price` is reached
+            pub fn new_close_position_trigger(
+                symbol: String,
+                side: BinanceSide,
+                order_type: BinanceOrderType,
+                stop_price: f64,
+                id: String,
+            ) -> Self {
+                Self {
+                    symbol,
+                    side,
+                    order_type,
+                    time_in_force: None,
+                    units: None,
+                    price: None,
+                    stop_price: Some(stop_price),
+                    reduce_only: None,
+                    close_position: Some(true),
+                    id,
+                }
+            }
+        }
+
+        impl From<Order> for CreateOrderDto {
+            fn from(order: Order) -> Self {
+                let is_limit = order.order_type == OrderType::Limit;
+                Self {
+                    symbol: order.symbol,
+                    side: order.side.into(),
+                    order_type: order.order_type.into(),
+                    time_in_force: is_limit.then_some(order.time_in_force.into()),
+                    units: Some(order.units),
+                    price: if is_limit { order.avg_price } else { None },
+                    stop_price: None,
+                    reduce_only: order.is_close.then_some(true),
+                    close_position: None,
+                    id: order.id,
+                }
+            }
+        }
+
+        /// Only limit orders can be amended, and both quantity and price must be sent
+        #[derive(Debug, Clone, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct AmendOrderDto {
+            pub symbol: String,
+            #[serde(rename = "origClientOrderId")]
+            pub id: String,
+            pub side: BinanceSide,
+            #[serde(
+                rename = "quantity",
+                serialize_with = "crate::shared::serializers::f64_as_string"
+            )]
+            pub units: f64,
+            #[serde(serialize_with = "crate::shared::serializers::f64_as_string")]
+            pub price: f64,
+        }
+
+        #[derive(Debug, Clone, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct OrderIdDto {
+            pub symbol: String,
+            #[serde(rename = "origClientOrderId")]
+            pub id: String,
+        }
+
+        #[derive(Debug, Clone, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct FetchExecutionsDto {
+            pub symbol: String,
+            #[serde(rename = "orderId")]
+            pub order_uuid: String,
+        }
+
+        #[derive(Debug, Clone, Serialize)]
+        pub struct FetchAllOrdersDto {
+            pub symbol: String,
+            pub limit: u32,
+        }
+
+        #[derive(Debug, Clone, Serialize)]
+        pub struct SymbolDto {
+            pub symbol: String,
+        }
+
+        #[derive(Debug, Clone, Serialize)]
+        pub struct SetLeverageDto {
+            pub symbol: String,
+            pub leverage: u32,
+        }
+
+        /// Empty payloads and responses, such as listen key keep alive ones
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        pub struct EmptyDto {}
+    }
+
     pub mod response {
+        use crate::{
+            binance::enums::{
+                BinanceOrderStatus, BinanceOrderType, BinanceSide, BinanceTimeInForce,
+            },
+            binance::functions::get_order_status,
+            bybit::functions::parse_f64_option,
+            shared::deserializers::parse_f64,
+        };
+        use common::{
+            enums::order_type::OrderType,
+            structs::{Execution, Order},
+        };
         use serde::Deserialize;
+
+        #[derive(Debug, Deserialize)]
+        pub struct BinanceErrorResponse {
+            pub code: i32,
+            pub msg: String,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct OrderResponse {
+            pub order_id: i64,
+            pub client_order_id: String,
+            pub symbol: String,
+            pub status: BinanceOrderStatus,
+            pub side: BinanceSide,
+            #[serde(rename = "type")]
+            pub order_type: BinanceOrderType,
+            pub orig_type: BinanceOrderType,
+            pub time_in_force: BinanceTimeInForce,
+            #[serde(deserialize_with = "parse_f64")]
+            pub orig_qty: f64,
+            #[serde(deserialize_with = "parse_f64")]
+            pub executed_qty: f64,
+            #[serde(deserialize_with = "parse_f64_option")]
+            pub avg_price: Option<f64>,
+            #[serde(deserialize_with = "parse_f64_option")]
+            pub price: Option<f64>,
+            #[serde(deserialize_with = "parse_f64_option")]
+            pub stop_price: Option<f64>,
+            pub reduce_only: bool,
+            #[serde(default)]
+            pub close_position: bool,
+            // order creation responses don't include the creation time
+            pub time: Option<i64>,
+            pub update_time: i64,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct UserTradeResponse {
+            pub id: i64,
+            pub order_id: i64,
+            pub symbol: String,
+            pub side: BinanceSide,
+            #[serde(deserialize_with = "parse_f64")]
+            pub price: f64,
+            #[serde(deserialize_with = "parse_f64")]
+            pub qty: f64,
+            #[serde(deserialize_with = "parse_f64")]
+            pub commission: f64,
+            #[serde(deserialize_with = "parse_f64")]
+            pub realized_pnl: f64,
+            pub maker: bool,
+            pub time: i64,
+        }
+
+        impl OrderResponse {
+            pub fn is_close(&self) -> bool {
+                self.reduce_only || self.close_position
+            }
+
+            pub fn new_order_from_response_data(
+                &self,
+                leverage_factor: f64,
+                taker_fee_rate: f64,
+            ) -> Order {
+                let (status, is_stop) = get_order_status(
+                    self.status,
+                    self.orig_type,
+                    self.is_close(),
+                    &self.client_order_id,
+                );
+                Order::new(
+                    self.avg_price,
+                    0.0,
+                    self.time.unwrap_or(self.update_time),
+                    vec![],
+                    self.client_order_id.clone(),
+                    self.is_close(),
+                    is_stop,
+                    leverage_factor,
+                    self.order_type.into(),
+                    self.side.into(),
+                    status,
+                    None,
+                    self.symbol.clone(),
+                    None,
+                    taker_fee_rate,
+                    self.time_in_force.into(),
+                    self.orig_qty,
+                    self.update_time,
+                    self.order_id.to_string(),
+                )
+            }
+        }
+
+        impl From<UserTradeResponse> for Execution {
+            fn from(trade: UserTradeResponse) -> Self {
+                let order_type = if trade.maker {
+                    OrderType::Limit
+                } else {
+                    OrderType::Market
+                };
+                let fee_rate = trade.commission / (trade.price * trade.qty);
+                // only closing executions realize profit and loss
+                let closed_qty = if trade.realized_pnl != 0.0 {
+                    trade.qty
+                } else {
+                    0.0
+                };
+                Execution::new(
+                    trade.id.to_string(),
+                    trade.order_id.to_string(),
+                    order_type,
+                    trade.time,
+                    trade.price,
+                    trade.qty,
+                    trade.commission,
+                    fee_rate,
+                    trade.maker,
+                    closed_qty,
+                )
+            }
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct PositionRiskResponse {
+            pub symbol: String,
+            #[serde(deserialize_with = "parse_f64")]
+            pub position_amt: f64,
+            #[serde(deserialize_with = "parse_f64")]
+            pub entry_price: f64,
+            #[serde(deserialize_with = "parse_f64")]
+            pub leverage: f64,
+            pub update_time: i64,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct BalanceResponse {
+            pub asset: String,
+            #[serde(deserialize_with = "parse_f64")]
+            pub balance: f64,
+            #[serde(deserialize_with = "parse_f64")]
+            pub available_balance: f64,
+            pub update_time: i64,
+        }
+
+        #[derive(Debug, Clone, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct ListenKeyResponse {
+            pub listen_key: String,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct SetLeverageResponse {
+            pub leverage: u32,
+            pub symbol: String,
+        }
+
         #[allow(dead_code, non_snake_case)]
         #[derive(Debug, Deserialize)]
         pub struct BinanceHttpKlineResponse {
@@ -17,6 +334,16 @@ pub mod http {
             taker_buy_quote_asset_volume: String,
             unused_field: String,
         }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Clone, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct BinanceHttpFundingRateResponse {
+            pub symbol: String,
+            #[serde(deserialize_with = "parse_f64")]
+            pub funding_rate: f64,
+            pub funding_time: i64,
+        }
     }
 }
 
@@ -38,7 +365,18 @@ pub mod ws {
         // use common::structs::TickData;
         use serde::Deserialize;
 
-        use crate::shared::deserializers::{parse_f64, parse_u64_as_datetime};
+        use crate::{
+            binance::enums::{
+                BinanceOrderStatus, BinanceOrderType, BinanceSide, BinanceTimeInForce,
+            },
+            binance::functions::get_order_status,
+            bybit::functions::parse_f64_option,
+            shared::deserializers::{parse_f64, parse_u64_as_datetime},
+        };
+        use common::{
+            enums::order_type::OrderType,
+            structs::{Execution, Order},
+        };
 
         #[derive(Debug, Deserialize)]
         pub struct EmptyMessage {
@@ -90,6 +428,169 @@ pub mod ws {
             ignore: String, // Ignore
         }
 
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        pub struct OrderTradeUpdateMessage {
+            #[serde(rename = "E")]
+            pub event_time: i64,
+            #[serde(rename = "T")]
+            pub transaction_time: i64,
+            #[serde(rename = "o")]
+            pub data: OrderTradeUpdateData,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        pub struct OrderTradeUpdateData {
+            #[serde(rename = "s")]
+            pub symbol: String,
+            #[serde(rename = "c")]
+            pub client_order_id: String,
+            #[serde(rename = "S")]
+            pub side: BinanceSide,
+            #[serde(rename = "o")]
+            pub order_type: BinanceOrderType,
+            #[serde(rename = "ot")]
+            pub orig_type: BinanceOrderType,
+            #[serde(rename = "f")]
+            pub time_in_force: BinanceTimeInForce,
+            #[serde(rename = "q", deserialize_with = "parse_f64")]
+            pub orig_qty: f64,
+            #[serde(rename = "p", deserialize_with = "parse_f64_option")]
+            pub price: Option<f64>,
+            #[serde(rename = "ap", deserialize_with = "parse_f64_option")]
+            pub avg_price: Option<f64>,
+            #[serde(rename = "sp", deserialize_with = "parse_f64_option")]
+            pub stop_price: Option<f64>,
+            #[serde(rename = "x")]
+            pub execution_type: String,
+            #[serde(rename = "X")]
+            pub status: BinanceOrderStatus,
+            #[serde(rename = "i")]
+            pub order_id: i64,
+            #[serde(rename = "l", deserialize_with = "parse_f64")]
+            pub last_filled_qty: f64,
+            #[serde(rename = "z", deserialize_with = "parse_f64")]
+            pub cum_filled_qty: f64,
+            #[serde(rename = "L", deserialize_with = "parse_f64")]
+            pub last_filled_price: f64,
+            #[serde(rename = "n", default, deserialize_with = "parse_f64_option")]
+            pub commission: Option<f64>,
+            #[serde(rename = "T")]
+            pub trade_time: i64,
+            #[serde(rename = "t")]
+            pub trade_id: i64,
+            #[serde(rename = "m")]
+            pub is_maker: bool,
+            #[serde(rename = "R")]
+            pub reduce_only: bool,
+            #[serde(rename = "cp", default)]
+            pub close_position: bool,
+            #[serde(rename = "rp", deserialize_with = "parse_f64")]
+            pub realized_pnl: f64,
+        }
+
+        impl OrderTradeUpdateData {
+            pub fn is_close(&self) -> bool {
+                self.reduce_only || self.close_position
+            }
+
+            pub fn new_order_from_update_data(
+                &self,
+                timestamp: i64,
+                leverage_factor: f64,
+                taker_fee_rate: f64,
+            ) -> Order {
+                let (status, is_stop) = get_order_status(
+                    self.status,
+                    self.orig_type,
+                    self.is_close(),
+                    &self.client_order_id,
+                );
+                Order::new(
+                    self.avg_price,
+                    0.0,
+                    timestamp,
+                    vec![],
+                    self.client_order_id.clone(),
+                    self.is_close(),
+                    is_stop,
+                    leverage_factor,
+                    self.order_type.into(),
+                    self.side.into(),
+                    status,
+                    None,
+                    self.symbol.clone(),
+                    None,
+                    taker_fee_rate,
+                    self.time_in_force.into(),
+                    self.orig_qty,
+                    timestamp,
+                    self.order_id.to_string(),
+                )
+            }
+
+            /// Returns the fill carried by a `TRADE` update, if any
+            pub fn get_execution(&self) -> Option<Execution> {
+                if self.execution_type != "TRADE" || self.last_filled_qty == 0.0 {
+                    return None;
+                }
+                let order_type = if self.is_maker {
+                    OrderType::Limit
+                } else {
+                    OrderType::Market
+                };
+                let fee = self.commission.unwrap_or_default();
+                let fee_rate = fee / (self.last_filled_price * self.last_filled_qty);
+                let closed_qty = if self.is_close() {
+                    self.last_filled_qty
+                } else {
+                    0.0
+                };
+                Some(Execution::new(
+                    self.trade_id.to_string(),
+                    self.order_id.to_string(),
+                    order_type,
+                    self.trade_time,
+                    self.last_filled_price,
+                    self.last_filled_qty,
+                    fee,
+                    fee_rate,
+                    self.is_maker,
+                    closed_qty,
+                ))
+            }
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        pub struct AccountUpdateMessage {
+            #[serde(rename = "E")]
+            pub event_time: i64,
+            #[serde(rename = "a")]
+            pub data: AccountUpdateData,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        pub struct AccountUpdateData {
+            #[serde(rename = "m")]
+            pub reason: String,
+            #[serde(rename = "B")]
+            pub balances: Vec<AccountUpdateBalance>,
+        }
+
+        #[allow(dead_code)]
+        #[derive(Debug, Deserialize)]
+        pub struct AccountUpdateBalance {
+            #[serde(rename = "a")]
+            pub asset: String,
+            #[serde(rename = "wb", deserialize_with = "parse_f64")]
+            pub wallet_balance: f64,
+            #[serde(rename = "cw", deserialize_with = "parse_f64")]
+            pub cross_wallet_balance: f64,
+        }
+
         // impl From<BinanceWsTickResponse> for TickData {
         //     fn from(tick: BinanceWsTickResponse) -> Self {
         //         TickData {
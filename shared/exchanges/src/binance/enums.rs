@@ -1,4 +1,5 @@
-use super::dtos::ws::incoming::TickMessage;
+use super::dtos::ws::incoming::{AccountUpdateMessage, OrderTradeUpdateMessage, TickMessage};
+use common::enums::{order_type::OrderType, side::Side, time_in_force::TimeInForce};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -27,3 +28,144 @@ impl Default for IncomingWsMessage {
         Self::None
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum BinanceSide {
+    Buy,
+    Sell,
+}
+
+impl From<Side> for BinanceSide {
+    fn from(value: Side) -> Self {
+        match value {
+            Side::Sell => BinanceSide::Sell,
+            // orders are never created without a side
+            Side::Buy | Side::None => BinanceSide::Buy,
+        }
+    }
+}
+
+impl From<BinanceSide> for Side {
+    fn from(value: BinanceSide) -> Self {
+        match value {
+            BinanceSide::Buy => Side::Buy,
+            BinanceSide::Sell => Side::Sell,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BinanceOrderType {
+    Limit,
+    Market,
+    Stop,
+    StopMarket,
+    TakeProfit,
+    TakeProfitMarket,
+    TrailingStopMarket,
+    Liquidation,
+}
+
+impl BinanceOrderType {
+    pub fn is_trigger_order(&self) -> bool {
+        !matches!(self, BinanceOrderType::Limit | BinanceOrderType::Market)
+    }
+}
+
+impl From<OrderType> for BinanceOrderType {
+    fn from(value: OrderType) -> Self {
+        match value {
+            OrderType::Limit => BinanceOrderType::Limit,
+            OrderType::Market => BinanceOrderType::Market,
+        }
+    }
+}
+
+impl From<BinanceOrderType> for OrderType {
+    fn from(value: BinanceOrderType) -> Self {
+        match value {
+            BinanceOrderType::Limit | BinanceOrderType::Stop | BinanceOrderType::TakeProfit => {
+                OrderType::Limit
+            }
+            _ => OrderType::Market,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BinanceOrderStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+    Expired,
+    ExpiredInMatch,
+}
+
+impl BinanceOrderStatus {
+    pub fn is_cancel(&self) -> bool {
+        matches!(
+            self,
+            BinanceOrderStatus::Canceled
+                | BinanceOrderStatus::Rejected
+                | BinanceOrderStatus::Expired
+                | BinanceOrderStatus::ExpiredInMatch
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BinanceTimeInForce {
+    #[serde(rename = "GTC")]
+    GoodTillCancel,
+    #[serde(rename = "IOC")]
+    ImmediateOrCancel,
+    #[serde(rename = "FOK")]
+    FillOrKill,
+    #[serde(rename = "GTX")]
+    PostOnly,
+    #[serde(rename = "GTD")]
+    GoodTillDate,
+}
+
+impl From<TimeInForce> for BinanceTimeInForce {
+    fn from(value: TimeInForce) -> Self {
+        match value {
+            TimeInForce::GTC => BinanceTimeInForce::GoodTillCancel,
+            TimeInForce::IOC => BinanceTimeInForce::ImmediateOrCancel,
+            TimeInForce::FOK => BinanceTimeInForce::FillOrKill,
+            TimeInForce::PostOnly => BinanceTimeInForce::PostOnly,
+        }
+    }
+}
+
+impl From<BinanceTimeInForce> for TimeInForce {
+    fn from(value: BinanceTimeInForce) -> Self {
+        match value {
+            BinanceTimeInForce::GoodTillCancel | BinanceTimeInForce::GoodTillDate => {
+                TimeInForce::GTC
+            }
+            BinanceTimeInForce::ImmediateOrCancel => TimeInForce::IOC,
+            BinanceTimeInForce::FillOrKill => TimeInForce::FOK,
+            BinanceTimeInForce::PostOnly => TimeInForce::PostOnly,
+        }
+    }
+}
+
+/// User data stream events, tagged by their `e` field
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+pub enum UserDataWsMessage {
+    #[serde(rename = "ORDER_TRADE_UPDATE")]
+    OrderTradeUpdate(OrderTradeUpdateMessage),
+    #[serde(rename = "ACCOUNT_UPDATE")]
+    AccountUpdate(AccountUpdateMessage),
+    #[serde(rename = "listenKeyExpired")]
+    ListenKeyExpired,
+    #[serde(other)]
+    None,
+}
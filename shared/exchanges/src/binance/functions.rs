@@ -1,5 +1,8 @@
-use super::dtos::ws::incoming::TickMessage;
-use common::structs::TickData;
+use super::{
+    dtos::ws::incoming::TickMessage,
+    enums::{BinanceOrderStatus, BinanceOrderType},
+};
+use common::{enums::order_status::OrderStatus, structs::TickData};
 
 pub fn from_tick_to_tick_data(
     tick: TickMessage,
@@ -18,3 +21,42 @@ pub fn from_tick_to_tick_data(
         low: tick.data.low,
     }
 }
+
+/// Maps Binance order state into the common `OrderStatus`, returning whether the order was a stop.
+/// Binance tags its liquidation orders with an `autoclose-` client order id.
+pub fn get_order_status(
+    status: BinanceOrderStatus,
+    orig_type: BinanceOrderType,
+    is_close: bool,
+    client_order_id: &str,
+) -> (OrderStatus, bool) {
+    if status.is_cancel() {
+        return (OrderStatus::Cancelled, false);
+    }
+    if !is_close {
+        let status = match status {
+            BinanceOrderStatus::PartiallyFilled => OrderStatus::PartiallyFilled,
+            BinanceOrderStatus::Filled => OrderStatus::Filled,
+            _ => OrderStatus::StandBy,
+        };
+        return (status, false);
+    }
+    if client_order_id.starts_with("autoclose-") || orig_type == BinanceOrderType::Liquidation {
+        return (OrderStatus::StoppedBR, true);
+    }
+    match orig_type {
+        BinanceOrderType::Stop | BinanceOrderType::StopMarket => (OrderStatus::StoppedSL, true),
+        BinanceOrderType::TakeProfit | BinanceOrderType::TakeProfitMarket => {
+            (OrderStatus::StoppedTP, true)
+        }
+        BinanceOrderType::TrailingStopMarket => (OrderStatus::StoppedTSL, true),
+        _ => {
+            let status = match status {
+                BinanceOrderStatus::PartiallyFilled => OrderStatus::PartiallyClosed,
+                BinanceOrderStatus::Filled => OrderStatus::Closed,
+                _ => OrderStatus::StandBy,
+            };
+            (status, false)
+        }
+    }
+}
@@ -1,4 +1,5 @@
+mod dtos;
 pub mod enums;
 pub mod functions;
 pub mod structs;
-mod dtos;
\ No newline at end of file
+pub mod trader;
@@ -1,27 +1,36 @@
 use super::{
-    dtos::{http::response::BinanceHttpKlineResponse, ws::outgoing::WsOutgoingMessage},
+    dtos::{
+        http::response::{BinanceHttpFundingRateResponse, BinanceHttpKlineResponse},
+        ws::outgoing::WsOutgoingMessage,
+    },
     enums::OutgoingWsMessageMethod,
 };
 use crate::{
     binance::{enums::IncomingWsMessage, functions::from_tick_to_tick_data},
     config::WS_RECONNECT_INTERVAL_IN_SECS,
+    shared::functions::{adjust_benchmark_datetimes, set_ws_error_ts},
 };
-use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use chrono::{DateTime, Duration, Timelike, Utc};
 use common::{
     enums::trading_data_update::TradingDataUpdate,
     functions::{
         coerce_df_to_schema,
-        csv::{load_interval_tick_dataframe, save_kline_df_to_csv},
+        csv::load_interval_tick_dataframe,
         current_datetime, current_timestamp, current_timestamp_ms,
         downsample_tick_lf_to_kline_duration, filter_df_timestamps_to_lf,
-        get_date_start_and_end_timestamps, map_ticks_data_to_df, timestamp_minute_end,
-        timestamp_minute_start,
+        funding::{
+            append_funding_rates_to_df, get_funding_rate_col, load_funding_rates,
+            save_funding_rates,
+        },
+        get_date_start_and_end_timestamps, map_ticks_data_to_df,
+        parquet::save_kline_df_to_parquet,
+        timestamp_minute_end, timestamp_minute_start,
     },
-    structs::{BehaviorSubject, LogKlines, SymbolsPair, TickData, TradingSettings},
+    structs::{BehaviorSubject, FundingRate, LogKlines, SymbolsPair, TickData, TradingSettings},
     traits::exchange::DataProviderExchange,
 };
 use futures_util::SinkExt;
-use glow_error::{assert_or_error, GlowError};
+use glow_error::GlowError;
 use polars::{
     frame::DataFrame,
     prelude::{IntoLazy, Schema, SortMultipleOptions},
@@ -49,6 +58,7 @@ use url::Url;
 #[derive(Clone)]
 pub struct BinanceDataProvider {
     fetch_leeway: StdDuration,
+    futures_http_base_url: String,
     http: Client,
     kline_duration: Duration,
     last_ws_error_ts: Arc<Mutex<Option<i64>>>,
@@ -61,14 +71,16 @@ pub struct BinanceDataProvider {
 
 /// A single connection to stream.binance.com is only valid for 24 hours; expect to be disconnected at the 24 hour mark
 impl BinanceDataProvider {
-    pub fn new(trading_settings: &TradingSettings, strategy: &Strategy) -> Self {
+    pub fn new(trading_settings: &TradingSettings, strategy: &Strategy) -> Result<Self, GlowError> {
         let symbols = trading_settings.symbols_pair;
         let kline_duration = trading_settings.granularity.get_chrono_duration();
         let last_ws_error_ts = Arc::new(Mutex::new(None));
-        let minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation();
+        let minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation()?;
         let klines_data_update_emitter = BehaviorSubject::new(TradingDataUpdate::default());
-        Self {
+        Ok(Self {
             fetch_leeway: StdDuration::from_secs(5),
+            futures_http_base_url: env_var("BINANCE_FUTURES_HTTP_BASE_URL")
+                .unwrap_or(String::from("https://fapi.binance.com")),
             http: Client::new(),
             // kline_data_schema,
             kline_duration,
@@ -79,7 +91,7 @@ impl BinanceDataProvider {
             ticks_to_commit: BehaviorSubject::new(vec![]),
             // trading_data_schema,
             klines_data_update_emitter,
-        }
+        })
     }
 
     pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
@@ -87,8 +99,13 @@ impl BinanceDataProvider {
         self.kline_duration = trading_settings.granularity.get_chrono_duration();
     }
 
-    pub fn patch_strategy(&mut self, strategy: &Strategy) {
-        self.minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation();
+    pub fn patch_strategy(&mut self, strategy: &Strategy) -> Result<(), GlowError> {
+        self.minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation()?;
+        Ok(())
+    }
+
+    pub fn patch_futures_http_base_url(&mut self, futures_http_base_url: &str) {
+        self.futures_http_base_url = futures_http_base_url.to_string();
     }
 
     async fn load_or_fetch_kline_data(
@@ -129,7 +146,7 @@ impl BinanceDataProvider {
                 let daily_klines = Duration::days(1).num_seconds() / tick_duration.num_seconds();
 
                 if total_klines == daily_klines {
-                    let _ = save_kline_df_to_csv(&fetched_data_df, date, "binance", &symbol.name)?;
+                    save_kline_df_to_parquet(&fetched_data_df, "binance", symbol.name)?;
                 }
                 let fetched_data_df = coerce_df_to_schema(fetched_data_df, &trading_data_schema)?;
                 match &result_df.vstack(&fetched_data_df) {
@@ -170,13 +187,95 @@ impl BinanceDataProvider {
             Some(trading_data_schema),
         )?;
 
-        match kline_lf.collect() {
-            Ok(kline_df) => Ok(kline_df),
+        let kline_df = match kline_lf.collect() {
+            Ok(kline_df) => kline_df,
             Err(error) => {
                 println!("COLLECT ERROR {:?}", error);
-                Err(error.into())
+                return Err(error.into());
+            }
+        };
+
+        self.append_funding_rates(kline_df, trading_data_schema, start_datetime, end_datetime)
+            .await
+    }
+
+    /// Sets the funding rates of the symbols whose funding rate column is part of the schema.
+    /// Fetched funding rates are stored, so that the stored ones are used whenever fetching fails.
+    async fn append_funding_rates(
+        &self,
+        kline_df: DataFrame,
+        trading_data_schema: &Schema,
+        start_datetime: DateTime<Utc>,
+        end_datetime: DateTime<Utc>,
+    ) -> Result<DataFrame, GlowError> {
+        let mut kline_df = kline_df;
+        for symbol in &self.symbols.get_unique_symbols() {
+            if !trading_data_schema.contains(&get_funding_rate_col(symbol.name)) {
+                continue;
+            }
+            let funding_rates = match self
+                .fetch_funding_rates(
+                    symbol.name,
+                    start_datetime.timestamp_millis(),
+                    end_datetime.timestamp_millis(),
+                )
+                .await
+            {
+                Ok(funding_rates) => {
+                    save_funding_rates("binance", symbol.name, &funding_rates)?;
+                    funding_rates
+                }
+                Err(error) => {
+                    println!(
+                        "{:?} | ⚠️ Failed to fetch {} funding rates, using stored ones. {:?}",
+                        current_datetime(),
+                        symbol.name,
+                        error
+                    );
+                    load_funding_rates("binance", symbol.name, start_datetime, end_datetime)?
+                }
+            };
+            kline_df = append_funding_rates_to_df(
+                kline_df,
+                symbol.name,
+                &funding_rates,
+                self.kline_duration,
+            )?;
+        }
+        Ok(kline_df)
+    }
+
+    /// Fetches the symbol perpetual funding rates settled within `[start_timestamp_ms, end_timestamp_ms]`
+    pub async fn fetch_funding_rates(
+        &self,
+        symbol: &str,
+        start_timestamp_ms: i64,
+        end_timestamp_ms: i64,
+    ) -> Result<Vec<FundingRate>, GlowError> {
+        let limit = 1000;
+        let mut funding_rates = vec![];
+        let mut start_timestamp_ms = start_timestamp_ms;
+        loop {
+            let url = format!(
+                "{}/fapi/v1/fundingRate?symbol={}&startTime={}&endTime={}&limit={}",
+                self.futures_http_base_url, symbol, start_timestamp_ms, end_timestamp_ms, limit
+            );
+            let result: Vec<BinanceHttpFundingRateResponse> =
+                self.http.get(url).send().await?.json().await?;
+            let page_len = result.len();
+            let Some(last_funding_time) = result.last().map(|data| data.funding_time) else {
+                break;
+            };
+            funding_rates.extend(result.into_iter().filter_map(|data| {
+                DateTime::from_timestamp_millis(data.funding_time)
+                    .map(|funding_time| FundingRate::new(funding_time, data.funding_rate))
+            }));
+            if page_len < limit {
+                break;
             }
+            start_timestamp_ms = last_funding_time + 1;
         }
+        Ok(funding_rates)
     }
 
     async fn fetch_tick_data(
@@ -580,51 +679,3 @@ impl DataProviderExchange for BinanceDataProvider {
             .map_err(|err| GlowError::from(err))
     }
 }
-
-fn adjust_benchmark_datetimes(
-    benchmark_start: Option<DateTime<Utc>>,
-    benchmark_end: Option<DateTime<Utc>>,
-    kline_duration: Duration,
-    minimum_days_for_analysis: Option<i64>,
-    minimum_klines_for_benchmarking: i32,
-) -> Result<(DateTime<Utc>, DateTime<Utc>), GlowError> {
-    if let (Some(benchmark_start), Some(benchmark_end)) = (benchmark_start, benchmark_end) {
-        assert_or_error!(benchmark_end > benchmark_start);
-    }
-
-    let benchmark_end = benchmark_end.unwrap_or_else(|| {
-        let current_datetime = current_datetime();
-        let date = NaiveDate::from_ymd_opt(
-            current_datetime.year(),
-            current_datetime.month(),
-            current_datetime.day(),
-        )
-        .unwrap();
-        let time =
-            NaiveTime::from_hms_opt(current_datetime.hour(), current_datetime.minute(), 0).unwrap();
-        let datetime = NaiveDateTime::new(date, time);
-        DateTime::from_naive_utc_and_offset(datetime, Utc)
-    });
-
-    let benchmark_start = benchmark_start.unwrap_or_else(|| {
-        benchmark_end - (Duration::days(minimum_days_for_analysis.unwrap_or(1)))
-    });
-
-    let benchmark_start = benchmark_start - (kline_duration * minimum_klines_for_benchmarking);
-
-    assert_or_error!(benchmark_end > benchmark_start);
-
-    Ok((benchmark_start, benchmark_end))
-}
-
-fn set_ws_error_ts(last_ws_error_ts: Arc<Mutex<Option<i64>>>, error: GlowError) {
-    let mut last_error_guard = last_ws_error_ts
-        .lock()
-        .expect("init -> last_error_guard unwrap");
-    let error_timestamp = current_timestamp();
-    *last_error_guard = Some(error_timestamp);
-    eprintln!(
-        "Market Websocket connection error: {:?}. Retrying...",
-        error
-    );
-}
@@ -0,0 +1,1391 @@
+use super::{
+    dtos::http::{
+        request::{
+            AmendOrderDto, CreateOrderDto, EmptyDto, FetchAllOrdersDto, FetchExecutionsDto,
+            OrderIdDto, SetLeverageDto, SymbolDto,
+        },
+        response::{
+            BalanceResponse, BinanceErrorResponse, ListenKeyResponse, OrderResponse,
+            PositionRiskResponse, SetLeverageResponse, UserTradeResponse,
+        },
+    },
+    enums::{BinanceOrderType, UserDataWsMessage},
+};
+use crate::{
+    config::{TRADER_EXCHANGES_CONFIG_MAP, WS_RECONNECT_INTERVAL_IN_SECS},
+    enums::TraderExchangeId,
+    r#static::TRADER_EXCHANGES_CONTEXT_MAP,
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use common::{
+    constants::SECONDS_IN_MIN,
+    enums::{
+        balance::Balance, http_method::HttpMethod, modifiers::leverage::Leverage,
+        order_action::OrderAction, order_stage::OrderStage, order_status::OrderStatus,
+        order_type::OrderType, side::Side, symbol_id::SymbolId, time_in_force::TimeInForce,
+        trade_status::TradeStatus,
+    },
+    functions::{
+        calculate_hmac, calculate_remainder, count_decimal_places, current_datetime,
+        current_timestamp_ms, round_down_nth_decimal, timestamp_minute_end,
+    },
+    structs::{BehaviorSubject, Contract, Execution, Order, Trade, TradingSettings},
+    traits::exchange::{BenchmarkExchange, TraderExchange, TraderHelper},
+};
+use futures_util::SinkExt;
+use glow_error::GlowError;
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    Client, Error, RequestBuilder, Response,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::from_str;
+use serde_urlencoded::to_string as to_url_string;
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    net::TcpStream,
+    select,
+    time::{interval, sleep, sleep_until, Instant, Interval},
+};
+use tokio_stream::StreamExt;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tungstenite::client::IntoClientRequest;
+use url::Url;
+
+/// Listen keys expire after 60 minutes without being kept alive
+static LISTEN_KEY_KEEP_ALIVE_INTERVAL_IN_SECS: u64 = 30 * 60;
+
+/// Binance USDⓈ-M futures trader.
+///
+/// Stop loss and take profit are placed as `closePosition` trigger orders identified by
+/// `{trade_id}_sl` and `{trade_id}_tp`, which Binance expires as soon as the position is closed.
+#[derive(Clone)]
+pub struct BinanceTraderExchange {
+    balance_update_emitter: BehaviorSubject<Balance>,
+    pub contracts: &'static HashMap<SymbolId, Contract>,
+    credentials: ApiCredentials,
+    endpoints: ApiEndpoints,
+    executions_update_emitter: BehaviorSubject<Vec<Execution>>,
+    pub fee_rates: (f64, f64),
+    http: Client,
+    last_ws_error_ts: Arc<Mutex<Option<i64>>>,
+    listen_key: Arc<Mutex<Option<String>>>,
+    minimum_notional_value: Option<f64>,
+    pub name: &'static str,
+    pub trading_settings: TradingSettings,
+    order_update_emitter: BehaviorSubject<OrderAction>,
+    trade_update_emitter: BehaviorSubject<Option<Trade>>,
+}
+
+impl BinanceTraderExchange {
+    pub fn new(trading_settings: &TradingSettings) -> Self {
+        let config = TRADER_EXCHANGES_CONFIG_MAP
+            .get(&TraderExchangeId::Binance)
+            .expect("Binance to has Exchange Config");
+
+        Self::with_config(trading_settings, *config)
+    }
+
+    /// Creates the exchange with explicit credentials and endpoints, instead of the ones loaded from env vars
+    pub fn with_config(trading_settings: &TradingSettings, config: ExchangeConfig) -> Self {
+        let context = TRADER_EXCHANGES_CONTEXT_MAP
+            .get(&TraderExchangeId::Binance)
+            .expect("Binance to has Exchange Context");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        headers.insert(header::CONNECTION, HeaderValue::from_static("keep-alive"));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+
+        let balance_update_emitter = BehaviorSubject::new(Balance::default());
+        let executions_update_emitter = BehaviorSubject::new(vec![]);
+        let order_update_emitter = BehaviorSubject::new(OrderAction::default());
+        let trade_update_emitter = BehaviorSubject::new(None);
+
+        Self {
+            balance_update_emitter,
+            contracts: &context.contracts,
+            credentials: config.credentials,
+            executions_update_emitter,
+            endpoints: config.endpoints,
+            fee_rates: (context.maker_fee, context.taker_fee),
+            http: Client::builder()
+                .default_headers(headers)
+                .build()
+                .expect("Reqwest client to build"),
+            last_ws_error_ts: Arc::new(Mutex::new(None)),
+            listen_key: Arc::new(Mutex::new(None)),
+            minimum_notional_value: Some(5.0),
+            name: "Binance",
+            order_update_emitter,
+            trade_update_emitter,
+            trading_settings: trading_settings.clone(),
+        }
+    }
+
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+        self.trading_settings = trading_settings.clone();
+    }
+
+    async fn try_parse_response<T: DeserializeOwned>(
+        result: Result<Response, Error>,
+    ) -> Result<T, GlowError> {
+        let response = result?;
+        let status = response.status();
+        let response_text = response.text().await?;
+        if !status.is_success() {
+            let description = match from_str::<BinanceErrorResponse>(&response_text) {
+                Ok(error) => format!(
+                    "try_response -> unsucessful response {} | code = {}, msg = {}",
+                    status, error.code, error.msg
+                ),
+                Err(_) => format!(
+                    "try_response -> unsucessful response {} | {}",
+                    status, response_text
+                ),
+            };
+            return Err(GlowError::new_unsuccessful_response(description));
+        }
+        let parsed_response = from_str::<T>(&response_text)?;
+        Ok(parsed_response)
+    }
+
+    fn get_request_builder(&self, method: HttpMethod, url: String) -> RequestBuilder {
+        let http = self.get_http_client();
+        let request_builder = match method {
+            HttpMethod::Get => http.get(url),
+            HttpMethod::Post => http.post(url),
+            HttpMethod::Put => http.put(url),
+            HttpMethod::Delete => http.delete(url),
+        };
+        request_builder.header("X-MBX-APIKEY", self.credentials.key)
+    }
+
+    /// Signed endpoints take the whole query string, timestamp included, signed with the api secret
+    fn prepare_request_builder<T: Serialize>(
+        &self,
+        method: HttpMethod,
+        req_uri: &str,
+        payload: &T,
+    ) -> Result<RequestBuilder, GlowError> {
+        let timestamp = current_timestamp_ms();
+        let recv_window = 5000;
+
+        let payload_str = to_url_string(payload)?;
+        let query_params_str = if payload_str.is_empty() {
+            format!("timestamp={}&recvWindow={}", timestamp, recv_window)
+        } else {
+            format!(
+                "{}&timestamp={}&recvWindow={}",
+                payload_str, timestamp, recv_window
+            )
+        };
+        let signature = calculate_hmac(self.credentials.secret, &query_params_str)?;
+        let url = format!(
+            "{}{}?{}&signature={}",
+            self.endpoints.http, req_uri, query_params_str, signature
+        );
+        Ok(self.get_request_builder(method, url))
+    }
+
+    /// Creates a new user data stream listen key, or returns the current one if it's still valid
+    async fn create_listen_key(&self) -> Result<String, GlowError> {
+        let url = format!("{}/fapi/v1/listenKey", self.endpoints.http);
+        let result = self.get_request_builder(HttpMethod::Post, url).send().await;
+        let parsed_response = Self::try_parse_response::<ListenKeyResponse>(result).await?;
+        let listen_key = parsed_response.listen_key;
+        {
+            let mut listen_key_guard = self.listen_key.lock().unwrap();
+            *listen_key_guard = Some(listen_key.clone());
+        }
+        Ok(listen_key)
+    }
+
+    async fn keep_alive_listen_key(&self) -> Result<(), GlowError> {
+        let url = format!("{}/fapi/v1/listenKey", self.endpoints.http);
+        let result = self.get_request_builder(HttpMethod::Put, url).send().await;
+        Self::try_parse_response::<EmptyDto>(result).await?;
+        Ok(())
+    }
+
+    async fn fetch_order(&self, order_id: String) -> Result<OrderResponse, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = OrderIdDto {
+            symbol: traded_symbol.name.to_string(),
+            id: order_id,
+        };
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/fapi/v1/order", &payload)?;
+        let result = request_builder.send().await;
+        Self::try_parse_response::<OrderResponse>(result).await
+    }
+
+    async fn place_order(&self, payload: &CreateOrderDto) -> Result<OrderResponse, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/fapi/v1/order", payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<OrderResponse>(result).await?;
+        if parsed_response.client_order_id != payload.id {
+            let error = format!(
+                "place_order -> parsed_response.client_order_id != order_id! => {:?}",
+                parsed_response
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Order Id Error"),
+                error,
+            ));
+        }
+        Ok(parsed_response)
+    }
+
+    /// Places a `closePosition` trigger order for the trade, as Binance orders don't carry stop loss and take profit prices
+    async fn place_trigger_order(
+        &self,
+        trade_id: &str,
+        close_side: Side,
+        order_type: BinanceOrderType,
+        stop_price: f64,
+    ) -> Result<OrderResponse, GlowError> {
+        let suffix = if order_type == BinanceOrderType::StopMarket {
+            "sl"
+        } else {
+            "tp"
+        };
+        let payload = CreateOrderDto::new_close_position_trigger(
+            self.get_traded_symbol().name.to_string(),
+            close_side.into(),
+            order_type,
+            stop_price,
+            format!("{}_{}", trade_id, suffix),
+        );
+        self.place_order(&payload).await
+    }
+
+    async fn replace_trigger_order(
+        &self,
+        trade_id: &str,
+        order_type: BinanceOrderType,
+        stop_price: f64,
+    ) -> Result<bool, GlowError> {
+        let open_order = self
+            .fetch_order(format!("{}_{}", trade_id, OrderStage::Open.to_string()))
+            .await?;
+        let close_side = Side::from(open_order.side).get_opposite_side()?;
+        let suffix = if order_type == BinanceOrderType::StopMarket {
+            "sl"
+        } else {
+            "tp"
+        };
+        // the previous trigger order might have been expired already
+        let _ = self.cancel_order(format!("{}_{}", trade_id, suffix)).await;
+        let result = self
+            .place_trigger_order(trade_id, close_side, order_type, stop_price)
+            .await;
+        Ok(result.is_ok())
+    }
+}
+
+impl TraderHelper for BinanceTraderExchange {
+    #[inline]
+    fn get_contracts(&self) -> &HashMap<SymbolId, Contract> {
+        self.contracts
+    }
+    #[inline]
+    fn get_maker_fee(&self) -> f64 {
+        self.fee_rates.0
+    }
+    #[inline]
+    fn get_taker_fee(&self) -> f64 {
+        self.fee_rates.1
+    }
+    #[inline]
+    fn get_trading_settings(&self) -> &TradingSettings {
+        &self.trading_settings
+    }
+
+    fn calculate_open_order_units_and_balance_remainder(
+        &self,
+        side: Side,
+        order_cost: f64,
+        price: f64,
+    ) -> Result<(f64, f64), GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        // Order Cost = Initial Margin + Fee to Open Position + Fee to Close Position
+        // Initial Margin = (Order Price × Order Quantity) / Leverage
+        // Binance charges fees over the notional value, so both fees are estimated at the order price
+        let contract = self.get_traded_contract();
+        let taker_fee_rate = self.get_taker_fee();
+        let maximum_order_sizes = contract.maximum_order_sizes;
+
+        if side == Side::None {
+            let error = format!(
+                "calculate_order_units_and_balance_remainder -> Invalid side {:?}",
+                side
+            );
+            return Err(GlowError::new(String::from("Invalid Side Error"), error));
+        }
+
+        let mut units =
+            order_cost * leverage_factor / (price * (1.0 + 2.0 * taker_fee_rate * leverage_factor));
+
+        let fract_units = calculate_remainder(units, contract.minimum_order_size);
+        let size_decimals = count_decimal_places(contract.minimum_order_size);
+        units = round_down_nth_decimal(units - fract_units, size_decimals);
+
+        let open_order_type = trading_settings.order_types.0;
+        let maximum_order_size = if open_order_type == OrderType::Market {
+            maximum_order_sizes.0
+        } else {
+            maximum_order_sizes.1
+        };
+
+        let error = if units == 0.0 || units < contract.minimum_order_size {
+            Some(format!(
+                "units < contract.minimum_order_size | units = {}, minimum order size = {}",
+                units, contract.minimum_order_size
+            ))
+        } else if units > maximum_order_size {
+            Some(format!(
+                "units > contract.maximum_order_size | units = {}, maximum order size = {}",
+                units, maximum_order_size
+            ))
+        } else if leverage_factor > contract.max_leverage {
+            Some(format!(
+                "leverage_factor > contract.max_leverage | leverage_factor = {}, max_leverage = {}",
+                leverage_factor, contract.max_leverage
+            ))
+        } else {
+            None
+        };
+
+        if let Some(error) = error {
+            println!("Some contract constraints stopped the order from being placed");
+            let error = format!("calculate_order_units -> side {:?}, error {}", side, error);
+            return Err(GlowError::new(String::from("Invalid Side Error"), error));
+        }
+
+        let balance_remainder = fract_units * price / leverage_factor;
+
+        Ok((units, balance_remainder))
+    }
+
+    fn get_order_fee_rate(&self, order_type: OrderType) -> (f64, bool) {
+        let maker_fee_rate = self.get_maker_fee();
+        let taker_fee_rate = self.get_taker_fee();
+
+        if order_type == OrderType::Limit {
+            (maker_fee_rate, true)
+        } else {
+            (taker_fee_rate, false)
+        }
+    }
+
+    fn calculate_order_fees(
+        &self,
+        order_type: OrderType,
+        side: Side,
+        units: f64,
+        price: f64,
+    ) -> ((f64, f64), f64, bool) {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let (fee_rate, is_maker) = self.get_order_fee_rate(order_type);
+        let open_fee = units * price * fee_rate;
+        let bankruptcy_price = if side == Side::Sell {
+            price * (leverage_factor + 1.0) / leverage_factor
+        } else if side == Side::Buy {
+            price * (leverage_factor - 1.0) / leverage_factor
+        } else {
+            0.0
+        };
+        let close_fee = units * bankruptcy_price * fee_rate;
+
+        ((open_fee, close_fee), fee_rate, is_maker)
+    }
+
+    fn calculate_order_stop_loss_price(&self, side: Side, price: f64) -> Option<f64> {
+        let contract = self.get_traded_contract();
+        let trading_settings = self.get_trading_settings();
+        let stop_loss = trading_settings.price_level_modifier_map.get("sl")?;
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let stop_loss_percentage = stop_loss.get_percentage();
+        let position_mod = match side {
+            Side::Sell => leverage_factor + stop_loss_percentage,
+            Side::Buy => leverage_factor - stop_loss_percentage,
+            Side::None => return None,
+        };
+        let stop_loss_price = price * position_mod / leverage_factor;
+        let tick_decimals = count_decimal_places(contract.tick_size);
+        let stop_loss_price_remainder = calculate_remainder(stop_loss_price, contract.tick_size);
+        Some(round_down_nth_decimal(
+            stop_loss_price - stop_loss_price_remainder,
+            tick_decimals,
+        ))
+    }
+
+    fn calculate_order_take_profit_price(&self, side: Side, price: f64) -> Option<f64> {
+        let contract = self.get_traded_contract();
+        let trading_settings = self.get_trading_settings();
+        let take_profit = trading_settings.price_level_modifier_map.get("tp")?;
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let take_profit_percentage = take_profit.get_percentage();
+        let position_mod = match side {
+            Side::Sell => leverage_factor - take_profit_percentage,
+            Side::Buy => leverage_factor + take_profit_percentage,
+            Side::None => return None,
+        };
+        let take_profit_price = price * position_mod / leverage_factor;
+        let tick_decimals = count_decimal_places(contract.tick_size);
+        let take_profit_price_remainder =
+            calculate_remainder(take_profit_price, contract.tick_size);
+        Some(round_down_nth_decimal(
+            take_profit_price - take_profit_price_remainder,
+            tick_decimals,
+        ))
+    }
+}
+
+impl TraderExchange for BinanceTraderExchange {
+    #[inline]
+    fn get_http_client(&self) -> &Client {
+        &self.http
+    }
+
+    fn get_ws_url(&self) -> Result<Url, GlowError> {
+        let listen_key_guard = self.listen_key.lock().unwrap();
+        let listen_key = listen_key_guard.as_ref().ok_or(GlowError::new(
+            String::from("Missing Listen Key"),
+            String::from("get_ws_url -> user data stream listen key wasn't created"),
+        ))?;
+        let url = Url::parse(&format!("{}/ws/{}", self.endpoints.ws, listen_key))?;
+        Ok(url)
+    }
+
+    /// User data streams are authenticated by their listen key
+    async fn auth_ws(
+        &self,
+        _wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    /// User data streams push every account event, no subscription is needed
+    async fn subscribe_ws(
+        &self,
+        _wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    fn process_ws_message(&self, json: &String) -> Result<(), GlowError> {
+        let message = from_str::<UserDataWsMessage>(json).unwrap_or(UserDataWsMessage::None);
+        let traded_symbol_str = self.get_traded_symbol().name;
+
+        match message {
+            UserDataWsMessage::None => Ok(()),
+            UserDataWsMessage::ListenKeyExpired => Err(GlowError::new(
+                String::from("Listen Key Expired"),
+                String::from("process_ws_message -> user data stream listen key expired"),
+            )),
+            UserDataWsMessage::OrderTradeUpdate(message) => {
+                let data = message.data;
+                // untriggered stop orders only matter once they're executed
+                if data.symbol != traded_symbol_str || data.order_type.is_trigger_order() {
+                    return Ok(());
+                }
+
+                if let Some(execution) = data.get_execution() {
+                    self.executions_update_emitter.next(vec![execution]);
+                }
+
+                let updated_order = data.new_order_from_update_data(
+                    message.transaction_time,
+                    self.get_leverage_factor(),
+                    self.get_taker_fee(),
+                );
+
+                let order_action = if updated_order.is_cancel_order() {
+                    OrderAction::Cancel(updated_order)
+                } else if updated_order.is_stop {
+                    OrderAction::Stop(updated_order)
+                } else {
+                    OrderAction::Update(updated_order)
+                };
+                self.order_update_emitter.next(order_action);
+                Ok(())
+            }
+            UserDataWsMessage::AccountUpdate(message) => {
+                let usdt_data = message
+                    .data
+                    .balances
+                    .into_iter()
+                    .find(|balance| balance.asset == "USDT");
+
+                if let Some(usdt_data) = usdt_data {
+                    let balance = Balance::new(
+                        message.event_time,
+                        usdt_data.cross_wallet_balance,
+                        usdt_data.wallet_balance,
+                    );
+                    self.balance_update_emitter.next(balance);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn fetch_order_executions(
+        &self,
+        order_uuid: String,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<Execution>, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = FetchExecutionsDto {
+            symbol: traded_symbol.name.to_string(),
+            order_uuid,
+        };
+
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/fapi/v1/userTrades", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<Vec<UserTradeResponse>>(result).await?;
+
+        let executions = parsed_response
+            .into_iter()
+            .filter(|trade| trade.time >= start_timestamp && trade.time <= end_timestamp)
+            .map(|trade| trade.into())
+            .collect();
+        Ok(executions)
+    }
+
+    async fn fetch_history_order(
+        &self,
+        id: Option<String>,
+        side: Option<Side>,
+        fetch_executions: bool,
+    ) -> Result<Order, GlowError> {
+        let order_response = match id {
+            Some(id) => self.fetch_order(id).await?,
+            None => {
+                let traded_symbol = self.get_traded_symbol();
+                let payload = FetchAllOrdersDto {
+                    symbol: traded_symbol.name.to_string(),
+                    limit: 50,
+                };
+                let request_builder =
+                    self.prepare_request_builder(HttpMethod::Get, "/fapi/v1/allOrders", &payload)?;
+                let result = request_builder.send().await;
+                let mut parsed_response =
+                    Self::try_parse_response::<Vec<OrderResponse>>(result).await?;
+                parsed_response.sort_by_key(|order| Reverse(order.update_time));
+
+                parsed_response
+                    .into_iter()
+                    .find(|order| {
+                        !order.status.is_cancel()
+                            && !order.order_type.is_trigger_order()
+                            && side.is_none_or(|side| Side::from(order.side) == side)
+                    })
+                    .ok_or(GlowError::new(
+                        String::from("Failed Query"),
+                        "fetch_history_order -> no closed order was found".to_string(),
+                    ))?
+            }
+        };
+
+        let executed_qty = order_response.executed_qty;
+        let mut order = order_response
+            .new_order_from_response_data(self.get_leverage_factor(), self.get_taker_fee());
+
+        if fetch_executions && executed_qty > 0.0 {
+            let executions = self
+                .fetch_order_executions(order.uuid.clone(), order.created_at, order.updated_at)
+                .await?;
+            order = order.push_executions_if_new(executions);
+        }
+        Ok(order)
+    }
+
+    /// Binance queries open and finished orders through the same endpoint
+    async fn fetch_current_order(
+        &self,
+        order_id: String,
+        fetch_executions: bool,
+    ) -> Result<Order, GlowError> {
+        self.fetch_history_order(Some(order_id), None, fetch_executions)
+            .await
+    }
+
+    async fn fetch_current_trade_position(&self) -> Result<Option<Trade>, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = SymbolDto {
+            symbol: traded_symbol.name.to_string(),
+        };
+
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/fapi/v2/positionRisk", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<Vec<PositionRiskResponse>>(result).await?;
+
+        let position_response = parsed_response
+            .into_iter()
+            .find(|position| position.symbol == traded_symbol.name && position.position_amt != 0.0);
+
+        let Some(position_response) = position_response else {
+            return Ok(None);
+        };
+
+        let position_side = if position_response.position_amt > 0.0 {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+
+        let open_order = self
+            .fetch_history_order(None, Some(position_side), true)
+            .await?;
+        let close_order_id = open_order.id.replace("open", "close");
+        let close_order = self.fetch_current_order(close_order_id, true).await.ok();
+
+        Ok(Some(Trade::new(open_order, close_order)))
+    }
+
+    async fn fetch_trade_state(
+        &self,
+        trade_id: String,
+        last_status: TradeStatus,
+    ) -> Result<Trade, GlowError> {
+        let open_order_id = format!("{}_{}", trade_id, OrderStage::Open.to_string());
+        let close_order_id = format!("{}_{}", trade_id, OrderStage::Close.to_string());
+
+        let open_order = self.fetch_current_order(open_order_id, true).await?;
+
+        let close_order = match last_status {
+            TradeStatus::Cancelled => None,
+            TradeStatus::New | TradeStatus::PartiallyOpen => match open_order.status {
+                OrderStatus::Filled => self.fetch_current_order(close_order_id, true).await.ok(),
+                OrderStatus::Cancelled | OrderStatus::StandBy | OrderStatus::PartiallyFilled => {
+                    None
+                }
+                _ => {
+                    let error = format!(
+                        r#"fetch_trade_state -> {:?} -> open order has {:?} status"#,
+                        last_status, open_order.status
+                    );
+                    return Err(GlowError::new(String::from("Wrong Status Error"), error));
+                }
+            },
+            TradeStatus::PendingCloseOrder => {
+                self.fetch_current_order(close_order_id, true).await.ok()
+            }
+            TradeStatus::CloseOrderStandBy | TradeStatus::PartiallyClosed | TradeStatus::Closed => {
+                Some(self.fetch_current_order(close_order_id, true).await?)
+            }
+        };
+
+        Ok(Trade::new(open_order, close_order))
+    }
+
+    async fn fetch_current_usdt_balance(&self) -> Result<Balance, GlowError> {
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Get, "/fapi/v2/balance", &EmptyDto {})?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<Vec<BalanceResponse>>(result).await?;
+
+        let usdt_data = parsed_response
+            .into_iter()
+            .find(|balance| balance.asset == "USDT")
+            .ok_or_else(|| {
+                GlowError::new_str(
+                    "Missing Balance Error",
+                    "fetch_current_usdt_balance -> missing usdt balance",
+                )
+            })?;
+
+        Ok(Balance::new(
+            usdt_data.update_time,
+            usdt_data.available_balance,
+            usdt_data.balance,
+        ))
+    }
+
+    async fn open_order(
+        &self,
+        side: Side,
+        total_balance: f64,
+        expected_price: f64,
+    ) -> Result<Order, GlowError> {
+        assert_ne!(side, Side::None, "Invalid Open Order Side!");
+        assert!(
+            expected_price > 0.0,
+            "open_order -> expected price is less than 0!"
+        );
+
+        let trading_settings = self.get_trading_settings();
+        let traded_contract = self.get_traded_contract();
+
+        let mut expected_price = expected_price;
+        if trading_settings.get_open_order_type() == OrderType::Limit {
+            let tick_decimals = count_decimal_places(traded_contract.tick_size);
+            // move price marginally away from the last price in order to realize maker_fee
+            expected_price = if side == Side::Sell {
+                round_down_nth_decimal(expected_price + traded_contract.tick_size, tick_decimals)
+            } else {
+                round_down_nth_decimal(expected_price - traded_contract.tick_size, tick_decimals)
+            };
+        }
+        let order_cost = total_balance * trading_settings.allocation_percentage;
+
+        let mut order = self.new_open_order(side, order_cost, expected_price)?;
+        let payload: CreateOrderDto = order.clone().into();
+        let parsed_response = self.place_order(&payload).await?;
+        order.uuid = parsed_response.order_id.to_string();
+
+        let trade_id = order
+            .id
+            .replace(&format!("_{}", OrderStage::Open.to_string()), "");
+        let close_side = side.get_opposite_side()?;
+        if let Some(stop_loss_price) = order.stop_loss_price {
+            if let Err(error) = self
+                .place_trigger_order(
+                    &trade_id,
+                    close_side,
+                    BinanceOrderType::StopMarket,
+                    stop_loss_price,
+                )
+                .await
+            {
+                println!("open_order -> stop loss order error {:?}", error);
+            }
+        }
+        if let Some(take_profit_price) = order.take_profit_price {
+            if let Err(error) = self
+                .place_trigger_order(
+                    &trade_id,
+                    close_side,
+                    BinanceOrderType::TakeProfitMarket,
+                    take_profit_price,
+                )
+                .await
+            {
+                println!("open_order -> take profit order error {:?}", error);
+            }
+        }
+
+        Ok(order)
+    }
+
+    async fn amend_order(
+        &self,
+        order_id: String,
+        updated_units: Option<f64>,
+        updated_price: Option<f64>,
+        updated_stop_loss_price: Option<f64>,
+        updated_take_profit_price: Option<f64>,
+    ) -> Result<bool, GlowError> {
+        let trade_id = order_id
+            .trim_end_matches(&format!("_{}", OrderStage::Open.to_string()))
+            .trim_end_matches(&format!("_{}", OrderStage::Close.to_string()))
+            .to_string();
+
+        let mut amended = true;
+        if updated_units.is_some() || updated_price.is_some() {
+            // Binance requires side, quantity and price to be sent altogether
+            let current_order = self.fetch_order(order_id.clone()).await?;
+            let payload = AmendOrderDto {
+                symbol: current_order.symbol,
+                id: order_id.clone(),
+                side: current_order.side,
+                units: updated_units.unwrap_or(current_order.orig_qty),
+                price: updated_price.or(current_order.price).unwrap_or_default(),
+            };
+            let request_builder =
+                self.prepare_request_builder(HttpMethod::Put, "/fapi/v1/order", &payload)?;
+            let result = request_builder.send().await;
+            match Self::try_parse_response::<OrderResponse>(result).await {
+                Ok(parsed_response) => amended &= parsed_response.client_order_id == order_id,
+                Err(error) => {
+                    println!("amend_order -> unexpected response {:?}", error);
+                    amended = false;
+                }
+            }
+        }
+
+        if let Some(stop_loss_price) = updated_stop_loss_price {
+            amended &= self
+                .replace_trigger_order(&trade_id, BinanceOrderType::StopMarket, stop_loss_price)
+                .await?;
+        }
+        if let Some(take_profit_price) = updated_take_profit_price {
+            amended &= self
+                .replace_trigger_order(
+                    &trade_id,
+                    BinanceOrderType::TakeProfitMarket,
+                    take_profit_price,
+                )
+                .await?;
+        }
+
+        Ok(amended)
+    }
+
+    async fn try_close_position(&self, trade: &Trade, est_price: f64) -> Result<Order, GlowError> {
+        let mut est_price = est_price;
+        let traded_contract = self.get_traded_contract();
+        let trading_settings = self.get_trading_settings();
+        let close_order_type = trading_settings.get_close_order_type();
+
+        if close_order_type == OrderType::Limit {
+            if trade.open_order.side == Side::Sell {
+                // close order will have open order opposite side, subtract last price marginally in order to realize maker_fee
+                est_price -= traded_contract.tick_size
+            } else if trade.open_order.side == Side::Buy {
+                // close order will have open order opposite side, add last price marginally in order to realize maker_fee
+                est_price += traded_contract.tick_size;
+            }
+        }
+
+        let (est_fee_rate, _) = self.get_order_fee_rate(close_order_type);
+
+        let mut close_order = trade.new_close_order(close_order_type, est_price)?;
+
+        let position_lock = trading_settings.position_lock_modifier;
+        let price_pnl = trade.calculate_price_pnl(est_price);
+        let open_fee = trade.open_order.get_executed_order_fee();
+        let close_fee = close_order.get_estimate_close_order_fee(est_fee_rate, est_price);
+        if position_lock.is_close_locked(price_pnl, open_fee, close_fee) {
+            let error = format!(
+                "Trade wasn't closed due to PositionLockModifier::{:?} -> price profit and loss = {}, open fee = {}, close fee = {}",
+                position_lock, price_pnl, open_fee, close_fee
+            );
+            return Err(GlowError::new(String::from("Close Position Error"), error));
+        }
+
+        let payload: CreateOrderDto = close_order.clone().into();
+        let parsed_response = self.place_order(&payload).await?;
+        close_order.uuid = parsed_response.order_id.to_string();
+        Ok(close_order)
+    }
+
+    async fn update_trailing_stop_loss(
+        &self,
+        _trade: &Trade,
+        _last_price: f64,
+    ) -> Result<bool, GlowError> {
+        // TODO: support trailing stop losses through TRAILING_STOP_MARKET orders
+        Ok(false)
+    }
+
+    async fn cancel_order(&self, order_id: String) -> Result<bool, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = OrderIdDto {
+            symbol: traded_symbol.name.to_string(),
+            id: order_id.clone(),
+        };
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Delete, "/fapi/v1/order", &payload)?;
+        let result = request_builder.send().await;
+        match Self::try_parse_response::<OrderResponse>(result).await {
+            Ok(parsed_response) => Ok(parsed_response.client_order_id == order_id),
+            Err(error) => {
+                println!("cancel_order -> unexpected response {:?}", error);
+                Ok(false)
+            }
+        }
+    }
+
+    async fn set_leverage(&self, leverage: Leverage) -> Result<bool, GlowError> {
+        let leverage_factor = leverage.get_factor();
+        let traded_contract = self.get_traded_contract();
+        let traded_symbol = traded_contract.symbol;
+        if leverage_factor > traded_contract.max_leverage {
+            let error = format!(
+                "symbol {} only allows for max {} leverage, {} was sent",
+                traded_symbol.name, traded_contract.max_leverage, leverage_factor
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Leverage Error"),
+                error,
+            ));
+        }
+
+        let payload = SetLeverageDto {
+            symbol: traded_symbol.name.to_string(),
+            leverage: leverage_factor as u32,
+        };
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/fapi/v1/leverage", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response = Self::try_parse_response::<SetLeverageResponse>(result).await?;
+        Ok(parsed_response.leverage == payload.leverage)
+    }
+
+    fn new_open_order(&self, side: Side, order_cost: f64, price: f64) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let open_order_type = trading_settings.get_open_order_type();
+        let (units, balance_remainder) =
+            self.calculate_open_order_units_and_balance_remainder(side, order_cost, price)?;
+
+        let contract = self.get_traded_contract();
+
+        let timestamp = current_timestamp_ms();
+        let id = format!(
+            "{}_{}_{}",
+            &contract.symbol.name,
+            timestamp,
+            OrderStage::Open.to_string()
+        );
+        let avg_price = if open_order_type == OrderType::Limit {
+            Some(price)
+        } else {
+            None
+        };
+        let stop_loss_price = self.calculate_order_stop_loss_price(side, price);
+        let take_profit_price = self.calculate_order_take_profit_price(side, price);
+
+        let time_in_force = if open_order_type == OrderType::Limit {
+            TimeInForce::GTC
+        } else {
+            TimeInForce::IOC
+        };
+
+        let order = Order::new(
+            avg_price,
+            balance_remainder,
+            timestamp,
+            vec![],
+            id,
+            false,
+            false,
+            leverage_factor,
+            open_order_type,
+            side,
+            OrderStatus::StandBy,
+            stop_loss_price,
+            contract.symbol.name.to_string(),
+            take_profit_price,
+            self.get_taker_fee(),
+            time_in_force,
+            units,
+            timestamp,
+            "".to_string(),
+        );
+        Ok(order)
+    }
+
+    fn get_ws_ping_interval(&self) -> u64 {
+        60
+    }
+
+    /// Binance doesn't take ping requests from clients, but accepts unsolicited pong frames as heartbeats
+    fn get_ws_ping_message(&self) -> Result<Message, GlowError> {
+        Ok(Message::Pong(vec![]))
+    }
+
+    async fn update_position_data_on_faulty_exchange_ws(&self) -> Result<(), GlowError> {
+        let balance = self.fetch_current_usdt_balance().await?;
+        self.balance_update_emitter.next(balance);
+
+        let last_error_ts = *self.last_ws_error_ts.lock().unwrap();
+
+        let Some(last_error_ts) = last_error_ts else {
+            let trade = self.fetch_current_trade_position().await?;
+            if let Some(trade) = &trade {
+                println!(
+                    "{:?} | A initial trade was found! {:?}",
+                    current_datetime(),
+                    trade
+                );
+            }
+            self.trade_update_emitter.next(trade);
+            return Ok(());
+        };
+
+        let Some(current_trade) = self.trade_update_emitter.value() else {
+            return Ok(());
+        };
+        let current_trade_status = current_trade.status();
+        match current_trade_status {
+            // no close order is pending, or the trade is already finished
+            TradeStatus::PendingCloseOrder | TradeStatus::Closed | TradeStatus::Cancelled => {}
+            _ => {
+                let current_order_uuid = current_trade.get_active_order_uuid().unwrap_or_else(|| panic!(
+                    "update_position_data_on_faulty_exchange_ws -> missing current order uuid. trade = {:?}",
+                    &current_trade
+                ));
+                let current_minute_end_timestamp = timestamp_minute_end(true, None);
+                let interim_executions = self
+                    .fetch_order_executions(
+                        current_order_uuid,
+                        last_error_ts,
+                        current_minute_end_timestamp,
+                    )
+                    .await
+                    .unwrap_or_else(|error| {
+                        println!(
+                            "update_position_data_on_faulty_exchange_ws -> executions error {:?}",
+                            error
+                        );
+                        vec![]
+                    });
+
+                if !interim_executions.is_empty() || current_trade_status == TradeStatus::New {
+                    if !interim_executions.is_empty() {
+                        self.executions_update_emitter.next(interim_executions);
+                    }
+                    let current_order_id = current_trade.get_active_order_id().unwrap_or_else(|| panic!(
+                        "update_position_data_on_faulty_exchange_ws -> missing current order id. trade = {:?}",
+                        &current_trade
+                    ));
+
+                    match self.fetch_current_order(current_order_id, false).await {
+                        Ok(updated_order) => {
+                            let order_action = if updated_order.is_cancel_order() {
+                                OrderAction::Cancel(updated_order)
+                            } else {
+                                OrderAction::Update(updated_order)
+                            };
+                            self.order_update_emitter.next(order_action);
+                        }
+                        Err(error) => {
+                            println!("update_position_data_on_faulty_exchange_ws -> fetch_current_order failed {:?}. Error = {:?}", current_trade_status, error);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn init(&mut self) -> Result<(), GlowError> {
+        loop {
+            if let Err(error) = self.create_listen_key().await {
+                eprintln!(
+                    "Exchange listen key creation failed. \n
+                    Error: {:?} \n
+                    Retrying in {} seconds...",
+                    error, WS_RECONNECT_INTERVAL_IN_SECS
+                );
+                sleep(Duration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                continue;
+            }
+
+            let url = self.get_ws_url()?;
+            let request = url.as_str().into_client_request()?;
+            let connection = connect_async(request).await;
+            if let Err(error) = connection {
+                eprintln!(
+                    "Exchange WebSocket connection failed. \n
+                    Error: {} \n
+                    Retrying in {} seconds...",
+                    error, WS_RECONNECT_INTERVAL_IN_SECS
+                );
+
+                sleep(Duration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                continue;
+            }
+
+            let (wss, resp) = connection.unwrap();
+            eprintln!("Exchange connection stablished. \n Response: {:?}", resp);
+            if let Err(err) = self.listen_messages(wss).await {
+                {
+                    let mut last_error_guard = self
+                        .last_ws_error_ts
+                        .lock()
+                        .expect("handle_websocket -> last_error_guard unwrap");
+                    *last_error_guard = Some(current_timestamp_ms());
+                }
+
+                eprintln!(
+                    "{:?} | Exchange websocket connection error: {:?}. Retrying...",
+                    current_timestamp_ms(),
+                    err
+                );
+            }
+        }
+    }
+
+    async fn listen_messages(
+        &mut self,
+        mut wss: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        self.auth_ws(&mut wss).await?;
+        self.subscribe_ws(&mut wss).await?;
+        let ping_message = self.get_ws_ping_message()?;
+
+        let mut heartbeat_interval: Interval =
+            interval(Duration::from_secs(self.get_ws_ping_interval()));
+        let mut listen_key_interval: Interval =
+            interval(Duration::from_secs(LISTEN_KEY_KEEP_ALIVE_INTERVAL_IN_SECS));
+        // the listen key was just created, so the first immediate tick is skipped
+        listen_key_interval.tick().await;
+
+        let mut sleep_deadline = Instant::now();
+        let mut timeout_executed = false;
+
+        loop {
+            select! {
+                ws_message = wss.next() => {
+                    let Some(message) = ws_message else {
+                        return Err(GlowError::new(
+                            String::from("Closed Connection"),
+                            String::from("listen_messages -> user data stream was closed"),
+                        ));
+                    };
+
+                    match message? {
+                        Message::Text(json) => {
+                            // an expired listen key requires a new connection
+                            self.process_ws_message(&json)?;
+                        },
+                        Message::Ping(payload) => {
+                            wss.send(Message::Pong(payload)).await?
+                        },
+                        fallback => {
+                            println!("exchange fallback message {:?}", fallback);
+                        }
+                    }
+                },
+                _ = sleep_until(sleep_deadline) => {
+                    sleep_deadline = Instant::now() + Duration::from_secs(SECONDS_IN_MIN as u64);
+                    if timeout_executed {
+                        continue;
+                    }
+
+                    let _ = self.update_position_data_on_faulty_exchange_ws().await;
+
+                    {
+                        let mut last_error_guard = self.last_ws_error_ts.lock().unwrap();
+                        *last_error_guard = None;
+                    }
+
+                    timeout_executed = true;
+                }
+                _ = listen_key_interval.tick() => {
+                    if let Err(error) = self.keep_alive_listen_key().await {
+                        println!("listen_messages -> listen key keep alive error {:?}", error);
+                    }
+                }
+                _ = heartbeat_interval.tick() => {
+                    wss.send(ping_message.clone()).await?;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_balance_update_emitter(&self) -> &BehaviorSubject<Balance> {
+        &self.balance_update_emitter
+    }
+
+    #[inline]
+    fn get_executions_update_emitter(&self) -> &BehaviorSubject<Vec<Execution>> {
+        &self.executions_update_emitter
+    }
+
+    #[inline]
+    fn get_order_update_emitter(&self) -> &BehaviorSubject<OrderAction> {
+        &self.order_update_emitter
+    }
+
+    #[inline]
+    fn get_trade_update_emitter(&self) -> &BehaviorSubject<Option<Trade>> {
+        &self.trade_update_emitter
+    }
+}
+
+impl BenchmarkExchange for BinanceTraderExchange {
+    fn new_benchmark_open_order(
+        &self,
+        timestamp: i64,
+        side: Side,
+        order_cost: f64,
+        price: f64,
+    ) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let open_order_type = trading_settings.get_open_order_type();
+        let (units, balance_remainder) =
+            self.calculate_open_order_units_and_balance_remainder(side, order_cost, price)?;
+        let ((open_fee, _), fee_rate, is_maker) =
+            self.calculate_order_fees(open_order_type, side, units, price);
+        let contract = self.get_traded_contract();
+        let id = format!(
+            "{}_{}_{}",
+            &contract.symbol.name,
+            timestamp,
+            OrderStage::Open.to_string()
+        );
+        let stop_loss_price = self.calculate_order_stop_loss_price(side, price);
+        let take_profit_price = self.calculate_order_take_profit_price(side, price);
+        let time_in_force = if open_order_type == OrderType::Limit {
+            TimeInForce::GTC
+        } else {
+            TimeInForce::IOC
+        };
+
+        let order_uuid = format!("benchmark_open_order_{}", timestamp);
+
+        let benchmark_execution = Execution::new(
+            id.clone(),
+            order_uuid.clone(),
+            open_order_type,
+            timestamp,
+            price,
+            units,
+            open_fee,
+            fee_rate,
+            is_maker,
+            0.0,
+        );
+
+        let order = Order::new(
+            Some(price),
+            balance_remainder,
+            timestamp,
+            vec![benchmark_execution],
+            id,
+            false,
+            false,
+            leverage_factor,
+            open_order_type,
+            side,
+            OrderStatus::Filled,
+            stop_loss_price,
+            contract.symbol.name.to_string(),
+            take_profit_price,
+            self.get_taker_fee(),
+            time_in_force,
+            units,
+            timestamp,
+            order_uuid,
+        );
+        Ok(order)
+    }
+
+    fn new_benchmark_close_order(
+        &self,
+        timestamp: i64,
+        trade_id: &String,
+        close_price: f64,
+        open_order: Order,
+        final_status: OrderStatus,
+    ) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let close_order_type = match final_status {
+            OrderStatus::StoppedBR
+            | OrderStatus::StoppedSL
+            | OrderStatus::StoppedTSL
+            | OrderStatus::StoppedTP => trading_settings.get_close_order_type(),
+            _ => OrderType::Market,
+        };
+        let id = format!("{}_{}", trade_id, OrderStage::Close.to_string());
+        let time_in_force = if close_order_type == OrderType::Market {
+            TimeInForce::IOC
+        } else {
+            TimeInForce::GTC
+        };
+
+        let close_side = open_order.side.get_opposite_side()?;
+
+        let ((_, close_fee), fee_rate, is_maker) =
+            self.calculate_order_fees(close_order_type, close_side, open_order.units, close_price);
+
+        let order_uuid = format!("pending_order_uuid_{}", timestamp);
+
+        let benchmark_execution = Execution::new(
+            id.clone(),
+            order_uuid.clone(),
+            close_order_type,
+            timestamp,
+            close_price,
+            open_order.units,
+            close_fee,
+            fee_rate,
+            is_maker,
+            open_order.units,
+        );
+
+        let is_stop = final_status == OrderStatus::StoppedBR
+            || final_status == OrderStatus::StoppedSL
+            || final_status == OrderStatus::StoppedTSL
+            || final_status == OrderStatus::StoppedTP;
+
+        let order = Order::new(
+            Some(close_price),
+            0.0,
+            timestamp,
+            vec![benchmark_execution],
+            id,
+            true,
+            is_stop,
+            open_order.leverage_factor,
+            close_order_type,
+            close_side,
+            final_status,
+            None,
+            open_order.symbol.clone(),
+            None,
+            0.0,
+            time_in_force,
+            open_order.units,
+            timestamp,
+            order_uuid,
+        );
+
+        Ok(order)
+    }
+
+    fn close_benchmark_trade_on_binding_price(
+        &self,
+        trade: &Trade,
+        current_timestamp: i64,
+        binding_price: f64,
+    ) -> Result<Trade, GlowError> {
+        let stop_loss_price = trade.open_order.stop_loss_price.unwrap_or_default();
+        let take_profit_price = trade.open_order.take_profit_price.unwrap_or_default();
+        let bankruptcy_price = trade.open_order.get_bankruptcy_price().unwrap_or_default();
+        let final_status = if binding_price == stop_loss_price {
+            OrderStatus::StoppedSL
+        } else if binding_price == take_profit_price {
+            OrderStatus::StoppedTP
+        } else if binding_price == bankruptcy_price {
+            OrderStatus::StoppedBR
+        } else {
+            return Err(GlowError::new(
+                "Invalid binding price".to_owned(),
+                format!(
+                    "binding price = {:?}, SL price = {:?}, TP price = {:?} BR price = {:?}",
+                    binding_price, stop_loss_price, take_profit_price, bankruptcy_price
+                ),
+            ));
+        };
+
+        let close_order = self.new_benchmark_close_order(
+            current_timestamp,
+            &trade.id,
+            binding_price,
+            trade.open_order.clone(),
+            final_status,
+        )?;
+
+        let closed_trade = trade.update_trade(close_order)?;
+        Ok(closed_trade)
+    }
+
+    fn get_minimum_notional_value(&self) -> Option<f64> {
+        self.minimum_notional_value
+    }
+}
@@ -0,0 +1,565 @@
+use super::{
+    enums::BybitPublicWsMessage,
+    structs::{BybitHttpResponseWrapper, KlineResult, PingWsMessage, WsRequest},
+};
+use crate::{
+    config::WS_RECONNECT_INTERVAL_IN_SECS,
+    shared::functions::{adjust_benchmark_datetimes, set_ws_error_ts},
+};
+use chrono::{DateTime, Duration, Utc};
+use common::{
+    enums::trading_data_update::TradingDataUpdate,
+    functions::{
+        coerce_df_to_schema, csv::load_interval_tick_dataframe, current_datetime,
+        current_timestamp, current_timestamp_ms, downsample_tick_lf_to_kline_duration,
+        filter_df_timestamps_to_lf, get_date_start_and_end_timestamps, map_ticks_data_to_df,
+        parquet::save_kline_df_to_parquet, timestamp_minute_end, timestamp_minute_start,
+    },
+    structs::{BehaviorSubject, LogKlines, SymbolsPair, TickData, TradingSettings},
+    traits::exchange::DataProviderExchange,
+};
+use futures_util::SinkExt;
+use glow_error::GlowError;
+use polars::{
+    frame::DataFrame,
+    prelude::{IntoLazy, Schema, SortMultipleOptions},
+    time::ClosedWindow,
+};
+use reqwest::Client;
+use serde_json::{from_str, to_string};
+use std::{
+    collections::HashMap,
+    env::var as env_var,
+    sync::{Arc, Mutex},
+    time::Duration as StdDuration,
+};
+use strategy::Strategy;
+use tokio::{
+    net::TcpStream,
+    select, spawn,
+    time::{interval, sleep, sleep_until, Instant},
+};
+use tokio_stream::StreamExt;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tungstenite::client::IntoClientRequest;
+use url::Url;
+
+static BYBIT_PUBLIC_HTTP_BASE_URL: &str = "https://api.bybit.com";
+/// Max klines returned by a single `/v5/market/kline` request
+static KLINES_PAGE_LIMIT: i64 = 1000;
+/// Bybit drops public connections that don't ping within 20 seconds
+static WS_PING_INTERVAL_IN_SECS: u64 = 20;
+
+#[derive(Clone)]
+pub struct BybitDataProvider {
+    fetch_leeway: StdDuration,
+    http: Client,
+    kline_duration: Duration,
+    last_ws_error_ts: Arc<Mutex<Option<i64>>>,
+    minimum_klines_for_benchmarking: u32,
+    staged_ticks: HashMap<i64, Vec<TickData>>, // confirmed klines, by start timestamp (ms)
+    symbols: SymbolsPair,
+    ticks_to_commit: BehaviorSubject<Vec<TickData>>,
+    klines_data_update_emitter: BehaviorSubject<TradingDataUpdate>,
+}
+
+/// Provides Bybit linear perpetual prices, so that benchmarks of Bybit trades see the same wicks the exchange does
+impl BybitDataProvider {
+    pub fn new(trading_settings: &TradingSettings, strategy: &Strategy) -> Result<Self, GlowError> {
+        let symbols = trading_settings.symbols_pair;
+        let kline_duration = trading_settings.granularity.get_chrono_duration();
+        let last_ws_error_ts = Arc::new(Mutex::new(None));
+        let minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation()?;
+        let klines_data_update_emitter = BehaviorSubject::new(TradingDataUpdate::default());
+        Ok(Self {
+            fetch_leeway: StdDuration::from_secs(5),
+            http: Client::new(),
+            kline_duration,
+            last_ws_error_ts,
+            minimum_klines_for_benchmarking,
+            staged_ticks: HashMap::new(),
+            symbols,
+            ticks_to_commit: BehaviorSubject::new(vec![]),
+            klines_data_update_emitter,
+        })
+    }
+
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+        self.symbols = trading_settings.symbols_pair;
+        self.kline_duration = trading_settings.granularity.get_chrono_duration();
+    }
+
+    pub fn patch_strategy(&mut self, strategy: &Strategy) -> Result<(), GlowError> {
+        self.minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation()?;
+        Ok(())
+    }
+
+    async fn load_or_fetch_kline_data(
+        &self,
+        trading_data_schema: &Schema,
+        start_datetime: DateTime<Utc>,
+        end_datetime: DateTime<Utc>,
+        tick_duration: Duration,
+    ) -> Result<DataFrame, GlowError> {
+        let mut kline_df = DataFrame::empty_with_schema(trading_data_schema);
+        for symbol in &self.symbols.get_unique_symbols() {
+            let (loaded_data_df, not_loaded_dates) =
+                load_interval_tick_dataframe(start_datetime, end_datetime, symbol, "bybit")?;
+
+            let mut result_df =
+                loaded_data_df.unwrap_or_else(|| DataFrame::empty_with_schema(trading_data_schema));
+
+            if result_df.schema().len() != trading_data_schema.len() {
+                result_df = coerce_df_to_schema(result_df, trading_data_schema)?;
+            }
+
+            for (i, date) in not_loaded_dates.into_iter().enumerate() {
+                let datetimes = get_date_start_and_end_timestamps(date);
+                let mut day_ticks_data = vec![];
+                if i > 0 {
+                    // avoid spamming API
+                    sleep(StdDuration::from_secs(1)).await;
+                }
+                for (start_timestamp_ms, end_timestamp_ms) in datetimes {
+                    let fetched_ticks = self
+                        .fetch_tick_data(symbol.name, start_timestamp_ms, end_timestamp_ms)
+                        .await?;
+                    day_ticks_data.extend(fetched_ticks);
+                }
+                let fetched_data_df = map_ticks_data_to_df(&day_ticks_data)?;
+
+                let total_klines = fetched_data_df.height() as i64;
+                let daily_klines = Duration::days(1).num_seconds() / tick_duration.num_seconds();
+
+                if total_klines == daily_klines {
+                    save_kline_df_to_parquet(&fetched_data_df, "bybit", symbol.name)?;
+                }
+                let fetched_data_df = coerce_df_to_schema(fetched_data_df, trading_data_schema)?;
+                let sort_options = SortMultipleOptions::default()
+                    .with_order_descending(false)
+                    .with_maintain_order(false);
+                result_df = result_df
+                    .vstack(&fetched_data_df)?
+                    .sort(["start_time"], sort_options)?;
+            }
+            result_df = coerce_df_to_schema(result_df, trading_data_schema)?;
+            kline_df = kline_df.vstack(&result_df)?;
+        }
+        let sort_options = SortMultipleOptions::default()
+            .with_order_descending(false)
+            .with_maintain_order(false);
+        let mut kline_df = kline_df.sort(["start_time"], sort_options)?;
+        kline_df.align_chunks();
+        let kline_lf = filter_df_timestamps_to_lf(kline_df, start_datetime, end_datetime)?;
+        let kline_lf = downsample_tick_lf_to_kline_duration(
+            &self.symbols.get_unique_symbols(),
+            self.kline_duration,
+            kline_lf,
+            ClosedWindow::Left,
+            Some(trading_data_schema),
+        )?;
+
+        Ok(kline_lf.collect()?)
+    }
+
+    /// Fetches 1 minute klines within `[start_timestamp_ms, end_timestamp_ms]`.
+    ///
+    /// Bybit returns the most recent klines first, so pages are requested backwards,
+    /// moving the end of the window to right before the oldest kline of the previous page.
+    async fn fetch_tick_data(
+        &self,
+        symbol: &'static str,
+        start_timestamp_ms: i64,
+        end_timestamp_ms: i64,
+    ) -> Result<Vec<TickData>, GlowError> {
+        println!(
+            "{:?} | 🦴 Fetching {} data for interval between {} and {}",
+            current_datetime(),
+            symbol,
+            DateTime::from_timestamp_millis(start_timestamp_ms).unwrap(),
+            DateTime::from_timestamp_millis(end_timestamp_ms).unwrap()
+        );
+
+        let mut ticks_data = vec![];
+        let mut page_end_timestamp_ms = end_timestamp_ms;
+        loop {
+            let url = format!(
+                "{}/v5/market/kline?category=linear&symbol={}&interval=1&start={}&end={}&limit={}",
+                BYBIT_PUBLIC_HTTP_BASE_URL,
+                symbol,
+                start_timestamp_ms,
+                page_end_timestamp_ms,
+                KLINES_PAGE_LIMIT
+            );
+
+            let response: BybitHttpResponseWrapper<KlineResult> =
+                self.http.get(url).send().await?.json().await?;
+
+            if response.ret_code != 0 {
+                return Err(GlowError::new_unsuccessful_response(format!(
+                    "Bybit kline fetch failed ({}): {}",
+                    response.ret_code, response.ret_message
+                )));
+            }
+
+            let page = response.result.list;
+            let page_len = page.len() as i64;
+            let oldest_timestamp_ms = page.last().map(|kline| kline.0);
+            ticks_data.extend(page.into_iter().map(|kline| kline.into_tick_data(symbol)));
+
+            match oldest_timestamp_ms {
+                Some(oldest_timestamp_ms)
+                    if page_len == KLINES_PAGE_LIMIT
+                        && oldest_timestamp_ms > start_timestamp_ms =>
+                {
+                    page_end_timestamp_ms = oldest_timestamp_ms - 1;
+                }
+                _ => break,
+            }
+        }
+
+        ticks_data.sort_by_key(|tick| tick.start_time);
+        ticks_data.dedup_by_key(|tick| tick.start_time);
+        Ok(ticks_data)
+    }
+
+    async fn fetch_data_after_waiting(
+        &self,
+        wait_until: Instant,
+        start_timestamp_ms: i64,
+        end_timestamp_ms: i64,
+        trading_data_schema: &Schema,
+    ) -> Result<DataFrame, GlowError> {
+        sleep_until(wait_until).await;
+
+        let mut ticks_data = Vec::new();
+        for symbol in &self.symbols.get_unique_symbols() {
+            let symbol_kline_data = self
+                .fetch_tick_data(symbol.name, start_timestamp_ms, end_timestamp_ms)
+                .await?;
+            ticks_data.extend(symbol_kline_data);
+        }
+
+        let kline_data_df = map_ticks_data_to_df(&ticks_data)?;
+        let kline_data_df = coerce_df_to_schema(kline_data_df, trading_data_schema)?;
+
+        Ok(kline_data_df)
+    }
+
+    async fn handle_initial_klines_fetch(
+        &self,
+        benchmark_start: DateTime<Utc>,
+        benchmark_end: DateTime<Utc>,
+        trading_data_schema: &Schema,
+    ) -> Result<(), GlowError> {
+        let initial_kline_data_df = self
+            .load_or_fetch_kline_data(
+                trading_data_schema,
+                benchmark_start,
+                benchmark_end,
+                self.kline_duration,
+            )
+            .await?;
+
+        let current_datetime = current_datetime();
+        let is_last_kline_available = current_datetime > benchmark_end;
+
+        if is_last_kline_available {
+            let initial_data = TradingDataUpdate::Initial(initial_kline_data_df);
+            self.klines_data_update_emitter.next(initial_data);
+            return Ok(());
+        }
+
+        let current_timestamp = current_timestamp();
+        let seconds_until_pending_kline_available = benchmark_end.timestamp() - current_timestamp;
+        let duration_until_pending_kline_available =
+            StdDuration::from_secs(seconds_until_pending_kline_available as u64);
+        let pending_kline_available_at = Instant::now() + duration_until_pending_kline_available;
+
+        let remaining_seconds_from_current_ts = current_timestamp % 60;
+        let start_ms = (current_timestamp - remaining_seconds_from_current_ts) * 1000;
+        let end_ms = benchmark_end.timestamp_millis();
+
+        let pending_kline_df = self
+            .fetch_data_after_waiting(
+                pending_kline_available_at,
+                start_ms,
+                end_ms,
+                trading_data_schema,
+            )
+            .await?;
+
+        let initial_kline_data_df = initial_kline_data_df.vstack(&pending_kline_df)?;
+        let initial_data = TradingDataUpdate::Initial(initial_kline_data_df);
+        self.klines_data_update_emitter.next(initial_data);
+        Ok(())
+    }
+
+    fn get_ws_ping_message(&self) -> Result<Message, GlowError> {
+        let ping_request = PingWsMessage {
+            req_id: String::from("100001"),
+            op: String::from("ping"),
+        };
+        let ping_json_str = to_string(&ping_request)?;
+        Ok(Message::Text(ping_json_str))
+    }
+}
+
+impl DataProviderExchange for BybitDataProvider {
+    #[inline]
+    fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
+        &self.klines_data_update_emitter
+    }
+
+    async fn handle_committed_ticks_data(
+        &self,
+        discard_ticks_before: DateTime<Utc>,
+        trading_data_schema: &Schema,
+    ) -> Result<(), GlowError> {
+        let mut ticks_to_commit_subscription = self.ticks_to_commit.subscribe();
+        let discard_ticks_before = discard_ticks_before - Duration::nanoseconds(1);
+        let trading_data_schema = trading_data_schema.clone();
+        let kline_duration = self.kline_duration;
+        let unique_symbols = self.symbols.get_unique_symbols().clone();
+        let klines_data_update_emitter = self.klines_data_update_emitter.clone();
+
+        loop {
+            let committed_ticks = ticks_to_commit_subscription.next().await;
+            if committed_ticks.is_none() {
+                continue;
+            }
+            let mut committed_ticks = committed_ticks.unwrap();
+            if !committed_ticks
+                .iter()
+                .any(|tick| tick.start_time > discard_ticks_before)
+            {
+                continue;
+            }
+
+            committed_ticks.sort_by_key(|tick| tick.start_time);
+
+            let committed_kline_df = map_ticks_data_to_df(&committed_ticks)?;
+            let committed_kline_lf =
+                coerce_df_to_schema(committed_kline_df, &trading_data_schema)?.lazy();
+
+            let committed_kline_lf = downsample_tick_lf_to_kline_duration(
+                &unique_symbols,
+                kline_duration,
+                committed_kline_lf,
+                ClosedWindow::Left,
+                None,
+            )?;
+
+            let committed_kline_df = committed_kline_lf.collect()?;
+
+            let market_data = TradingDataUpdate::Market(committed_kline_df);
+            klines_data_update_emitter.next(market_data);
+        }
+    }
+
+    fn handle_ws_error(&self, trading_data_schema: &Schema) -> Option<DateTime<Utc>> {
+        let schema = trading_data_schema.clone();
+        let last_error_ts: Option<i64>;
+        {
+            let last_error_guard = self
+                .last_ws_error_ts
+                .lock()
+                .expect("handle_ws_error -> last_error_guard unwrap");
+            last_error_ts = *last_error_guard;
+        }
+        last_error_ts?;
+        let now = Instant::now();
+        let now_ts = current_timestamp_ms();
+
+        let start_ms = timestamp_minute_start(true, last_error_ts);
+        let end_ms = timestamp_minute_end(true, Some(now_ts));
+        let result = DateTime::from_timestamp_millis(end_ms).unwrap();
+        let data_provider = self.clone();
+        spawn(async move {
+            let duration_until_available = StdDuration::from_millis((now_ts - end_ms) as u64);
+
+            let wait_until = now + duration_until_available + data_provider.fetch_leeway;
+            let pending_kline_df = data_provider
+                .fetch_data_after_waiting(wait_until, start_ms, end_ms, &schema)
+                .await
+                .expect("pending kline df");
+            let market_data = TradingDataUpdate::Market(pending_kline_df);
+            data_provider.klines_data_update_emitter.next(market_data);
+
+            {
+                let mut last_error_guard = data_provider.last_ws_error_ts.lock().unwrap();
+                *last_error_guard = None;
+            }
+        });
+        Some(result)
+    }
+
+    async fn init(
+        &mut self,
+        benchmark_start: Option<DateTime<Utc>>,
+        benchmark_end: Option<DateTime<Utc>>,
+        run_benchmark_only: bool,
+        trading_data_schema: Schema,
+    ) -> Result<(), GlowError> {
+        let (benchmark_start, benchmark_end) = adjust_benchmark_datetimes(
+            benchmark_start,
+            benchmark_end,
+            self.kline_duration,
+            Some(1),
+            self.minimum_klines_for_benchmarking as i32,
+        )?;
+
+        self.handle_initial_klines_fetch(benchmark_start, benchmark_end, &trading_data_schema)
+            .await?;
+
+        if run_benchmark_only {
+            return Ok(());
+        }
+
+        println!(
+            "{} | 💹 Initializing DataFeed -> trades might be open after {}",
+            current_datetime(),
+            benchmark_end
+        );
+
+        let bybit_ws_base_url = env_var("BYBIT_PUBLIC_WS_BASE_URL")?;
+        let url = Url::parse(&format!("{}/v5/public/linear", bybit_ws_base_url))?;
+
+        loop {
+            let request = url.as_str().into_client_request()?;
+            match connect_async(request).await {
+                Ok((wss, resp)) => {
+                    eprintln!(
+                        "Data provider connection stablished. \n Response: {:?}",
+                        resp
+                    );
+                    let discard_ticks_before = self
+                        .handle_ws_error(&trading_data_schema)
+                        .unwrap_or(benchmark_end);
+                    match (
+                        self.handle_committed_ticks_data(
+                            discard_ticks_before,
+                            &trading_data_schema,
+                        )
+                        .await,
+                        self.listen_ticks(wss, discard_ticks_before).await,
+                    ) {
+                        (_, Err(error)) => {
+                            set_ws_error_ts(self.last_ws_error_ts.clone(), error);
+                            sleep(StdDuration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                        }
+                        (Err(error), _) => {
+                            set_ws_error_ts(self.last_ws_error_ts.clone(), error);
+                            sleep(StdDuration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                        }
+                        _ => {}
+                    }
+                }
+                Err(error) => {
+                    set_ws_error_ts(self.last_ws_error_ts.clone(), error.into());
+                    sleep(StdDuration::from_secs(WS_RECONNECT_INTERVAL_IN_SECS)).await;
+                }
+            }
+        }
+    }
+
+    async fn listen_ticks(
+        &mut self,
+        mut wss: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        discard_ticks_before: DateTime<Utc>,
+    ) -> Result<(), GlowError> {
+        self.subscribe_to_tick_stream(&mut wss).await?;
+
+        let unique_symbols = self.symbols.get_unique_symbols();
+        let discard_ticks_before_ms = discard_ticks_before.timestamp_millis();
+        let ping_message = self.get_ws_ping_message()?;
+        let mut heartbeat_interval = interval(StdDuration::from_secs(WS_PING_INTERVAL_IN_SECS));
+
+        loop {
+            select! {
+                _ = heartbeat_interval.tick() => {
+                    wss.send(ping_message.clone()).await?;
+                }
+                message = wss.try_next() => {
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(error) => {
+                            {
+                                let mut last_error_guard = self
+                                    .last_ws_error_ts
+                                    .lock()
+                                    .expect("handle_websocket -> last_error_guard unwrap");
+                                *last_error_guard = Some(current_timestamp());
+                            }
+                            eprintln!("WebSocket message error: {:?}", error);
+                            return Err(GlowError::from(error));
+                        }
+                    };
+                    let Some(message) = message else {
+                        continue;
+                    };
+                    match message {
+                        Message::Text(json) => {
+                            let incoming_msg =
+                                from_str::<BybitPublicWsMessage>(&json).unwrap_or_default();
+                            let BybitPublicWsMessage::Kline(kline_message) = incoming_msg else {
+                                continue;
+                            };
+                            let Some(symbol) = unique_symbols
+                                .iter()
+                                .find(|symbol| kline_message.topic.rsplit('.').next() == Some(symbol.name))
+                            else {
+                                continue;
+                            };
+                            // only closed klines are staged, Bybit pushes the open kline every second
+                            for kline in kline_message.data.into_iter().filter(|kline| kline.confirm) {
+                                let start_ms = kline.start;
+                                if start_ms < discard_ticks_before_ms {
+                                    continue;
+                                }
+                                let staged_ticks = self.staged_ticks.entry(start_ms).or_default();
+                                if staged_ticks.iter().any(|tick| tick.symbol == symbol.name) {
+                                    continue;
+                                }
+                                staged_ticks.push(kline.into_tick_data(symbol.name));
+
+                                // once every symbol closed its kline, staged ticks are committed as kline data
+                                if staged_ticks.len() == unique_symbols.len() {
+                                    let committed_ticks =
+                                        self.staged_ticks.remove(&start_ms).unwrap_or_default();
+                                    print!("{}", LogKlines(committed_ticks.clone()));
+                                    // klines that never got completed by every symbol are dropped
+                                    self.staged_ticks.retain(|staged_start_ms, _| *staged_start_ms > start_ms);
+                                    self.ticks_to_commit.next(committed_ticks);
+                                }
+                            }
+                        }
+                        Message::Ping(_) => wss.send(Message::Pong(vec![])).await?,
+                        fallback => {
+                            println!("fallback msg from bybit data provider {:?}", fallback);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn subscribe_to_tick_stream(
+        &mut self,
+        wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        let topics: Vec<String> = self
+            .symbols
+            .get_unique_symbols()
+            .into_iter()
+            .map(|symbol| format!("kline.1.{}", symbol.name))
+            .collect();
+
+        let subscribe_message = WsRequest::new(String::from("subscribe"), topics);
+        let subscribe_json_str = to_string(&subscribe_message)?;
+
+        wss.send(Message::Text(subscribe_json_str))
+            .await
+            .map_err(GlowError::from)
+    }
+}
@@ -18,6 +18,14 @@ impl Default for BybitWsMessage {
         BybitWsMessage::None
     }
 }
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+pub enum BybitPublicWsMessage {
+    #[default]
+    None,
+    Kline(KlineWsMessage),
+}
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum BybitHttpResponse {
@@ -1,3 +1,4 @@
+pub mod data_provider;
 pub mod enums;
 pub mod functions;
 pub mod structs;
@@ -5,7 +6,7 @@ use self::enums::BybitWsMessage;
 use self::structs::{
     AmendOrderDto, EmptyObject, ExecutionData, FetchCurrentOrderDto, FetchExecutionsDto,
     FetchHistoryOrderDto, FetchPositionDto, OrderData, OrderResponse, PositionResponseData,
-    SetLeverageDto, WsRequest,
+    SetLeverageDto, SetTradingStopDto, TrailingStopState, WsRequest,
 };
 use crate::enums::TraderExchangeId;
 use crate::r#static::TRADER_EXCHANGES_CONTEXT_MAP;
@@ -24,7 +25,7 @@ use common::{
     enums::{
         balance::Balance,
         http_method::HttpMethod,
-        modifiers::{leverage::Leverage, position_lock::PositionLock},
+        modifiers::{leverage::Leverage, price_level::TrailingStopLoss},
         order_stage::OrderStage,
         order_status::OrderStatus,
         order_type::OrderType,
@@ -79,6 +80,7 @@ pub struct BybitTraderExchange {
     pub trading_settings: TradingSettings,
     order_update_emitter: BehaviorSubject<OrderAction>,
     trade_update_emitter: BehaviorSubject<Option<Trade>>,
+    trailing_stop_state: Arc<Mutex<Option<TrailingStopState>>>,
 }
 
 impl BybitTraderExchange {
@@ -125,6 +127,7 @@ impl BybitTraderExchange {
             order_update_emitter,
             trade_update_emitter,
             trading_settings: trading_settings.clone(),
+            trailing_stop_state: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -132,6 +135,59 @@ impl BybitTraderExchange {
         self.trading_settings = trading_settings.clone();
     }
 
+    fn round_price_to_tick(&self, price: f64) -> f64 {
+        let contract = self.get_traded_contract();
+        let tick_decimals = count_decimal_places(contract.tick_size);
+        let price_remainder = calculate_remainder(price, contract.tick_size);
+        round_down_nth_decimal(price - price_remainder, tick_decimals)
+    }
+
+    fn get_trailing_stop_state(&self, trade_id: &String) -> Option<TrailingStopState> {
+        let trailing_stop_state_guard = self
+            .trailing_stop_state
+            .lock()
+            .expect("get_trailing_stop_state -> trailing_stop_state deadlock");
+        trailing_stop_state_guard
+            .clone()
+            .filter(|trailing_stop_state| &trailing_stop_state.trade_id == trade_id)
+    }
+
+    fn set_trailing_stop_state(&self, trailing_stop_state: TrailingStopState) {
+        let mut trailing_stop_state_guard = self
+            .trailing_stop_state
+            .lock()
+            .expect("set_trailing_stop_state -> trailing_stop_state deadlock");
+        *trailing_stop_state_guard = Some(trailing_stop_state);
+    }
+
+    /// Sets a trailing stop on the current position, trailed by Bybit at a fixed price distance
+    async fn set_native_trailing_stop(
+        &self,
+        trailing_stop: f64,
+        active_price: f64,
+    ) -> Result<bool, GlowError> {
+        let traded_symbol = self.get_traded_symbol();
+        let payload = SetTradingStopDto::new(
+            "linear".to_string(),
+            traded_symbol.name.to_string(),
+            trailing_stop,
+            active_price,
+        );
+        let request_builder =
+            self.prepare_request_builder(HttpMethod::Post, "/v5/position/trading-stop", &payload)?;
+        let result = request_builder.send().await;
+        let parsed_response =
+            Self::try_parse_response::<BybitHttpResponseWrapper<EmptyObject>>(result).await?;
+        if parsed_response.ret_code != 0 || parsed_response.ret_message != "OK" {
+            println!(
+                "set_native_trailing_stop -> unexpected response {:?}",
+                parsed_response
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
     async fn try_parse_response<T: DeserializeOwned>(
         result: Result<Response, Error>,
     ) -> Result<T, GlowError> {
@@ -157,8 +213,8 @@ impl BybitTraderExchange {
         payload: &S,
     ) -> Result<String, GlowError> {
         let suffix = match method {
-            HttpMethod::Get => to_url_string(&payload)?,
-            HttpMethod::Post => to_json_string(&payload)?,
+            HttpMethod::Get | HttpMethod::Delete => to_url_string(&payload)?,
+            HttpMethod::Post | HttpMethod::Put => to_json_string(&payload)?,
         };
         let query_string = format!(
             "{}{}{}{}",
@@ -937,6 +993,7 @@ impl TraderExchange for BybitTraderExchange {
                 | OrderStatus::Closed
                 | OrderStatus::StoppedBR
                 | OrderStatus::StoppedSL
+                | OrderStatus::StoppedTSL
                 | OrderStatus::StoppedTP => {
                     let error = format!(
                         r#"fetch_trade_state -> TradeStatus::New -> open order has {:?} status"#,
@@ -968,7 +1025,7 @@ impl TraderExchange for BybitTraderExchange {
                 | OrderStatus::Closed
                 | OrderStatus::StoppedBR
                 | OrderStatus::StoppedSL
-                // | OrderStatus::StoppedTSL
+                | OrderStatus::StoppedTSL
                 | OrderStatus::StoppedTP => {
                     let error = format!(
                         r#"fetch_trade_state -> TradeStatus::PartiallyOpen -> open order has {:?} status"#,
@@ -1100,7 +1157,12 @@ impl TraderExchange for BybitTraderExchange {
                 .find(|coin_data| coin_data.coin == "USDT".to_string())
         });
 
-        let usdt_data = usdt_coin_data.expect("get_current_usdt_balance -> missing usdt coin data");
+        let usdt_data = usdt_coin_data.ok_or_else(|| {
+            GlowError::new_str(
+                "Missing Balance Error",
+                "fetch_current_usdt_balance -> missing usdt coin data",
+            )
+        })?;
 
         let balance = Balance::new(
             parsed_response.time,
@@ -1234,35 +1296,19 @@ impl TraderExchange for BybitTraderExchange {
 
         // println!("try_close_position -> close order = {:?}", close_order);
 
-        match trading_settings.position_lock_modifier {
-            PositionLock::None => {}
-            PositionLock::Fee => {
-                // since we didn't assign close_order to trade, it makes sense to get unrealized profit and loss
-                // as an estimate of profit and loss, and calculate it against the sum of fees
-                let (profit_and_loss, _) = trade.calculate_unrealized_pnl_and_returns(est_price);
-                let total_fee = trade.open_order.get_executed_order_fee()
-                    + close_order.get_estimate_close_order_fee(est_fee_rate, est_price);
-                if total_fee >= profit_and_loss.abs() {
-                    let error = format!(
-                        "Trade wasn't closed due to PositionLockModifier::Fee -> profit and loss = {}, total fee = {}",
-                        profit_and_loss,
-                        total_fee
-                    );
-                    return Err(GlowError::new(String::from("Close Position Error"), error));
-                }
-            }
-            PositionLock::Loss => {
-                let (profit_and_loss, _returns) =
-                    trade.calculate_unrealized_pnl_and_returns(est_price);
-                if profit_and_loss <= 0.0 {
-                    let error = format!(
-                        "Trade wasn't closed due to PositionLockModifier::Loss -> profit and loss = {}",
-                        profit_and_loss
-                    );
-                    print!("{}", &error);
-                    return Err(GlowError::new(String::from("Close Position Error"), error));
-                }
-            }
+        // since we didn't assign close_order to trade, it makes sense to get unrealized profit and loss
+        // as an estimate of profit and loss, and calculate it against the sum of fees
+        let position_lock = trading_settings.position_lock_modifier;
+        let price_pnl = trade.calculate_price_pnl(est_price);
+        let open_fee = trade.open_order.get_executed_order_fee();
+        let close_fee = close_order.get_estimate_close_order_fee(est_fee_rate, est_price);
+        if position_lock.is_close_locked(price_pnl, open_fee, close_fee) {
+            let error = format!(
+                "Trade wasn't closed due to PositionLockModifier::{:?} -> price profit and loss = {}, open fee = {}, close fee = {}",
+                position_lock, price_pnl, open_fee, close_fee
+            );
+            print!("{}", &error);
+            return Err(GlowError::new(String::from("Close Position Error"), error));
         }
 
         let close_order_id = close_order.id.clone();
@@ -1287,6 +1333,89 @@ impl TraderExchange for BybitTraderExchange {
         }
     }
 
+    async fn update_trailing_stop_loss(
+        &self,
+        trade: &Trade,
+        last_price: f64,
+    ) -> Result<bool, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let Some(trailing_stop_loss) = trading_settings.get_trailing_stop_loss() else {
+            return Ok(false);
+        };
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let open_order = &trade.open_order;
+        let side = open_order.side;
+        let entry_price = open_order.get_executed_avg_price();
+        let trailing_stop_state = self.get_trailing_stop_state(&trade.id);
+
+        match trailing_stop_loss {
+            TrailingStopLoss::Percent(distance, activation_returns) => {
+                // Bybit trails it natively, so it only needs to be set once per trade
+                if trailing_stop_state.is_some() {
+                    return Ok(false);
+                }
+                let returns_mod = if side == Side::Sell { -1.0 } else { 1.0 };
+                let trailing_stop =
+                    self.round_price_to_tick(entry_price * distance / leverage_factor);
+                let active_price = self.round_price_to_tick(
+                    entry_price * (leverage_factor + returns_mod * activation_returns)
+                        / leverage_factor,
+                );
+                let was_set = self
+                    .set_native_trailing_stop(trailing_stop, active_price)
+                    .await?;
+                if was_set {
+                    self.set_trailing_stop_state(TrailingStopState {
+                        trade_id: trade.id.clone(),
+                        peak_price: entry_price,
+                        stop_price: None,
+                    });
+                }
+                Ok(was_set)
+            }
+            TrailingStopLoss::Stepped(_, _) => {
+                // stepped stops aren't supported natively, so the order stop loss is amended on every step
+                let (peak_price, current_stop_price) = match &trailing_stop_state {
+                    Some(state) => (state.peak_price, state.stop_price),
+                    None => (entry_price, open_order.stop_loss_price),
+                };
+                let peak_price = match side {
+                    Side::Buy => peak_price.max(last_price),
+                    Side::Sell => peak_price.min(last_price),
+                    Side::None => return Ok(false),
+                };
+                let stop_price = trailing_stop_loss
+                    .get_stop_price(side, entry_price, peak_price, leverage_factor)
+                    .map(|stop_price| self.round_price_to_tick(stop_price));
+                let should_amend = match (stop_price, current_stop_price) {
+                    (Some(stop_price), Some(current_stop_price)) => {
+                        (side == Side::Buy && stop_price > current_stop_price)
+                            || (side == Side::Sell && stop_price < current_stop_price)
+                    }
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
+                let mut updated_state = TrailingStopState {
+                    trade_id: trade.id.clone(),
+                    peak_price,
+                    stop_price: current_stop_price,
+                };
+                if !should_amend {
+                    self.set_trailing_stop_state(updated_state);
+                    return Ok(false);
+                }
+                let amended = self
+                    .amend_order(open_order.id.clone(), None, None, stop_price, None)
+                    .await?;
+                if amended {
+                    updated_state.stop_price = stop_price;
+                }
+                self.set_trailing_stop_state(updated_state);
+                Ok(amended)
+            }
+        }
+    }
+
     async fn cancel_order(&self, order_id: String) -> Result<bool, GlowError> {
         let traded_symbol = self.get_traded_symbol();
         let payload = CancelOrderDto::new(
@@ -1417,10 +1546,7 @@ impl TraderExchange for BybitTraderExchange {
 
     async fn update_position_data_on_faulty_exchange_ws(&self) -> Result<(), GlowError> {
         // updates balance. TODO: check if it's convenient to separate this in a new thread
-        let balance = self
-            .fetch_current_usdt_balance()
-            .await
-            .expect("set_current_balance_handle -> get_current_usdt_balance error");
+        let balance = self.fetch_current_usdt_balance().await?;
         // let update_action = UpdateAction::Balance(balance);
         self.balance_update_emitter.next(balance);
 
@@ -1719,9 +1845,10 @@ impl BenchmarkExchange for BybitTraderExchange {
     ) -> Result<Order, GlowError> {
         let trading_settings = self.get_trading_settings();
         let close_order_type = match final_status {
-            OrderStatus::StoppedBR | OrderStatus::StoppedSL | OrderStatus::StoppedTP => {
-                trading_settings.get_close_order_type()
-            }
+            OrderStatus::StoppedBR
+            | OrderStatus::StoppedSL
+            | OrderStatus::StoppedTSL
+            | OrderStatus::StoppedTP => trading_settings.get_close_order_type(),
             _ => OrderType::Market,
         };
         let id = format!("{}_{}", trade_id, OrderStage::Close.to_string());
@@ -1757,6 +1884,7 @@ impl BenchmarkExchange for BybitTraderExchange {
 
         let is_stop = final_status == OrderStatus::StoppedBR
             || final_status == OrderStatus::StoppedSL
+            || final_status == OrderStatus::StoppedTSL
             || final_status == OrderStatus::StoppedTP;
 
         let order = Order::new(
@@ -4,11 +4,12 @@ use crate::shared::{
 };
 
 use super::{enums::*, functions::*};
+use chrono::DateTime;
 use common::{
     enums::{
         order_status::OrderStatus, order_type::OrderType, side::Side, time_in_force::TimeInForce,
     },
-    structs::{Execution, Order},
+    structs::{Execution, Order, TickData},
 };
 use serde::{Deserialize, Serialize};
 
@@ -254,7 +255,7 @@ impl OrderData {
                     match self.stop_order_type {
                         StopOrderType::StopLoss => OrderStatus::StoppedSL,
                         StopOrderType::TakeProfit => OrderStatus::StoppedTP,
-                        // StopOrderType::TrailingStop => OrderStatus::StoppedTSL,
+                        StopOrderType::TrailingStop => OrderStatus::StoppedTSL,
                         StopOrderType::Stop => OrderStatus::StoppedBR,
                         _ => unreachable!(),
                     }
@@ -661,6 +662,41 @@ impl SetLeverageDto {
         }
     }
 }
+#[derive(Debug, Clone, Serialize)]
+pub struct SetTradingStopDto {
+    category: String,
+    symbol: String,
+    #[serde(rename = "tpslMode")]
+    tpsl_mode: String,
+    #[serde(rename = "positionIdx")]
+    position_idx: u8, // 0: one-way mode
+    #[serde(rename = "trailingStop", serialize_with = "f64_as_string")]
+    trailing_stop: f64, // Trailing stop by price distance
+    #[serde(rename = "activePrice", serialize_with = "f64_as_string")]
+    active_price: f64, // Trailing stop trigger price, it's triggered when market price reaches it
+}
+
+impl SetTradingStopDto {
+    pub fn new(category: String, symbol: String, trailing_stop: f64, active_price: f64) -> Self {
+        SetTradingStopDto {
+            category,
+            symbol,
+            tpsl_mode: "Full".to_string(),
+            position_idx: 0,
+            trailing_stop,
+            active_price,
+        }
+    }
+}
+
+/// Trailing stop loss last set for a trade, `stop_price` being None if it is trailed natively by Bybit
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrailingStopState {
+    pub trade_id: String,
+    pub peak_price: f64,
+    pub stop_price: Option<f64>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct OrderResponse {
     #[serde(rename = "orderId")]
@@ -668,3 +704,69 @@ pub struct OrderResponse {
     #[serde(rename = "orderLinkId")]
     pub order_link_id: String,
 }
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct KlineResult {
+    pub symbol: String,
+    pub category: String,
+    pub list: Vec<KlineRowData>,
+}
+
+/// Bybit serializes each kline as `[startTime, open, high, low, close, volume, turnover]`
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct KlineRowData(
+    #[serde(deserialize_with = "parse_i64")] pub i64,
+    #[serde(deserialize_with = "parse_f64")] pub f64,
+    #[serde(deserialize_with = "parse_f64")] pub f64,
+    #[serde(deserialize_with = "parse_f64")] pub f64,
+    #[serde(deserialize_with = "parse_f64")] pub f64,
+    pub String,
+    pub String,
+);
+
+impl KlineRowData {
+    pub fn into_tick_data(self, symbol: &'static str) -> TickData {
+        let KlineRowData(start_time_ms, open, high, low, close, ..) = self;
+        let start_time = DateTime::from_timestamp_millis(start_time_ms).unwrap();
+        TickData::new_from_string(symbol, start_time, open, high, close, low)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct KlineWsMessage {
+    pub topic: String,
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub ts: i64,
+    pub data: Vec<KlineWsData>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct KlineWsData {
+    pub start: i64,
+    pub end: i64,
+    pub interval: String,
+    #[serde(deserialize_with = "parse_f64")]
+    pub open: f64,
+    #[serde(deserialize_with = "parse_f64")]
+    pub close: f64,
+    #[serde(deserialize_with = "parse_f64")]
+    pub high: f64,
+    #[serde(deserialize_with = "parse_f64")]
+    pub low: f64,
+    pub confirm: bool,
+    pub timestamp: i64,
+}
+
+impl KlineWsData {
+    pub fn into_tick_data(self, symbol: &'static str) -> TickData {
+        let start_time = DateTime::from_timestamp_millis(self.start).unwrap();
+        TickData::new_from_string(
+            symbol, start_time, self.open, self.high, self.close, self.low,
+        )
+    }
+}
@@ -8,49 +8,56 @@ use std::{collections::HashMap, env::var, sync::LazyLock};
 
 pub static WS_RECONNECT_INTERVAL_IN_SECS: u64 = 2;
 
+fn get_api_key_env_var(exchange_title: &str, env: &str) -> String {
+    format!("{}_{}_{}", exchange_title, API_KEY_ENV_SUFFIX, env)
+}
+
+/// Reads `{EXCHANGE}_API_KEY_{ENV}`, `{EXCHANGE}_API_SECRET_{ENV}` and the PROD/DEV base urls
+fn load_exchange_config(exchange_title: &str, env: &str) -> ExchangeConfig {
+    let exchange_title = exchange_title.to_uppercase();
+    let api_key_env_var = get_api_key_env_var(&exchange_title, env);
+    let api_key = var(&api_key_env_var)
+        .unwrap_or_else(|_| panic!("{} env var to be provided", api_key_env_var));
+    let api_secret_env_var = format!("{}_{}_{}", exchange_title, API_SECRET_ENV_SUFFIX, env);
+    let api_secret = var(&api_secret_env_var)
+        .unwrap_or_else(|_| panic!("{} env var to be provided", api_secret_env_var));
+
+    let urls_env = if env == "PROD" { "PROD" } else { "DEV" };
+    let http_url_env_var = format!("{}_HTTP_BASE_URL_{}", exchange_title, urls_env);
+    let http_url = var(&http_url_env_var)
+        .unwrap_or_else(|_| panic!("{} env var to be provided", http_url_env_var));
+    let ws_url_env_var = format!("{}_WS_BASE_URL_{}", exchange_title, urls_env);
+    let ws_url = var(&ws_url_env_var)
+        .unwrap_or_else(|_| panic!("{} env var to be provided", ws_url_env_var));
+
+    ExchangeConfig {
+        credentials: ApiCredentials {
+            key: Box::leak(api_key.into_boxed_str()),
+            secret: Box::leak(api_secret.into_boxed_str()),
+        },
+        endpoints: ApiEndpoints {
+            ws: Box::leak(ws_url.into_boxed_str()),
+            http: Box::leak(http_url.into_boxed_str()),
+        },
+    }
+}
+
 pub static TRADER_EXCHANGES_CONFIG_MAP: LazyLock<HashMap<TraderExchangeId, ExchangeConfig>> =
     LazyLock::new(|| {
         // dotenv().ok();
         let mut configs = HashMap::new();
-        {
-            let env = var("ENV_NAME")
-                .expect("ENV_NAME env var to be provided")
-                .to_uppercase();
-            let http_url: String;
-            let ws_url: String;
-            let exchange_title = "Bybit".to_uppercase();
-            let api_key_env_var = format!("{}_{}_{}", exchange_title, API_KEY_ENV_SUFFIX, env);
-            let api_key = var(&api_key_env_var)
-                .unwrap_or_else(|_| panic!("{} env var to be provided", api_key_env_var));
-            let api_secret_env_var =
-                format!("{}_{}_{}", exchange_title, API_SECRET_ENV_SUFFIX, env);
-            let api_secret = var(&api_secret_env_var)
-                .unwrap_or_else(|_| panic!("{} env var to be provided", api_secret_env_var));
-
-            if env == *"PROD" {
-                http_url = var("BYBIT_HTTP_BASE_URL_PROD")
-                    .expect("BYBIT_HTTP_BASE_URL_PROD env var to be provided");
-                ws_url = var("BYBIT_WS_BASE_URL_PROD")
-                    .expect("BYBIT_WS_BASE_URL_PROD env var to be provided");
-            } else {
-                http_url = var("BYBIT_HTTP_BASE_URL_DEV")
-                    .expect("BYBIT_HTTP_BASE_URL_DEV env var to be provided");
-                ws_url = var("BYBIT_WS_BASE_URL_DEV")
-                    .expect("BYBIT_WS_BASE_URL_DEV env var to be provided");
-            }
-
-            let config = ExchangeConfig {
-                credentials: ApiCredentials {
-                    key: Box::leak(api_key.into_boxed_str()),
-                    secret: Box::leak(api_secret.into_boxed_str()),
-                },
-                endpoints: ApiEndpoints {
-                    ws: Box::leak(ws_url.into_boxed_str()),
-                    http: Box::leak(http_url.into_boxed_str()),
-                },
-            };
-
-            configs.insert(TraderExchangeId::Bybit, config);
+        let env = var("ENV_NAME")
+            .expect("ENV_NAME env var to be provided")
+            .to_uppercase();
+
+        configs.insert(TraderExchangeId::Bybit, load_exchange_config("Bybit", &env));
+
+        // Binance credentials are optional, so setups trading only at Bybit keep working
+        if var(get_api_key_env_var("BINANCE", &env)).is_ok() {
+            configs.insert(
+                TraderExchangeId::Binance,
+                load_exchange_config("Binance", &env),
+            );
         }
 
         configs
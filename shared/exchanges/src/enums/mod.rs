@@ -1,4 +1,9 @@
-use crate::{binance::structs::BinanceDataProvider, bybit::BybitTraderExchange};
+use crate::{
+    binance::{structs::BinanceDataProvider, trader::BinanceTraderExchange},
+    bybit::{data_provider::BybitDataProvider, BybitTraderExchange},
+    offline::OfflineDataProvider,
+    paper::PaperTraderExchange,
+};
 use chrono::{DateTime, Utc};
 use common::{
     enums::{
@@ -23,11 +28,15 @@ use url::Url;
 pub enum DataProviderExchangeId {
     #[default]
     Binance,
+    Bybit,
+    Offline,
 }
 
 #[derive(Clone)]
 pub enum DataProviderExchangeWrapper {
     Binance(BinanceDataProvider),
+    Bybit(BybitDataProvider),
+    Offline(OfflineDataProvider),
 }
 
 impl DataProviderExchangeWrapper {
@@ -35,27 +44,42 @@ impl DataProviderExchangeWrapper {
         exchange_id: DataProviderExchangeId,
         strategy: &Strategy,
         trading_settings: &TradingSettings,
-    ) -> Self {
-        match exchange_id {
+    ) -> Result<Self, GlowError> {
+        let data_provider = match exchange_id {
             DataProviderExchangeId::Binance => {
-                Self::Binance(BinanceDataProvider::new(trading_settings, strategy))
+                Self::Binance(BinanceDataProvider::new(trading_settings, strategy)?)
             }
-        }
+            DataProviderExchangeId::Bybit => {
+                Self::Bybit(BybitDataProvider::new(trading_settings, strategy)?)
+            }
+            DataProviderExchangeId::Offline => {
+                Self::Offline(OfflineDataProvider::new(trading_settings, strategy)?)
+            }
+        };
+        Ok(data_provider)
     }
 
     pub fn get_selection_list() -> Vec<String> {
-        vec![String::from("Binance")]
+        vec![
+            String::from("Binance"),
+            String::from("Bybit"),
+            String::from("Offline"),
+        ]
     }
 
     pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
         match self {
             Self::Binance(ex) => ex.patch_settings(trading_settings),
+            Self::Bybit(ex) => ex.patch_settings(trading_settings),
+            Self::Offline(ex) => ex.patch_settings(trading_settings),
         }
     }
 
-    pub fn patch_strategy(&mut self, strategy: &Strategy) {
+    pub fn patch_strategy(&mut self, strategy: &Strategy) -> Result<(), GlowError> {
         match self {
             Self::Binance(ex) => ex.patch_strategy(strategy),
+            Self::Bybit(ex) => ex.patch_strategy(strategy),
+            Self::Offline(ex) => ex.patch_strategy(strategy),
         }
     }
 }
@@ -64,6 +88,8 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
     fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
         match self {
             Self::Binance(ex) => ex.get_kline_data_emitter(),
+            Self::Bybit(ex) => ex.get_kline_data_emitter(),
+            Self::Offline(ex) => ex.get_kline_data_emitter(),
         }
     }
 
@@ -73,6 +99,8 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Binance(ex) => ex.subscribe_to_tick_stream(wss).await,
+            Self::Bybit(ex) => ex.subscribe_to_tick_stream(wss).await,
+            Self::Offline(ex) => ex.subscribe_to_tick_stream(wss).await,
         }
     }
 
@@ -93,6 +121,24 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
                 )
                 .await
             }
+            Self::Bybit(ex) => {
+                ex.init(
+                    benchmark_start,
+                    benchmark_end,
+                    run_benchmark_only,
+                    trading_data_schema,
+                )
+                .await
+            }
+            Self::Offline(ex) => {
+                ex.init(
+                    benchmark_start,
+                    benchmark_end,
+                    run_benchmark_only,
+                    trading_data_schema,
+                )
+                .await
+            }
         }
     }
 
@@ -103,6 +149,8 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Binance(ex) => ex.listen_ticks(wss, benchmark_end).await,
+            Self::Bybit(ex) => ex.listen_ticks(wss, benchmark_end).await,
+            Self::Offline(ex) => ex.listen_ticks(wss, benchmark_end).await,
         }
     }
 
@@ -116,12 +164,22 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
                 ex.handle_committed_ticks_data(benchmark_end, trading_data_schema)
                     .await
             }
+            Self::Bybit(ex) => {
+                ex.handle_committed_ticks_data(benchmark_end, trading_data_schema)
+                    .await
+            }
+            Self::Offline(ex) => {
+                ex.handle_committed_ticks_data(benchmark_end, trading_data_schema)
+                    .await
+            }
         }
     }
 
     fn handle_ws_error(&self, trading_data_schema: &Schema) -> Option<DateTime<Utc>> {
         match self {
             Self::Binance(ex) => ex.handle_ws_error(trading_data_schema),
+            Self::Bybit(ex) => ex.handle_ws_error(trading_data_schema),
+            Self::Offline(ex) => ex.handle_ws_error(trading_data_schema),
         }
     }
 }
@@ -130,27 +188,41 @@ impl DataProviderExchange for DataProviderExchangeWrapper {
 pub enum TraderExchangeId {
     #[default]
     Bybit,
+    Binance,
+    Paper,
 }
 
 #[derive(Clone)]
 pub enum TraderExchangeWrapper {
     Bybit(BybitTraderExchange),
+    Binance(BinanceTraderExchange),
+    Paper(PaperTraderExchange),
 }
 
 impl TraderExchangeWrapper {
     pub fn new(trader_exchange_id: TraderExchangeId, trading_settings: &TradingSettings) -> Self {
         match trader_exchange_id {
             TraderExchangeId::Bybit => Self::Bybit(BybitTraderExchange::new(trading_settings)),
+            TraderExchangeId::Binance => {
+                Self::Binance(BinanceTraderExchange::new(trading_settings))
+            }
+            TraderExchangeId::Paper => Self::Paper(PaperTraderExchange::new(trading_settings)),
         }
     }
 
     pub fn get_selection_list() -> Vec<String> {
-        vec![String::from("Bybit")]
+        vec![
+            String::from("Bybit"),
+            String::from("Binance"),
+            String::from("Paper"),
+        ]
     }
 
     pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.patch_settings(trading_settings),
+            TraderExchangeWrapper::Binance(ex) => ex.patch_settings(trading_settings),
+            TraderExchangeWrapper::Paper(ex) => ex.patch_settings(trading_settings),
         }
     }
 }
@@ -159,18 +231,24 @@ impl TraderHelper for TraderExchangeWrapper {
     fn get_trading_settings(&self) -> &TradingSettings {
         match self {
             Self::Bybit(ex) => ex.get_trading_settings(),
+            Self::Binance(ex) => ex.get_trading_settings(),
+            Self::Paper(ex) => ex.get_trading_settings(),
         }
     }
 
     fn get_taker_fee(&self) -> f64 {
         match self {
             Self::Bybit(ex) => ex.get_taker_fee(),
+            Self::Binance(ex) => ex.get_taker_fee(),
+            Self::Paper(ex) => ex.get_taker_fee(),
         }
     }
 
     fn get_maker_fee(&self) -> f64 {
         match self {
             Self::Bybit(ex) => ex.get_maker_fee(),
+            Self::Binance(ex) => ex.get_maker_fee(),
+            Self::Paper(ex) => ex.get_maker_fee(),
         }
     }
 
@@ -184,12 +262,20 @@ impl TraderHelper for TraderExchangeWrapper {
             Self::Bybit(ex) => {
                 ex.calculate_open_order_units_and_balance_remainder(side, order_cost, price)
             }
+            Self::Binance(ex) => {
+                ex.calculate_open_order_units_and_balance_remainder(side, order_cost, price)
+            }
+            Self::Paper(ex) => {
+                ex.calculate_open_order_units_and_balance_remainder(side, order_cost, price)
+            }
         }
     }
 
     fn get_order_fee_rate(&self, order_type: OrderType) -> (f64, bool) {
         match self {
             Self::Bybit(ex) => ex.get_order_fee_rate(order_type),
+            Self::Binance(ex) => ex.get_order_fee_rate(order_type),
+            Self::Paper(ex) => ex.get_order_fee_rate(order_type),
         }
     }
 
@@ -202,24 +288,32 @@ impl TraderHelper for TraderExchangeWrapper {
     ) -> ((f64, f64), f64, bool) {
         match self {
             Self::Bybit(ex) => ex.calculate_order_fees(order_type, side, units, price),
+            Self::Binance(ex) => ex.calculate_order_fees(order_type, side, units, price),
+            Self::Paper(ex) => ex.calculate_order_fees(order_type, side, units, price),
         }
     }
 
     fn calculate_order_stop_loss_price(&self, side: Side, price: f64) -> Option<f64> {
         match self {
             Self::Bybit(ex) => ex.calculate_order_stop_loss_price(side, price),
+            Self::Binance(ex) => ex.calculate_order_stop_loss_price(side, price),
+            Self::Paper(ex) => ex.calculate_order_stop_loss_price(side, price),
         }
     }
 
     fn calculate_order_take_profit_price(&self, side: Side, price: f64) -> Option<f64> {
         match self {
             Self::Bybit(ex) => ex.calculate_order_take_profit_price(side, price),
+            Self::Binance(ex) => ex.calculate_order_take_profit_price(side, price),
+            Self::Paper(ex) => ex.calculate_order_take_profit_price(side, price),
         }
     }
 
     fn get_contracts(&self) -> &HashMap<SymbolId, Contract> {
         match self {
             Self::Bybit(ex) => ex.get_contracts(),
+            Self::Binance(ex) => ex.get_contracts(),
+            Self::Paper(ex) => ex.get_contracts(),
         }
     }
 }
@@ -228,12 +322,16 @@ impl TraderExchange for TraderExchangeWrapper {
     fn new_open_order(&self, side: Side, order_cost: f64, price: f64) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.new_open_order(side, order_cost, price),
+            Self::Binance(ex) => ex.new_open_order(side, order_cost, price),
+            Self::Paper(ex) => ex.new_open_order(side, order_cost, price),
         }
     }
 
     fn get_ws_url(&self) -> Result<Url, GlowError> {
         match self {
             Self::Bybit(ex) => ex.get_ws_url(),
+            Self::Binance(ex) => ex.get_ws_url(),
+            Self::Paper(ex) => ex.get_ws_url(),
         }
     }
 
@@ -243,6 +341,8 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.auth_ws(wss).await,
+            Self::Binance(ex) => ex.auth_ws(wss).await,
+            Self::Paper(ex) => ex.auth_ws(wss).await,
         }
     }
 
@@ -252,6 +352,8 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.subscribe_ws(wss).await,
+            Self::Binance(ex) => ex.subscribe_ws(wss).await,
+            Self::Paper(ex) => ex.subscribe_ws(wss).await,
         }
     }
 
@@ -266,6 +368,14 @@ impl TraderExchange for TraderExchangeWrapper {
                 ex.fetch_order_executions(order_uuid, start_timestamp, end_timestamp)
                     .await
             }
+            Self::Binance(ex) => {
+                ex.fetch_order_executions(order_uuid, start_timestamp, end_timestamp)
+                    .await
+            }
+            Self::Paper(ex) => {
+                ex.fetch_order_executions(order_uuid, start_timestamp, end_timestamp)
+                    .await
+            }
         }
     }
 
@@ -277,6 +387,8 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_history_order(id, side, fetch_executions).await,
+            Self::Binance(ex) => ex.fetch_history_order(id, side, fetch_executions).await,
+            Self::Paper(ex) => ex.fetch_history_order(id, side, fetch_executions).await,
         }
     }
 
@@ -287,12 +399,16 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_current_order(order_id, fetch_executions).await,
+            Self::Binance(ex) => ex.fetch_current_order(order_id, fetch_executions).await,
+            Self::Paper(ex) => ex.fetch_current_order(order_id, fetch_executions).await,
         }
     }
 
     async fn fetch_current_trade_position(&self) -> Result<Option<Trade>, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_current_trade_position().await,
+            Self::Binance(ex) => ex.fetch_current_trade_position().await,
+            Self::Paper(ex) => ex.fetch_current_trade_position().await,
         }
     }
 
@@ -303,12 +419,16 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Trade, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_trade_state(trade_id, last_status).await,
+            Self::Binance(ex) => ex.fetch_trade_state(trade_id, last_status).await,
+            Self::Paper(ex) => ex.fetch_trade_state(trade_id, last_status).await,
         }
     }
 
     async fn fetch_current_usdt_balance(&self) -> Result<Balance, GlowError> {
         match self {
             Self::Bybit(ex) => ex.fetch_current_usdt_balance().await,
+            Self::Binance(ex) => ex.fetch_current_usdt_balance().await,
+            Self::Paper(ex) => ex.fetch_current_usdt_balance().await,
         }
     }
 
@@ -320,6 +440,8 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.open_order(side, amount, expected_price).await,
+            Self::Binance(ex) => ex.open_order(side, amount, expected_price).await,
+            Self::Paper(ex) => ex.open_order(side, amount, expected_price).await,
         }
     }
 
@@ -342,60 +464,110 @@ impl TraderExchange for TraderExchangeWrapper {
                 )
                 .await
             }
+            Self::Binance(ex) => {
+                ex.amend_order(
+                    order_id,
+                    updated_units,
+                    updated_price,
+                    updated_stop_loss_price,
+                    updated_take_profit_price,
+                )
+                .await
+            }
+            Self::Paper(ex) => {
+                ex.amend_order(
+                    order_id,
+                    updated_units,
+                    updated_price,
+                    updated_stop_loss_price,
+                    updated_take_profit_price,
+                )
+                .await
+            }
         }
     }
 
     async fn try_close_position(&self, trade: &Trade, est_price: f64) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.try_close_position(trade, est_price).await,
+            Self::Binance(ex) => ex.try_close_position(trade, est_price).await,
+            Self::Paper(ex) => ex.try_close_position(trade, est_price).await,
+        }
+    }
+
+    async fn update_trailing_stop_loss(
+        &self,
+        trade: &Trade,
+        last_price: f64,
+    ) -> Result<bool, GlowError> {
+        match self {
+            Self::Bybit(ex) => ex.update_trailing_stop_loss(trade, last_price).await,
+            Self::Binance(ex) => ex.update_trailing_stop_loss(trade, last_price).await,
+            Self::Paper(ex) => ex.update_trailing_stop_loss(trade, last_price).await,
         }
     }
 
     async fn cancel_order(&self, order_id: String) -> Result<bool, GlowError> {
         match self {
             Self::Bybit(ex) => ex.cancel_order(order_id).await,
+            Self::Binance(ex) => ex.cancel_order(order_id).await,
+            Self::Paper(ex) => ex.cancel_order(order_id).await,
         }
     }
 
     async fn set_leverage(&self, leverage: Leverage) -> Result<bool, GlowError> {
         match self {
             Self::Bybit(ex) => ex.set_leverage(leverage).await,
+            Self::Binance(ex) => ex.set_leverage(leverage).await,
+            Self::Paper(ex) => ex.set_leverage(leverage).await,
         }
     }
 
     fn get_http_client(&self) -> &Client {
         match self {
             Self::Bybit(ex) => ex.get_http_client(),
+            Self::Binance(ex) => ex.get_http_client(),
+            Self::Paper(ex) => ex.get_http_client(),
         }
     }
 
     fn get_ws_ping_interval(&self) -> u64 {
         match self {
             Self::Bybit(ex) => ex.get_ws_ping_interval(),
+            Self::Binance(ex) => ex.get_ws_ping_interval(),
+            Self::Paper(ex) => ex.get_ws_ping_interval(),
         }
     }
 
     fn get_ws_ping_message(&self) -> Result<Message, GlowError> {
         match self {
             Self::Bybit(ex) => ex.get_ws_ping_message(),
+            Self::Binance(ex) => ex.get_ws_ping_message(),
+            Self::Paper(ex) => ex.get_ws_ping_message(),
         }
     }
 
     fn process_ws_message(&self, json: &String) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.process_ws_message(json),
+            Self::Binance(ex) => ex.process_ws_message(json),
+            Self::Paper(ex) => ex.process_ws_message(json),
         }
     }
 
     async fn update_position_data_on_faulty_exchange_ws(&self) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.update_position_data_on_faulty_exchange_ws().await,
+            Self::Binance(ex) => ex.update_position_data_on_faulty_exchange_ws().await,
+            Self::Paper(ex) => ex.update_position_data_on_faulty_exchange_ws().await,
         }
     }
 
     async fn init(&mut self) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.init().await,
+            Self::Binance(ex) => ex.init().await,
+            Self::Paper(ex) => ex.init().await,
         }
     }
 
@@ -405,30 +577,40 @@ impl TraderExchange for TraderExchangeWrapper {
     ) -> Result<(), GlowError> {
         match self {
             Self::Bybit(ex) => ex.listen_messages(wss).await,
+            Self::Binance(ex) => ex.listen_messages(wss).await,
+            Self::Paper(ex) => ex.listen_messages(wss).await,
         }
     }
 
     fn get_balance_update_emitter(&self) -> &BehaviorSubject<Balance> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_balance_update_emitter(),
+            TraderExchangeWrapper::Binance(ex) => ex.get_balance_update_emitter(),
+            TraderExchangeWrapper::Paper(ex) => ex.get_balance_update_emitter(),
         }
     }
 
     fn get_executions_update_emitter(&self) -> &BehaviorSubject<Vec<Execution>> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_executions_update_emitter(),
+            TraderExchangeWrapper::Binance(ex) => ex.get_executions_update_emitter(),
+            TraderExchangeWrapper::Paper(ex) => ex.get_executions_update_emitter(),
         }
     }
 
     fn get_order_update_emitter(&self) -> &BehaviorSubject<OrderAction> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_order_update_emitter(),
+            TraderExchangeWrapper::Binance(ex) => ex.get_order_update_emitter(),
+            TraderExchangeWrapper::Paper(ex) => ex.get_order_update_emitter(),
         }
     }
 
     fn get_trade_update_emitter(&self) -> &BehaviorSubject<Option<Trade>> {
         match self {
             TraderExchangeWrapper::Bybit(ex) => ex.get_trade_update_emitter(),
+            TraderExchangeWrapper::Binance(ex) => ex.get_trade_update_emitter(),
+            TraderExchangeWrapper::Paper(ex) => ex.get_trade_update_emitter(),
         }
     }
 }
@@ -443,6 +625,8 @@ impl BenchmarkExchange for TraderExchangeWrapper {
     ) -> Result<Order, GlowError> {
         match self {
             Self::Bybit(ex) => ex.new_benchmark_open_order(timestamp, side, order_cost, price),
+            Self::Binance(ex) => ex.new_benchmark_open_order(timestamp, side, order_cost, price),
+            Self::Paper(ex) => ex.new_benchmark_open_order(timestamp, side, order_cost, price),
         }
     }
 
@@ -462,6 +646,20 @@ impl BenchmarkExchange for TraderExchangeWrapper {
                 open_order,
                 final_status,
             ),
+            Self::Binance(ex) => ex.new_benchmark_close_order(
+                timestamp,
+                trade_id,
+                close_price,
+                open_order,
+                final_status,
+            ),
+            Self::Paper(ex) => ex.new_benchmark_close_order(
+                timestamp,
+                trade_id,
+                close_price,
+                open_order,
+                final_status,
+            ),
         }
     }
 
@@ -475,12 +673,20 @@ impl BenchmarkExchange for TraderExchangeWrapper {
             Self::Bybit(ex) => {
                 ex.close_benchmark_trade_on_binding_price(trade, current_timestamp, binding_price)
             }
+            Self::Binance(ex) => {
+                ex.close_benchmark_trade_on_binding_price(trade, current_timestamp, binding_price)
+            }
+            Self::Paper(ex) => {
+                ex.close_benchmark_trade_on_binding_price(trade, current_timestamp, binding_price)
+            }
         }
     }
 
     fn get_minimum_notional_value(&self) -> Option<f64> {
         match self {
             Self::Bybit(ex) => ex.get_minimum_notional_value(),
+            Self::Binance(ex) => ex.get_minimum_notional_value(),
+            Self::Paper(ex) => ex.get_minimum_notional_value(),
         }
     }
 
@@ -2,6 +2,8 @@ pub mod binance;
 pub mod bybit;
 pub mod config;
 pub mod enums;
+pub mod offline;
+pub mod paper;
 pub mod shared;
 pub mod structs;
 pub mod r#static;
\ No newline at end of file
@@ -0,0 +1,417 @@
+pub mod structs;
+
+use self::structs::{KlineDataGap, OfflineDataSettings};
+use crate::shared::functions::adjust_benchmark_datetimes;
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, Utc};
+use common::{
+    enums::trading_data_update::TradingDataUpdate,
+    functions::{
+        coerce_df_to_schema,
+        csv::{get_tick_data_csv_path_from_dir, load_csv},
+        current_datetime, downsample_tick_lf_to_kline_duration,
+        funding::{append_funding_rates_to_df, get_funding_rate_col, load_funding_rates_from_dir},
+        parquet::{get_kline_parquet_path_from_dir, scan_kline_parquet_from_dir},
+    },
+    structs::{BehaviorSubject, Symbol, SymbolsPair, TradingSettings},
+    traits::exchange::DataProviderExchange,
+};
+use glow_error::GlowError;
+use polars::{
+    frame::DataFrame,
+    prelude::{
+        col, lit, IntoLazy, LazyFrame, ScanArgsParquet, Schema, SortMultipleOptions,
+        UniqueKeepStrategy,
+    },
+    time::ClosedWindow,
+};
+use std::{collections::HashSet, path::PathBuf};
+use strategy::Strategy;
+use tokio::{net::TcpStream, time::sleep};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+static MINUTE_IN_MS: i64 = 60_000;
+
+/// Reads klines exclusively from local files, so benchmarks are reproducible without network access.
+/// Missing klines are reported as a [`KlineDataGap`] error instead of being fetched.
+#[derive(Clone)]
+pub struct OfflineDataProvider {
+    kline_duration: Duration,
+    klines_data_update_emitter: BehaviorSubject<TradingDataUpdate>,
+    minimum_klines_for_benchmarking: u32,
+    settings: OfflineDataSettings,
+    symbols: SymbolsPair,
+}
+
+impl OfflineDataProvider {
+    pub fn new(trading_settings: &TradingSettings, strategy: &Strategy) -> Result<Self, GlowError> {
+        Self::with_settings(trading_settings, strategy, OfflineDataSettings::from_env())
+    }
+
+    pub fn with_settings(
+        trading_settings: &TradingSettings,
+        strategy: &Strategy,
+        settings: OfflineDataSettings,
+    ) -> Result<Self, GlowError> {
+        Ok(Self {
+            kline_duration: trading_settings.granularity.get_chrono_duration(),
+            klines_data_update_emitter: BehaviorSubject::new(TradingDataUpdate::default()),
+            minimum_klines_for_benchmarking: strategy.get_minimum_klines_for_calculation()?,
+            settings,
+            symbols: trading_settings.symbols_pair,
+        })
+    }
+
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+        self.symbols = trading_settings.symbols_pair;
+        self.kline_duration = trading_settings.granularity.get_chrono_duration();
+    }
+
+    pub fn patch_strategy(&mut self, strategy: &Strategy) -> Result<(), GlowError> {
+        self.minimum_klines_for_benchmarking = strategy.get_minimum_klines_for_calculation()?;
+        Ok(())
+    }
+
+    fn get_single_file_path(&self, symbol: &Symbol) -> PathBuf {
+        self.settings.directory.join(format!("{}.csv", symbol.name))
+    }
+
+    fn get_daily_file_path(&self, symbol: &Symbol, date: NaiveDate) -> PathBuf {
+        get_tick_data_csv_path_from_dir(&self.settings.directory, date, symbol.name)
+    }
+
+    /// Loads symbol 1 minute klines within `[start_datetime, end_datetime)`. Absent files are skipped,
+    /// as they show up as gaps afterwards.
+    fn load_symbol_tick_df(
+        &self,
+        symbol: &Symbol,
+        start_datetime: DateTime<Utc>,
+        end_datetime: DateTime<Utc>,
+    ) -> Result<DataFrame, GlowError> {
+        let symbol_tick_data_schema = symbol.derive_symbol_tick_data_schema();
+        let single_file_path = self.get_single_file_path(symbol);
+
+        let mut symbol_df = if single_file_path.is_file() {
+            load_csv(single_file_path, &symbol_tick_data_schema)?
+        } else {
+            let mut symbol_df = match scan_kline_parquet_from_dir(
+                &self.settings.directory,
+                symbol.name,
+                start_datetime,
+                end_datetime,
+            )? {
+                Some(symbol_lf) => symbol_lf.collect()?,
+                None => DataFrame::empty_with_schema(&symbol_tick_data_schema),
+            };
+            // days not migrated to parquet yet
+            let mut date = start_datetime.date_naive();
+            while date <= end_datetime.date_naive() {
+                let path = self.get_daily_file_path(symbol, date);
+                if path.is_file() {
+                    symbol_df.vstack_mut(&load_csv(path, &symbol_tick_data_schema)?)?;
+                }
+                date = date.succ_opt().unwrap();
+            }
+            symbol_df.unique_stable(
+                Some(&["start_time".to_string()]),
+                UniqueKeepStrategy::First,
+                None,
+            )?
+        };
+        symbol_df.align_chunks();
+
+        let symbol_df = symbol_df
+            .lazy()
+            .filter(
+                col("start_time")
+                    .gt_eq(lit(start_datetime.timestamp_millis()))
+                    .and(col("start_time").lt(lit(end_datetime.timestamp_millis()))),
+            )
+            .collect()?;
+        Ok(symbol_df)
+    }
+
+    /// Loads every symbol klines within `[start_datetime, end_datetime)`, downsampled to kline duration.
+    /// Fails with the missing data gaps if any minute is absent.
+    /// Funding rates stored at `{symbol}/funding_rates.parquet` are set if their column is part of the schema.
+    fn load_kline_data(
+        &self,
+        trading_data_schema: &Schema,
+        start_datetime: DateTime<Utc>,
+        end_datetime: DateTime<Utc>,
+    ) -> Result<DataFrame, GlowError> {
+        let unique_symbols = self.symbols.get_unique_symbols();
+        let mut kline_df = DataFrame::empty_with_schema(trading_data_schema);
+        let mut gaps = vec![];
+
+        for symbol in &unique_symbols {
+            let symbol_df = self.load_symbol_tick_df(symbol, start_datetime, end_datetime)?;
+            gaps.extend(find_kline_gaps(
+                symbol.name,
+                &symbol_df,
+                start_datetime,
+                end_datetime,
+            )?);
+            let symbol_df = coerce_df_to_schema(symbol_df, trading_data_schema)?;
+            kline_df.vstack_mut(&symbol_df)?;
+        }
+
+        if !gaps.is_empty() {
+            return Err(KlineDataGap::into_error(&gaps));
+        }
+
+        let sort_options = SortMultipleOptions::default()
+            .with_order_descending(false)
+            .with_maintain_order(false);
+        let mut kline_df = kline_df.sort(["start_time"], sort_options)?;
+        kline_df.align_chunks();
+        let kline_lf = downsample_tick_lf_to_kline_duration(
+            &unique_symbols,
+            self.kline_duration,
+            kline_df.lazy(),
+            ClosedWindow::Left,
+            Some(trading_data_schema),
+        )?;
+
+        let mut kline_df = kline_lf.collect()?;
+        for symbol in &unique_symbols {
+            if !trading_data_schema.contains(&get_funding_rate_col(symbol.name)) {
+                continue;
+            }
+            let funding_rates = load_funding_rates_from_dir(
+                &self.settings.directory,
+                symbol.name,
+                start_datetime,
+                end_datetime,
+            )?;
+            kline_df = append_funding_rates_to_df(
+                kline_df,
+                symbol.name,
+                &funding_rates,
+                self.kline_duration,
+            )?;
+        }
+
+        Ok(kline_df)
+    }
+
+    /// Returns the end (exclusive) of the last kline every symbol has on disk, looking from `from` onwards
+    fn get_available_data_end(
+        &self,
+        from: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, GlowError> {
+        let mut available_data_end: Option<DateTime<Utc>> = None;
+        for symbol in &self.symbols.get_unique_symbols() {
+            let symbol_tick_data_schema = symbol.derive_symbol_tick_data_schema();
+            let single_file_path = self.get_single_file_path(symbol);
+            let last_timestamp_ms = if single_file_path.is_file() {
+                get_last_start_time(&load_csv(single_file_path, &symbol_tick_data_schema)?)?
+            } else {
+                let mut date = from.date_naive();
+                let mut last_daily_file_path = None;
+                while self.get_daily_file_path(symbol, date).is_file() {
+                    last_daily_file_path = Some(self.get_daily_file_path(symbol, date));
+                    date = date.succ_opt().unwrap();
+                }
+                let mut month = from.date_naive().with_day(1).unwrap();
+                let mut last_partition_path = None;
+                loop {
+                    let path = get_kline_parquet_path_from_dir(
+                        &self.settings.directory,
+                        symbol.name,
+                        month,
+                    );
+                    if !path.is_file() {
+                        break;
+                    }
+                    last_partition_path = Some(path);
+                    month = month + Months::new(1);
+                }
+
+                let last_daily_timestamp_ms = match last_daily_file_path {
+                    Some(path) => get_last_start_time(&load_csv(path, &symbol_tick_data_schema)?)?,
+                    None => None,
+                };
+                let last_partition_timestamp_ms = match last_partition_path {
+                    Some(path) => {
+                        let partition_df =
+                            LazyFrame::scan_parquet(path, ScanArgsParquet::default())?.collect()?;
+                        get_last_start_time(&partition_df)?
+                    }
+                    None => None,
+                };
+                last_daily_timestamp_ms.max(last_partition_timestamp_ms)
+            };
+
+            let Some(last_timestamp_ms) = last_timestamp_ms else {
+                return Ok(None);
+            };
+            let symbol_data_end =
+                DateTime::from_timestamp_millis(last_timestamp_ms + MINUTE_IN_MS).unwrap();
+            available_data_end = Some(match available_data_end {
+                Some(data_end) => data_end.min(symbol_data_end),
+                None => symbol_data_end,
+            });
+        }
+        Ok(available_data_end)
+    }
+
+    /// Emits klines after `benchmark_end` one by one as market updates, as a live data provider would.
+    /// Klines are emitted at `replay_interval`, which must leave enough time for the data feed to handle each update.
+    async fn replay_klines(
+        &self,
+        benchmark_end: DateTime<Utc>,
+        trading_data_schema: &Schema,
+    ) -> Result<(), GlowError> {
+        let replay_end = match self.settings.replay_end {
+            Some(replay_end) => Some(replay_end),
+            None => self.get_available_data_end(benchmark_end)?,
+        };
+        let Some(replay_end) = replay_end.filter(|replay_end| *replay_end > benchmark_end) else {
+            println!(
+                "{} | 💤 No offline klines to replay after {}",
+                current_datetime(),
+                benchmark_end
+            );
+            return Ok(());
+        };
+
+        println!(
+            "{} | 📼 Replaying offline klines between {} and {}",
+            current_datetime(),
+            benchmark_end,
+            replay_end
+        );
+
+        let replay_df = self.load_kline_data(trading_data_schema, benchmark_end, replay_end)?;
+        // only complete klines are replayed
+        let last_kline_start = replay_end - self.kline_duration;
+        let replay_df = replay_df
+            .lazy()
+            .filter(col("start_time").lt_eq(lit(last_kline_start.timestamp_millis())))
+            .collect()?;
+
+        for index in 0..replay_df.height() {
+            sleep(self.settings.replay_interval).await;
+            let market_data = TradingDataUpdate::Market(replay_df.slice(index as i64, 1));
+            self.klines_data_update_emitter.next(market_data);
+        }
+
+        Ok(())
+    }
+}
+
+impl DataProviderExchange for OfflineDataProvider {
+    #[inline]
+    fn get_kline_data_emitter(&self) -> &BehaviorSubject<TradingDataUpdate> {
+        &self.klines_data_update_emitter
+    }
+
+    /// Nothing to commit, replayed klines are emitted as they are read
+    async fn handle_committed_ticks_data(
+        &self,
+        _discard_ticks_before: DateTime<Utc>,
+        _trading_data_schema: &Schema,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    fn handle_ws_error(&self, _trading_data_schema: &Schema) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    async fn init(
+        &mut self,
+        benchmark_start: Option<DateTime<Utc>>,
+        benchmark_end: Option<DateTime<Utc>>,
+        run_benchmark_only: bool,
+        trading_data_schema: Schema,
+    ) -> Result<(), GlowError> {
+        let (benchmark_start, benchmark_end) = adjust_benchmark_datetimes(
+            benchmark_start,
+            benchmark_end,
+            self.kline_duration,
+            Some(1),
+            self.minimum_klines_for_benchmarking as i32,
+        )?;
+
+        let initial_kline_data_df =
+            self.load_kline_data(&trading_data_schema, benchmark_start, benchmark_end)?;
+        let initial_data = TradingDataUpdate::Initial(initial_kline_data_df);
+        self.klines_data_update_emitter.next(initial_data);
+
+        if run_benchmark_only {
+            return Ok(());
+        }
+
+        self.replay_klines(benchmark_end, &trading_data_schema)
+            .await
+    }
+
+    /// Offline data isn't streamed, klines are replayed from disk by `init`
+    async fn listen_ticks(
+        &mut self,
+        _wss: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        _discard_ticks_before: DateTime<Utc>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    async fn subscribe_to_tick_stream(
+        &mut self,
+        _wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+}
+
+fn get_last_start_time(df: &DataFrame) -> Result<Option<i64>, GlowError> {
+    Ok(df
+        .column("start_time")?
+        .datetime()?
+        .into_no_null_iter()
+        .max())
+}
+
+/// Lists runs of 1 minute klines missing from `symbol_df` within `[start_datetime, end_datetime)`
+pub fn find_kline_gaps(
+    symbol_name: &str,
+    symbol_df: &DataFrame,
+    start_datetime: DateTime<Utc>,
+    end_datetime: DateTime<Utc>,
+) -> Result<Vec<KlineDataGap>, GlowError> {
+    let timestamps: HashSet<i64> = symbol_df
+        .column("start_time")?
+        .datetime()?
+        .into_no_null_iter()
+        .collect();
+
+    let new_gap = |start_ms: i64, end_ms: i64| KlineDataGap {
+        symbol: symbol_name.to_string(),
+        start: DateTime::from_timestamp_millis(start_ms).unwrap(),
+        end: DateTime::from_timestamp_millis(end_ms).unwrap(),
+    };
+
+    let start_ms = start_datetime.timestamp_millis();
+    let end_ms = end_datetime.timestamp_millis();
+    // first whole minute within the interval
+    let mut timestamp_ms =
+        start_ms + (MINUTE_IN_MS - start_ms.rem_euclid(MINUTE_IN_MS)) % MINUTE_IN_MS;
+    let mut gaps = vec![];
+    let mut gap_start_ms = None;
+    while timestamp_ms < end_ms {
+        match (timestamps.contains(&timestamp_ms), gap_start_ms) {
+            (false, None) => gap_start_ms = Some(timestamp_ms),
+            (true, Some(start_ms)) => {
+                gaps.push(new_gap(start_ms, timestamp_ms));
+                gap_start_ms = None;
+            }
+            _ => {}
+        }
+        timestamp_ms += MINUTE_IN_MS;
+    }
+    if let Some(start_ms) = gap_start_ms {
+        gaps.push(new_gap(start_ms, timestamp_ms));
+    }
+
+    Ok(gaps)
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use glow_error::GlowError;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string};
+use std::{env::var as env_var, path::PathBuf, time::Duration as StdDuration};
+
+pub static MISSING_KLINE_DATA_ERROR_TITLE: &str = "Missing Kline Data";
+
+/// Run of consecutive 1 minute klines absent from the local files, `end` being exclusive
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KlineDataGap {
+    pub symbol: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl KlineDataGap {
+    /// Wraps the gaps into a `GlowError`, serializing them as the error description
+    pub fn into_error(gaps: &[KlineDataGap]) -> GlowError {
+        GlowError::new(
+            String::from(MISSING_KLINE_DATA_ERROR_TITLE),
+            to_string(gaps).unwrap_or_default(),
+        )
+    }
+
+    /// Recovers the gaps reported by the offline data provider, if the error is a missing data one
+    pub fn from_error(error: &GlowError) -> Option<Vec<KlineDataGap>> {
+        if error.title != MISSING_KLINE_DATA_ERROR_TITLE {
+            return None;
+        }
+        from_str(&error.description).ok()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OfflineDataSettings {
+    /// Holds the kline store layout (`{symbol}/{year}/{month}.parquet` partitions and legacy
+    /// `{symbol}/{year}/{month}/{day}.csv` files) or a single `{symbol}.csv` per symbol
+    pub directory: PathBuf,
+    /// Interval between replayed klines when simulating live mode
+    pub replay_interval: StdDuration,
+    /// Last datetime to replay. Defaults to the last kline available for every symbol
+    pub replay_end: Option<DateTime<Utc>>,
+}
+
+impl OfflineDataSettings {
+    /// Reads `OFFLINE_DATA_DIR`, `OFFLINE_REPLAY_INTERVAL_MS` and `OFFLINE_REPLAY_END` (RFC 3339)
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let directory = env_var("OFFLINE_DATA_DIR")
+            .map(PathBuf::from)
+            .unwrap_or(default.directory);
+        let replay_interval = env_var("OFFLINE_REPLAY_INTERVAL_MS")
+            .ok()
+            .and_then(|interval| interval.parse::<u64>().ok())
+            .map(StdDuration::from_millis)
+            .unwrap_or(default.replay_interval);
+        let replay_end = env_var("OFFLINE_REPLAY_END")
+            .ok()
+            .and_then(|end| DateTime::parse_from_rfc3339(&end).ok())
+            .map(|end| end.with_timezone(&Utc));
+
+        Self {
+            directory,
+            replay_interval,
+            replay_end,
+        }
+    }
+}
+
+impl Default for OfflineDataSettings {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("data/ticks/binance"),
+            replay_interval: StdDuration::from_secs(1),
+            replay_end: None,
+        }
+    }
+}
@@ -0,0 +1,1145 @@
+pub mod structs;
+
+use self::structs::{PaperKline, PaperState};
+use crate::{enums::TraderExchangeId, r#static::TRADER_EXCHANGES_CONTEXT_MAP};
+use common::{
+    enums::{
+        balance::Balance, modifiers::leverage::Leverage, order_action::OrderAction,
+        order_stage::OrderStage, order_status::OrderStatus, order_type::OrderType, side::Side,
+        symbol_id::SymbolId, time_in_force::TimeInForce, trade_status::TradeStatus,
+    },
+    functions::{calculate_remainder, count_decimal_places, round_down_nth_decimal},
+    structs::{BehaviorSubject, Clock, Contract, Execution, Order, Trade, TradingSettings},
+    traits::exchange::{BenchmarkExchange, TraderExchange, TraderHelper},
+};
+use glow_error::GlowError;
+use polars::prelude::DataFrame;
+use reqwest::Client;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+/// Wallet balance paper trading sessions start from, in USDT
+pub static PAPER_INITIAL_BALANCE: f64 = 100.0;
+
+/// Exchange whose contracts and fees are simulated by paper trading
+static SIMULATED_TRADER_EXCHANGE_ID: TraderExchangeId = TraderExchangeId::Bybit;
+
+/// Paper trader, simulating Bybit contracts and fees over a virtual wallet.
+///
+/// Market orders are filled at their expected price as soon as they're placed, while limit orders,
+/// stop losses and take profits are filled once the klines passed to [`Self::process_kline`] reach their prices.
+/// Updates are emitted through the same emitters live exchanges use, so the trader can't tell them apart.
+#[derive(Clone)]
+pub struct PaperTraderExchange {
+    balance_update_emitter: BehaviorSubject<Balance>,
+    clock: Clock,
+    pub contracts: &'static HashMap<SymbolId, Contract>,
+    executions_update_emitter: BehaviorSubject<Vec<Execution>>,
+    pub fee_rates: (f64, f64),
+    http: Client,
+    minimum_notional_value: Option<f64>,
+    pub name: &'static str,
+    order_update_emitter: BehaviorSubject<OrderAction>,
+    state: Arc<Mutex<PaperState>>,
+    trade_update_emitter: BehaviorSubject<Option<Trade>>,
+    pub trading_settings: TradingSettings,
+}
+
+impl PaperTraderExchange {
+    pub fn new(trading_settings: &TradingSettings) -> Self {
+        Self::with_balance(trading_settings, PAPER_INITIAL_BALANCE, &Clock::default())
+    }
+
+    /// Paper trader starting from `initial_balance`, whose orders and executions are timestamped by `clock`
+    pub fn with_balance(
+        trading_settings: &TradingSettings,
+        initial_balance: f64,
+        clock: &Clock,
+    ) -> Self {
+        let context = TRADER_EXCHANGES_CONTEXT_MAP
+            .get(&SIMULATED_TRADER_EXCHANGE_ID)
+            .expect("Simulated exchange to has Exchange Context");
+        let state = PaperState::new(initial_balance);
+
+        Self {
+            balance_update_emitter: BehaviorSubject::new(
+                state.get_balance(clock.current_timestamp_ms()),
+            ),
+            clock: clock.clone(),
+            contracts: &context.contracts,
+            executions_update_emitter: BehaviorSubject::new(vec![]),
+            fee_rates: (context.maker_fee, context.taker_fee),
+            http: Client::new(),
+            minimum_notional_value: Some(5.0),
+            name: "Paper",
+            order_update_emitter: BehaviorSubject::new(OrderAction::default()),
+            state: Arc::new(Mutex::new(state)),
+            trade_update_emitter: BehaviorSubject::new(None),
+            trading_settings: trading_settings.clone(),
+        }
+    }
+
+    pub fn patch_settings(&mut self, trading_settings: &TradingSettings) {
+        self.trading_settings = trading_settings.clone();
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, PaperState> {
+        self.state.lock().expect("paper state deadlock")
+    }
+
+    fn round_price_to_tick(&self, price: f64) -> f64 {
+        let contract = self.get_traded_contract();
+        let tick_decimals = count_decimal_places(contract.tick_size);
+        let price_remainder = calculate_remainder(price, contract.tick_size);
+        round_down_nth_decimal(price - price_remainder, tick_decimals)
+    }
+
+    fn new_execution(
+        &self,
+        state: &mut PaperState,
+        order: &Order,
+        price: f64,
+        timestamp: i64,
+    ) -> Execution {
+        state.executions_count += 1;
+        let (fee_rate, is_maker) = self.get_order_fee_rate(order.order_type);
+        let closed_qty = if order.is_close { order.units } else { 0.0 };
+        Execution::new(
+            format!("paper_execution_{}", state.executions_count),
+            order.uuid.clone(),
+            order.order_type,
+            timestamp,
+            price,
+            order.units,
+            order.units * price * fee_rate,
+            fee_rate,
+            is_maker,
+            closed_qty,
+        )
+    }
+
+    /// Close order of the trade, timestamped by the paper clock instead of the system one
+    fn new_close_order(
+        &self,
+        trade: &Trade,
+        order_type: OrderType,
+        price: f64,
+    ) -> Result<Order, GlowError> {
+        let mut close_order = trade.new_close_order(order_type, price)?;
+        let timestamp = self.clock.current_timestamp_ms();
+        close_order.created_at = timestamp;
+        close_order.updated_at = timestamp;
+        close_order.uuid = format!("paper_{}", close_order.id);
+        Ok(close_order)
+    }
+
+    /// Fills the whole open order at the given price, charging its fee from the wallet
+    fn fill_open_order(
+        &self,
+        state: &mut PaperState,
+        order: Order,
+        price: f64,
+    ) -> (Vec<Execution>, Order) {
+        let timestamp = self.clock.current_timestamp_ms();
+        let execution = self.new_execution(state, &order, price, timestamp);
+        state.wallet_balance -= execution.fee;
+        let mut filled_order = order;
+        filled_order.avg_price = Some(price);
+        filled_order.updated_at = timestamp;
+        let filled_order = filled_order.push_executions_if_new(vec![execution.clone()]);
+        state.peak_price = Some(price);
+        state.trade = Some(Trade::new(filled_order.clone(), None));
+        (vec![execution], filled_order)
+    }
+
+    /// Fills the whole close order at the given price, settling the position results into the wallet
+    fn fill_close_order(
+        &self,
+        state: &mut PaperState,
+        trade: &Trade,
+        order: Order,
+        price: f64,
+        final_status: OrderStatus,
+    ) -> (Vec<Execution>, Order) {
+        let timestamp = self.clock.current_timestamp_ms();
+        let execution = self.new_execution(state, &order, price, timestamp);
+        let entry_price = trade.open_order.get_executed_avg_price();
+        let price_delta = if trade.open_order.side == Side::Sell {
+            entry_price - price
+        } else {
+            price - entry_price
+        };
+        state.wallet_balance += price_delta * execution.closed_qty - execution.fee;
+        let mut filled_order = order;
+        filled_order.avg_price = Some(price);
+        filled_order.updated_at = timestamp;
+        let mut filled_order = filled_order.push_executions_if_new(vec![execution.clone()]);
+        filled_order.status = final_status;
+        state.peak_price = None;
+        state.pending_close_order = None;
+        state.trade = None;
+        (vec![execution], filled_order)
+    }
+
+    /// Returns the (price, final status) of the first trade threshold reached by the kline, the low price being checked first
+    fn get_binding_stop(&self, trade: &Trade, kline: &PaperKline) -> Option<(f64, OrderStatus)> {
+        let (lower_price, upper_price) = trade.get_threshold_prices();
+        let binding_price = match (lower_price, upper_price) {
+            (Some(lower_price), _) if kline.low <= lower_price => lower_price,
+            (_, Some(upper_price)) if kline.high >= upper_price => upper_price,
+            _ => return None,
+        };
+        let final_status = if Some(binding_price) == trade.open_order.stop_loss_price {
+            OrderStatus::StoppedSL
+        } else if Some(binding_price) == trade.open_order.take_profit_price {
+            OrderStatus::StoppedTP
+        } else {
+            OrderStatus::StoppedBR
+        };
+        Some((binding_price, final_status))
+    }
+
+    fn find_order(&self, order_id: &str) -> Result<Order, GlowError> {
+        let state = self.lock_state();
+        let trade_orders = state
+            .trade
+            .iter()
+            .map(|trade| &trade.open_order)
+            .chain(state.pending_close_order.iter());
+        trade_orders
+            .into_iter()
+            .find(|order| order.id == order_id)
+            .cloned()
+            .ok_or_else(|| {
+                GlowError::new(
+                    String::from("Paper Order Error"),
+                    format!("order {} isn't open", order_id),
+                )
+            })
+    }
+
+    fn emit_order_update(
+        &self,
+        executions: Vec<Execution>,
+        order_action: OrderAction,
+        balance: Balance,
+    ) {
+        if !executions.is_empty() {
+            self.executions_update_emitter.next(executions);
+        }
+        self.order_update_emitter.next(order_action);
+        self.balance_update_emitter.next(balance);
+    }
+
+    /// Fills pending orders and triggers the stops of the current trade reached by the kline prices
+    pub fn process_kline(&self, kline: PaperKline) -> Result<(), GlowError> {
+        let mut order_updates = vec![];
+        let balance = {
+            let mut state = self.lock_state();
+            state.last_kline = Some(kline);
+
+            if let Some(trade) = state.trade.clone() {
+                let open_order = trade.open_order;
+                let price = open_order.avg_price.unwrap_or(kline.close);
+                if open_order.status == OrderStatus::StandBy
+                    && is_limit_price_reached(open_order.side, price, &kline)
+                {
+                    let (executions, filled_order) =
+                        self.fill_open_order(&mut state, open_order, price);
+                    order_updates.push((executions, OrderAction::Update(filled_order)));
+                }
+            }
+
+            if let Some(trade) = state.trade.clone() {
+                if trade.status() != TradeStatus::New {
+                    if let Some((price, final_status)) = self.get_binding_stop(&trade, &kline) {
+                        let mut stop_order =
+                            self.new_close_order(&trade, OrderType::Market, price)?;
+                        stop_order.is_stop = true;
+                        let (executions, filled_order) = self.fill_close_order(
+                            &mut state,
+                            &trade,
+                            stop_order,
+                            price,
+                            final_status,
+                        );
+                        order_updates.push((executions, OrderAction::Stop(filled_order)));
+                    } else if let Some(close_order) = state.pending_close_order.clone() {
+                        let price = close_order.avg_price.unwrap_or(kline.close);
+                        if is_limit_price_reached(close_order.side, price, &kline) {
+                            let (executions, filled_order) = self.fill_close_order(
+                                &mut state,
+                                &trade,
+                                close_order,
+                                price,
+                                OrderStatus::Closed,
+                            );
+                            order_updates.push((executions, OrderAction::Update(filled_order)));
+                        }
+                    }
+                }
+            }
+
+            state.get_balance(self.clock.current_timestamp_ms())
+        };
+
+        for (executions, order_action) in order_updates {
+            self.emit_order_update(executions, order_action, balance);
+        }
+        Ok(())
+    }
+
+    /// Processes the last kline of the strategy data, skipping it if the traded symbol prices are missing
+    pub fn process_kline_data(&self, df: &DataFrame) -> Result<(), GlowError> {
+        let Some(index) = df.height().checked_sub(1) else {
+            return Ok(());
+        };
+        let traded_symbol = self.get_traded_symbol();
+        let (open_col, high_col, low_col, close_col) = traded_symbol.get_ohlc_cols();
+        let start_time = df.column("start_time")?.datetime()?.get(index);
+        let get_price = |column: &str| -> Result<Option<f64>, GlowError> {
+            Ok(df.column(column)?.f64()?.get(index))
+        };
+        let (Some(start_time), Some(open), Some(high), Some(low), Some(close)) = (
+            start_time,
+            get_price(open_col)?,
+            get_price(high_col)?,
+            get_price(low_col)?,
+            get_price(close_col)?,
+        ) else {
+            return Ok(());
+        };
+        self.process_kline(PaperKline::new(start_time, open, high, low, close))
+    }
+}
+
+/// Whether the kline reached the price of a resting limit order of the given side
+fn is_limit_price_reached(side: Side, price: f64, kline: &PaperKline) -> bool {
+    match side {
+        Side::Buy => kline.low <= price,
+        Side::Sell => kline.high >= price,
+        Side::None => false,
+    }
+}
+
+impl TraderHelper for PaperTraderExchange {
+    #[inline]
+    fn get_contracts(&self) -> &HashMap<SymbolId, Contract> {
+        self.contracts
+    }
+    #[inline]
+    fn get_maker_fee(&self) -> f64 {
+        self.fee_rates.0
+    }
+    #[inline]
+    fn get_taker_fee(&self) -> f64 {
+        self.fee_rates.1
+    }
+    #[inline]
+    fn get_trading_settings(&self) -> &TradingSettings {
+        &self.trading_settings
+    }
+
+    fn calculate_open_order_units_and_balance_remainder(
+        &self,
+        side: Side,
+        order_cost: f64,
+        price: f64,
+    ) -> Result<(f64, f64), GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        // same order cost as the simulated exchange:
+        // Order Cost × Leverage / [Order Price × (2 × Taker Fee Rate × Leverage + 1 ± Taker Fee Rate)]
+        let contract = self.get_traded_contract();
+        let taker_fee_rate = self.get_taker_fee();
+        let maximum_order_sizes = contract.maximum_order_sizes;
+
+        let fee_position_modifier = match side {
+            Side::Sell => taker_fee_rate,
+            Side::Buy => -taker_fee_rate,
+            Side::None => {
+                let error = format!(
+                    "calculate_order_units_and_balance_remainder -> Invalid side {:?}",
+                    side
+                );
+                return Err(GlowError::new(String::from("Invalid Side Error"), error));
+            }
+        };
+
+        let mut units = order_cost * leverage_factor
+            / (price * ((2.0 * taker_fee_rate * leverage_factor) + (1.0 + fee_position_modifier)));
+
+        let fract_units = calculate_remainder(units, contract.minimum_order_size);
+        let size_decimals = count_decimal_places(contract.minimum_order_size);
+        units = round_down_nth_decimal(units - fract_units, size_decimals);
+
+        let open_order_type = trading_settings.order_types.0;
+        let maximum_order_size = if open_order_type == OrderType::Market {
+            maximum_order_sizes.0
+        } else {
+            maximum_order_sizes.1
+        };
+
+        let error = if units == 0.0 || units < contract.minimum_order_size {
+            Some(format!(
+                "units < contract.minimum_order_size | units = {}, minimum order size = {}",
+                units, contract.minimum_order_size
+            ))
+        } else if units > maximum_order_size {
+            Some(format!(
+                "units > contract.maximum_order_size | units = {}, maximum order size = {}",
+                units, maximum_order_size
+            ))
+        } else if leverage_factor > contract.max_leverage {
+            Some(format!(
+                "leverage_factor > contract.max_leverage | leverage_factor = {}, max_leverage = {}",
+                leverage_factor, contract.max_leverage
+            ))
+        } else {
+            None
+        };
+
+        if let Some(error) = error {
+            let error = format!("calculate_order_units -> side {:?}, error {}", side, error);
+            return Err(GlowError::new(String::from("Invalid Side Error"), error));
+        }
+
+        let balance_remainder = fract_units * price / leverage_factor;
+
+        Ok((units, balance_remainder))
+    }
+
+    fn get_order_fee_rate(&self, order_type: OrderType) -> (f64, bool) {
+        if order_type == OrderType::Limit {
+            (self.get_maker_fee(), true)
+        } else {
+            (self.get_taker_fee(), false)
+        }
+    }
+
+    fn calculate_order_fees(
+        &self,
+        order_type: OrderType,
+        side: Side,
+        units: f64,
+        price: f64,
+    ) -> ((f64, f64), f64, bool) {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let (fee_rate, is_maker) = self.get_order_fee_rate(order_type);
+        let open_fee = units * price * fee_rate;
+        let bankruptcy_price = match side {
+            Side::Sell => price * (leverage_factor + 1.0) / leverage_factor,
+            Side::Buy => price * (leverage_factor - 1.0) / leverage_factor,
+            Side::None => 0.0,
+        };
+        let close_fee = units * bankruptcy_price * fee_rate;
+
+        ((open_fee, close_fee), fee_rate, is_maker)
+    }
+
+    fn calculate_order_stop_loss_price(&self, side: Side, price: f64) -> Option<f64> {
+        let trading_settings = self.get_trading_settings();
+        let stop_loss = trading_settings.price_level_modifier_map.get("sl")?;
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let stop_loss_percentage = stop_loss.get_percentage();
+        let position_mod = match side {
+            Side::Sell => leverage_factor + stop_loss_percentage,
+            Side::Buy => leverage_factor - stop_loss_percentage,
+            Side::None => return None,
+        };
+        Some(self.round_price_to_tick(price * position_mod / leverage_factor))
+    }
+
+    fn calculate_order_take_profit_price(&self, side: Side, price: f64) -> Option<f64> {
+        let trading_settings = self.get_trading_settings();
+        let take_profit = trading_settings.price_level_modifier_map.get("tp")?;
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let take_profit_percentage = take_profit.get_percentage();
+        let position_mod = match side {
+            Side::Sell => leverage_factor - take_profit_percentage,
+            Side::Buy => leverage_factor + take_profit_percentage,
+            Side::None => return None,
+        };
+        Some(self.round_price_to_tick(price * position_mod / leverage_factor))
+    }
+}
+
+impl TraderExchange for PaperTraderExchange {
+    #[inline]
+    fn get_http_client(&self) -> &Client {
+        &self.http
+    }
+
+    fn get_ws_url(&self) -> Result<Url, GlowError> {
+        Err(GlowError::new_str(
+            "Paper Trading Error",
+            "paper trading doesn't connect to any websocket",
+        ))
+    }
+
+    async fn auth_ws(
+        &self,
+        _wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    async fn subscribe_ws(
+        &self,
+        _wss: &mut WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    fn process_ws_message(&self, _json: &String) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    async fn fetch_order_executions(
+        &self,
+        order_uuid: String,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<Execution>, GlowError> {
+        let state = self.lock_state();
+        let executions = state
+            .trade
+            .iter()
+            .flat_map(|trade| trade.open_order.executions.iter())
+            .filter(|execution| {
+                execution.order_uuid == order_uuid
+                    && start_timestamp <= execution.timestamp
+                    && execution.timestamp <= end_timestamp
+            })
+            .cloned()
+            .collect();
+        Ok(executions)
+    }
+
+    async fn fetch_history_order(
+        &self,
+        id: Option<String>,
+        _side: Option<Side>,
+        _fetch_executions: bool,
+    ) -> Result<Order, GlowError> {
+        let Some(id) = id else {
+            return Err(GlowError::new_str(
+                "Paper Order Error",
+                "paper orders can only be fetched by id",
+            ));
+        };
+        self.find_order(&id)
+    }
+
+    async fn fetch_current_order(
+        &self,
+        order_id: String,
+        _fetch_executions: bool,
+    ) -> Result<Order, GlowError> {
+        self.find_order(&order_id)
+    }
+
+    async fn fetch_current_trade_position(&self) -> Result<Option<Trade>, GlowError> {
+        let state = self.lock_state();
+        Ok(state.trade.clone())
+    }
+
+    async fn fetch_trade_state(
+        &self,
+        trade_id: String,
+        _last_status: TradeStatus,
+    ) -> Result<Trade, GlowError> {
+        let state = self.lock_state();
+        state
+            .trade
+            .clone()
+            .filter(|trade| trade.id == trade_id)
+            .ok_or_else(|| {
+                GlowError::new(
+                    String::from("Paper Trade Error"),
+                    format!("trade {} isn't open", trade_id),
+                )
+            })
+    }
+
+    async fn fetch_current_usdt_balance(&self) -> Result<Balance, GlowError> {
+        let state = self.lock_state();
+        Ok(state.get_balance(self.clock.current_timestamp_ms()))
+    }
+
+    async fn open_order(
+        &self,
+        side: Side,
+        total_balance: f64,
+        expected_price: f64,
+    ) -> Result<Order, GlowError> {
+        assert_ne!(side, Side::None, "Invalid Open Order Side!");
+        assert!(
+            expected_price > 0.0,
+            "open_order -> expected price is less than 0!"
+        );
+
+        let trading_settings = self.get_trading_settings();
+        let traded_contract = self.get_traded_contract();
+        let open_order_type = trading_settings.get_open_order_type();
+
+        let mut expected_price = expected_price;
+        if open_order_type == OrderType::Limit {
+            let tick_decimals = count_decimal_places(traded_contract.tick_size);
+            // move price marginally away from the last price in order to realize maker_fee
+            expected_price = if side == Side::Sell {
+                round_down_nth_decimal(expected_price + traded_contract.tick_size, tick_decimals)
+            } else {
+                round_down_nth_decimal(expected_price - traded_contract.tick_size, tick_decimals)
+            };
+        }
+        // allocation percentage is taken the same way benchmarks do, 100.0 being the whole balance
+        let order_cost = total_balance * trading_settings.allocation_percentage / 100.0;
+
+        let mut order = self.new_open_order(side, order_cost, expected_price)?;
+        order.uuid = format!("paper_{}", order.id);
+
+        let (executions, balance) = {
+            let mut state = self.lock_state();
+            if state.trade.is_some() {
+                return Err(GlowError::new_str(
+                    "Open Order Error",
+                    "paper position is already open",
+                ));
+            }
+            let order_margin = order.units * expected_price / order.leverage_factor;
+            let available_to_withdraw = state.get_balance(order.created_at).available_to_withdraw;
+            if order_margin > available_to_withdraw {
+                let error = format!(
+                    "insufficient paper balance | order margin = {}, available = {}",
+                    order_margin, available_to_withdraw
+                );
+                return Err(GlowError::new(String::from("Open Order Error"), error));
+            }
+
+            let executions = if open_order_type == OrderType::Market {
+                let (executions, filled_order) =
+                    self.fill_open_order(&mut state, order, expected_price);
+                order = filled_order;
+                executions
+            } else {
+                state.trade = Some(Trade::new(order.clone(), None));
+                vec![]
+            };
+            (executions, state.get_balance(self.clock.current_timestamp_ms()))
+        };
+
+        self.emit_order_update(executions, OrderAction::Update(order.clone()), balance);
+        Ok(order)
+    }
+
+    async fn amend_order(
+        &self,
+        order_id: String,
+        updated_units: Option<f64>,
+        updated_price: Option<f64>,
+        updated_stop_loss_price: Option<f64>,
+        updated_take_profit_price: Option<f64>,
+    ) -> Result<bool, GlowError> {
+        let mut state = self.lock_state();
+        if let Some(trade) = state
+            .trade
+            .as_mut()
+            .filter(|trade| trade.open_order.id == order_id)
+        {
+            let open_order = &mut trade.open_order;
+            if open_order.status == OrderStatus::StandBy {
+                if let Some(updated_units) = updated_units {
+                    open_order.update_units(updated_units);
+                }
+                if updated_price.is_some() {
+                    open_order.avg_price = updated_price;
+                }
+            }
+            if updated_stop_loss_price.is_some() {
+                open_order.stop_loss_price = updated_stop_loss_price;
+            }
+            if updated_take_profit_price.is_some() {
+                open_order.take_profit_price = updated_take_profit_price;
+            }
+            return Ok(true);
+        }
+        if let Some(close_order) = state
+            .pending_close_order
+            .as_mut()
+            .filter(|close_order| close_order.id == order_id)
+        {
+            if let Some(updated_units) = updated_units {
+                close_order.update_units(updated_units);
+            }
+            if updated_price.is_some() {
+                close_order.avg_price = updated_price;
+            }
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    async fn try_close_position(&self, trade: &Trade, est_price: f64) -> Result<Order, GlowError> {
+        let mut est_price = est_price;
+        let traded_contract = self.get_traded_contract();
+        let trading_settings = self.get_trading_settings();
+        let close_order_type = trading_settings.get_close_order_type();
+
+        if close_order_type == OrderType::Limit {
+            if trade.open_order.side == Side::Sell {
+                // close order will have open order opposite side, subtract last price marginally in order to realize maker_fee
+                est_price -= traded_contract.tick_size
+            } else if trade.open_order.side == Side::Buy {
+                // close order will have open order opposite side, add last price marginally in order to realize maker_fee
+                est_price += traded_contract.tick_size;
+            }
+        }
+
+        let (est_fee_rate, _) = self.get_order_fee_rate(close_order_type);
+
+        let mut close_order = self.new_close_order(trade, close_order_type, est_price)?;
+
+        let position_lock = trading_settings.position_lock_modifier;
+        let price_pnl = trade.calculate_price_pnl(est_price);
+        let open_fee = trade.open_order.get_executed_order_fee();
+        let close_fee = close_order.get_estimate_close_order_fee(est_fee_rate, est_price);
+        if position_lock.is_close_locked(price_pnl, open_fee, close_fee) {
+            let error = format!(
+                "Trade wasn't closed due to PositionLockModifier::{:?} -> price profit and loss = {}, open fee = {}, close fee = {}",
+                position_lock, price_pnl, open_fee, close_fee
+            );
+            return Err(GlowError::new(String::from("Close Position Error"), error));
+        }
+
+        let (executions, balance) = {
+            let mut state = self.lock_state();
+            let current_trade = match &state.trade {
+                Some(current_trade)
+                    if current_trade.id == trade.id
+                        && current_trade.status() != TradeStatus::New =>
+                {
+                    current_trade.clone()
+                }
+                _ => {
+                    let error = format!("paper trade {} isn't open", trade.id);
+                    return Err(GlowError::new(String::from("Close Position Error"), error));
+                }
+            };
+            let executions = if close_order_type == OrderType::Market {
+                let (executions, filled_order) = self.fill_close_order(
+                    &mut state,
+                    &current_trade,
+                    close_order,
+                    est_price,
+                    OrderStatus::Closed,
+                );
+                close_order = filled_order;
+                executions
+            } else {
+                state.pending_close_order = Some(close_order.clone());
+                vec![]
+            };
+            (executions, state.get_balance(self.clock.current_timestamp_ms()))
+        };
+
+        self.emit_order_update(
+            executions,
+            OrderAction::Update(close_order.clone()),
+            balance,
+        );
+        Ok(close_order)
+    }
+
+    async fn update_trailing_stop_loss(
+        &self,
+        trade: &Trade,
+        last_price: f64,
+    ) -> Result<bool, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let Some(trailing_stop_loss) = trading_settings.get_trailing_stop_loss() else {
+            return Ok(false);
+        };
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let mut state = self.lock_state();
+        let Some(current_trade) = state
+            .trade
+            .clone()
+            .filter(|current_trade| current_trade.id == trade.id)
+        else {
+            return Ok(false);
+        };
+        let open_order = &current_trade.open_order;
+        if open_order.status == OrderStatus::StandBy {
+            return Ok(false);
+        }
+        // paper stops are checked against klines, so both trailing stops are amended on every step
+        let side = open_order.side;
+        let entry_price = open_order.get_executed_avg_price();
+        let peak_price = state.peak_price.unwrap_or(entry_price);
+        let peak_price = match side {
+            Side::Buy => peak_price.max(last_price),
+            Side::Sell => peak_price.min(last_price),
+            Side::None => return Ok(false),
+        };
+        state.peak_price = Some(peak_price);
+        let stop_price = trailing_stop_loss
+            .get_stop_price(side, entry_price, peak_price, leverage_factor)
+            .map(|stop_price| self.round_price_to_tick(stop_price));
+        let should_amend = match (stop_price, open_order.stop_loss_price) {
+            (Some(stop_price), Some(current_stop_price)) => {
+                (side == Side::Buy && stop_price > current_stop_price)
+                    || (side == Side::Sell && stop_price < current_stop_price)
+            }
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if !should_amend {
+            return Ok(false);
+        }
+        if let Some(current_trade) = state.trade.as_mut() {
+            current_trade.open_order.stop_loss_price = stop_price;
+        }
+        Ok(true)
+    }
+
+    async fn cancel_order(&self, order_id: String) -> Result<bool, GlowError> {
+        let (cancelled_order, balance) = {
+            let mut state = self.lock_state();
+            let cancelled_order = match (&state.trade, &state.pending_close_order) {
+                (Some(trade), _)
+                    if trade.open_order.id == order_id
+                        && trade.open_order.status == OrderStatus::StandBy =>
+                {
+                    let cancelled_order = trade.open_order.cancel();
+                    state.trade = None;
+                    cancelled_order
+                }
+                (_, Some(close_order)) if close_order.id == order_id => {
+                    let cancelled_order = close_order.cancel();
+                    state.pending_close_order = None;
+                    cancelled_order
+                }
+                _ => return Ok(false),
+            };
+            (cancelled_order, state.get_balance(self.clock.current_timestamp_ms()))
+        };
+
+        self.emit_order_update(vec![], OrderAction::Cancel(cancelled_order), balance);
+        Ok(true)
+    }
+
+    async fn set_leverage(&self, leverage: Leverage) -> Result<bool, GlowError> {
+        let leverage_factor = leverage.get_factor();
+        let traded_contract = self.get_traded_contract();
+        if leverage_factor > traded_contract.max_leverage {
+            let error = format!(
+                "symbol {} only allows for max {} leverage, {} was sent",
+                traded_contract.symbol.name, traded_contract.max_leverage, leverage_factor
+            );
+            return Err(GlowError::new(
+                String::from("Invalid Leverage Error"),
+                error,
+            ));
+        }
+        Ok(true)
+    }
+
+    fn new_open_order(&self, side: Side, order_cost: f64, price: f64) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let open_order_type = trading_settings.get_open_order_type();
+        let (units, balance_remainder) =
+            self.calculate_open_order_units_and_balance_remainder(side, order_cost, price)?;
+
+        let contract = self.get_traded_contract();
+
+        let timestamp = self.clock.current_timestamp_ms();
+        let id = format!(
+            "{}_{}_{}",
+            &contract.symbol.name,
+            timestamp,
+            OrderStage::Open.to_string()
+        );
+        let avg_price = if open_order_type == OrderType::Limit {
+            Some(price)
+        } else {
+            None
+        };
+        let stop_loss_price = self.calculate_order_stop_loss_price(side, price);
+        let take_profit_price = self.calculate_order_take_profit_price(side, price);
+
+        let time_in_force = if open_order_type == OrderType::Limit {
+            TimeInForce::GTC
+        } else {
+            TimeInForce::IOC
+        };
+
+        let order = Order::new(
+            avg_price,
+            balance_remainder,
+            timestamp,
+            vec![],
+            id,
+            false,
+            false,
+            leverage_factor,
+            open_order_type,
+            side,
+            OrderStatus::StandBy,
+            stop_loss_price,
+            contract.symbol.name.to_string(),
+            take_profit_price,
+            self.get_taker_fee(),
+            time_in_force,
+            units,
+            timestamp,
+            "".to_string(),
+        );
+        Ok(order)
+    }
+
+    fn get_ws_ping_interval(&self) -> u64 {
+        60
+    }
+
+    fn get_ws_ping_message(&self) -> Result<Message, GlowError> {
+        Ok(Message::Ping(vec![]))
+    }
+
+    /// Paper trading state never gets out of sync, so the current balance and trade are just emitted again
+    async fn update_position_data_on_faulty_exchange_ws(&self) -> Result<(), GlowError> {
+        let (balance, trade) = {
+            let state = self.lock_state();
+            (
+                state.get_balance(self.clock.current_timestamp_ms()),
+                state.trade.clone(),
+            )
+        };
+        self.balance_update_emitter.next(balance);
+        self.trade_update_emitter.next(trade);
+        Ok(())
+    }
+
+    async fn init(&mut self) -> Result<(), GlowError> {
+        self.update_position_data_on_faulty_exchange_ws().await
+    }
+
+    /// Paper orders are filled from klines, so there are no exchange messages to listen to
+    async fn listen_messages(
+        &mut self,
+        _wss: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) -> Result<(), GlowError> {
+        Ok(())
+    }
+
+    #[inline]
+    fn get_balance_update_emitter(&self) -> &BehaviorSubject<Balance> {
+        &self.balance_update_emitter
+    }
+
+    #[inline]
+    fn get_executions_update_emitter(&self) -> &BehaviorSubject<Vec<Execution>> {
+        &self.executions_update_emitter
+    }
+
+    #[inline]
+    fn get_order_update_emitter(&self) -> &BehaviorSubject<OrderAction> {
+        &self.order_update_emitter
+    }
+
+    #[inline]
+    fn get_trade_update_emitter(&self) -> &BehaviorSubject<Option<Trade>> {
+        &self.trade_update_emitter
+    }
+}
+
+impl BenchmarkExchange for PaperTraderExchange {
+    fn new_benchmark_open_order(
+        &self,
+        timestamp: i64,
+        side: Side,
+        order_cost: f64,
+        price: f64,
+    ) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let leverage_factor = trading_settings.leverage.get_factor();
+        let open_order_type = trading_settings.get_open_order_type();
+        let (units, balance_remainder) =
+            self.calculate_open_order_units_and_balance_remainder(side, order_cost, price)?;
+        let ((open_fee, _), fee_rate, is_maker) =
+            self.calculate_order_fees(open_order_type, side, units, price);
+        let contract = self.get_traded_contract();
+        let id = format!(
+            "{}_{}_{}",
+            &contract.symbol.name,
+            timestamp,
+            OrderStage::Open.to_string()
+        );
+        let stop_loss_price = self.calculate_order_stop_loss_price(side, price);
+        let take_profit_price = self.calculate_order_take_profit_price(side, price);
+        let time_in_force = if open_order_type == OrderType::Limit {
+            TimeInForce::GTC
+        } else {
+            TimeInForce::IOC
+        };
+
+        let order_uuid = format!("benchmark_open_order_{}", timestamp);
+
+        let benchmark_execution = Execution::new(
+            id.clone(),
+            order_uuid.clone(),
+            open_order_type,
+            timestamp,
+            price,
+            units,
+            open_fee,
+            fee_rate,
+            is_maker,
+            0.0,
+        );
+
+        let order = Order::new(
+            Some(price),
+            balance_remainder,
+            timestamp,
+            vec![benchmark_execution],
+            id,
+            false,
+            false,
+            leverage_factor,
+            open_order_type,
+            side,
+            OrderStatus::Filled,
+            stop_loss_price,
+            contract.symbol.name.to_string(),
+            take_profit_price,
+            self.get_taker_fee(),
+            time_in_force,
+            units,
+            timestamp,
+            order_uuid,
+        );
+        Ok(order)
+    }
+
+    fn new_benchmark_close_order(
+        &self,
+        timestamp: i64,
+        trade_id: &String,
+        close_price: f64,
+        open_order: Order,
+        final_status: OrderStatus,
+    ) -> Result<Order, GlowError> {
+        let trading_settings = self.get_trading_settings();
+        let is_stop = matches!(
+            final_status,
+            OrderStatus::StoppedBR
+                | OrderStatus::StoppedSL
+                | OrderStatus::StoppedTSL
+                | OrderStatus::StoppedTP
+        );
+        let close_order_type = if is_stop {
+            trading_settings.get_close_order_type()
+        } else {
+            OrderType::Market
+        };
+        let id = format!("{}_{}", trade_id, OrderStage::Close.to_string());
+        let time_in_force = if close_order_type == OrderType::Market {
+            TimeInForce::IOC
+        } else {
+            TimeInForce::GTC
+        };
+
+        let close_side = open_order.side.get_opposite_side()?;
+
+        let ((_, close_fee), fee_rate, is_maker) =
+            self.calculate_order_fees(close_order_type, close_side, open_order.units, close_price);
+
+        let order_uuid = format!("pending_order_uuid_{}", timestamp);
+
+        let benchmark_execution = Execution::new(
+            id.clone(),
+            order_uuid.clone(),
+            close_order_type,
+            timestamp,
+            close_price,
+            open_order.units,
+            close_fee,
+            fee_rate,
+            is_maker,
+            open_order.units,
+        );
+
+        let order = Order::new(
+            Some(close_price),
+            0.0,
+            timestamp,
+            vec![benchmark_execution],
+            id,
+            true,
+            is_stop,
+            open_order.leverage_factor,
+            close_order_type,
+            close_side,
+            final_status,
+            None,
+            open_order.symbol.clone(),
+            None,
+            0.0,
+            time_in_force,
+            open_order.units,
+            timestamp,
+            order_uuid,
+        );
+
+        Ok(order)
+    }
+
+    fn close_benchmark_trade_on_binding_price(
+        &self,
+        trade: &Trade,
+        current_timestamp: i64,
+        binding_price: f64,
+    ) -> Result<Trade, GlowError> {
+        let stop_loss_price = trade.open_order.stop_loss_price.unwrap_or_default();
+        let take_profit_price = trade.open_order.take_profit_price.unwrap_or_default();
+        let bankruptcy_price = trade.open_order.get_bankruptcy_price().unwrap_or_default();
+        let final_status = if binding_price == stop_loss_price {
+            OrderStatus::StoppedSL
+        } else if binding_price == take_profit_price {
+            OrderStatus::StoppedTP
+        } else if binding_price == bankruptcy_price {
+            OrderStatus::StoppedBR
+        } else {
+            return Err(GlowError::new(
+                "Invalid binding price".to_owned(),
+                format!(
+                    "binding price = {:?}, SL price = {:?}, TP price = {:?} BR price = {:?}",
+                    binding_price, stop_loss_price, take_profit_price, bankruptcy_price
+                ),
+            ));
+        };
+
+        let close_order = self.new_benchmark_close_order(
+            current_timestamp,
+            &trade.id,
+            binding_price,
+            trade.open_order.clone(),
+            final_status,
+        )?;
+
+        let closed_trade = trade.update_trade(close_order)?;
+        Ok(closed_trade)
+    }
+
+    fn get_minimum_notional_value(&self) -> Option<f64> {
+        self.minimum_notional_value
+    }
+}
@@ -0,0 +1,70 @@
+use common::{
+    enums::{balance::Balance, trade_status::TradeStatus},
+    structs::{Order, Trade},
+};
+
+/// Traded symbol prices of the last kline, which paper orders are filled against
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PaperKline {
+    pub start_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+impl PaperKline {
+    pub fn new(start_time: i64, open: f64, high: f64, low: f64, close: f64) -> Self {
+        Self {
+            start_time,
+            open,
+            high,
+            low,
+            close,
+        }
+    }
+}
+
+/// Virtual wallet and orders of the paper trading session
+#[derive(Clone, Debug, Default)]
+pub struct PaperState {
+    pub executions_count: u64,
+    pub last_kline: Option<PaperKline>,
+    /// Limit close order waiting for the price to reach it
+    pub pending_close_order: Option<Order>,
+    /// Best price reached since the position was opened, trailed by the stop loss
+    pub peak_price: Option<f64>,
+    /// Current trade, whose open order is still waiting to be filled while its status is `TradeStatus::New`
+    pub trade: Option<Trade>,
+    pub wallet_balance: f64,
+}
+
+impl PaperState {
+    pub fn new(wallet_balance: f64) -> Self {
+        Self {
+            wallet_balance,
+            ..Default::default()
+        }
+    }
+
+    /// Margin held by the current trade, either executed or reserved by its pending open order
+    pub fn get_position_margin(&self) -> f64 {
+        match &self.trade {
+            Some(trade) if trade.status() == TradeStatus::New => trade
+                .open_order
+                .get_order_value()
+                .map(|order_value| order_value / trade.open_order.leverage_factor)
+                .unwrap_or_default(),
+            Some(trade) => trade.calculate_initial_margin(),
+            None => 0.0,
+        }
+    }
+
+    pub fn get_balance(&self, timestamp: i64) -> Balance {
+        Balance::new(
+            timestamp,
+            self.wallet_balance - self.get_position_margin(),
+            self.wallet_balance,
+        )
+    }
+}
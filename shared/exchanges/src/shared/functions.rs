@@ -0,0 +1,52 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use common::functions::{current_datetime, current_timestamp};
+use glow_error::{assert_or_error, GlowError};
+use std::sync::{Arc, Mutex};
+
+pub(crate) fn adjust_benchmark_datetimes(
+    benchmark_start: Option<DateTime<Utc>>,
+    benchmark_end: Option<DateTime<Utc>>,
+    kline_duration: Duration,
+    minimum_days_for_analysis: Option<i64>,
+    minimum_klines_for_benchmarking: i32,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), GlowError> {
+    if let (Some(benchmark_start), Some(benchmark_end)) = (benchmark_start, benchmark_end) {
+        assert_or_error!(benchmark_end > benchmark_start);
+    }
+
+    let benchmark_end = benchmark_end.unwrap_or_else(|| {
+        let current_datetime = current_datetime();
+        let date = NaiveDate::from_ymd_opt(
+            current_datetime.year(),
+            current_datetime.month(),
+            current_datetime.day(),
+        )
+        .unwrap();
+        let time =
+            NaiveTime::from_hms_opt(current_datetime.hour(), current_datetime.minute(), 0).unwrap();
+        let datetime = NaiveDateTime::new(date, time);
+        DateTime::from_naive_utc_and_offset(datetime, Utc)
+    });
+
+    let benchmark_start = benchmark_start.unwrap_or_else(|| {
+        benchmark_end - (Duration::days(minimum_days_for_analysis.unwrap_or(1)))
+    });
+
+    let benchmark_start = benchmark_start - (kline_duration * minimum_klines_for_benchmarking);
+
+    assert_or_error!(benchmark_end > benchmark_start);
+
+    Ok((benchmark_start, benchmark_end))
+}
+
+pub(crate) fn set_ws_error_ts(last_ws_error_ts: Arc<Mutex<Option<i64>>>, error: GlowError) {
+    let mut last_error_guard = last_ws_error_ts
+        .lock()
+        .expect("init -> last_error_guard unwrap");
+    let error_timestamp = current_timestamp();
+    *last_error_guard = Some(error_timestamp);
+    eprintln!(
+        "Market Websocket connection error: {:?}. Retrying...",
+        error
+    );
+}
@@ -1,2 +1,3 @@
 pub mod deserializers;
+pub mod functions;
 pub mod serializers;
@@ -1,8 +1,72 @@
 use crate::{enums::TraderExchangeId, structs::ExchangeContext};
 use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
-use common::{r#static::SYMBOLS_MAP, structs::Contract};
+use common::{
+    r#static::SYMBOLS_MAP,
+    structs::{Contract, RiskLimit},
+};
 use std::{collections::HashMap, sync::LazyLock};
 
+// maintenance margin tiers, as published by each exchange
+const BYBIT_BTCUSDT_RISK_LIMITS: [RiskLimit; 5] = [
+    RiskLimit::new(0.005, 2_000_000.0),
+    RiskLimit::new(0.01, 4_000_000.0),
+    RiskLimit::new(0.015, 6_000_000.0),
+    RiskLimit::new(0.02, 8_000_000.0),
+    RiskLimit::new(0.025, 10_000_000.0),
+];
+
+const BYBIT_ETHUSDT_RISK_LIMITS: [RiskLimit; 5] = [
+    RiskLimit::new(0.005, 1_000_000.0),
+    RiskLimit::new(0.01, 2_000_000.0),
+    RiskLimit::new(0.015, 3_000_000.0),
+    RiskLimit::new(0.02, 4_000_000.0),
+    RiskLimit::new(0.025, 5_000_000.0),
+];
+
+const BYBIT_ALTCOIN_RISK_LIMITS: [RiskLimit; 5] = [
+    RiskLimit::new(0.01, 200_000.0),
+    RiskLimit::new(0.015, 400_000.0),
+    RiskLimit::new(0.02, 600_000.0),
+    RiskLimit::new(0.025, 800_000.0),
+    RiskLimit::new(0.03, 1_000_000.0),
+];
+
+const BINANCE_BTCUSDT_RISK_LIMITS: [RiskLimit; 9] = [
+    RiskLimit::new(0.004, 50_000.0),
+    RiskLimit::new(0.005, 500_000.0),
+    RiskLimit::new(0.01, 8_000_000.0),
+    RiskLimit::new(0.025, 50_000_000.0),
+    RiskLimit::new(0.05, 80_000_000.0),
+    RiskLimit::new(0.1, 100_000_000.0),
+    RiskLimit::new(0.125, 200_000_000.0),
+    RiskLimit::new(0.15, 300_000_000.0),
+    RiskLimit::new(0.25, 500_000_000.0),
+];
+
+const BINANCE_ETHUSDT_RISK_LIMITS: [RiskLimit; 9] = [
+    RiskLimit::new(0.005, 50_000.0),
+    RiskLimit::new(0.0065, 600_000.0),
+    RiskLimit::new(0.01, 3_000_000.0),
+    RiskLimit::new(0.02, 12_000_000.0),
+    RiskLimit::new(0.05, 50_000_000.0),
+    RiskLimit::new(0.1, 65_000_000.0),
+    RiskLimit::new(0.125, 150_000_000.0),
+    RiskLimit::new(0.15, 320_000_000.0),
+    RiskLimit::new(0.25, 400_000_000.0),
+];
+
+const BINANCE_ALTCOIN_RISK_LIMITS: [RiskLimit; 9] = [
+    RiskLimit::new(0.01, 5_000.0),
+    RiskLimit::new(0.015, 25_000.0),
+    RiskLimit::new(0.02, 100_000.0),
+    RiskLimit::new(0.05, 250_000.0),
+    RiskLimit::new(0.1, 1_000_000.0),
+    RiskLimit::new(0.125, 2_000_000.0),
+    RiskLimit::new(0.15, 5_000_000.0),
+    RiskLimit::new(0.25, 10_000_000.0),
+    RiskLimit::new(0.5, 20_000_000.0),
+];
+
 pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, ExchangeContext>> =
     LazyLock::new(|| {
         let mut exchanges_contexts = HashMap::new();
@@ -18,10 +82,12 @@ pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, Exch
                 ),
                 Duration::hours(8),
                 0.000009,
+                0.005,
                 100.0,
                 (119.0, 1190.0),
                 0.001,
                 None,
+                &BYBIT_BTCUSDT_RISK_LIMITS,
                 SYMBOLS_MAP.get("BTCUSDT").unwrap(),
                 0.1,
             );
@@ -36,10 +102,12 @@ pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, Exch
                 ),
                 Duration::hours(8),
                 0.000031,
+                0.005,
                 50.0,
                 (724.0, 7240.0),
                 0.01,
                 None,
+                &BYBIT_ETHUSDT_RISK_LIMITS,
                 SYMBOLS_MAP.get("ETHUSDT").unwrap(),
                 0.01,
             );
@@ -54,10 +122,12 @@ pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, Exch
                 ),
                 Duration::hours(8),
                 0.000048,
+                0.01,
                 50.0,
                 (11740.0, 79770.0),
                 0.1,
                 None,
+                &BYBIT_ALTCOIN_RISK_LIMITS,
                 SYMBOLS_MAP.get("SOLUSDT").unwrap(),
                 0.001,
             );
@@ -72,10 +142,12 @@ pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, Exch
                 ),
                 Duration::hours(8),
                 -0.0288,
+                0.01,
                 50.0,
                 (283360.0, 1799900.0),
                 0.1,
                 None,
+                &BYBIT_ALTCOIN_RISK_LIMITS,
                 SYMBOLS_MAP.get("ARBUSDT").unwrap(),
                 0.0001,
             );
@@ -90,10 +162,12 @@ pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, Exch
                 ),
                 Duration::hours(8),
                 0.000048,
+                0.01,
                 50.0,
                 (31450.0, 196310.0),
                 0.1,
                 None,
+                &BYBIT_ALTCOIN_RISK_LIMITS,
                 SYMBOLS_MAP.get("LINKUSDT").unwrap(),
                 0.001,
             );
@@ -114,5 +188,122 @@ pub static TRADER_EXCHANGES_CONTEXT_MAP: LazyLock<HashMap<TraderExchangeId, Exch
             exchanges_contexts.insert(TraderExchangeId::Bybit, context);
         }
 
+        {
+            let btcusdt_contract = Contract::new(
+                DateTime::from_naive_utc_and_offset(
+                    NaiveDateTime::new(
+                        NaiveDate::from_ymd_opt(2019, 9, 8).unwrap(),
+                        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                    ),
+                    Utc,
+                ),
+                Duration::hours(8),
+                0.0001,
+                0.0125,
+                125.0,
+                (120.0, 1000.0),
+                0.001,
+                None,
+                &BINANCE_BTCUSDT_RISK_LIMITS,
+                SYMBOLS_MAP.get("BTCUSDT").unwrap(),
+                0.1,
+            );
+
+            let ethusdt_contract = Contract::new(
+                DateTime::from_naive_utc_and_offset(
+                    NaiveDateTime::new(
+                        NaiveDate::from_ymd_opt(2019, 11, 27).unwrap(),
+                        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                    ),
+                    Utc,
+                ),
+                Duration::hours(8),
+                0.0001,
+                0.0125,
+                100.0,
+                (2000.0, 10000.0),
+                0.001,
+                None,
+                &BINANCE_ETHUSDT_RISK_LIMITS,
+                SYMBOLS_MAP.get("ETHUSDT").unwrap(),
+                0.01,
+            );
+
+            let solusdt_contract = Contract::new(
+                DateTime::from_naive_utc_and_offset(
+                    NaiveDateTime::new(
+                        NaiveDate::from_ymd_opt(2020, 9, 14).unwrap(),
+                        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                    ),
+                    Utc,
+                ),
+                Duration::hours(8),
+                0.0001,
+                0.015,
+                75.0,
+                (5000.0, 1000000.0),
+                1.0,
+                None,
+                &BINANCE_ALTCOIN_RISK_LIMITS,
+                SYMBOLS_MAP.get("SOLUSDT").unwrap(),
+                0.01,
+            );
+
+            let arbusdt_contract = Contract::new(
+                DateTime::from_naive_utc_and_offset(
+                    NaiveDateTime::new(
+                        NaiveDate::from_ymd_opt(2023, 3, 23).unwrap(),
+                        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                    ),
+                    Utc,
+                ),
+                Duration::hours(8),
+                0.0001,
+                0.015,
+                50.0,
+                (1000000.0, 2000000.0),
+                0.1,
+                None,
+                &BINANCE_ALTCOIN_RISK_LIMITS,
+                SYMBOLS_MAP.get("ARBUSDT").unwrap(),
+                0.0001,
+            );
+
+            let linkusdt_contract = Contract::new(
+                DateTime::from_naive_utc_and_offset(
+                    NaiveDateTime::new(
+                        NaiveDate::from_ymd_opt(2020, 1, 17).unwrap(),
+                        NaiveTime::from_hms_opt(0, 0, 0).unwrap(),
+                    ),
+                    Utc,
+                ),
+                Duration::hours(8),
+                0.0001,
+                0.015,
+                75.0,
+                (10000.0, 200000.0),
+                0.01,
+                None,
+                &BINANCE_ALTCOIN_RISK_LIMITS,
+                SYMBOLS_MAP.get("LINKUSDT").unwrap(),
+                0.001,
+            );
+
+            let mut contracts = HashMap::new();
+            contracts.insert(btcusdt_contract.symbol.id, btcusdt_contract);
+            contracts.insert(ethusdt_contract.symbol.id, ethusdt_contract);
+            contracts.insert(solusdt_contract.symbol.id, solusdt_contract);
+            contracts.insert(arbusdt_contract.symbol.id, arbusdt_contract);
+            contracts.insert(linkusdt_contract.symbol.id, linkusdt_contract);
+
+            let context = ExchangeContext {
+                taker_fee: 0.0005,
+                maker_fee: 0.0002,
+                contracts,
+            };
+
+            exchanges_contexts.insert(TraderExchangeId::Binance, context);
+        }
+
         exchanges_contexts
     });
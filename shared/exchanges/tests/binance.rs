@@ -0,0 +1,492 @@
+use chrono::DateTime;
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{leverage::Leverage, position_lock::PositionLock, price_level::PriceLevel},
+        order_action::OrderAction,
+        order_status::OrderStatus,
+        order_type::OrderType,
+        side::Side,
+        symbol_id::SymbolId,
+    },
+    functions::calculate_hmac,
+    structs::{FundingRate, TradingSettings},
+    traits::exchange::TraderExchange,
+};
+use exchanges::{
+    binance::{structs::BinanceDataProvider, trader::BinanceTraderExchange},
+    structs::{ApiCredentials, ApiEndpoints, ExchangeConfig},
+};
+use futures_util::SinkExt;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use strategy::{Strategy, StrategyId};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    spawn,
+    time::{sleep, timeout},
+};
+use tokio_stream::StreamExt;
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{Request, Response},
+        Message,
+    },
+};
+
+const API_KEY: &str = "test-api-key";
+const API_SECRET: &str = "test-api-secret";
+const LISTEN_KEY: &str = "test-listen-key";
+const BALANCES_JSON: &str = r#"[{"accountAlias":"test","asset":"USDT","balance":"1000.0","crossWalletBalance":"1000.0","availableBalance":"900.0","updateTime":1700000000000}]"#;
+
+#[derive(Clone, Debug)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    query: String,
+    api_key: Option<String>,
+}
+
+impl RecordedRequest {
+    fn params(&self) -> HashMap<String, String> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn has_valid_signature(&self) -> bool {
+        let Some((message, signature)) = self.query.rsplit_once("&signature=") else {
+            return false;
+        };
+        calculate_hmac(API_SECRET, message).unwrap() == signature
+    }
+}
+
+/// Minimal Binance futures REST mock, answering one request per connection
+struct MockHttpServer {
+    url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockHttpServer {
+    async fn start() -> Self {
+        Self::start_with_balances(BALANCES_JSON).await
+    }
+
+    async fn start_with_balances(balances_json: &'static str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let server_requests = requests.clone();
+        spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let requests = server_requests.clone();
+                spawn(async move { handle_http_connection(stream, requests, balances_json).await });
+            }
+        });
+        Self { url, requests }
+    }
+
+    fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn handle_http_connection(
+    mut stream: TcpStream,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    balances_json: &str,
+) {
+    let mut buffer = vec![];
+    let mut chunk = [0; 4096];
+    while !String::from_utf8_lossy(&buffer).contains("\r\n\r\n") {
+        let read = stream.read(&mut chunk).await.unwrap();
+        if read == 0 {
+            return;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let raw_request = String::from_utf8_lossy(&buffer).to_string();
+    let mut lines = raw_request.lines();
+    let mut request_line = lines.next().unwrap().split_whitespace();
+    let method = request_line.next().unwrap().to_string();
+    let target = request_line.next().unwrap();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let api_key = lines
+        .filter_map(|line| line.split_once(": "))
+        .find(|(header, _)| header.eq_ignore_ascii_case("x-mbx-apikey"))
+        .map(|(_, value)| value.to_string());
+
+    let request = RecordedRequest {
+        method,
+        path: path.to_string(),
+        query: query.to_string(),
+        api_key,
+    };
+    let body = mock_response(&request, balances_json);
+    requests.lock().unwrap().push(request);
+
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    stream.shutdown().await.unwrap();
+}
+
+fn order_json(params: &HashMap<String, String>, id: &str, status: &str) -> String {
+    let order_type = params
+        .get("type")
+        .map(|order_type| order_type.as_str())
+        .unwrap_or("LIMIT");
+    format!(
+        r#"{{"orderId":1001,"clientOrderId":"{}","symbol":"BTCUSDT","status":"{}","side":"{}","type":"{}","origType":"{}","timeInForce":"GTC","origQty":"{}","executedQty":"0","avgPrice":"0","price":"{}","stopPrice":"{}","reduceOnly":false,"closePosition":{},"updateTime":1700000000000}}"#,
+        id,
+        status,
+        params
+            .get("side")
+            .map(|side| side.as_str())
+            .unwrap_or("BUY"),
+        order_type,
+        order_type,
+        params
+            .get("quantity")
+            .map(|qty| qty.as_str())
+            .unwrap_or("0.1"),
+        params
+            .get("price")
+            .map(|price| price.as_str())
+            .unwrap_or("30000"),
+        params
+            .get("stopPrice")
+            .map(|price| price.as_str())
+            .unwrap_or("0"),
+        params.contains_key("closePosition")
+    )
+}
+
+fn mock_response(request: &RecordedRequest, balances_json: &str) -> String {
+    let params = request.params();
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/fapi/v1/order") => order_json(&params, &params["newClientOrderId"], "NEW"),
+        ("GET", "/fapi/v1/order") | ("PUT", "/fapi/v1/order") => {
+            order_json(&params, &params["origClientOrderId"], "NEW")
+        }
+        ("DELETE", "/fapi/v1/order") => {
+            order_json(&params, &params["origClientOrderId"], "CANCELED")
+        }
+        ("POST", "/fapi/v1/leverage") => format!(
+            r#"{{"leverage":{},"maxNotionalValue":"1000000","symbol":"{}"}}"#,
+            params["leverage"], params["symbol"]
+        ),
+        ("POST", "/fapi/v1/listenKey") => format!(r#"{{"listenKey":"{}"}}"#, LISTEN_KEY),
+        ("PUT", "/fapi/v1/listenKey") => "{}".to_string(),
+        ("GET", "/fapi/v2/balance") => balances_json.to_string(),
+        ("GET", "/fapi/v2/positionRisk") => "[]".to_string(),
+        ("GET", "/fapi/v1/fundingRate") => funding_rates_json(&params),
+        _ => "{}".to_string(),
+    }
+}
+
+const FUNDING_RATES: [(i64, &str); 3] = [
+    (1704067200000, "0.00010000"),
+    (1704096000000, "-0.00005000"),
+    (1704124800000, "0.00020000"),
+];
+
+fn funding_rates_json(params: &HashMap<String, String>) -> String {
+    let start_time: i64 = params["startTime"].parse().unwrap();
+    let end_time: i64 = params["endTime"].parse().unwrap();
+    let funding_rates: Vec<String> = FUNDING_RATES
+        .iter()
+        .filter(|(funding_time, _)| (start_time..=end_time).contains(funding_time))
+        .map(|(funding_time, funding_rate)| {
+            format!(
+                r#"{{"symbol":"{}","fundingTime":{},"fundingRate":"{}","markPrice":"42000.0"}}"#,
+                params["symbol"], funding_time, funding_rate
+            )
+        })
+        .collect();
+    format!("[{}]", funding_rates.join(","))
+}
+
+fn get_trading_settings() -> TradingSettings {
+    let mut price_level_modifier_map = HashMap::new();
+    price_level_modifier_map.insert("sl".to_string(), PriceLevel::StopLoss(0.5));
+    price_level_modifier_map.insert("tp".to_string(), PriceLevel::TakeProfit(1.0));
+    TradingSettings::new(
+        1.0,
+        Leverage::Isolated(10),
+        OrderType::Market,
+        OrderType::Market,
+        PositionLock::None,
+        price_level_modifier_map,
+        false,
+        &SymbolId::Bitcoin,
+        &SymbolId::Bitcoin,
+        1,
+        Granularity::m1,
+    )
+}
+
+fn new_exchange(http_url: &str, ws_url: &str) -> BinanceTraderExchange {
+    let config = ExchangeConfig {
+        credentials: ApiCredentials {
+            key: API_KEY,
+            secret: API_SECRET,
+        },
+        endpoints: ApiEndpoints {
+            http: Box::leak(http_url.to_string().into_boxed_str()),
+            ws: Box::leak(ws_url.to_string().into_boxed_str()),
+        },
+    };
+    BinanceTraderExchange::with_config(&get_trading_settings(), config)
+}
+
+#[tokio::test]
+async fn test_open_order_is_signed_and_places_trigger_orders() {
+    let server = MockHttpServer::start().await;
+    let exchange = new_exchange(&server.url, "ws://127.0.0.1:0");
+
+    let order = exchange
+        .open_order(Side::Buy, 1000.0, 30000.0)
+        .await
+        .unwrap();
+    assert_eq!(order.uuid, "1001");
+    assert_eq!(order.status, OrderStatus::StandBy);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    for request in &requests {
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/fapi/v1/order");
+        assert_eq!(request.api_key.as_deref(), Some(API_KEY));
+        assert!(request.has_valid_signature(), "{:?}", request);
+    }
+
+    let open_params = requests[0].params();
+    assert_eq!(open_params["newClientOrderId"], order.id);
+    assert_eq!(open_params["side"], "BUY");
+    assert_eq!(open_params["type"], "MARKET");
+    assert_eq!(open_params["quantity"], order.units.to_string());
+    assert!(!open_params.contains_key("price"));
+
+    let trade_id = order.id.trim_end_matches("_open");
+    let stop_loss_params = requests[1].params();
+    assert_eq!(
+        stop_loss_params["newClientOrderId"],
+        format!("{}_sl", trade_id)
+    );
+    assert_eq!(stop_loss_params["type"], "STOP_MARKET");
+    assert_eq!(stop_loss_params["side"], "SELL");
+    assert_eq!(stop_loss_params["closePosition"], "true");
+    assert_eq!(
+        stop_loss_params["stopPrice"],
+        order.stop_loss_price.unwrap().to_string()
+    );
+
+    let take_profit_params = requests[2].params();
+    assert_eq!(
+        take_profit_params["newClientOrderId"],
+        format!("{}_tp", trade_id)
+    );
+    assert_eq!(take_profit_params["type"], "TAKE_PROFIT_MARKET");
+    assert_eq!(
+        take_profit_params["stopPrice"],
+        order.take_profit_price.unwrap().to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_amend_cancel_and_set_leverage() {
+    let server = MockHttpServer::start().await;
+    let exchange = new_exchange(&server.url, "ws://127.0.0.1:0");
+    let order_id = "BTCUSDT_1700000000000_open".to_string();
+
+    let amended = exchange
+        .amend_order(
+            order_id.clone(),
+            Some(0.5),
+            Some(29000.0),
+            Some(28000.0),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(amended);
+
+    let cancelled = exchange.cancel_order(order_id.clone()).await.unwrap();
+    assert!(cancelled);
+
+    let leverage_set = exchange.set_leverage(Leverage::Isolated(20)).await.unwrap();
+    assert!(leverage_set);
+
+    let requests = server.requests();
+    let calls: Vec<(&str, &str)> = requests
+        .iter()
+        .map(|request| (request.method.as_str(), request.path.as_str()))
+        .collect();
+    assert_eq!(
+        calls,
+        vec![
+            ("GET", "/fapi/v1/order"),
+            ("PUT", "/fapi/v1/order"),
+            ("GET", "/fapi/v1/order"),
+            ("DELETE", "/fapi/v1/order"),
+            ("POST", "/fapi/v1/order"),
+            ("DELETE", "/fapi/v1/order"),
+            ("POST", "/fapi/v1/leverage"),
+        ]
+    );
+    assert!(requests.iter().all(|request| request.has_valid_signature()));
+
+    let amend_params = requests[1].params();
+    assert_eq!(amend_params["origClientOrderId"], order_id);
+    assert_eq!(amend_params["quantity"], "0.5");
+    assert_eq!(amend_params["price"], "29000");
+
+    let stop_loss_params = requests[4].params();
+    assert_eq!(
+        stop_loss_params["newClientOrderId"],
+        "BTCUSDT_1700000000000_sl"
+    );
+    assert_eq!(stop_loss_params["stopPrice"], "28000");
+
+    assert_eq!(requests[6].params()["leverage"], "20");
+}
+
+#[tokio::test]
+async fn test_missing_usdt_balance_returns_error() {
+    let server = MockHttpServer::start_with_balances(
+        r#"[{"accountAlias":"test","asset":"BNB","balance":"1.0","crossWalletBalance":"1.0","availableBalance":"1.0","updateTime":1700000000000}]"#,
+    )
+    .await;
+    let exchange = new_exchange(&server.url, "ws://127.0.0.1:0");
+
+    let error = exchange.fetch_current_usdt_balance().await.unwrap_err();
+    assert_eq!(error.title, "Missing Balance Error");
+    assert!(exchange
+        .update_position_data_on_faulty_exchange_ws()
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_fetch_funding_rates_within_interval() {
+    let server = MockHttpServer::start().await;
+    let trading_settings = get_trading_settings();
+    let strategy = Strategy::new(StrategyId::default(), trading_settings.symbols_pair).unwrap();
+    let mut data_provider = BinanceDataProvider::new(&trading_settings, &strategy).unwrap();
+    data_provider.patch_futures_http_base_url(&server.url);
+
+    let funding_rates = data_provider
+        .fetch_funding_rates("BTCUSDT", 1704067200000, 1704100000000)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        funding_rates,
+        vec![
+            FundingRate::new(
+                DateTime::from_timestamp_millis(1704067200000).unwrap(),
+                0.0001
+            ),
+            FundingRate::new(
+                DateTime::from_timestamp_millis(1704096000000).unwrap(),
+                -0.00005
+            ),
+        ]
+    );
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/fapi/v1/fundingRate");
+    assert_eq!(requests[0].params()["symbol"], "BTCUSDT");
+}
+
+// the handshake callback signature is defined by tungstenite
+#[allow(clippy::result_large_err)]
+#[tokio::test]
+async fn test_user_data_stream_updates_emitters() {
+    let server = MockHttpServer::start().await;
+    let server_requests = server.requests.clone();
+    let ws_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url = format!("ws://{}", ws_listener.local_addr().unwrap());
+    let ws_path = Arc::new(Mutex::new(String::new()));
+    let server_ws_path = ws_path.clone();
+
+    spawn(async move {
+        let (stream, _) = ws_listener.accept().await.unwrap();
+        let mut wss = accept_hdr_async(stream, |request: &Request, response: Response| {
+            *server_ws_path.lock().unwrap() = request.uri().path().to_string();
+            Ok(response)
+        })
+        .await
+        .unwrap();
+
+        // the initial REST sync emits its own balance, so events are only pushed after it finishes
+        while !server_requests
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|request| request.path == "/fapi/v2/positionRisk")
+        {
+            sleep(Duration::from_millis(10)).await;
+        }
+
+        let messages = [
+            r#"{"e":"ORDER_TRADE_UPDATE","E":1700000000100,"T":1700000000099,"o":{"s":"BTCUSDT","c":"BTCUSDT_1700000000000_open","S":"BUY","o":"MARKET","f":"GTC","q":"0.3","p":"0","ap":"30000","sp":"0","x":"TRADE","X":"FILLED","i":1001,"l":"0.3","z":"0.3","L":"30000","N":"USDT","n":"4.5","T":1700000000099,"t":555,"b":"0","a":"0","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"MARKET","ps":"BOTH","cp":false,"rp":"0"}}"#,
+            r#"{"e":"ORDER_TRADE_UPDATE","E":1700000000200,"T":1700000000199,"o":{"s":"BTCUSDT","c":"BTCUSDT_1700000000000_sl","S":"SELL","o":"MARKET","f":"GTC","q":"0","p":"0","ap":"28500","sp":"28500","x":"TRADE","X":"FILLED","i":1002,"l":"0.3","z":"0.3","L":"28500","N":"USDT","n":"4.2","T":1700000000199,"t":556,"b":"0","a":"0","m":false,"R":true,"wt":"CONTRACT_PRICE","ot":"STOP_MARKET","ps":"BOTH","cp":true,"rp":"-450"}}"#,
+            r#"{"e":"ACCOUNT_UPDATE","E":1700000000300,"T":1700000000299,"a":{"m":"ORDER","B":[{"a":"USDT","wb":"541.3","cw":"541.3","bc":"0"}],"P":[]}}"#,
+        ];
+        for message in messages {
+            wss.send(Message::Text(message.to_string())).await.unwrap();
+        }
+        // keeps the connection open until the test finishes
+        while wss.next().await.is_some() {}
+    });
+
+    let mut exchange = new_exchange(&server.url, &ws_url);
+    let mut balance_stream = exchange.get_balance_update_emitter().subscribe();
+    let executions_emitter = exchange.get_executions_update_emitter().clone();
+    let order_emitter = exchange.get_order_update_emitter().clone();
+    spawn(async move { exchange.init().await });
+
+    let balance = timeout(Duration::from_secs(5), async {
+        loop {
+            let balance = balance_stream.next().await.unwrap();
+            if balance.timestamp == 1700000000300 {
+                return balance;
+            }
+        }
+    })
+    .await
+    .expect("account update to be emitted");
+
+    assert_eq!(balance.wallet_balance, 541.3);
+    assert_eq!(*ws_path.lock().unwrap(), format!("/ws/{}", LISTEN_KEY));
+
+    let executions = executions_emitter.value();
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].id, "556");
+    assert_eq!(executions[0].order_uuid, "1002");
+    assert_eq!(executions[0].closed_qty, 0.3);
+
+    match order_emitter.value() {
+        OrderAction::Stop(order) => {
+            assert_eq!(order.id, "BTCUSDT_1700000000000_sl");
+            assert_eq!(order.status, OrderStatus::StoppedSL);
+            assert!(order.is_close);
+            assert_eq!(order.side, Side::Sell);
+        }
+        order_action => panic!("unexpected order action {:?}", order_action),
+    }
+}
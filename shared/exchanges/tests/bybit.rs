@@ -0,0 +1,62 @@
+use chrono::DateTime;
+use exchanges::bybit::{
+    enums::BybitPublicWsMessage,
+    structs::{BybitHttpResponseWrapper, KlineResult},
+};
+use serde_json::from_str;
+
+#[test]
+fn test_parse_kline_page_into_tick_data() {
+    let json = r#"{"retCode":0,"retMsg":"OK","result":{"symbol":"BTCUSDT","category":"linear","list":[["1670608860000","17071","17073","17027","17055.5","268611","15.74462667"],["1670608800000","17060","17080","17050","17071","1000","0.5"]]},"retExtInfo":{},"time":1672025956592}"#;
+
+    let response: BybitHttpResponseWrapper<KlineResult> =
+        from_str(json).expect("kline page to be parsed");
+    assert_eq!(response.ret_code, 0);
+
+    let ticks: Vec<_> = response
+        .result
+        .list
+        .into_iter()
+        .map(|kline| kline.into_tick_data("BTCUSDT"))
+        .collect();
+
+    assert_eq!(ticks.len(), 2);
+    let newest = &ticks[0];
+    assert_eq!(
+        newest.start_time,
+        DateTime::from_timestamp_millis(1670608860000).unwrap()
+    );
+    assert_eq!(newest.open, 17071.0);
+    assert_eq!(newest.high, 17073.0);
+    assert_eq!(newest.low, 17027.0);
+    assert_eq!(newest.close, 17055.5);
+}
+
+#[test]
+fn test_parse_public_kline_ws_message() {
+    let json = r#"{"topic":"kline.1.ETHUSDT","data":[{"start":1672324800000,"end":1672324859999,"interval":"1","open":"1195.5","close":"1196","high":"1197.5","low":"1194","volume":"120","turnover":"143460","confirm":true,"timestamp":1672324860012}],"ts":1672324860012,"type":"snapshot"}"#;
+
+    let BybitPublicWsMessage::Kline(message) = from_str::<BybitPublicWsMessage>(json).unwrap()
+    else {
+        panic!("kline message to be parsed");
+    };
+    assert_eq!(message.topic, "kline.1.ETHUSDT");
+    let kline = message.data.into_iter().next().unwrap();
+    assert!(kline.confirm);
+
+    let tick = kline.into_tick_data("ETHUSDT");
+    assert_eq!(tick.open, 1195.5);
+    assert_eq!(tick.close, 1196.0);
+}
+
+#[test]
+fn test_parse_public_ws_responses_as_none() {
+    let pong = r#"{"success":true,"ret_msg":"pong","conn_id":"0970e817-426e-429a-a679-ff7f55e0b16a","op":"ping"}"#;
+    let subscribe =
+        r#"{"success":true,"ret_msg":"","conn_id":"0970e817","req_id":"","op":"subscribe"}"#;
+
+    for json in [pong, subscribe] {
+        let message = from_str::<BybitPublicWsMessage>(json).unwrap_or_default();
+        assert!(matches!(message, BybitPublicWsMessage::None));
+    }
+}
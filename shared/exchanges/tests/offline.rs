@@ -0,0 +1,376 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{leverage::Leverage, position_lock::PositionLock},
+        order_type::OrderType,
+        symbol_id::SymbolId,
+        trading_data_update::TradingDataUpdate,
+    },
+    functions::{
+        csv::get_tick_data_csv_path_from_dir,
+        funding::{get_funding_rate_col, save_funding_rates_to_dir},
+        get_price_columns,
+        intrabar::{get_intrabar_fields, get_intrabar_paths},
+        map_ticks_data_to_df,
+        parquet::save_kline_df_to_parquet_dir,
+    },
+    structs::{FundingRate, TickData, TradingSettings},
+    traits::exchange::DataProviderExchange,
+};
+use exchanges::offline::{
+    structs::{KlineDataGap, OfflineDataSettings},
+    OfflineDataProvider,
+};
+use polars::prelude::{CsvWriter, DataType, Field, Schema, SerWriter, TimeUnit};
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, remove_dir_all, File},
+    path::{Path, PathBuf},
+    time::Duration as StdDuration,
+};
+use strategy::{Strategy, StrategyId};
+use tokio::{spawn, time::timeout};
+use tokio_stream::StreamExt;
+
+static SYMBOL: &str = "BTCUSDT";
+
+fn get_trading_settings() -> TradingSettings {
+    get_trading_settings_with_granularity(Granularity::m1)
+}
+
+fn get_trading_settings_with_granularity(granularity: Granularity) -> TradingSettings {
+    TradingSettings::new(
+        1.0,
+        Leverage::Isolated(10),
+        OrderType::Market,
+        OrderType::Market,
+        PositionLock::None,
+        HashMap::new(),
+        false,
+        &SymbolId::Bitcoin,
+        &SymbolId::Bitcoin,
+        1,
+        granularity,
+    )
+}
+
+fn get_kline_schema() -> Schema {
+    Schema::from_iter(vec![
+        Field::new(
+            "start_time".into(),
+            DataType::Datetime(TimeUnit::Milliseconds, None),
+        ),
+        Field::new(format!("{}_open", SYMBOL).into(), DataType::Float64),
+        Field::new(format!("{}_high", SYMBOL).into(), DataType::Float64),
+        Field::new(format!("{}_low", SYMBOL).into(), DataType::Float64),
+        Field::new(format!("{}_close", SYMBOL).into(), DataType::Float64),
+    ])
+}
+
+fn new_data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("glow_offline_{}", name));
+    let _ = remove_dir_all(&dir);
+    dir
+}
+
+fn get_day_ticks(date: NaiveDate, minutes: i64, missing_minutes: &[i64]) -> Vec<TickData> {
+    let day_start = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+    (0..minutes)
+        .filter(|minute| !missing_minutes.contains(minute))
+        .map(|minute| {
+            let price = 100.0 + minute as f64;
+            TickData::new_from_string(
+                SYMBOL,
+                day_start + Duration::minutes(minute),
+                price,
+                price + 1.0,
+                price + 0.5,
+                price - 1.0,
+            )
+        })
+        .collect()
+}
+
+/// Writes a daily file holding the first `minutes` 1 minute klines of `date`, skipping `missing_minutes`
+fn write_day_file(dir: &Path, date: NaiveDate, minutes: i64, missing_minutes: &[i64]) {
+    let ticks = get_day_ticks(date, minutes, missing_minutes);
+    let mut df = map_ticks_data_to_df(&ticks).unwrap();
+    let path = get_tick_data_csv_path_from_dir(dir, date, SYMBOL);
+    create_dir_all(path.parent().unwrap()).unwrap();
+    CsvWriter::new(File::create(path).unwrap())
+        .include_header(true)
+        .finish(&mut df)
+        .unwrap();
+}
+
+fn new_data_provider(dir: PathBuf, replay_interval: StdDuration) -> OfflineDataProvider {
+    new_data_provider_with_settings(dir, replay_interval, get_trading_settings())
+}
+
+fn new_data_provider_with_settings(
+    dir: PathBuf,
+    replay_interval: StdDuration,
+    trading_settings: TradingSettings,
+) -> OfflineDataProvider {
+    let strategy = Strategy::new(StrategyId::default(), trading_settings.symbols_pair).unwrap();
+    let settings = OfflineDataSettings {
+        directory: dir,
+        replay_interval,
+        replay_end: None,
+    };
+    OfflineDataProvider::with_settings(&trading_settings, &strategy, settings).unwrap()
+}
+
+fn get_benchmark_datetimes() -> (DateTime<Utc>, DateTime<Utc>) {
+    (
+        Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_loads_benchmark_klines_from_daily_files() {
+    let dir = new_data_dir("benchmark");
+    write_day_file(
+        &dir,
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        1440,
+        &[],
+    );
+    let mut data_provider = new_data_provider(dir, StdDuration::ZERO);
+    let (benchmark_start, benchmark_end) = get_benchmark_datetimes();
+
+    data_provider
+        .init(
+            Some(benchmark_start),
+            Some(benchmark_end),
+            true,
+            get_kline_schema(),
+        )
+        .await
+        .unwrap();
+
+    let TradingDataUpdate::Initial(initial_df) = data_provider.get_kline_data_emitter().value()
+    else {
+        panic!("initial klines to be emitted");
+    };
+    let strategy =
+        Strategy::new(StrategyId::default(), get_trading_settings().symbols_pair).unwrap();
+    let expected_klines = 720 + strategy.get_minimum_klines_for_calculation().unwrap() as usize;
+    assert_eq!(initial_df.height(), expected_klines);
+    assert_eq!(initial_df.get_column_names().len(), 5);
+}
+
+#[tokio::test]
+async fn test_loads_benchmark_klines_from_parquet_store() {
+    let dir = new_data_dir("parquet");
+    let ticks = get_day_ticks(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 1440, &[]);
+    save_kline_df_to_parquet_dir(&dir, &map_ticks_data_to_df(&ticks).unwrap(), SYMBOL).unwrap();
+    let mut data_provider = new_data_provider(dir, StdDuration::ZERO);
+    let (benchmark_start, benchmark_end) = get_benchmark_datetimes();
+
+    data_provider
+        .init(
+            Some(benchmark_start),
+            Some(benchmark_end),
+            true,
+            get_kline_schema(),
+        )
+        .await
+        .unwrap();
+
+    let TradingDataUpdate::Initial(initial_df) = data_provider.get_kline_data_emitter().value()
+    else {
+        panic!("initial klines to be emitted");
+    };
+    assert!(initial_df.height() >= 720);
+}
+
+#[tokio::test]
+async fn test_sets_stored_funding_rates_within_benchmark() {
+    let dir = new_data_dir("funding");
+    let ticks = get_day_ticks(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), 1440, &[]);
+    save_kline_df_to_parquet_dir(&dir, &map_ticks_data_to_df(&ticks).unwrap(), SYMBOL).unwrap();
+    let funding_time = Utc.with_ymd_and_hms(2024, 1, 1, 16, 0, 0).unwrap();
+    save_funding_rates_to_dir(
+        &dir,
+        SYMBOL,
+        &[
+            FundingRate::new(funding_time - Duration::hours(8), 0.0002),
+            FundingRate::new(funding_time, 0.0001),
+        ],
+    )
+    .unwrap();
+    let mut data_provider = new_data_provider(dir, StdDuration::ZERO);
+    let (benchmark_start, benchmark_end) = get_benchmark_datetimes();
+    let funding_rate_col = get_funding_rate_col(SYMBOL);
+    let mut schema = get_kline_schema();
+    schema.with_column(funding_rate_col.clone().into(), DataType::Float64);
+
+    data_provider
+        .init(Some(benchmark_start), Some(benchmark_end), true, schema)
+        .await
+        .unwrap();
+
+    let TradingDataUpdate::Initial(initial_df) = data_provider.get_kline_data_emitter().value()
+    else {
+        panic!("initial klines to be emitted");
+    };
+    let funding_rates = initial_df.column(&funding_rate_col).unwrap().f64().unwrap();
+    // the first funding rate precedes the benchmark start
+    assert_eq!(
+        funding_rates.into_iter().flatten().collect::<Vec<_>>(),
+        [0.0001]
+    );
+    let funding_kline_start = initial_df
+        .column("start_time")
+        .unwrap()
+        .datetime()
+        .unwrap()
+        .get(funding_rates.first_non_null().unwrap())
+        .unwrap();
+    assert_eq!(funding_kline_start, funding_time.timestamp_millis());
+}
+
+#[tokio::test]
+async fn test_keeps_minute_path_within_coarse_klines() {
+    let dir = new_data_dir("intrabar");
+    // hourly klines required for calculation reach back a few days before the benchmark start
+    let ticks: Vec<TickData> = NaiveDate::from_ymd_opt(2023, 12, 25)
+        .unwrap()
+        .iter_days()
+        .take(8)
+        .flat_map(|date| get_day_ticks(date, 1440, &[]))
+        .collect();
+    save_kline_df_to_parquet_dir(&dir, &map_ticks_data_to_df(&ticks).unwrap(), SYMBOL).unwrap();
+    let trading_settings = get_trading_settings_with_granularity(Granularity::h1);
+    let mut data_provider =
+        new_data_provider_with_settings(dir, StdDuration::ZERO, trading_settings);
+    let (benchmark_start, benchmark_end) = get_benchmark_datetimes();
+    let mut schema = get_kline_schema();
+    for field in get_intrabar_fields(SYMBOL) {
+        schema.with_column(field.name, field.dtype);
+    }
+
+    data_provider
+        .init(Some(benchmark_start), Some(benchmark_end), true, schema)
+        .await
+        .unwrap();
+
+    let TradingDataUpdate::Initial(initial_df) = data_provider.get_kline_data_emitter().value()
+    else {
+        panic!("initial klines to be emitted");
+    };
+    let kline_start = initial_df
+        .column("start_time")
+        .unwrap()
+        .datetime()
+        .unwrap()
+        .get(0)
+        .unwrap();
+    let paths = get_intrabar_paths(&initial_df, SYMBOL).unwrap().unwrap();
+    assert_eq!(paths.len(), initial_df.height());
+    let path = &paths[0];
+    assert_eq!(path.len(), 60);
+    assert_eq!(path[0].0, kline_start);
+    assert_eq!(path[59].0, kline_start + 59 * 60_000);
+    let (_, highs, lows, _) =
+        get_price_columns(&initial_df, get_trading_settings().symbols_pair.traded).unwrap();
+    let path_high = path.iter().map(|minute| minute.1).fold(f64::MIN, f64::max);
+    let path_low = path.iter().map(|minute| minute.2).fold(f64::MAX, f64::min);
+    assert_eq!((path_high, path_low), (highs[0], lows[0]));
+}
+
+#[tokio::test]
+async fn test_reports_missing_klines_as_gaps() {
+    let dir = new_data_dir("gaps");
+    write_day_file(
+        &dir,
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        1440,
+        &[780, 781, 782, 900],
+    );
+    let mut data_provider = new_data_provider(dir, StdDuration::ZERO);
+    let (benchmark_start, benchmark_end) = get_benchmark_datetimes();
+
+    let error = data_provider
+        .init(
+            Some(benchmark_start),
+            Some(benchmark_end),
+            true,
+            get_kline_schema(),
+        )
+        .await
+        .unwrap_err();
+
+    let gaps = KlineDataGap::from_error(&error).expect("error to hold kline gaps");
+    let day_start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        gaps,
+        vec![
+            KlineDataGap {
+                symbol: SYMBOL.to_string(),
+                start: day_start + Duration::minutes(780),
+                end: day_start + Duration::minutes(783),
+            },
+            KlineDataGap {
+                symbol: SYMBOL.to_string(),
+                start: day_start + Duration::minutes(900),
+                end: day_start + Duration::minutes(901),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_replays_klines_after_benchmark_as_market_updates() {
+    let dir = new_data_dir("replay");
+    write_day_file(
+        &dir,
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        1440,
+        &[],
+    );
+    write_day_file(&dir, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(), 5, &[]);
+    let mut data_provider = new_data_provider(dir, StdDuration::from_millis(50));
+    let mut subscription = data_provider.get_kline_data_emitter().subscribe();
+    let (benchmark_start, benchmark_end) = get_benchmark_datetimes();
+
+    let init_handle = spawn(async move {
+        data_provider
+            .init(
+                Some(benchmark_start),
+                Some(benchmark_end),
+                false,
+                get_kline_schema(),
+            )
+            .await
+    });
+
+    let mut replayed_start_times = vec![];
+    let _ = timeout(StdDuration::from_secs(10), async {
+        while let Some(update) = subscription.next().await {
+            if let TradingDataUpdate::Market(market_df) = update {
+                let start_time = market_df
+                    .column("start_time")
+                    .unwrap()
+                    .datetime()
+                    .unwrap()
+                    .get(0)
+                    .unwrap();
+                replayed_start_times.push(start_time);
+                if replayed_start_times.len() == 5 {
+                    break;
+                }
+            }
+        }
+    })
+    .await;
+
+    init_handle.await.unwrap().unwrap();
+    let expected_start_times: Vec<i64> = (0..5)
+        .map(|minute| (benchmark_end + Duration::minutes(minute)).timestamp_millis())
+        .collect();
+    assert_eq!(replayed_start_times, expected_start_times);
+}
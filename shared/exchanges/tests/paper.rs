@@ -0,0 +1,177 @@
+use common::{
+    enums::{
+        granularity::Granularity,
+        modifiers::{leverage::Leverage, position_lock::PositionLock, price_level::PriceLevel},
+        order_action::OrderAction,
+        order_status::OrderStatus,
+        order_type::OrderType,
+        side::Side,
+        symbol_id::SymbolId,
+    },
+    structs::TradingSettings,
+    traits::exchange::TraderExchange,
+};
+use exchanges::paper::{structs::PaperKline, PaperTraderExchange};
+use std::collections::HashMap;
+
+fn get_trading_settings(open_order_type: OrderType) -> TradingSettings {
+    let mut price_level_modifier_map = HashMap::new();
+    price_level_modifier_map.insert("sl".to_string(), PriceLevel::StopLoss(0.5));
+    price_level_modifier_map.insert("tp".to_string(), PriceLevel::TakeProfit(1.0));
+    TradingSettings::new(
+        50.0,
+        Leverage::Isolated(10),
+        open_order_type,
+        OrderType::Market,
+        PositionLock::None,
+        price_level_modifier_map,
+        false,
+        &SymbolId::Bitcoin,
+        &SymbolId::Bitcoin,
+        1,
+        Granularity::m1,
+    )
+}
+
+fn new_kline(low: f64, high: f64) -> PaperKline {
+    PaperKline::new(0, (low + high) / 2.0, high, low, (low + high) / 2.0)
+}
+
+#[tokio::test]
+async fn test_market_open_order_fills_at_expected_price() {
+    let exchange = PaperTraderExchange::new(&get_trading_settings(OrderType::Market));
+
+    let order = exchange
+        .open_order(Side::Buy, 100.0, 30000.0)
+        .await
+        .unwrap();
+
+    assert_eq!(order.status, OrderStatus::Filled);
+    assert_eq!(order.get_executed_avg_price(), 30000.0);
+    let executions = exchange.get_executions_update_emitter().value();
+    assert_eq!(executions.len(), 1);
+    assert!(!executions[0].is_maker);
+    let OrderAction::Update(updated_order) = exchange.get_order_update_emitter().value() else {
+        panic!("open order update to be emitted");
+    };
+    assert_eq!(updated_order.id, order.id);
+
+    let open_fee = executions[0].fee;
+    let margin = order.units * 30000.0 / 10.0;
+    let balance = exchange.get_balance_update_emitter().value();
+    assert!((balance.wallet_balance - (100.0 - open_fee)).abs() < 1e-9);
+    assert!((balance.available_to_withdraw - (100.0 - open_fee - margin)).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_limit_open_order_fills_once_kline_reaches_it() {
+    let exchange = PaperTraderExchange::new(&get_trading_settings(OrderType::Limit));
+
+    let order = exchange
+        .open_order(Side::Buy, 100.0, 30000.0)
+        .await
+        .unwrap();
+    let limit_price = order.avg_price.unwrap();
+    assert!(limit_price < 30000.0);
+    assert_eq!(order.status, OrderStatus::StandBy);
+
+    exchange
+        .process_kline(new_kline(limit_price + 10.0, 30100.0))
+        .unwrap();
+    let trade = exchange.fetch_current_trade_position().await.unwrap();
+    assert_eq!(trade.unwrap().open_order.status, OrderStatus::StandBy);
+
+    exchange
+        .process_kline(new_kline(limit_price - 10.0, 30100.0))
+        .unwrap();
+    let trade = exchange
+        .fetch_current_trade_position()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade.open_order.status, OrderStatus::Filled);
+    let executions = exchange.get_executions_update_emitter().value();
+    assert!(executions[0].is_maker);
+    assert_eq!(executions[0].price, limit_price);
+    assert_eq!(executions[0].fee_rate, exchange.fee_rates.0);
+}
+
+#[tokio::test]
+async fn test_stop_loss_closes_trade_on_kline_low() {
+    let exchange = PaperTraderExchange::new(&get_trading_settings(OrderType::Market));
+    let order = exchange
+        .open_order(Side::Buy, 100.0, 30000.0)
+        .await
+        .unwrap();
+    let stop_loss_price = order.stop_loss_price.unwrap();
+    // 5% below the entry price with the 0.5 stop loss at 10x, rounded down to the tick size
+    assert!((28499.9..=28500.0).contains(&stop_loss_price));
+
+    exchange.process_kline(new_kline(28000.0, 30000.0)).unwrap();
+
+    let OrderAction::Stop(stop_order) = exchange.get_order_update_emitter().value() else {
+        panic!("stop order to be emitted");
+    };
+    assert_eq!(stop_order.status, OrderStatus::StoppedSL);
+    assert!((stop_order.get_executed_avg_price() - stop_loss_price).abs() < 1e-6);
+    assert!(exchange
+        .fetch_current_trade_position()
+        .await
+        .unwrap()
+        .is_none());
+
+    let open_fee = order.executions[0].fee;
+    let close_fee = stop_order.executions[0].fee;
+    let expected_wallet_balance =
+        100.0 - open_fee + (stop_loss_price - 30000.0) * order.units - close_fee;
+    let balance = exchange.get_balance_update_emitter().value();
+    assert!((balance.wallet_balance - expected_wallet_balance).abs() < 1e-9);
+    assert_eq!(balance.available_to_withdraw, balance.wallet_balance);
+}
+
+#[tokio::test]
+async fn test_close_position_settles_profit_into_wallet() {
+    let exchange = PaperTraderExchange::new(&get_trading_settings(OrderType::Market));
+    let order = exchange
+        .open_order(Side::Sell, 100.0, 30000.0)
+        .await
+        .unwrap();
+    let trade = exchange
+        .fetch_current_trade_position()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let close_order = exchange.try_close_position(&trade, 29000.0).await.unwrap();
+
+    assert_eq!(close_order.status, OrderStatus::Closed);
+    let open_fee = order.executions[0].fee;
+    let close_fee = close_order.executions[0].fee;
+    let expected_wallet_balance = 100.0 - open_fee + 1000.0 * order.units - close_fee;
+    let balance = exchange.fetch_current_usdt_balance().await.unwrap();
+    assert!((balance.wallet_balance - expected_wallet_balance).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_cancel_resting_limit_order() {
+    let exchange = PaperTraderExchange::new(&get_trading_settings(OrderType::Limit));
+    let order = exchange
+        .open_order(Side::Buy, 100.0, 30000.0)
+        .await
+        .unwrap();
+
+    assert!(exchange.cancel_order(order.id.clone()).await.unwrap());
+
+    let OrderAction::Cancel(cancelled_order) = exchange.get_order_update_emitter().value() else {
+        panic!("cancelled order to be emitted");
+    };
+    assert_eq!(cancelled_order.id, order.id);
+    assert!(exchange
+        .fetch_current_trade_position()
+        .await
+        .unwrap()
+        .is_none());
+    assert!(!exchange.cancel_order(order.id).await.unwrap());
+    let balance = exchange.get_balance_update_emitter().value();
+    assert_eq!(balance.available_to_withdraw, 100.0);
+}
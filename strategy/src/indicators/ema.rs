@@ -0,0 +1,88 @@
+use super::IndicatorBuilder;
+use crate::functions::calculate_span_alpha;
+use common::structs::Symbol;
+use glow_error::GlowError;
+use polars::prelude::*;
+
+pub const TREND_COL: &str = "EMA_bullish";
+
+/// Fast and slow exponential moving averages of the symbol close, and whether the fast one is above the slow one
+#[derive(Clone, Copy, Debug)]
+pub struct ExponentialMovingAverages {
+    pub symbol: &'static Symbol,
+    pub fast_span: u32,
+    pub slow_span: u32,
+}
+
+impl ExponentialMovingAverages {
+    pub fn new(symbol: &'static Symbol, fast_span: u32, slow_span: u32) -> Self {
+        Self {
+            symbol,
+            fast_span,
+            slow_span,
+        }
+    }
+
+    pub fn get_fast_ema_col(&self) -> String {
+        format!("{}_fast_ema", self.symbol.name)
+    }
+
+    pub fn get_slow_ema_col(&self) -> String {
+        format!("{}_slow_ema", self.symbol.name)
+    }
+}
+
+impl IndicatorBuilder for ExponentialMovingAverages {
+    fn append_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let close_col = self.symbol.get_close_col();
+        let ema_fast_col = &self.get_fast_ema_col();
+        let ema_slow_col = &self.get_slow_ema_col();
+
+        let fast_alpha = calculate_span_alpha(self.fast_span as f64)?;
+        let slow_alpha = calculate_span_alpha(self.slow_span as f64)?;
+        let fast_opts = EWMOptions {
+            alpha: fast_alpha,
+            adjust: false,
+            bias: false,
+            min_periods: 1,
+            ignore_nulls: false,
+        };
+        let slow_opts = EWMOptions {
+            alpha: slow_alpha,
+            adjust: false,
+            bias: false,
+            min_periods: 1,
+            ignore_nulls: false,
+        };
+
+        let lf = lf
+            .with_columns([
+                col(close_col).ewm_mean(slow_opts).alias(ema_slow_col),
+                col(close_col).ewm_mean(fast_opts).alias(ema_fast_col),
+            ])
+            .with_column(
+                when(col(ema_fast_col).is_null().or(col(ema_slow_col).is_null()))
+                    .then(lit(NULL))
+                    .otherwise(
+                        when(col(ema_fast_col).gt(col(ema_slow_col)))
+                            .then(true)
+                            .otherwise(false),
+                    )
+                    .alias(TREND_COL),
+            );
+
+        Ok(lf)
+    }
+
+    fn get_columns(&self) -> Vec<(String, DataType)> {
+        vec![
+            (self.get_fast_ema_col(), DataType::Float64),
+            (self.get_slow_ema_col(), DataType::Float64),
+            (TREND_COL.to_string(), DataType::Boolean),
+        ]
+    }
+
+    fn get_minimum_klines_for_calculation(&self) -> u32 {
+        self.slow_span
+    }
+}
@@ -0,0 +1,83 @@
+use ema::ExponentialMovingAverages;
+use enum_dispatch::enum_dispatch;
+use glow_error::GlowError;
+use polars::prelude::*;
+use rma_clouds::{RmaClouds, RmaCloudsMomentum};
+use stc::SchaffTrendCycle;
+use stochastic::Stochastic;
+use tsi::TrueStrengthIndex;
+
+pub mod ema;
+pub mod rma_clouds;
+pub mod stc;
+pub mod stochastic;
+pub mod tsi;
+
+/// Appends indicator columns to klines LazyFrames.
+///
+/// Builders are meant to be composed by `Schema` implementations, which forward
+/// `get_columns` and `get_minimum_klines_for_calculation` from their own
+/// `get_indicators_columns` and `get_minimum_klines_for_calculation`.
+#[enum_dispatch]
+pub trait IndicatorBuilder {
+    fn append_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError>;
+    fn get_columns(&self) -> Vec<(String, DataType)>;
+    fn get_minimum_klines_for_calculation(&self) -> u32;
+}
+
+#[enum_dispatch(IndicatorBuilder)]
+#[derive(Clone, Copy, Debug)]
+pub enum IndicatorBuilderWrapper {
+    ExponentialMovingAverages(ExponentialMovingAverages),
+    Stochastic(Stochastic),
+    TrueStrengthIndex(TrueStrengthIndex),
+    SchaffTrendCycle(SchaffTrendCycle),
+    RmaClouds(RmaClouds),
+    RmaCloudsMomentum(RmaCloudsMomentum),
+}
+
+pub fn get_fixed_window_opts(window_size: u32) -> RollingOptionsFixedWindow {
+    RollingOptionsFixedWindow {
+        window_size: window_size as usize,
+        min_periods: window_size as usize,
+        ..Default::default()
+    }
+}
+
+pub fn get_ewm_opts(alpha: f64, min_periods: usize) -> EWMOptions {
+    EWMOptions {
+        alpha,
+        adjust: false,
+        bias: false,
+        min_periods,
+        ignore_nulls: true,
+    }
+}
+
+type RecursiveCalculation = fn(&[Option<f64>], &[f64]) -> Vec<Option<f64>>;
+
+/// Applies a sequential calculation over a whole f64 column, for indicators that depend on their own previous values
+pub fn apply_recursive(
+    expr: Expr,
+    name: &str,
+    calculate: RecursiveCalculation,
+    args: Vec<f64>,
+) -> Expr {
+    let output_name: PlSmallStr = name.into();
+    let returns_output: SpecialEq<Arc<dyn FunctionOutputField>> =
+        GetOutput::from_type(DataType::Float64);
+    expr.apply_many(
+        move |columns| {
+            let values: Vec<Option<f64>> = columns[0]
+                .cast(&DataType::Float64)?
+                .f64()?
+                .into_iter()
+                .collect();
+            let result = calculate(&values, &args);
+            Ok(Some(Column::new(output_name.clone(), result)))
+        },
+        &[],
+        returns_output,
+    )
+    .alias(name)
+}
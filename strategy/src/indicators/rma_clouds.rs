@@ -0,0 +1,296 @@
+use super::{apply_recursive, get_fixed_window_opts, IndicatorBuilder};
+use common::structs::Symbol;
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Fast and slow running moving averages (Wilder's smoothing, as pine's `ta.rma`),
+/// the close cloud over the last `cloud_length` klines, and the trend they describe.
+#[derive(Clone, Copy, Debug)]
+pub struct RmaClouds {
+    pub symbol: &'static Symbol,
+    pub fast_period: u32,
+    pub slow_period: u32,
+    pub cloud_length: u32,
+}
+
+// pine_rma(src, length) =>
+//      alpha = 1/length
+//      sum = 0.0
+//      sum := na(sum[1]) ? ta.sma(src, length) : alpha * src + (1 - alpha) * nz(sum[1])
+/// args: [length]
+pub fn calculate_rma(values: &[Option<f64>], args: &[f64]) -> Vec<Option<f64>> {
+    let length = args[0] as usize;
+    let alpha = 1.0 / args[0];
+    let mut result: Vec<Option<f64>> = Vec::with_capacity(values.len());
+    let mut prev_rma: Option<f64> = None;
+    for index in 0..values.len() {
+        let rma = match (prev_rma, values[index]) {
+            (Some(prev), Some(value)) => Some(alpha * value + (1.0 - alpha) * prev),
+            (Some(prev), None) => Some(prev),
+            (None, Some(_)) if index + 1 >= length => {
+                let window: Option<Vec<f64>> =
+                    values[index + 1 - length..=index].iter().copied().collect();
+                window.map(|window| window.iter().sum::<f64>() / length as f64)
+            }
+            (None, _) => None,
+        };
+        prev_rma = rma;
+        result.push(rma);
+    }
+    result
+}
+
+impl RmaClouds {
+    pub fn new(
+        symbol: &'static Symbol,
+        fast_period: u32,
+        slow_period: u32,
+        cloud_length: u32,
+    ) -> Self {
+        Self {
+            symbol,
+            fast_period,
+            slow_period,
+            cloud_length,
+        }
+    }
+
+    pub fn get_fast_rma_col(&self) -> String {
+        format!("{}_rma_fast", self.symbol.name)
+    }
+
+    pub fn get_slow_rma_col(&self) -> String {
+        format!("{}_rma_slow", self.symbol.name)
+    }
+
+    pub fn get_cloud_top_col(&self) -> String {
+        format!("{}_{}_cloud_top", self.symbol.name, self.cloud_length)
+    }
+
+    pub fn get_cloud_base_col(&self) -> String {
+        format!("{}_{}_cloud_base", self.symbol.name, self.cloud_length)
+    }
+
+    /// One of `strong_bull`, `slight_bull`, `revert_bull`, `strong_bear`, `slight_bear`, `revert_bear` or `undefined`
+    pub fn get_trend_col(&self) -> String {
+        format!("{}_rma_trend", self.symbol.name)
+    }
+}
+
+impl IndicatorBuilder for RmaClouds {
+    fn append_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let close_col = self.symbol.get_close_col();
+        let rma_fast_col = &self.get_fast_rma_col();
+        let rma_slow_col = &self.get_slow_rma_col();
+        let cloud_opts = get_fixed_window_opts(self.cloud_length);
+
+        let lf = lf
+            .with_columns([
+                apply_recursive(
+                    col(close_col),
+                    rma_fast_col,
+                    calculate_rma,
+                    vec![self.fast_period as f64],
+                ),
+                apply_recursive(
+                    col(close_col),
+                    rma_slow_col,
+                    calculate_rma,
+                    vec![self.slow_period as f64],
+                ),
+                col(close_col)
+                    .rolling_max(cloud_opts.clone())
+                    .alias(self.get_cloud_top_col()),
+                col(close_col)
+                    .rolling_min(cloud_opts)
+                    .alias(self.get_cloud_base_col()),
+            ])
+            .with_column(
+                when(
+                    col(rma_slow_col)
+                        .lt(col(rma_fast_col))
+                        .and(col(rma_fast_col).lt(col(close_col))),
+                )
+                .then(lit("strong_bull"))
+                .when(
+                    col(rma_slow_col)
+                        .lt(col(close_col))
+                        .and(col(close_col).lt(col(rma_fast_col))),
+                )
+                .then(lit("slight_bull"))
+                .when(
+                    col(close_col)
+                        .lt(col(rma_slow_col))
+                        .and(col(rma_slow_col).lt(col(rma_fast_col))),
+                )
+                .then(lit("revert_bull"))
+                .when(
+                    col(close_col)
+                        .lt(col(rma_fast_col))
+                        .and(col(rma_fast_col).lt(col(rma_slow_col))),
+                )
+                .then(lit("strong_bear"))
+                .when(
+                    col(rma_fast_col)
+                        .lt(col(close_col))
+                        .and(col(close_col).lt(col(rma_slow_col))),
+                )
+                .then(lit("slight_bear"))
+                .when(
+                    col(rma_fast_col)
+                        .lt(col(rma_slow_col))
+                        .and(col(rma_slow_col).lt(col(close_col))),
+                )
+                .then(lit("revert_bear"))
+                .otherwise(lit("undefined"))
+                .alias(self.get_trend_col()),
+            );
+
+        Ok(lf)
+    }
+
+    fn get_columns(&self) -> Vec<(String, DataType)> {
+        vec![
+            (self.get_fast_rma_col(), DataType::Float64),
+            (self.get_slow_rma_col(), DataType::Float64),
+            (self.get_cloud_top_col(), DataType::Float64),
+            (self.get_cloud_base_col(), DataType::Float64),
+            (self.get_trend_col(), DataType::String),
+        ]
+    }
+
+    fn get_minimum_klines_for_calculation(&self) -> u32 {
+        self.slow_period
+            .max(self.fast_period)
+            .max(self.cloud_length)
+    }
+}
+
+/// Momentum of the RMA clouds: relative distances between close and both RMAs, and the
+/// ranking of the fast/slow RMA spread summed over the last 4 `momentum_span` windows.
+///
+/// The ranking is a string like `1234`, which lists the windows (1 being the oldest and
+/// 4 the current one) from the lowest summed spread to the highest; `1234` means the
+/// spread widened at every window.
+#[derive(Clone, Copy, Debug)]
+pub struct RmaCloudsMomentum {
+    pub rma_clouds: RmaClouds,
+    pub momentum_span: u32,
+}
+
+const MOMENTUM_WINDOWS: i64 = 4;
+
+/// Lists each row's windows by ascending value, `windows` being ordered from the oldest to the newest
+fn calculate_trends(windows: &[Vec<Option<f64>>]) -> Vec<Option<String>> {
+    let rows = windows
+        .first()
+        .map(|values| values.len())
+        .unwrap_or_default();
+    (0..rows)
+        .map(|index| {
+            let values: Option<Vec<f64>> = windows.iter().map(|values| values[index]).collect();
+            let mut ranked: Vec<(usize, f64)> = values?.into_iter().enumerate().collect();
+            ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+            Some(
+                ranked
+                    .iter()
+                    .map(|(window_index, _)| (window_index + 1).to_string())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+impl RmaCloudsMomentum {
+    pub fn new(rma_clouds: RmaClouds, momentum_span: u32) -> Self {
+        Self {
+            rma_clouds,
+            momentum_span,
+        }
+    }
+
+    pub fn get_trend_diff_col(&self) -> String {
+        format!("{}_trend_diff", self.rma_clouds.symbol.name)
+    }
+
+    pub fn get_close_fast_diff_col(&self) -> String {
+        format!("{}_close_fast_diff", self.rma_clouds.symbol.name)
+    }
+
+    pub fn get_close_slow_diff_col(&self) -> String {
+        format!("{}_close_slow_diff", self.rma_clouds.symbol.name)
+    }
+
+    pub fn get_trends_col(&self) -> String {
+        format!("{}_trends", self.rma_clouds.symbol.name)
+    }
+}
+
+impl IndicatorBuilder for RmaCloudsMomentum {
+    fn append_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let close_col = self.rma_clouds.symbol.get_close_col();
+        let rma_fast_col = &self.rma_clouds.get_fast_rma_col();
+        let rma_slow_col = &self.rma_clouds.get_slow_rma_col();
+        let trend_diff_col = &self.get_trend_diff_col();
+        let trends_col: PlSmallStr = self.get_trends_col().into();
+        let lag = self.momentum_span as i64;
+
+        let lf = self.rma_clouds.append_to_lf(lf)?.with_columns([
+            ((col(rma_fast_col) - col(rma_slow_col)) / col(rma_slow_col)).alias(trend_diff_col),
+            ((col(close_col) - col(rma_fast_col)) / col(rma_fast_col))
+                .alias(self.get_close_fast_diff_col()),
+            ((col(close_col) - col(rma_slow_col)) / col(rma_slow_col))
+                .alias(self.get_close_slow_diff_col()),
+        ]);
+
+        let momentum = col(trend_diff_col).rolling_sum(get_fixed_window_opts(self.momentum_span));
+        // oldest window first
+        let lagged_momentums: Vec<Expr> = (1..MOMENTUM_WINDOWS)
+            .rev()
+            .map(|window| momentum.clone().shift(lit(window * lag)))
+            .collect();
+        let returns_output: SpecialEq<Arc<dyn FunctionOutputField>> =
+            GetOutput::from_type(DataType::String);
+
+        let lf = lf.with_column(
+            momentum
+                .apply_many(
+                    move |columns| {
+                        let mut windows = Vec::with_capacity(columns.len());
+                        // apply_many puts the expression itself first, the newest window
+                        for column in columns[1..].iter().chain(columns[..1].iter()) {
+                            let values: Vec<Option<f64>> = column
+                                .cast(&DataType::Float64)?
+                                .f64()?
+                                .into_iter()
+                                .collect();
+                            windows.push(values);
+                        }
+                        let result = calculate_trends(&windows);
+                        Ok(Some(Column::new(trends_col.clone(), result)))
+                    },
+                    &lagged_momentums,
+                    returns_output,
+                )
+                .alias(self.get_trends_col()),
+        );
+
+        Ok(lf)
+    }
+
+    fn get_columns(&self) -> Vec<(String, DataType)> {
+        let mut columns = self.rma_clouds.get_columns();
+        columns.push((self.get_trend_diff_col(), DataType::Float64));
+        columns.push((self.get_close_fast_diff_col(), DataType::Float64));
+        columns.push((self.get_close_slow_diff_col(), DataType::Float64));
+        columns.push((self.get_trends_col(), DataType::String));
+        columns
+    }
+
+    fn get_minimum_klines_for_calculation(&self) -> u32 {
+        let momentum_klines = self.rma_clouds.slow_period.max(self.rma_clouds.fast_period)
+            + self.momentum_span * MOMENTUM_WINDOWS as u32
+            - 1;
+        momentum_klines.max(self.rma_clouds.get_minimum_klines_for_calculation())
+    }
+}
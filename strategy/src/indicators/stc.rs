@@ -0,0 +1,117 @@
+use super::{apply_recursive, get_ewm_opts, IndicatorBuilder};
+use crate::functions::calculate_span_alpha;
+use common::structs::Symbol;
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Schaff trend cycle: a doubly smoothed stochastic of the MACD line.
+/// `weight` is the smoothing factor applied after each stochastic (usually 0.5).
+#[derive(Clone, Copy, Debug)]
+pub struct SchaffTrendCycle {
+    pub symbol: &'static Symbol,
+    pub length: u32,
+    pub fast_span: u32,
+    pub slow_span: u32,
+    pub weight: f64,
+}
+
+impl SchaffTrendCycle {
+    pub fn new(
+        symbol: &'static Symbol,
+        length: u32,
+        fast_span: u32,
+        slow_span: u32,
+        weight: f64,
+    ) -> Self {
+        Self {
+            symbol,
+            length,
+            fast_span,
+            slow_span,
+            weight,
+        }
+    }
+
+    pub fn get_stc_col(&self) -> String {
+        format!("STC_{}", self.symbol.name)
+    }
+}
+
+/// Stochastic of `values` over `length` entries, followed by `weight` smoothing.
+/// A flat range repeats the previous stochastic value.
+fn smoothed_stochastic(values: &[Option<f64>], length: usize, weight: f64) -> Vec<Option<f64>> {
+    let mut result: Vec<Option<f64>> = Vec::with_capacity(values.len());
+    let mut prev_stochastic: Option<f64> = None;
+    let mut prev_smoothed: Option<f64> = None;
+    for index in 0..values.len() {
+        let window_start = (index + 1).saturating_sub(length);
+        let window = &values[window_start..=index];
+        let (Some(value), true) = (values[index], window.len() == length) else {
+            result.push(None);
+            continue;
+        };
+        let window: Option<Vec<f64>> = window.iter().copied().collect();
+        let Some(window) = window else {
+            result.push(None);
+            continue;
+        };
+        let lowest = window.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest = window.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = highest - lowest;
+        let stochastic = if range > 0.0 {
+            (value - lowest) / range * 100.0
+        } else {
+            prev_stochastic.unwrap_or_default()
+        };
+        prev_stochastic = Some(stochastic);
+        let smoothed = match prev_smoothed {
+            Some(prev) => prev + weight * (stochastic - prev),
+            None => stochastic,
+        };
+        prev_smoothed = Some(smoothed);
+        result.push(Some(smoothed));
+    }
+    result
+}
+
+/// args: [length, weight]
+fn calculate_stc(macd: &[Option<f64>], args: &[f64]) -> Vec<Option<f64>> {
+    let length = args[0] as usize;
+    let weight = args[1];
+    let first_pass = smoothed_stochastic(macd, length, weight);
+    smoothed_stochastic(&first_pass, length, weight)
+}
+
+impl IndicatorBuilder for SchaffTrendCycle {
+    fn append_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let close_col = self.symbol.get_close_col();
+        let stc_col = &self.get_stc_col();
+
+        let fast_opts = get_ewm_opts(
+            calculate_span_alpha(self.fast_span as f64)?,
+            self.fast_span as usize,
+        );
+        let slow_opts = get_ewm_opts(
+            calculate_span_alpha(self.slow_span as f64)?,
+            self.slow_span as usize,
+        );
+        let macd = col(close_col).ewm_mean(fast_opts) - col(close_col).ewm_mean(slow_opts);
+
+        let lf = lf.with_column(apply_recursive(
+            macd,
+            stc_col,
+            calculate_stc,
+            vec![self.length as f64, self.weight],
+        ));
+
+        Ok(lf)
+    }
+
+    fn get_columns(&self) -> Vec<(String, DataType)> {
+        vec![(self.get_stc_col(), DataType::Float64)]
+    }
+
+    fn get_minimum_klines_for_calculation(&self) -> u32 {
+        self.slow_span + 2 * self.length
+    }
+}
@@ -0,0 +1,75 @@
+use super::{get_fixed_window_opts, IndicatorBuilder};
+use common::structs::Symbol;
+use glow_error::GlowError;
+use polars::prelude::*;
+
+/// Stochastic oscillator: %K compares the close to the high/low range of the last `k_length` klines,
+/// smoothed over `k_smoothing` klines, and %D is the `d_smoothing` moving average of %K.
+#[derive(Clone, Copy, Debug)]
+pub struct Stochastic {
+    pub symbol: &'static Symbol,
+    pub k_length: u32,
+    pub k_smoothing: u32,
+    pub d_smoothing: u32,
+}
+
+impl Stochastic {
+    pub fn new(symbol: &'static Symbol, k_length: u32, k_smoothing: u32, d_smoothing: u32) -> Self {
+        Self {
+            symbol,
+            k_length,
+            k_smoothing,
+            d_smoothing,
+        }
+    }
+
+    pub fn get_k_col(&self) -> String {
+        format!("K%_{}_{}", self.symbol.name, self.k_length)
+    }
+
+    pub fn get_d_col(&self) -> String {
+        format!("D%_{}_{}", self.symbol.name, self.k_length)
+    }
+}
+
+impl IndicatorBuilder for Stochastic {
+    fn append_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let (_, high_col, low_col, close_col) = self.symbol.get_ohlc_cols();
+        let k_col = &self.get_k_col();
+        let d_col = &self.get_d_col();
+
+        let k_opts = get_fixed_window_opts(self.k_length);
+        let highest = col(high_col).rolling_max(k_opts.clone());
+        let lowest = col(low_col).rolling_min(k_opts);
+        let range = highest - lowest.clone();
+        // a flat range leaves %K undefined
+        let raw_k = when(range.clone().eq(lit(0.0)))
+            .then(lit(NULL))
+            .otherwise(lit(100.0) * (col(close_col) - lowest) / range);
+
+        let k = if self.k_smoothing > 1 {
+            raw_k.rolling_mean(get_fixed_window_opts(self.k_smoothing))
+        } else {
+            raw_k
+        };
+
+        let lf = lf.with_column(k.alias(k_col)).with_column(
+            col(k_col)
+                .rolling_mean(get_fixed_window_opts(self.d_smoothing))
+                .alias(d_col),
+        );
+
+        Ok(lf)
+    }
+
+    fn get_columns(&self) -> Vec<(String, DataType)> {
+        vec![
+            (self.get_k_col(), DataType::Float64),
+            (self.get_d_col(), DataType::Float64),
+        ]
+    }
+
+    fn get_minimum_klines_for_calculation(&self) -> u32 {
+        self.k_length + self.k_smoothing + self.d_smoothing - 2
+    }
+}
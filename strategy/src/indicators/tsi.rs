@@ -0,0 +1,89 @@
+use super::{get_ewm_opts, IndicatorBuilder};
+use crate::functions::calculate_span_alpha;
+use common::structs::Symbol;
+use glow_error::GlowError;
+use polars::prelude::*;
+
+// TSI = (PCDS/APCDS) x 100
+// PC = CCP − PCP
+// PCS = 25-period EMA of PC
+// PCDS = 13-period EMA of PCS
+// APC = AVCCP − PCP
+// APCS = 25-period EMA of APC
+// APCDS = 13-period EMA of APCS
+// where:
+// PCDS = PC double smoothed
+// APCDS = Absolute PC double smoothed
+// PC = Price change
+// CCP = Current close price
+// PCP = Prior close price
+// PCS = PC smoothed
+// EMA = Exponential moving average
+// APC = Absolute PC
+// APCS = Absolute PC smoothed
+
+/// True strength index of the symbol close
+#[derive(Clone, Copy, Debug)]
+pub struct TrueStrengthIndex {
+    pub symbol: &'static Symbol,
+    pub long_span: u32,
+    pub short_span: u32,
+}
+
+impl TrueStrengthIndex {
+    pub fn new(symbol: &'static Symbol, long_span: u32, short_span: u32) -> Self {
+        Self {
+            symbol,
+            long_span,
+            short_span,
+        }
+    }
+
+    pub fn get_tsi_col(&self) -> String {
+        format!("TSI_{}", self.symbol.name)
+    }
+}
+
+impl IndicatorBuilder for TrueStrengthIndex {
+    fn append_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
+        let close_col = self.symbol.get_close_col();
+        let tsi_col = &self.get_tsi_col();
+
+        let long_opts = get_ewm_opts(
+            calculate_span_alpha(self.long_span as f64)?,
+            self.long_span as usize,
+        );
+        let short_opts = get_ewm_opts(
+            calculate_span_alpha(self.short_span as f64)?,
+            self.short_span as usize,
+        );
+
+        let price_change = when(col(close_col).shift(lit(1)).is_null())
+            .then(lit(0.0))
+            .otherwise(col(close_col) - col(close_col).shift(lit(1)));
+
+        let double_smoothed_pc = price_change
+            .clone()
+            .ewm_mean(long_opts)
+            .ewm_mean(short_opts);
+        let absolute_double_smoothed_pc =
+            price_change.abs().ewm_mean(long_opts).ewm_mean(short_opts);
+
+        let lf = lf.with_column(
+            when(absolute_double_smoothed_pc.clone().eq(lit(0.0)))
+                .then(lit(NULL))
+                .otherwise(lit(100.0) * double_smoothed_pc / absolute_double_smoothed_pc)
+                .alias(tsi_col),
+        );
+
+        Ok(lf)
+    }
+
+    fn get_columns(&self) -> Vec<(String, DataType)> {
+        vec![(self.get_tsi_col(), DataType::Float64)]
+    }
+
+    fn get_minimum_klines_for_calculation(&self) -> u32 {
+        self.long_span + self.short_span
+    }
+}
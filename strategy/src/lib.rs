@@ -2,10 +2,14 @@ use common::structs::SymbolsPair;
 use glow_error::GlowError;
 use params::{Param, ParamId};
 use polars::prelude::{DataFrame, DataType, LazyFrame};
-use schemas::{Schema, StrategySchema};
+use schemas::{
+    declarative::{definition::StrategyDefinition, DeclarativeStrategySchema},
+    Schema, StrategySchema,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 pub mod functions;
+pub mod indicators;
 pub mod params;
 pub mod schemas;
 pub mod r#static;
@@ -14,6 +18,13 @@ pub mod r#static;
 pub enum StrategyId {
     #[default]
     SimpleTrend,
+    CloudMomentum,
+    TsiStc,
+    MultipleStochasticWithThreshold,
+    RmaClouds,
+    /// Built from a `StrategyDefinition`, see `Strategy::from_definition`
+    #[serde(skip)]
+    Declarative,
 }
 
 #[derive(Clone)]
@@ -25,15 +36,27 @@ pub struct Strategy {
 }
 
 impl Strategy {
-    pub fn new(id: StrategyId, symbols_pair: SymbolsPair) -> Self {
-        let schema: StrategySchema = id.into();
+    pub fn new(id: StrategyId, symbols_pair: SymbolsPair) -> Result<Self, GlowError> {
+        let schema = StrategySchema::try_from(id)?;
         let params = schema.get_params_config();
 
-        Self {
+        Ok(Self {
             id,
             schema,
             symbols_pair,
             params,
+        })
+    }
+
+    pub fn from_definition(definition: StrategyDefinition, symbols_pair: SymbolsPair) -> Self {
+        let schema = StrategySchema::Declarative(DeclarativeStrategySchema::new(definition));
+        let params = schema.get_params_config();
+
+        Self {
+            id: StrategyId::Declarative,
+            schema,
+            symbols_pair,
+            params,
         }
     }
 
@@ -56,6 +79,17 @@ impl Strategy {
         Ok(updated)
     }
 
+    pub fn patch_number_param(&self, param_id: ParamId, value: f64) -> Result<Self, GlowError> {
+        let current_param = self.params.get(&param_id).ok_or_else(|| {
+            GlowError::new(
+                String::from("Invalid param"),
+                format!("Param {:?} doesn't exist at strategy {:?}", param_id, self.id),
+            )
+        })?;
+        let updated_param = current_param.with_number(value)?;
+        self.patch_param(param_id, updated_param)
+    }
+
     pub fn append_indicators_to_lf(&self, lf: LazyFrame) -> Result<LazyFrame, GlowError> {
         self.schema
             .append_indicators_to_lf(lf, self.symbols_pair, &self.params)
@@ -80,7 +114,7 @@ impl Strategy {
         self.schema.get_params_config()
     }
 
-    pub fn get_indicators_columns(&self) -> Vec<(String, DataType)> {
+    pub fn get_indicators_columns(&self) -> Result<Vec<(String, DataType)>, GlowError> {
         self.schema
             .get_indicators_columns(self.symbols_pair, &self.params)
     }
@@ -89,15 +123,21 @@ impl Strategy {
             .get_signals_columns(self.symbols_pair, &self.params)
     }
 
-    pub fn get_minimum_klines_for_calculation(&self) -> u32 {
+    pub fn get_minimum_klines_for_calculation(&self) -> Result<u32, GlowError> {
         self.schema.get_minimum_klines_for_calculation(&self.params)
     }
 }
 
 impl Default for Strategy {
     fn default() -> Self {
-        let symbols_pair = SymbolsPair::default();
-        let default_schema_id = StrategyId::default();
-        Self::new(default_schema_id, symbols_pair)
+        let schema = StrategySchema::default();
+        let params = schema.get_params_config();
+
+        Self {
+            id: StrategyId::default(),
+            schema,
+            symbols_pair: SymbolsPair::default(),
+            params,
+        }
     }
 }
@@ -3,10 +3,24 @@
 // use polars::prelude::TimeUnit;
 use std::fmt::Display;
 use glow_error::GlowError;
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+use serde::{Deserialize, Serialize};
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
 pub enum ParamId {
     SlowSpan,
     FastSpan,
+    LongSpan,
+    ShortSpan,
+    FastPeriod,
+    SlowPeriod,
+    CloudLength,
+    MomentumSpan,
+    KLength,
+    KSmoothing,
+    DSmoothing,
+    UpperThreshold,
+    LowerThreshold,
+    StcLength,
+    TrendPeriod,
 }
 
 #[derive(Clone, Debug)]
@@ -60,6 +74,129 @@ impl Param {
             )),
         }
     }
+
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Param::UInt8(_, _)
+                | Param::UInt16(_, _)
+                | Param::UInt32(_, _)
+                | Param::UInt64(_, _)
+                | Param::Int8(_, _)
+                | Param::Int16(_, _)
+                | Param::Int32(_, _)
+                | Param::Int64(_, _)
+        )
+    }
+
+    /// Returns (min, max) for number params that have both bounds configured
+    pub fn number_bounds(&self) -> Option<(f64, f64)> {
+        let (min, max) = match self {
+            Param::UInt8(_, config) => (config.min.map(f64::from), config.max.map(f64::from)),
+            Param::UInt16(_, config) => (config.min.map(f64::from), config.max.map(f64::from)),
+            Param::UInt32(_, config) => (config.min.map(f64::from), config.max.map(f64::from)),
+            Param::UInt64(_, config) => (
+                config.min.map(|v| v as f64),
+                config.max.map(|v| v as f64),
+            ),
+            Param::Int8(_, config) => (config.min.map(f64::from), config.max.map(f64::from)),
+            Param::Int16(_, config) => (config.min.map(f64::from), config.max.map(f64::from)),
+            Param::Int32(_, config) => (config.min.map(f64::from), config.max.map(f64::from)),
+            Param::Int64(_, config) => (
+                config.min.map(|v| v as f64),
+                config.max.map(|v| v as f64),
+            ),
+            Param::Float32(_, config) => (config.min.map(f64::from), config.max.map(f64::from)),
+            Param::Float64(_, config) => (config.min, config.max),
+            Param::Boolean(_, _) | Param::String(_, _) => (None, None),
+        };
+        min.zip(max)
+    }
+
+    /// Returns the current value for number params
+    pub fn number_value(&self) -> Option<f64> {
+        match self {
+            Param::UInt8(value, _) => Some(f64::from(*value)),
+            Param::UInt16(value, _) => Some(f64::from(*value)),
+            Param::UInt32(value, _) => Some(f64::from(*value)),
+            Param::UInt64(value, _) => Some(*value as f64),
+            Param::Int8(value, _) => Some(f64::from(*value)),
+            Param::Int16(value, _) => Some(f64::from(*value)),
+            Param::Int32(value, _) => Some(f64::from(*value)),
+            Param::Int64(value, _) => Some(*value as f64),
+            Param::Float32(value, _) => Some(f64::from(*value)),
+            Param::Float64(value, _) => Some(*value),
+            Param::Boolean(_, _) | Param::String(_, _) => None,
+        }
+    }
+
+    /// Builds a param of the same type and config as `self`, holding `value`.
+    /// Integer params require `value` to have no fractional part.
+    pub fn with_number(&self, value: f64) -> Result<Self, GlowError> {
+        let is_integer = value.fract() == 0.0;
+        let result = match self {
+            Param::Boolean(_, config) => Param::Boolean(value != 0.0, *config),
+            Param::Float32(_, _) if (value as f32).is_infinite() && value.is_finite() => {
+                return Err(GlowError::new(
+                    String::from("Invalid param value"),
+                    format!(
+                        "Number {} is out of the type range of param {:?}",
+                        value, self
+                    ),
+                ))
+            }
+            Param::Float32(_, config) => Param::Float32(value as f32, *config),
+            Param::Float64(_, config) => Param::Float64(value, *config),
+            Param::String(_, _) => {
+                return Err(GlowError::new(
+                    format!("Invalid param value"),
+                    format!("String param {:?} can't hold number {}", self, value),
+                ))
+            }
+            _ if !is_integer => {
+                return Err(GlowError::new(
+                    format!("Invalid param value"),
+                    format!("Integer param {:?} can't hold number {}", self, value),
+                ))
+            }
+            _ if !self.fits_integer_type(value) => {
+                return Err(GlowError::new(
+                    String::from("Invalid param value"),
+                    format!(
+                        "Number {} is out of the type range of param {:?}",
+                        value, self
+                    ),
+                ))
+            }
+            Param::UInt8(_, config) => Param::UInt8(value as u8, *config),
+            Param::UInt16(_, config) => Param::UInt16(value as u16, *config),
+            Param::UInt32(_, config) => Param::UInt32(value as u32, *config),
+            Param::UInt64(_, config) => Param::UInt64(value as u64, *config),
+            Param::Int8(_, config) => Param::Int8(value as i8, *config),
+            Param::Int16(_, config) => Param::Int16(value as i16, *config),
+            Param::Int32(_, config) => Param::Int32(value as i32, *config),
+            Param::Int64(_, config) => Param::Int64(value as i64, *config),
+        };
+        self.validate(&result)?;
+        Ok(result)
+    }
+
+    /// Whether the integer `value` fits the integer type of the param, as casting saturates otherwise
+    fn fits_integer_type(&self, value: f64) -> bool {
+        let (min, max) = match self {
+            Param::UInt8(_, _) => (u8::MIN as f64, u8::MAX as f64),
+            Param::UInt16(_, _) => (u16::MIN as f64, u16::MAX as f64),
+            Param::UInt32(_, _) => (u32::MIN as f64, u32::MAX as f64),
+            Param::UInt64(_, _) => (u64::MIN as f64, u64::MAX as f64),
+            Param::Int8(_, _) => (i8::MIN as f64, i8::MAX as f64),
+            Param::Int16(_, _) => (i16::MIN as f64, i16::MAX as f64),
+            Param::Int32(_, _) => (i32::MIN as f64, i32::MAX as f64),
+            Param::Int64(_, _) => (i64::MIN as f64, i64::MAX as f64),
+            _ => return true,
+        };
+        // the 64 bits max rounds up to the next power of two, which no longer fits
+        value >= min && value < max + 1.0
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -90,7 +227,7 @@ impl<T: Clone + Copy + PartialOrd + PartialEq + Display> NumberParamConfig<T> {
             }
         }
         if self.max.is_some() {
-            let max = self.min.unwrap();
+            let max = self.max.unwrap();
             if value > &max {
                 return Err(GlowError::new(
                     format!("Invalid param"),
@@ -0,0 +1,146 @@
+use super::{get_u32_param, Schema};
+use crate::{
+    indicators::{rma_clouds::RmaClouds, IndicatorBuilder},
+    params::{NumberParamConfig, Param, ParamId},
+};
+use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Trades the close leaving a cloud edge it has been riding for the last 2 klines,
+/// filtered by the RMA trend.
+///
+/// The legacy signals were written against `consistent`, `reversal` and `residual` trend
+/// statuses, which map to the RMA clouds `strong`, `revert` and `slight` trends.
+#[derive(Clone, Copy, Default)]
+pub struct CloudMomentumStrategySchema {}
+
+impl CloudMomentumStrategySchema {
+    fn get_rma_clouds(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<RmaClouds, GlowError> {
+        Ok(RmaClouds::new(
+            symbols_pair.anchor,
+            get_u32_param(params, ParamId::FastPeriod)?,
+            get_u32_param(params, ParamId::SlowPeriod)?,
+            get_u32_param(params, ParamId::CloudLength)?,
+        ))
+    }
+}
+
+/// Whether the close just left `edge_col` after matching it for the previous 2 klines
+fn get_edge_departure_clause(edge_col: &str, close_col: &str) -> Expr {
+    col(edge_col)
+        .neq(col(close_col))
+        .and(col(edge_col).shift(lit(1)).eq(col(close_col).shift(lit(1))))
+        .and(col(edge_col).shift(lit(2)).eq(col(close_col).shift(lit(2))))
+}
+
+fn get_trend_clause(trend_col: &str, trends: &[&str]) -> Expr {
+    trends.iter().fold(lit(false), |clause, trend| {
+        clause.or(col(trend_col).eq(lit(*trend)))
+    })
+}
+
+impl Schema for CloudMomentumStrategySchema {
+    fn append_indicators_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        self.get_rma_clouds(symbols_pair, params)?.append_to_lf(lf)
+    }
+
+    fn append_signals_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let short_col = SignalCategory::GoShort.get_column();
+        let long_col = SignalCategory::GoLong.get_column();
+        let close_short_col = SignalCategory::CloseShort.get_column();
+        let close_long_col = SignalCategory::CloseLong.get_column();
+
+        let rma_clouds = self.get_rma_clouds(symbols_pair, params)?;
+        let close_col = symbols_pair.anchor.get_close_col();
+        let trend_col = &rma_clouds.get_trend_col();
+        let leaves_top = get_edge_departure_clause(&rma_clouds.get_cloud_top_col(), close_col);
+        let leaves_base = get_edge_departure_clause(&rma_clouds.get_cloud_base_col(), close_col);
+
+        let signal_lf = lf.with_columns([
+            when(
+                leaves_top
+                    .clone()
+                    .and(get_trend_clause(trend_col, &["revert_bull"])),
+            )
+            .then(lit(1))
+            .otherwise(lit(0))
+            .alias(short_col),
+            when(
+                leaves_base
+                    .clone()
+                    .and(get_trend_clause(trend_col, &["revert_bear", "slight_bull"])),
+            )
+            .then(lit(1))
+            .otherwise(lit(0))
+            .alias(long_col),
+            when(leaves_base.and(get_trend_clause(
+                trend_col,
+                &["strong_bull", "revert_bull", "slight_bull"],
+            )))
+            .then(lit(1))
+            .otherwise(lit(0))
+            .alias(close_short_col),
+            when(leaves_top.and(get_trend_clause(
+                trend_col,
+                &["strong_bear", "revert_bear", "slight_bear"],
+            )))
+            .then(lit(1))
+            .otherwise(lit(0))
+            .alias(close_long_col),
+        ]);
+
+        Ok(signal_lf)
+    }
+
+    fn get_params_config(&self) -> HashMap<ParamId, Param> {
+        let mut default_params = HashMap::new();
+        default_params.insert(
+            ParamId::FastPeriod,
+            Param::UInt32(9, NumberParamConfig::new(9, Some(2), Some(50))),
+        );
+        default_params.insert(
+            ParamId::SlowPeriod,
+            Param::UInt32(21, NumberParamConfig::new(21, Some(5), Some(200))),
+        );
+        default_params.insert(
+            ParamId::CloudLength,
+            Param::UInt32(20, NumberParamConfig::new(20, Some(3), Some(200))),
+        );
+
+        default_params
+    }
+
+    fn get_indicators_columns(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<(String, DataType)>, GlowError> {
+        Ok(self.get_rma_clouds(symbols_pair, params)?.get_columns())
+    }
+
+    fn get_minimum_klines_for_calculation(
+        &self,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<u32, GlowError> {
+        // symbols don't change the klines required by the indicators;
+        // two more klines to check the previous cloud edges
+        let rma_clouds = self.get_rma_clouds(SymbolsPair::default(), params)?;
+        Ok(rma_clouds.get_minimum_klines_for_calculation() + 2)
+    }
+}
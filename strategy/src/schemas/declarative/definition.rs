@@ -0,0 +1,534 @@
+use crate::{
+    indicators::{
+        ema::ExponentialMovingAverages, rma_clouds::RmaClouds, stc::SchaffTrendCycle,
+        stochastic::Stochastic, tsi::TrueStrengthIndex, IndicatorBuilderWrapper,
+    },
+    params::{NumberParamConfig, Param, ParamId},
+};
+use common::structs::Symbol;
+use glow_error::GlowError;
+use polars::prelude::*;
+use serde::Deserialize;
+use serde_json::from_reader;
+use std::{collections::HashMap, fs::File, io::BufReader};
+
+/// A strategy composed of built-in indicators and boolean signal rules, loaded from JSON.
+///
+/// ```json
+/// {
+///     "name": "stochastic_reversal",
+///     "params": {
+///         "KLength": { "default": 14, "min": 5, "max": 30 },
+///         "UpperThreshold": { "default": 80, "min": 50, "max": 100 },
+///         "LowerThreshold": { "default": 20, "min": 0, "max": 50 }
+///     },
+///     "indicators": [
+///         { "id": "stoch", "type": "Stochastic", "k_length": { "param": "KLength" }, "k_smoothing": 3, "d_smoothing": 3 }
+///     ],
+///     "signals": {
+///         "long": { "all": [
+///             { "lt": [{ "indicator": "stoch", "output": "k" }, { "param": "LowerThreshold" }] },
+///             { "crosses_above": [{ "indicator": "stoch", "output": "k" }, { "indicator": "stoch", "output": "d" }] }
+///         ] },
+///         "long_close": { "gt": [{ "indicator": "stoch", "output": "k" }, { "param": "UpperThreshold" }] }
+///     }
+/// }
+/// ```
+///
+/// Indicator outputs are appended as `{id}_{output}` columns. Missing signals never fire.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct StrategyDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub params: HashMap<ParamId, ParamDefinition>,
+    #[serde(default)]
+    pub indicators: Vec<IndicatorDefinition>,
+    #[serde(default)]
+    pub signals: SignalsDefinition,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum ParamKind {
+    #[default]
+    UInt32,
+    Int32,
+    Float64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ParamDefinition {
+    #[serde(default)]
+    pub kind: ParamKind,
+    pub default: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// A literal number or a reference to one of the definition params
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ValueDefinition {
+    Number(f64),
+    Param { param: ParamId },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct IndicatorDefinition {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: IndicatorKind,
+}
+
+fn default_stc_weight() -> ValueDefinition {
+    ValueDefinition::Number(0.5)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum IndicatorKind {
+    Ema {
+        fast_span: ValueDefinition,
+        slow_span: ValueDefinition,
+    },
+    Stochastic {
+        k_length: ValueDefinition,
+        k_smoothing: ValueDefinition,
+        d_smoothing: ValueDefinition,
+    },
+    Tsi {
+        long_span: ValueDefinition,
+        short_span: ValueDefinition,
+    },
+    Stc {
+        length: ValueDefinition,
+        fast_span: ValueDefinition,
+        slow_span: ValueDefinition,
+        #[serde(default = "default_stc_weight")]
+        weight: ValueDefinition,
+    },
+    RmaClouds {
+        fast_period: ValueDefinition,
+        slow_period: ValueDefinition,
+        cloud_length: ValueDefinition,
+    },
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SignalsDefinition {
+    pub short: Option<RuleDefinition>,
+    pub long: Option<RuleDefinition>,
+    pub short_close: Option<RuleDefinition>,
+    pub long_close: Option<RuleDefinition>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleDefinition {
+    All(Vec<RuleDefinition>),
+    Any(Vec<RuleDefinition>),
+    Not(Box<RuleDefinition>),
+    Gt(OperandDefinition, OperandDefinition),
+    GtEq(OperandDefinition, OperandDefinition),
+    Lt(OperandDefinition, OperandDefinition),
+    LtEq(OperandDefinition, OperandDefinition),
+    Eq(OperandDefinition, OperandDefinition),
+    Neq(OperandDefinition, OperandDefinition),
+    /// The first operand went from below or at the second one to above it
+    CrossesAbove(OperandDefinition, OperandDefinition),
+    /// The first operand went from above or at the second one to below it
+    CrossesBelow(OperandDefinition, OperandDefinition),
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceDefinition {
+    Open,
+    High,
+    Low,
+    Close,
+}
+
+/// `shift` reads the value from that many klines ago
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OperandDefinition {
+    Number(f64),
+    Text(String),
+    Param {
+        param: ParamId,
+    },
+    Indicator {
+        indicator: String,
+        output: String,
+        #[serde(default)]
+        shift: u32,
+    },
+    Price {
+        price: PriceDefinition,
+        #[serde(default)]
+        shift: u32,
+    },
+}
+
+fn get_definition_error(message: String) -> GlowError {
+    GlowError::new(String::from("Invalid strategy definition"), message)
+}
+
+impl ParamDefinition {
+    pub fn get_param(&self, param_id: ParamId) -> Result<Param, GlowError> {
+        let values = [Some(self.default), self.min, self.max];
+        let param = match self.kind {
+            ParamKind::Float64 => Param::Float64(
+                self.default,
+                NumberParamConfig::new(self.default, self.min, self.max),
+            ),
+            _ if values.iter().flatten().any(|value| value.fract() != 0.0) => {
+                return Err(get_definition_error(format!(
+                    "{:?} param is {:?} but has fractional values",
+                    param_id, self.kind
+                )))
+            }
+            ParamKind::Int32 => Param::Int32(
+                self.default as i32,
+                NumberParamConfig::new(
+                    self.default as i32,
+                    self.min.map(|min| min as i32),
+                    self.max.map(|max| max as i32),
+                ),
+            ),
+            ParamKind::UInt32 if values.iter().flatten().any(|value| *value < 0.0) => {
+                return Err(get_definition_error(format!(
+                    "{:?} param is UInt32 but has negative values",
+                    param_id
+                )))
+            }
+            ParamKind::UInt32 => Param::UInt32(
+                self.default as u32,
+                NumberParamConfig::new(
+                    self.default as u32,
+                    self.min.map(|min| min as u32),
+                    self.max.map(|max| max as u32),
+                ),
+            ),
+        };
+        param.validate(&param)?;
+        Ok(param)
+    }
+}
+
+impl ValueDefinition {
+    pub fn get_value(&self, params: &HashMap<ParamId, Param>) -> f64 {
+        match self {
+            Self::Number(value) => *value,
+            Self::Param { param } => params
+                .get(param)
+                .unwrap_or_else(|| panic!("{:?} param to be set at ParamsMap", param))
+                .number_value()
+                .unwrap_or_else(|| panic!("{:?} param to be a number", param)),
+        }
+    }
+
+    fn get_param_id(&self) -> Option<ParamId> {
+        match self {
+            Self::Number(_) => None,
+            Self::Param { param } => Some(*param),
+        }
+    }
+}
+
+impl IndicatorKind {
+    /// Output names, in the same order as the builder columns
+    pub fn get_outputs(&self) -> &'static [&'static str] {
+        match self {
+            Self::Ema { .. } => &["fast", "slow", "bullish"],
+            Self::Stochastic { .. } => &["k", "d"],
+            Self::Tsi { .. } => &["tsi"],
+            Self::Stc { .. } => &["stc"],
+            Self::RmaClouds { .. } => &["fast", "slow", "cloud_top", "cloud_base", "trend"],
+        }
+    }
+
+    fn get_values(&self) -> Vec<&ValueDefinition> {
+        match self {
+            Self::Ema {
+                fast_span,
+                slow_span,
+            } => vec![fast_span, slow_span],
+            Self::Stochastic {
+                k_length,
+                k_smoothing,
+                d_smoothing,
+            } => vec![k_length, k_smoothing, d_smoothing],
+            Self::Tsi {
+                long_span,
+                short_span,
+            } => vec![long_span, short_span],
+            Self::Stc {
+                length,
+                fast_span,
+                slow_span,
+                weight,
+            } => vec![length, fast_span, slow_span, weight],
+            Self::RmaClouds {
+                fast_period,
+                slow_period,
+                cloud_length,
+            } => vec![fast_period, slow_period, cloud_length],
+        }
+    }
+
+    pub fn get_builder(
+        &self,
+        symbol: &'static Symbol,
+        params: &HashMap<ParamId, Param>,
+    ) -> IndicatorBuilderWrapper {
+        let get_u32 = |value: &ValueDefinition| value.get_value(params) as u32;
+        match self {
+            Self::Ema {
+                fast_span,
+                slow_span,
+            } => ExponentialMovingAverages::new(symbol, get_u32(fast_span), get_u32(slow_span))
+                .into(),
+            Self::Stochastic {
+                k_length,
+                k_smoothing,
+                d_smoothing,
+            } => Stochastic::new(
+                symbol,
+                get_u32(k_length),
+                get_u32(k_smoothing),
+                get_u32(d_smoothing),
+            )
+            .into(),
+            Self::Tsi {
+                long_span,
+                short_span,
+            } => TrueStrengthIndex::new(symbol, get_u32(long_span), get_u32(short_span)).into(),
+            Self::Stc {
+                length,
+                fast_span,
+                slow_span,
+                weight,
+            } => SchaffTrendCycle::new(
+                symbol,
+                get_u32(length),
+                get_u32(fast_span),
+                get_u32(slow_span),
+                weight.get_value(params),
+            )
+            .into(),
+            Self::RmaClouds {
+                fast_period,
+                slow_period,
+                cloud_length,
+            } => RmaClouds::new(
+                symbol,
+                get_u32(fast_period),
+                get_u32(slow_period),
+                get_u32(cloud_length),
+            )
+            .into(),
+        }
+    }
+}
+
+impl IndicatorDefinition {
+    pub fn get_output_col(&self, output: &str) -> String {
+        format!("{}_{}", self.id, output)
+    }
+}
+
+impl SignalsDefinition {
+    pub fn get_rules(&self) -> [&Option<RuleDefinition>; 4] {
+        [&self.short, &self.long, &self.short_close, &self.long_close]
+    }
+}
+
+impl RuleDefinition {
+    fn get_children(&self) -> Vec<&RuleDefinition> {
+        match self {
+            Self::All(rules) | Self::Any(rules) => rules.iter().collect(),
+            Self::Not(rule) => vec![rule.as_ref()],
+            _ => vec![],
+        }
+    }
+
+    fn get_operands(&self) -> Vec<&OperandDefinition> {
+        match self {
+            Self::All(_) | Self::Any(_) | Self::Not(_) => vec![],
+            Self::Gt(left, right)
+            | Self::GtEq(left, right)
+            | Self::Lt(left, right)
+            | Self::LtEq(left, right)
+            | Self::Eq(left, right)
+            | Self::Neq(left, right)
+            | Self::CrossesAbove(left, right)
+            | Self::CrossesBelow(left, right) => vec![left, right],
+        }
+    }
+
+    /// How many previous klines the rule reads
+    pub fn get_lookback(&self) -> u32 {
+        let crossing_lookback = match self {
+            Self::CrossesAbove(_, _) | Self::CrossesBelow(_, _) => 1,
+            _ => 0,
+        };
+        let operands_lookback = self
+            .get_operands()
+            .iter()
+            .map(|operand| operand.get_shift())
+            .max()
+            .unwrap_or_default();
+        let children_lookback = self
+            .get_children()
+            .iter()
+            .map(|rule| rule.get_lookback())
+            .max()
+            .unwrap_or_default();
+        (operands_lookback + crossing_lookback).max(children_lookback)
+    }
+
+    pub fn get_expr(&self, symbol: &Symbol, params: &HashMap<ParamId, Param>) -> Expr {
+        let operand =
+            |operand: &OperandDefinition, shift: u32| operand.get_expr(symbol, params, shift);
+        match self {
+            Self::All(rules) => rules.iter().fold(lit(true), |all, rule| {
+                all.and(rule.get_expr(symbol, params))
+            }),
+            Self::Any(rules) => rules.iter().fold(lit(false), |any, rule| {
+                any.or(rule.get_expr(symbol, params))
+            }),
+            Self::Not(rule) => rule.get_expr(symbol, params).not(),
+            Self::Gt(left, right) => operand(left, 0).gt(operand(right, 0)),
+            Self::GtEq(left, right) => operand(left, 0).gt_eq(operand(right, 0)),
+            Self::Lt(left, right) => operand(left, 0).lt(operand(right, 0)),
+            Self::LtEq(left, right) => operand(left, 0).lt_eq(operand(right, 0)),
+            Self::Eq(left, right) => operand(left, 0).eq(operand(right, 0)),
+            Self::Neq(left, right) => operand(left, 0).neq(operand(right, 0)),
+            Self::CrossesAbove(left, right) => operand(left, 0)
+                .gt(operand(right, 0))
+                .and(operand(left, 1).lt_eq(operand(right, 1))),
+            Self::CrossesBelow(left, right) => operand(left, 0)
+                .lt(operand(right, 0))
+                .and(operand(left, 1).gt_eq(operand(right, 1))),
+        }
+    }
+}
+
+impl OperandDefinition {
+    fn get_shift(&self) -> u32 {
+        match self {
+            Self::Indicator { shift, .. } | Self::Price { shift, .. } => *shift,
+            _ => 0,
+        }
+    }
+
+    /// `extra_shift` only applies to columns, as literals don't change between klines
+    fn get_expr(
+        &self,
+        symbol: &Symbol,
+        params: &HashMap<ParamId, Param>,
+        extra_shift: u32,
+    ) -> Expr {
+        let shift_col = |column: &str, shift: u32| match shift + extra_shift {
+            0 => col(column),
+            shift => col(column).shift(lit(shift as i64)),
+        };
+        match self {
+            Self::Number(value) => lit(*value),
+            Self::Text(value) => lit(value.as_str()),
+            Self::Param { param } => {
+                lit(ValueDefinition::Param { param: *param }.get_value(params))
+            }
+            Self::Indicator {
+                indicator,
+                output,
+                shift,
+            } => shift_col(&format!("{}_{}", indicator, output), *shift),
+            Self::Price { price, shift } => {
+                let (open_col, high_col, low_col, close_col) = symbol.get_ohlc_cols();
+                let price_col = match price {
+                    PriceDefinition::Open => open_col,
+                    PriceDefinition::High => high_col,
+                    PriceDefinition::Low => low_col,
+                    PriceDefinition::Close => close_col,
+                };
+                shift_col(price_col, *shift)
+            }
+        }
+    }
+}
+
+impl StrategyDefinition {
+    pub fn load(path: &str) -> Result<Self, GlowError> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let definition: Self = from_reader(reader)?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    pub fn validate(&self) -> Result<(), GlowError> {
+        for (param_id, param) in &self.params {
+            param.get_param(*param_id)?;
+        }
+        let check_param = |param_id: Option<ParamId>| match param_id {
+            Some(param_id) if !self.params.contains_key(&param_id) => Err(get_definition_error(
+                format!("{:?} param is used but not defined", param_id),
+            )),
+            _ => Ok(()),
+        };
+
+        let mut indicator_outputs: HashMap<&str, &[&str]> = HashMap::new();
+        for indicator in &self.indicators {
+            if indicator.id.is_empty() {
+                return Err(get_definition_error(String::from(
+                    "indicator ids can't be empty",
+                )));
+            }
+            if indicator_outputs
+                .insert(&indicator.id, indicator.kind.get_outputs())
+                .is_some()
+            {
+                return Err(get_definition_error(format!(
+                    "indicator id {} is used more than once",
+                    indicator.id
+                )));
+            }
+            for value in indicator.kind.get_values() {
+                check_param(value.get_param_id())?;
+            }
+        }
+
+        let mut pending_rules: Vec<&RuleDefinition> =
+            self.signals.get_rules().into_iter().flatten().collect();
+        while let Some(rule) = pending_rules.pop() {
+            pending_rules.extend(rule.get_children());
+            for operand in rule.get_operands() {
+                match operand {
+                    OperandDefinition::Param { param } => check_param(Some(*param))?,
+                    OperandDefinition::Indicator {
+                        indicator, output, ..
+                    } => {
+                        let outputs =
+                            indicator_outputs.get(indicator.as_str()).ok_or_else(|| {
+                                get_definition_error(format!(
+                                    "indicator {} is not defined",
+                                    indicator
+                                ))
+                            })?;
+                        if !outputs.contains(&output.as_str()) {
+                            return Err(get_definition_error(format!(
+                                "indicator {} has no {} output, available outputs are {:?}",
+                                indicator, output, outputs
+                            )));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use super::Schema;
+use crate::{
+    indicators::IndicatorBuilder,
+    params::{Param, ParamId},
+};
+use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
+use definition::StrategyDefinition;
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::{collections::HashMap, sync::Arc};
+pub mod definition;
+
+/// Strategy schema backed by a `StrategyDefinition`, so strategies can be changed without recompiling.
+#[derive(Clone)]
+pub struct DeclarativeStrategySchema {
+    pub definition: Arc<StrategyDefinition>,
+}
+
+impl DeclarativeStrategySchema {
+    pub fn new(definition: StrategyDefinition) -> Self {
+        Self {
+            definition: Arc::new(definition),
+        }
+    }
+}
+
+impl Schema for DeclarativeStrategySchema {
+    fn append_indicators_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let mut lf = lf;
+        for indicator in &self.definition.indicators {
+            let builder = indicator.kind.get_builder(symbols_pair.anchor, params);
+            let builder_columns: Vec<String> = builder
+                .get_columns()
+                .into_iter()
+                .map(|(column, _)| column)
+                .collect();
+            // builder columns are renamed after the indicator id, so the same indicator can be used more than once
+            let outputs: Vec<Expr> = builder_columns
+                .iter()
+                .zip(indicator.kind.get_outputs())
+                .map(|(column, output)| col(column).alias(indicator.get_output_col(output)))
+                .collect();
+            lf = builder
+                .append_to_lf(lf)?
+                .with_columns(outputs)
+                .drop(builder_columns);
+        }
+        Ok(lf)
+    }
+
+    fn append_signals_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let signals = [
+            SignalCategory::GoShort,
+            SignalCategory::GoLong,
+            SignalCategory::CloseShort,
+            SignalCategory::CloseLong,
+        ];
+        let signal_exprs: Vec<Expr> = signals
+            .iter()
+            .zip(self.definition.signals.get_rules())
+            .map(|(signal, rule)| match rule {
+                Some(rule) => when(
+                    rule.get_expr(symbols_pair.anchor, params)
+                        .fill_null(lit(false)),
+                )
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(signal.get_column()),
+                // a literal-only when expression isn't broadcasted to the frame height
+                None => lit(0).alias(signal.get_column()),
+            })
+            .collect();
+
+        Ok(lf.with_columns(signal_exprs))
+    }
+
+    fn get_params_config(&self) -> HashMap<ParamId, Param> {
+        self.definition
+            .params
+            .iter()
+            .map(|(param_id, param)| {
+                let param = param
+                    .get_param(*param_id)
+                    .expect("strategy definition params to be validated");
+                (*param_id, param)
+            })
+            .collect()
+    }
+
+    fn get_indicators_columns(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<(String, DataType)>, GlowError> {
+        let columns = self
+            .definition
+            .indicators
+            .iter()
+            .flat_map(|indicator| {
+                let builder = indicator.kind.get_builder(symbols_pair.anchor, params);
+                builder
+                    .get_columns()
+                    .into_iter()
+                    .zip(indicator.kind.get_outputs())
+                    .map(|((_, dtype), output)| (indicator.get_output_col(output), dtype))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Ok(columns)
+    }
+
+    fn get_minimum_klines_for_calculation(
+        &self,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<u32, GlowError> {
+        // symbols don't change the klines required by the indicators
+        let symbols_pair = SymbolsPair::default();
+        let indicators_klines = self
+            .definition
+            .indicators
+            .iter()
+            .map(|indicator| {
+                indicator
+                    .kind
+                    .get_builder(symbols_pair.anchor, params)
+                    .get_minimum_klines_for_calculation()
+            })
+            .max()
+            .unwrap_or(1);
+        let rules_lookback = self
+            .definition
+            .signals
+            .get_rules()
+            .into_iter()
+            .flatten()
+            .map(|rule| rule.get_lookback())
+            .max()
+            .unwrap_or_default();
+        Ok(indicators_klines + rules_lookback)
+    }
+}
@@ -1,19 +1,34 @@
+use cloud_momentum::CloudMomentumStrategySchema;
+use declarative::DeclarativeStrategySchema;
 use enum_dispatch::enum_dispatch;
+use multiple_stochastic_with_threshold::MultipleStochasticWithThresholdStrategySchema;
+use rma_clouds::RmaCloudsStrategySchema;
 use simple_trend::SimpleTrendStrategySchema;
+use tsi_stc::TsiStcStrategySchema;
+mod cloud_momentum;
+pub mod declarative;
+mod multiple_stochastic_with_threshold;
+mod rma_clouds;
 mod simple_trend;
+mod tsi_stc;
 use crate::{
     params::{Param, ParamId},
     StrategyId,
 };
-use common::structs::SymbolsPair;
+use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
 use glow_error::GlowError;
-use polars::prelude::{DataFrame, DataType, LazyFrame};
+use polars::prelude::{DataFrame, DataType, IntoLazy, LazyFrame};
 use std::collections::HashMap;
 
 #[enum_dispatch(Schema)]
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum StrategySchema {
     SimpleTrend(SimpleTrendStrategySchema),
+    CloudMomentum(CloudMomentumStrategySchema),
+    TsiStc(TsiStcStrategySchema),
+    MultipleStochasticWithThreshold(MultipleStochasticWithThresholdStrategySchema),
+    RmaClouds(RmaCloudsStrategySchema),
+    Declarative(DeclarativeStrategySchema),
 }
 
 #[enum_dispatch]
@@ -26,10 +41,24 @@ pub trait Schema: Clone + Sized {
     ) -> Result<LazyFrame, GlowError>;
     fn append_indicators_to_df(
         &self,
-        lf: DataFrame,
+        df: DataFrame,
         symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
-    ) -> Result<DataFrame, GlowError>;
+    ) -> Result<DataFrame, GlowError> {
+        // TODO: add filter by last minimum klines for calculation
+        let mut new_lf = df.clone().lazy();
+        new_lf = self.append_indicators_to_lf(new_lf, symbols_pair, params)?;
+        let new_df = new_lf.collect()?;
+        let mut result_df = df.clone();
+
+        for (column, _) in self.get_indicators_columns(symbols_pair, params)? {
+            let series = new_df.column(column.as_str())?;
+            let index = df.try_get_column_index(column.as_str())?;
+            let _ = result_df.replace_column(index, series.to_owned());
+        }
+
+        Ok(result_df)
+    }
     fn append_signals_to_lf(
         &self,
         lf: LazyFrame,
@@ -38,22 +67,67 @@ pub trait Schema: Clone + Sized {
     ) -> Result<LazyFrame, GlowError>;
     fn append_signals_to_df(
         &self,
-        lf: DataFrame,
+        df: DataFrame,
         symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
-    ) -> Result<DataFrame, GlowError>;
+    ) -> Result<DataFrame, GlowError> {
+        let mut new_lf = df.clone().lazy();
+        new_lf = self.append_signals_to_lf(new_lf, symbols_pair, params)?;
+        let new_df = new_lf.collect()?;
+        let mut result_df = df.clone();
+
+        for (column, _) in self.get_signals_columns(symbols_pair, params) {
+            let series = new_df.column(column.as_str())?;
+            let index = df.try_get_column_index(column.as_str())?;
+            let _ = result_df.replace_column(index, series.to_owned());
+        }
+
+        Ok(result_df)
+    }
     fn get_params_config(&self) -> HashMap<ParamId, Param>;
     fn get_indicators_columns(
         &self,
         symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
-    ) -> Vec<(String, DataType)>;
-    fn get_minimum_klines_for_calculation(&self, params: &HashMap<ParamId, Param>) -> u32;
-    fn get_signals_columns(
+    ) -> Result<Vec<(String, DataType)>, GlowError>;
+    fn get_minimum_klines_for_calculation(
         &self,
-        symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
-    ) -> Vec<(String, DataType)>;
+    ) -> Result<u32, GlowError>;
+    fn get_signals_columns(
+        &self,
+        _: SymbolsPair,
+        _: &HashMap<ParamId, Param>,
+    ) -> Vec<(String, DataType)> {
+        let available_signals = [
+            SignalCategory::GoShort,
+            SignalCategory::GoLong,
+            SignalCategory::CloseShort,
+            SignalCategory::CloseLong,
+        ];
+
+        available_signals
+            .iter()
+            .map(|s| (s.get_column().to_string(), DataType::Int32))
+            .collect()
+    }
+}
+
+/// Reads a number param as u32, as most indicator lengths are declared
+fn get_u32_param(params: &HashMap<ParamId, Param>, param_id: ParamId) -> Result<u32, GlowError> {
+    let param = params.get(&param_id).ok_or_else(|| {
+        GlowError::new(
+            String::from("Invalid param"),
+            format!("{:?} param is missing", param_id),
+        )
+    })?;
+    let value = param.number_value().ok_or_else(|| {
+        GlowError::new(
+            String::from("Invalid param"),
+            format!("{:?} param is not a number", param_id),
+        )
+    })?;
+    Ok(value as u32)
 }
 
 impl Default for StrategySchema {
@@ -63,13 +137,31 @@ impl Default for StrategySchema {
     }
 }
 
-impl From<StrategyId> for StrategySchema {
-    fn from(value: StrategyId) -> Self {
-        match value {
+impl TryFrom<StrategyId> for StrategySchema {
+    type Error = GlowError;
+
+    fn try_from(value: StrategyId) -> Result<Self, Self::Error> {
+        let schema = match value {
             StrategyId::SimpleTrend => {
-                let schema = value.into();
-                StrategySchema::SimpleTrend(schema)
+                StrategySchema::SimpleTrend(SimpleTrendStrategySchema::default())
             }
-        }
+            StrategyId::CloudMomentum => {
+                StrategySchema::CloudMomentum(CloudMomentumStrategySchema::default())
+            }
+            StrategyId::TsiStc => StrategySchema::TsiStc(TsiStcStrategySchema::default()),
+            StrategyId::MultipleStochasticWithThreshold => {
+                StrategySchema::MultipleStochasticWithThreshold(
+                    MultipleStochasticWithThresholdStrategySchema::default(),
+                )
+            }
+            StrategyId::RmaClouds => StrategySchema::RmaClouds(RmaCloudsStrategySchema::default()),
+            StrategyId::Declarative => {
+                return Err(GlowError::new_str(
+                    "Invalid strategy",
+                    "Declarative strategies are built from a definition, see Strategy::from_definition",
+                ));
+            }
+        };
+        Ok(schema)
     }
 }
@@ -0,0 +1,171 @@
+use super::{get_u32_param, Schema};
+use crate::{
+    indicators::{stc::SchaffTrendCycle, stochastic::Stochastic, IndicatorBuilder},
+    params::{NumberParamConfig, Param, ParamId},
+};
+use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+const STC_WEIGHT: f64 = 0.5;
+
+/// Stochastic reversals out of the threshold zones, confirmed by the STC direction:
+/// goes short when %K turns down above `UpperThreshold` while STC falls,
+/// and long when %K turns up below `LowerThreshold` while STC rises.
+#[derive(Clone, Copy, Default)]
+pub struct MultipleStochasticWithThresholdStrategySchema {}
+
+impl MultipleStochasticWithThresholdStrategySchema {
+    fn get_stochastic(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Stochastic, GlowError> {
+        Ok(Stochastic::new(
+            symbols_pair.anchor,
+            get_u32_param(params, ParamId::KLength)?,
+            get_u32_param(params, ParamId::KSmoothing)?,
+            get_u32_param(params, ParamId::DSmoothing)?,
+        ))
+    }
+
+    fn get_stc(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<SchaffTrendCycle, GlowError> {
+        Ok(SchaffTrendCycle::new(
+            symbols_pair.anchor,
+            get_u32_param(params, ParamId::StcLength)?,
+            get_u32_param(params, ParamId::FastSpan)?,
+            get_u32_param(params, ParamId::SlowSpan)?,
+            STC_WEIGHT,
+        ))
+    }
+}
+
+impl Schema for MultipleStochasticWithThresholdStrategySchema {
+    fn append_indicators_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let lf = self
+            .get_stochastic(symbols_pair, params)?
+            .append_to_lf(lf)?;
+        self.get_stc(symbols_pair, params)?.append_to_lf(lf)
+    }
+
+    fn append_signals_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let short_col = SignalCategory::GoShort.get_column();
+        let long_col = SignalCategory::GoLong.get_column();
+        let close_short_col = SignalCategory::CloseShort.get_column();
+        let close_long_col = SignalCategory::CloseLong.get_column();
+
+        let upper_threshold = get_u32_param(params, ParamId::UpperThreshold)?;
+        let lower_threshold = get_u32_param(params, ParamId::LowerThreshold)?;
+        let k_col = &self.get_stochastic(symbols_pair, params)?.get_k_col();
+        let stc_col = &self.get_stc(symbols_pair, params)?.get_stc_col();
+
+        let overbought_reversal = col(k_col)
+            .gt(lit(upper_threshold))
+            .and(col(k_col).lt(col(k_col).shift(lit(1))))
+            .and(col(stc_col).lt(col(stc_col).shift(lit(1))));
+        let oversold_reversal = col(k_col)
+            .lt(lit(lower_threshold))
+            .and(col(k_col).gt(col(k_col).shift(lit(1))))
+            .and(col(stc_col).gt(col(stc_col).shift(lit(1))));
+
+        let signal_lf = lf.with_columns([
+            when(overbought_reversal.clone())
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(short_col),
+            when(oversold_reversal.clone())
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(long_col),
+            when(oversold_reversal)
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(close_short_col),
+            when(overbought_reversal)
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(close_long_col),
+        ]);
+
+        Ok(signal_lf)
+    }
+
+    fn get_params_config(&self) -> HashMap<ParamId, Param> {
+        let mut default_params = HashMap::new();
+        default_params.insert(
+            ParamId::KLength,
+            Param::UInt32(5, NumberParamConfig::new(5, Some(3), Some(30))),
+        );
+        default_params.insert(
+            ParamId::KSmoothing,
+            Param::UInt32(1, NumberParamConfig::new(1, Some(1), Some(10))),
+        );
+        default_params.insert(
+            ParamId::DSmoothing,
+            Param::UInt32(3, NumberParamConfig::new(3, Some(1), Some(10))),
+        );
+        default_params.insert(
+            ParamId::UpperThreshold,
+            Param::UInt32(75, NumberParamConfig::new(75, Some(50), Some(100))),
+        );
+        default_params.insert(
+            ParamId::LowerThreshold,
+            Param::UInt32(25, NumberParamConfig::new(25, Some(0), Some(50))),
+        );
+        default_params.insert(
+            ParamId::StcLength,
+            Param::UInt32(10, NumberParamConfig::new(10, Some(3), Some(30))),
+        );
+        default_params.insert(
+            ParamId::FastSpan,
+            Param::UInt32(23, NumberParamConfig::new(23, Some(5), Some(50))),
+        );
+        default_params.insert(
+            ParamId::SlowSpan,
+            Param::UInt32(50, NumberParamConfig::new(50, Some(25), Some(100))),
+        );
+
+        default_params
+    }
+
+    fn get_indicators_columns(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<(String, DataType)>, GlowError> {
+        let mut columns = self.get_stochastic(symbols_pair, params)?.get_columns();
+        columns.extend(self.get_stc(symbols_pair, params)?.get_columns());
+        Ok(columns)
+    }
+
+    fn get_minimum_klines_for_calculation(
+        &self,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<u32, GlowError> {
+        // symbols don't change the klines required by the indicators
+        let symbols_pair = SymbolsPair::default();
+        let stochastic_klines = self
+            .get_stochastic(symbols_pair, params)?
+            .get_minimum_klines_for_calculation();
+        let stc_klines = self
+            .get_stc(symbols_pair, params)?
+            .get_minimum_klines_for_calculation();
+        // one more kline to compare against the previous values
+        Ok(stochastic_klines.max(stc_klines) + 1)
+    }
+}
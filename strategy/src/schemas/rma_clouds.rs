@@ -0,0 +1,314 @@
+use super::{get_u32_param, Schema};
+use crate::{
+    indicators::{
+        rma_clouds::{RmaClouds, RmaCloudsMomentum},
+        IndicatorBuilder,
+    },
+    params::{NumberParamConfig, Param, ParamId},
+};
+use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Cloud edge breakouts and touches, each one only taken at the RMA momentum rankings
+/// (and close distances to the RMAs) where it used to pay off.
+#[derive(Clone, Copy, Default)]
+pub struct RmaCloudsStrategySchema {}
+
+impl RmaCloudsStrategySchema {
+    fn get_momentum(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<RmaCloudsMomentum, GlowError> {
+        let rma_clouds = RmaClouds::new(
+            symbols_pair.anchor,
+            get_u32_param(params, ParamId::FastPeriod)?,
+            get_u32_param(params, ParamId::SlowPeriod)?,
+            get_u32_param(params, ParamId::CloudLength)?,
+        );
+        Ok(RmaCloudsMomentum::new(
+            rma_clouds,
+            get_u32_param(params, ParamId::MomentumSpan)?,
+        ))
+    }
+}
+
+struct SignalColumns {
+    close: String,
+    cloud_top: String,
+    cloud_base: String,
+    trends: String,
+    close_fast_diff: String,
+    close_slow_diff: String,
+}
+
+fn prev(column: &str) -> Expr {
+    col(column).shift(lit(1))
+}
+
+impl SignalColumns {
+    fn new(symbols_pair: SymbolsPair, momentum: &RmaCloudsMomentum) -> Self {
+        Self {
+            close: symbols_pair.anchor.get_close_col().to_string(),
+            cloud_top: momentum.rma_clouds.get_cloud_top_col(),
+            cloud_base: momentum.rma_clouds.get_cloud_base_col(),
+            trends: momentum.get_trends_col(),
+            close_fast_diff: momentum.get_close_fast_diff_col(),
+            close_slow_diff: momentum.get_close_slow_diff_col(),
+        }
+    }
+
+    /// The close just left `edge`, which it matched at the previous kline with `trends` ranking
+    fn leaves(&self, edge: &str, trends: &str) -> Expr {
+        col(edge)
+            .neq(col(&self.close))
+            .and(prev(edge).eq(prev(&self.close)))
+            .and(prev(&self.trends).eq(lit(trends)))
+    }
+
+    fn leaves_top(&self, trends: &str) -> Expr {
+        self.leaves(&self.cloud_top, trends)
+    }
+
+    fn leaves_base(&self, trends: &str) -> Expr {
+        self.leaves(&self.cloud_base, trends)
+    }
+
+    /// The close is at the cloud top with `trends` ranking
+    fn touches_top(&self, trends: &str) -> Expr {
+        col(&self.cloud_top)
+            .eq(col(&self.close))
+            .and(col(&self.trends).eq(lit(trends)))
+    }
+
+    fn fast(&self) -> Expr {
+        col(&self.close_fast_diff)
+    }
+
+    fn prev_fast(&self) -> Expr {
+        prev(&self.close_fast_diff)
+    }
+
+    fn slow(&self) -> Expr {
+        col(&self.close_slow_diff)
+    }
+
+    fn prev_slow(&self) -> Expr {
+        prev(&self.close_slow_diff)
+    }
+
+    fn get_short_clauses(&self) -> Vec<Expr> {
+        vec![
+            self.leaves_top("1234")
+                .and(self.prev_slow().gt_eq(lit(0.02)))
+                .and(self.prev_fast().gt_eq(lit(0.01))),
+            self.leaves_top("1324")
+                .and(self.prev_slow().gt_eq(lit(0.02)))
+                .and(self.prev_fast().gt_eq(lit(0.01))),
+            self.leaves_top("1423")
+                .and(self.prev_slow().gt_eq(lit(0.015)))
+                .and(self.prev_fast().gt_eq(lit(0.005))),
+            self.touches_top("2314")
+                .and(self.slow().lt_eq(lit(0.005)))
+                .and(self.prev_slow().gt(lit(0.0)))
+                .and(self.fast().lt_eq(lit(0.005)))
+                .and(self.prev_fast().gt(lit(0.0))),
+            self.leaves_top("3241")
+                .and(self.prev_slow().gt_eq(lit(0.01)))
+                .and(self.prev_fast().gt_eq(lit(0.005))),
+            self.touches_top("4321")
+                .and(self.slow().lt_eq(lit(0.005)))
+                .and(self.prev_slow().gt(lit(0.0)))
+                .and(self.fast().gt_eq(lit(-0.01)))
+                .and(self.prev_fast().lt(lit(0.0))),
+            self.leaves_top("4321")
+                .and(self.prev_slow().gt_eq(lit(0.02)))
+                .and(self.prev_fast().gt_eq(lit(0.005))),
+            self.leaves_top("4123")
+                .and(self.prev_slow().lt_eq(lit(-0.02)))
+                .and(self.prev_fast().lt_eq(lit(-0.0985))),
+        ]
+    }
+
+    fn get_long_clauses(&self) -> Vec<Expr> {
+        vec![
+            self.leaves_base("1234")
+                .and(self.prev_slow().gt_eq(lit(0.01)))
+                .and(self.prev_fast().gt_eq(lit(-0.005)))
+                .and(self.prev_fast().lt(lit(0.0))),
+            self.touches_top("1243")
+                .and(self.slow().gt_eq(lit(-0.005)))
+                .and(self.prev_slow().lt(lit(0.0)))
+                .and(self.fast().gt_eq(lit(-0.01)))
+                .and(self.prev_fast().lt(lit(-0.005))),
+            self.leaves_base("1423")
+                .and(self.prev_slow().gt_eq(lit(-0.01)))
+                .and(self.prev_slow().lt(lit(0.0)))
+                .and(self.prev_fast().gt_eq(lit(-0.005)))
+                .and(self.prev_fast().lt(lit(0.0))),
+            self.touches_top("3421")
+                .and(self.slow().lt_eq(lit(0.005)))
+                .and(self.prev_slow().gt(lit(0.0)))
+                .and(self.fast().lt_eq(lit(0.005)))
+                .and(self.prev_fast().gt(lit(0.0))),
+            self.touches_top("3241")
+                .and(self.slow().lt_eq(lit(0.005)))
+                .and(self.prev_slow().gt(lit(0.0)))
+                .and(self.fast().lt_eq(lit(0.005)))
+                .and(self.prev_fast().gt(lit(0.0))),
+            self.touches_top("4321")
+                .and(self.slow().lt_eq(lit(-0.02)))
+                .and(self.fast().lt_eq(lit(-0.01))),
+            self.touches_top("4312")
+                .and(self.slow().lt_eq(lit(0.005)))
+                .and(self.fast().lt_eq(lit(0.005))),
+        ]
+    }
+
+    fn get_close_long_clauses(&self) -> Vec<Expr> {
+        vec![
+            self.leaves_top("1234")
+                .and(self.prev_slow().gt_eq(lit(0.018)))
+                .and(self.prev_fast().gt_eq(lit(0.007))),
+            self.leaves_top("2134")
+                .and(self.prev_slow().lt_eq(lit(0.01)))
+                .and(self.prev_slow().gt_eq(lit(0.005)))
+                .and(self.prev_fast().gt_eq(lit(0.0)))
+                .and(self.prev_fast().lt_eq(lit(0.005))),
+            self.leaves_top("3241")
+                .and(self.prev_slow().gt_eq(lit(0.02)))
+                .and(self.prev_fast().gt_eq(lit(0.015))),
+            self.leaves_base("4132")
+                .and(self.slow().gt_eq(lit(0.0)))
+                .and(self.prev_slow().lt_eq(lit(0.005)))
+                .and(self.fast().gt_eq(lit(-0.01)))
+                .and(self.prev_fast().lt_eq(lit(-0.005))),
+        ]
+    }
+
+    fn get_close_short_clauses(&self) -> Vec<Expr> {
+        vec![
+            self.leaves_base("1234")
+                .and(self.prev_slow().gt_eq(lit(-0.005)))
+                .and(self.prev_slow().lt(lit(0.0)))
+                .and(self.prev_fast().gt_eq(lit(0.01))),
+            self.leaves_base("2134")
+                .and(self.prev_slow().gt_eq(lit(-0.005)))
+                .and(self.prev_slow().lt_eq(lit(0.005)))
+                .and(self.prev_fast().gt_eq(lit(0.01))),
+            self.leaves_base("4321")
+                .and(self.prev_slow().lt(lit(-0.02)))
+                .and(self.prev_fast().lt_eq(lit(-0.01))),
+            self.leaves_base("4312")
+                .and(self.prev_slow().gt_eq(lit(-0.01)))
+                .and(self.prev_slow().lt_eq(lit(-0.005)))
+                .and(self.prev_fast().gt_eq(lit(-0.005)))
+                .and(self.prev_fast().lt_eq(lit(0.0))),
+            self.leaves_base("4231")
+                .and(self.prev_slow().lt(lit(-0.02)))
+                .and(self.prev_fast().gt_eq(lit(-0.01)))
+                .and(self.prev_fast().lt_eq(lit(-0.005))),
+            self.leaves_base("4123")
+                .and(self.prev_slow().lt(lit(-0.02)))
+                .and(self.prev_fast().lt_eq(lit(-0.01))),
+            self.leaves_base("4132")
+                .and(self.prev_slow().gt_eq(lit(-0.01)))
+                .and(self.prev_slow().lt_eq(lit(0.0)))
+                .and(self.prev_fast().gt_eq(lit(-0.005)))
+                .and(self.prev_fast().lt_eq(lit(0.0))),
+        ]
+    }
+}
+
+fn get_signal_expr(clauses: Vec<Expr>, signal_col: &str) -> Expr {
+    let any_clause = clauses.into_iter().fold(lit(false), |any, clause| {
+        any.or(clause.fill_null(lit(false)))
+    });
+    when(any_clause)
+        .then(lit(1))
+        .otherwise(lit(0))
+        .alias(signal_col)
+}
+
+impl Schema for RmaCloudsStrategySchema {
+    fn append_indicators_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        self.get_momentum(symbols_pair, params)?.append_to_lf(lf)
+    }
+
+    fn append_signals_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let columns = SignalColumns::new(symbols_pair, &self.get_momentum(symbols_pair, params)?);
+
+        let signal_lf = lf.with_columns([
+            get_signal_expr(
+                columns.get_short_clauses(),
+                SignalCategory::GoShort.get_column(),
+            ),
+            get_signal_expr(
+                columns.get_long_clauses(),
+                SignalCategory::GoLong.get_column(),
+            ),
+            get_signal_expr(
+                columns.get_close_short_clauses(),
+                SignalCategory::CloseShort.get_column(),
+            ),
+            get_signal_expr(
+                columns.get_close_long_clauses(),
+                SignalCategory::CloseLong.get_column(),
+            ),
+        ]);
+
+        Ok(signal_lf)
+    }
+
+    fn get_params_config(&self) -> HashMap<ParamId, Param> {
+        let mut default_params = HashMap::new();
+        default_params.insert(
+            ParamId::FastPeriod,
+            Param::UInt32(9, NumberParamConfig::new(9, Some(2), Some(50))),
+        );
+        default_params.insert(
+            ParamId::SlowPeriod,
+            Param::UInt32(21, NumberParamConfig::new(21, Some(5), Some(200))),
+        );
+        default_params.insert(
+            ParamId::CloudLength,
+            Param::UInt32(20, NumberParamConfig::new(20, Some(3), Some(200))),
+        );
+        default_params.insert(
+            ParamId::MomentumSpan,
+            Param::UInt32(5, NumberParamConfig::new(5, Some(1), Some(60))),
+        );
+
+        default_params
+    }
+
+    fn get_indicators_columns(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<(String, DataType)>, GlowError> {
+        Ok(self.get_momentum(symbols_pair, params)?.get_columns())
+    }
+
+    fn get_minimum_klines_for_calculation(
+        &self,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<u32, GlowError> {
+        // symbols don't change the klines required by the indicators;
+        // one more kline to check the previous cloud edges
+        let momentum = self.get_momentum(SymbolsPair::default(), params)?;
+        Ok(momentum.get_minimum_klines_for_calculation() + 1)
+    }
+}
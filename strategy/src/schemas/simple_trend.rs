@@ -1,31 +1,22 @@
 use super::Schema;
 use crate::{
-    functions::calculate_span_alpha,
+    indicators::{ema::ExponentialMovingAverages, IndicatorBuilder},
     params::{NumberParamConfig, Param, ParamId},
-    StrategyId,
 };
 use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
 use glow_error::GlowError;
 use polars::prelude::*;
 use std::collections::HashMap;
 
-const TREND_COL: &str = "EMA_bullish";
-
 #[derive(Clone, Copy, Default)]
 pub struct SimpleTrendStrategySchema {}
 
-impl Schema for SimpleTrendStrategySchema {
-    fn append_indicators_to_lf(
+impl SimpleTrendStrategySchema {
+    fn get_emas(
         &self,
-        lf: LazyFrame,
         symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
-    ) -> Result<LazyFrame, GlowError> {
-        let close_col = symbols_pair.anchor.get_close_col();
-        let cols = self.get_indicators_columns(symbols_pair, params);
-        let (ema_fast_col, _) = cols
-            .first()
-            .expect("EMA indicator to have column at index 0");
+    ) -> ExponentialMovingAverages {
         let fast_span_param = params
             .get(&ParamId::FastSpan)
             .expect("FastSpan param to be set at ParamsMap");
@@ -34,9 +25,6 @@ impl Schema for SimpleTrendStrategySchema {
         } else {
             20
         };
-        let (ema_slow_col, _) = cols
-            .get(1)
-            .expect("EMA indicator to have column at index 1");
         let slow_span_param = params
             .get(&ParamId::SlowSpan)
             .expect("SlowSpan param to be set at ParamsMap");
@@ -45,62 +33,18 @@ impl Schema for SimpleTrendStrategySchema {
         } else {
             100
         };
-
-        let fast_alpha = calculate_span_alpha(fast_span as f64)?;
-        let slow_alpha = calculate_span_alpha(slow_span as f64)?;
-        let fast_opts = EWMOptions {
-            alpha: fast_alpha,
-            adjust: false,
-            bias: false,
-            min_periods: 1,
-            ignore_nulls: false,
-        };
-        let slow_opts = EWMOptions {
-            alpha: slow_alpha,
-            adjust: false,
-            bias: false,
-            min_periods: 1,
-            ignore_nulls: false,
-        };
-
-        let lf = lf
-            .with_columns([
-                col(close_col).ewm_mean(slow_opts).alias(ema_slow_col),
-                col(close_col).ewm_mean(fast_opts).alias(ema_fast_col),
-            ])
-            .with_column(
-                when(col(ema_fast_col).is_null().or(col(ema_slow_col).is_null()))
-                    .then(lit(NULL))
-                    .otherwise(
-                        when(col(ema_fast_col).gt(col(ema_slow_col)))
-                            .then(true)
-                            .otherwise(false),
-                    )
-                    .alias(TREND_COL),
-            );
-
-        Ok(lf)
+        ExponentialMovingAverages::new(symbols_pair.anchor, fast_span, slow_span)
     }
+}
 
-    fn append_indicators_to_df(
+impl Schema for SimpleTrendStrategySchema {
+    fn append_indicators_to_lf(
         &self,
-        df: DataFrame,
+        lf: LazyFrame,
         symbols_pair: SymbolsPair,
         params: &HashMap<ParamId, Param>,
-    ) -> Result<DataFrame, GlowError> {
-        // TODO: add filter by last minimum klines for calculation and make this the default impl for this fn
-        let mut new_lf = df.clone().lazy();
-        new_lf = self.append_indicators_to_lf(new_lf, symbols_pair, params)?;
-        let new_df = new_lf.collect()?;
-        let mut result_df = df.clone();
-
-        for (column, _) in self.get_indicators_columns(symbols_pair, params) {
-            let series = new_df.column(column.as_str())?;
-            let index = df.try_get_column_index(column.as_str())?;
-            let _ = result_df.replace_column(index, series.to_owned());
-        }
-
-        Ok(result_df)
+    ) -> Result<LazyFrame, GlowError> {
+        self.get_emas(symbols_pair, params).append_to_lf(lf)
     }
 
     fn append_signals_to_lf(
@@ -158,36 +102,6 @@ impl Schema for SimpleTrendStrategySchema {
         Ok(signal_lf)
     }
 
-    fn append_signals_to_df(
-        &self,
-        df: DataFrame,
-        symbols_pair: SymbolsPair,
-        params: &HashMap<ParamId, Param>,
-    ) -> Result<DataFrame, GlowError> {
-        let mut updated_lf = df.clone().lazy();
-        updated_lf = self.append_signals_to_lf(updated_lf, symbols_pair, params)?;
-        let updated_df = updated_lf.collect()?;
-        let mut result_df = updated_df.clone();
-
-        let signals = [
-            SignalCategory::GoShort,
-            SignalCategory::GoLong,
-            SignalCategory::CloseLong,
-            SignalCategory::CloseShort,
-        ];
-
-        // TODO: check this
-        for signal in signals.iter() {
-            let column = signal.get_column();
-            let series = result_df.column(column)?;
-            let index = result_df.try_get_column_index(column)?;
-            let _ = result_df.replace_column(index, series.to_owned());
-        }
-        // let signal = self.signal_category();
-
-        Ok(result_df)
-    }
-
     fn get_params_config(&self) -> HashMap<ParamId, Param> {
         let mut default_params = HashMap::new();
         default_params.insert(
@@ -206,56 +120,23 @@ impl Schema for SimpleTrendStrategySchema {
     fn get_indicators_columns(
         &self,
         symbols_pair: SymbolsPair,
-        _: &HashMap<ParamId, Param>,
-    ) -> Vec<(String, DataType)> {
-        let mut columns = Vec::new();
-        columns.push((
-            format!("{}_fast_ema", symbols_pair.anchor.name),
-            DataType::Float64,
-        ));
-        columns.push((
-            format!("{}_slow_ema", symbols_pair.anchor.name),
-            DataType::Float64,
-        ));
-        columns.push((TREND_COL.to_string(), DataType::Boolean));
-        columns
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<(String, DataType)>, GlowError> {
+        Ok(self.get_emas(symbols_pair, params).get_columns())
     }
 
-    fn get_minimum_klines_for_calculation(&self, params: &HashMap<ParamId, Param>) -> u32 {
+    fn get_minimum_klines_for_calculation(
+        &self,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<u32, GlowError> {
         let slow_span_param = params
             .get(&ParamId::SlowSpan)
             .expect("LongSpan param to be set at ParamsMap");
         if let Param::UInt32(value, _) = slow_span_param {
-            *value
+            Ok(*value)
         } else {
             // TODO: review this
-            0
-        }
-    }
-
-    fn get_signals_columns(
-        &self,
-        _: SymbolsPair,
-        _: &HashMap<ParamId, Param>,
-    ) -> Vec<(String, DataType)> {
-        let available_signals = [
-            SignalCategory::GoShort,
-            SignalCategory::GoLong,
-            SignalCategory::CloseShort,
-            SignalCategory::CloseLong,
-        ];
-
-        available_signals
-            .iter()
-            .map(|s| (s.get_column().to_string(), DataType::UInt32))
-            .collect()
-    }
-}
-
-impl From<StrategyId> for SimpleTrendStrategySchema {
-    fn from(value: StrategyId) -> Self {
-        match value {
-            StrategyId::SimpleTrend => SimpleTrendStrategySchema {},
+            Ok(0)
         }
     }
 }
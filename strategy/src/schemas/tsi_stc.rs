@@ -0,0 +1,166 @@
+use super::{get_u32_param, Schema};
+use crate::{
+    indicators::{stc::SchaffTrendCycle, tsi::TrueStrengthIndex, IndicatorBuilder},
+    params::{NumberParamConfig, Param, ParamId},
+};
+use common::{enums::signal_category::SignalCategory, structs::SymbolsPair};
+use glow_error::GlowError;
+use polars::prelude::*;
+use std::collections::HashMap;
+
+const STC_WEIGHT: f64 = 0.5;
+
+/// Follows TSI and STC momentum: opens when both have kept rising (or falling)
+/// against each of their last `TrendPeriod` values.
+#[derive(Clone, Copy, Default)]
+pub struct TsiStcStrategySchema {}
+
+impl TsiStcStrategySchema {
+    fn get_tsi(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<TrueStrengthIndex, GlowError> {
+        Ok(TrueStrengthIndex::new(
+            symbols_pair.anchor,
+            get_u32_param(params, ParamId::LongSpan)?,
+            get_u32_param(params, ParamId::ShortSpan)?,
+        ))
+    }
+
+    fn get_stc(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<SchaffTrendCycle, GlowError> {
+        Ok(SchaffTrendCycle::new(
+            symbols_pair.anchor,
+            get_u32_param(params, ParamId::StcLength)?,
+            get_u32_param(params, ParamId::FastSpan)?,
+            get_u32_param(params, ParamId::SlowSpan)?,
+            STC_WEIGHT,
+        ))
+    }
+}
+
+/// Whether `column` is greater (or lesser) than each of its last `period` values
+fn get_trend_clause(column: &str, period: u32, rising: bool) -> Expr {
+    (1..=period as i64).fold(lit(true), |clause, shift| {
+        let previous = col(column).shift(lit(shift));
+        let compared = if rising {
+            col(column).gt(previous)
+        } else {
+            col(column).lt(previous)
+        };
+        clause.and(compared.fill_null(lit(false)))
+    })
+}
+
+impl Schema for TsiStcStrategySchema {
+    fn append_indicators_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let lf = self.get_tsi(symbols_pair, params)?.append_to_lf(lf)?;
+        self.get_stc(symbols_pair, params)?.append_to_lf(lf)
+    }
+
+    fn append_signals_to_lf(
+        &self,
+        lf: LazyFrame,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<LazyFrame, GlowError> {
+        let short_col = SignalCategory::GoShort.get_column();
+        let long_col = SignalCategory::GoLong.get_column();
+        let close_short_col = SignalCategory::CloseShort.get_column();
+        let close_long_col = SignalCategory::CloseLong.get_column();
+
+        let period = get_u32_param(params, ParamId::TrendPeriod)?;
+        let tsi_col = &self.get_tsi(symbols_pair, params)?.get_tsi_col();
+        let stc_col = &self.get_stc(symbols_pair, params)?.get_stc_col();
+
+        let rising =
+            get_trend_clause(tsi_col, period, true).and(get_trend_clause(stc_col, period, true));
+        let falling =
+            get_trend_clause(tsi_col, period, false).and(get_trend_clause(stc_col, period, false));
+
+        let signal_lf = lf.with_columns([
+            when(falling.clone())
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(short_col),
+            when(rising.clone())
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(long_col),
+            when(rising)
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(close_short_col),
+            when(falling)
+                .then(lit(1))
+                .otherwise(lit(0))
+                .alias(close_long_col),
+        ]);
+
+        Ok(signal_lf)
+    }
+
+    fn get_params_config(&self) -> HashMap<ParamId, Param> {
+        let mut default_params = HashMap::new();
+        default_params.insert(
+            ParamId::LongSpan,
+            Param::UInt32(25, NumberParamConfig::new(25, Some(10), Some(50))),
+        );
+        default_params.insert(
+            ParamId::ShortSpan,
+            Param::UInt32(13, NumberParamConfig::new(13, Some(2), Some(25))),
+        );
+        default_params.insert(
+            ParamId::StcLength,
+            Param::UInt32(10, NumberParamConfig::new(10, Some(3), Some(30))),
+        );
+        default_params.insert(
+            ParamId::FastSpan,
+            Param::UInt32(23, NumberParamConfig::new(23, Some(5), Some(50))),
+        );
+        default_params.insert(
+            ParamId::SlowSpan,
+            Param::UInt32(50, NumberParamConfig::new(50, Some(25), Some(100))),
+        );
+        default_params.insert(
+            ParamId::TrendPeriod,
+            Param::UInt32(3, NumberParamConfig::new(3, Some(1), Some(10))),
+        );
+
+        default_params
+    }
+
+    fn get_indicators_columns(
+        &self,
+        symbols_pair: SymbolsPair,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<Vec<(String, DataType)>, GlowError> {
+        let mut columns = self.get_tsi(symbols_pair, params)?.get_columns();
+        columns.extend(self.get_stc(symbols_pair, params)?.get_columns());
+        Ok(columns)
+    }
+
+    fn get_minimum_klines_for_calculation(
+        &self,
+        params: &HashMap<ParamId, Param>,
+    ) -> Result<u32, GlowError> {
+        // symbols don't change the klines required by the indicators
+        let symbols_pair = SymbolsPair::default();
+        let tsi_klines = self
+            .get_tsi(symbols_pair, params)?
+            .get_minimum_klines_for_calculation();
+        let stc_klines = self
+            .get_stc(symbols_pair, params)?
+            .get_minimum_klines_for_calculation();
+        Ok(tsi_klines.max(stc_klines) + get_u32_param(params, ParamId::TrendPeriod)?)
+    }
+}
@@ -1,3 +1,9 @@
 use crate::StrategyId;
 
-pub const STRATEGIES_IDS: [StrategyId; 1] = [StrategyId::SimpleTrend];
+pub const STRATEGIES_IDS: [StrategyId; 5] = [
+    StrategyId::SimpleTrend,
+    StrategyId::CloudMomentum,
+    StrategyId::TsiStc,
+    StrategyId::MultipleStochasticWithThreshold,
+    StrategyId::RmaClouds,
+];
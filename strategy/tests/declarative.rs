@@ -0,0 +1,204 @@
+use common::{
+    enums::{signal_category::SignalCategory, symbol_id::SymbolId},
+    structs::SymbolsPair,
+};
+use polars::prelude::*;
+use strategy::{
+    params::ParamId, schemas::declarative::definition::StrategyDefinition, Strategy, StrategyId,
+};
+
+const KLINES_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/BTC_USDT_AGIX_USDT.csv"
+);
+const EXAMPLE_DEFINITION: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/definitions/stochastic_reversal.json"
+);
+
+const SIGNALS: [SignalCategory; 4] = [
+    SignalCategory::GoShort,
+    SignalCategory::GoLong,
+    SignalCategory::CloseShort,
+    SignalCategory::CloseLong,
+];
+
+fn read_klines() -> DataFrame {
+    CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(None)
+        .try_into_reader_with_file_path(Some(KLINES_CSV.into()))
+        .expect("test csv to exist")
+        .finish()
+        .expect("test csv to be parsed")
+        .select([
+            "start_time",
+            "BTCUSDT_open",
+            "BTCUSDT_high",
+            "BTCUSDT_low",
+            "BTCUSDT_close",
+        ])
+        .expect("klines columns to exist")
+}
+
+fn get_symbols_pair() -> SymbolsPair {
+    SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin)
+}
+
+fn run_strategy(strategy: &Strategy) -> DataFrame {
+    let lf = strategy
+        .append_indicators_to_lf(read_klines().lazy())
+        .unwrap();
+    strategy
+        .append_signals_to_lf(lf)
+        .unwrap()
+        .collect()
+        .unwrap()
+}
+
+fn get_signal_values(df: &DataFrame, signal: SignalCategory) -> Vec<Option<i32>> {
+    df.column(signal.get_column())
+        .unwrap()
+        .cast(&DataType::Int32)
+        .unwrap()
+        .i32()
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_example_definition_appends_declared_columns() {
+    let definition = StrategyDefinition::load(EXAMPLE_DEFINITION).unwrap();
+    let strategy = Strategy::from_definition(definition, get_symbols_pair());
+    assert_eq!(strategy.id, StrategyId::Declarative);
+    assert_eq!(strategy.params.len(), 4);
+
+    let strategy_df = run_strategy(&strategy);
+    let indicators_columns = strategy.get_indicators_columns().unwrap();
+    let indicators_names: Vec<&str> = indicators_columns
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(
+        indicators_names,
+        ["stoch_k", "stoch_d", "ema_fast", "ema_slow", "ema_bullish"]
+    );
+    // builder columns are dropped, only the declared ones are added
+    assert_eq!(
+        strategy_df.width(),
+        read_klines().width() + indicators_columns.len() + SIGNALS.len()
+    );
+    for (name, dtype) in indicators_columns {
+        assert_eq!(strategy_df.column(&name).unwrap().dtype(), &dtype);
+    }
+
+    assert!(strategy.get_minimum_klines_for_calculation().unwrap() > 50);
+    for signal in SIGNALS {
+        let values = get_signal_values(&strategy_df, signal);
+        assert!(values.iter().all(|value| matches!(value, Some(0 | 1))));
+        assert!(
+            values.iter().flatten().sum::<i32>() > 0,
+            "{:?} never fires",
+            signal
+        );
+    }
+}
+
+#[test]
+fn test_definition_params_can_be_patched() {
+    let definition = StrategyDefinition::load(EXAMPLE_DEFINITION).unwrap();
+    let strategy = Strategy::from_definition(definition, get_symbols_pair())
+        .patch_number_param(ParamId::KLength, 21.0)
+        .unwrap();
+    assert_eq!(
+        strategy
+            .params
+            .get(&ParamId::KLength)
+            .unwrap()
+            .number_value(),
+        Some(21.0)
+    );
+    assert!(strategy
+        .patch_number_param(ParamId::KLength, 100.0)
+        .is_err());
+}
+
+#[test]
+fn test_ema_cross_definition_matches_simple_trend() {
+    let definition: StrategyDefinition = serde_json::from_str(
+        r#"{
+            "name": "ema_cross",
+            "params": {
+                "FastSpan": { "default": 20, "min": 1, "max": 50 },
+                "SlowSpan": { "default": 100, "min": 50, "max": 200 }
+            },
+            "indicators": [
+                { "id": "ema", "type": "Ema", "fast_span": { "param": "FastSpan" }, "slow_span": { "param": "SlowSpan" } }
+            ],
+            "signals": {
+                "long": { "all": [
+                    { "gt": [{ "indicator": "ema", "output": "fast" }, { "indicator": "ema", "output": "slow" }] },
+                    { "lt": [{ "indicator": "ema", "output": "fast", "shift": 1 }, { "indicator": "ema", "output": "slow", "shift": 1 }] }
+                ] },
+                "short": { "all": [
+                    { "lt": [{ "indicator": "ema", "output": "fast" }, { "indicator": "ema", "output": "slow" }] },
+                    { "gt": [{ "indicator": "ema", "output": "fast", "shift": 1 }, { "indicator": "ema", "output": "slow", "shift": 1 }] }
+                ] }
+            }
+        }"#,
+    )
+    .unwrap();
+    definition.validate().unwrap();
+    let declarative_df = run_strategy(&Strategy::from_definition(definition, get_symbols_pair()));
+    let simple_trend_df =
+        run_strategy(&Strategy::new(StrategyId::SimpleTrend, get_symbols_pair()).unwrap());
+
+    for signal in [SignalCategory::GoShort, SignalCategory::GoLong] {
+        let values = get_signal_values(&declarative_df, signal);
+        assert!(values.iter().flatten().sum::<i32>() > 0);
+        assert_eq!(values, get_signal_values(&simple_trend_df, signal));
+    }
+}
+
+#[test]
+fn test_invalid_definitions_are_rejected() {
+    let unknown_output: StrategyDefinition = serde_json::from_str(
+        r#"{
+            "name": "unknown_output",
+            "indicators": [{ "id": "tsi", "type": "Tsi", "long_span": 25, "short_span": 13 }],
+            "signals": { "long": { "gt": [{ "indicator": "tsi", "output": "signal" }, 0] } }
+        }"#,
+    )
+    .unwrap();
+    assert!(unknown_output.validate().is_err());
+
+    let undefined_param: StrategyDefinition = serde_json::from_str(
+        r#"{
+            "name": "undefined_param",
+            "indicators": [{ "id": "tsi", "type": "Tsi", "long_span": { "param": "LongSpan" }, "short_span": 13 }]
+        }"#,
+    )
+    .unwrap();
+    assert!(undefined_param.validate().is_err());
+
+    let unknown_indicator: StrategyDefinition = serde_json::from_str(
+        r#"{
+            "name": "unknown_indicator",
+            "signals": { "short": { "lt": [{ "indicator": "stc", "output": "stc" }, 25] } }
+        }"#,
+    )
+    .unwrap();
+    assert!(unknown_indicator.validate().is_err());
+}
+
+#[test]
+fn test_declarative_id_requires_a_definition() {
+    assert!(Strategy::new(StrategyId::Declarative, get_symbols_pair()).is_err());
+    assert!(serde_json::from_str::<StrategyId>(r#""Declarative""#).is_err());
+    assert!(serde_json::to_string(&StrategyId::Declarative).is_err());
+
+    let definition = StrategyDefinition::load(EXAMPLE_DEFINITION).unwrap();
+    let strategy = Strategy::from_definition(definition, get_symbols_pair());
+    assert_eq!(strategy.id, StrategyId::Declarative);
+}
@@ -0,0 +1,244 @@
+use common::{r#static::SYMBOLS_MAP, structs::Symbol};
+use polars::prelude::*;
+use strategy::indicators::{
+    ema::ExponentialMovingAverages, rma_clouds::RmaClouds, stc::SchaffTrendCycle,
+    stochastic::Stochastic, tsi::TrueStrengthIndex, IndicatorBuilder,
+};
+
+const EMA_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/BTC_USDT_AGIX_USDT.csv"
+);
+const STOCHASTIC_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/calc 1BTC_USDT_AGIX_USDT.csv"
+);
+/// Legacy RMA, TSI and STC values over the `EMA_CSV` klines, see `legacy_indicators.rs`
+const LEGACY_INDICATORS_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/legacy_indicators.csv"
+);
+const TOLERANCE: f64 = 1e-6;
+
+fn get_btcusdt() -> &'static Symbol {
+    SYMBOLS_MAP
+        .get("BTCUSDT")
+        .expect("BTCUSDT to be a known symbol")
+}
+
+fn read_csv(path: &str) -> DataFrame {
+    CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(None)
+        .try_into_reader_with_file_path(Some(path.into()))
+        .expect("test csv to exist")
+        .finish()
+        .expect("test csv to be parsed")
+}
+
+fn get_f64_values(df: &DataFrame, column: &str) -> Vec<Option<f64>> {
+    df.column(column)
+        .expect("column to exist")
+        .cast(&DataType::Float64)
+        .expect("column to be numeric")
+        .f64()
+        .expect("column to be f64")
+        .into_iter()
+        .collect()
+}
+
+fn append_indicator(df: &DataFrame, builder: &impl IndicatorBuilder) -> DataFrame {
+    let df = builder
+        .append_to_lf(df.clone().lazy())
+        .expect("indicator to be appended")
+        .collect()
+        .expect("indicator to be calculated");
+    for (column, dtype) in builder.get_columns() {
+        assert_eq!(
+            df.column(&column)
+                .expect("declared column to exist")
+                .dtype(),
+            &dtype,
+            "{column} dtype"
+        );
+    }
+    df
+}
+
+fn assert_close(expected: &[Option<f64>], calculated: &[Option<f64>], from: usize, name: &str) {
+    assert_close_within(expected, calculated, from, name, TOLERANCE);
+}
+
+fn assert_close_within(
+    expected: &[Option<f64>],
+    calculated: &[Option<f64>],
+    from: usize,
+    name: &str,
+    tolerance: f64,
+) {
+    let mut compared = 0;
+    for index in from..expected.len() {
+        let Some(expected) = expected[index] else {
+            continue;
+        };
+        let calculated =
+            calculated[index].unwrap_or_else(|| panic!("{name} to be calculated at row {index}"));
+        assert!(
+            (expected - calculated).abs() < tolerance,
+            "{name} at row {index}: expected {expected}, got {calculated}"
+        );
+        compared += 1;
+    }
+    assert!(compared > 0, "{name} has no golden values");
+}
+
+#[test]
+fn test_ema_matches_golden_values() {
+    let df = read_csv(EMA_CSV);
+    let emas = ExponentialMovingAverages::new(get_btcusdt(), 21, 50);
+    let result = append_indicator(&df, &emas);
+
+    // the golden file starts mid stream, so only compare once the seed has faded out
+    let from = 800;
+    assert_close(
+        &get_f64_values(&df, "BTCUSDT_ema_s"),
+        &get_f64_values(&result, &emas.get_fast_ema_col()),
+        from,
+        "fast ema",
+    );
+    assert_close(
+        &get_f64_values(&df, "BTCUSDT_ema_l"),
+        &get_f64_values(&result, &emas.get_slow_ema_col()),
+        from,
+        "slow ema",
+    );
+}
+
+#[test]
+fn test_stochastic_matches_golden_values() {
+    let df = read_csv(STOCHASTIC_CSV);
+    for k_length in [5, 10] {
+        let stochastic = Stochastic::new(get_btcusdt(), k_length, 1, 3);
+        let result = append_indicator(&df, &stochastic);
+        let k = get_f64_values(&result, &stochastic.get_k_col());
+        let d = get_f64_values(&result, &stochastic.get_d_col());
+
+        let minimum_klines = stochastic.get_minimum_klines_for_calculation() as usize;
+        assert!(d[..minimum_klines - 1].iter().all(Option::is_none));
+        assert!(d[minimum_klines - 1].is_some());
+
+        assert_close(
+            &get_f64_values(&df, &format!("CALCULATED K% {k_length}")),
+            &k,
+            0,
+            "stochastic k",
+        );
+        assert_close(
+            &get_f64_values(&df, &format!("CALCULATED D% {k_length}")),
+            &d,
+            0,
+            "stochastic d",
+        );
+    }
+}
+
+#[test]
+fn test_rma_clouds_match_golden_values() {
+    let df = read_csv(EMA_CSV);
+    let symbol = get_btcusdt();
+    let legacy_df = read_csv(LEGACY_INDICATORS_CSV);
+    let rma_clouds = RmaClouds::new(symbol, 9, 21, 20);
+    let result = append_indicator(&df, &rma_clouds);
+    let closes = get_f64_values(&df, symbol.get_close_col());
+
+    for (period, column) in [
+        (rma_clouds.fast_period, rma_clouds.get_fast_rma_col()),
+        (rma_clouds.slow_period, rma_clouds.get_slow_rma_col()),
+    ] {
+        let period = period as usize;
+        let calculated = get_f64_values(&result, &column);
+        assert!(calculated[..period - 1].iter().all(Option::is_none));
+        assert!(calculated[period - 1..].iter().all(Option::is_some));
+
+        // legacy RMAs are seeded at the first close rather than at the first average,
+        // so only compare once the seed has faded out
+        assert_close(
+            &get_f64_values(&legacy_df, &column),
+            &calculated,
+            800,
+            &column,
+        );
+    }
+
+    let cloud_length = rma_clouds.cloud_length as usize;
+    let tops = get_f64_values(&result, &rma_clouds.get_cloud_top_col());
+    let bases = get_f64_values(&result, &rma_clouds.get_cloud_base_col());
+    for index in cloud_length - 1..closes.len() {
+        let window = closes[index + 1 - cloud_length..=index]
+            .iter()
+            .map(|close| close.unwrap());
+        let highest = window.clone().fold(f64::NEG_INFINITY, f64::max);
+        let lowest = window.fold(f64::INFINITY, f64::min);
+        assert_eq!(tops[index], Some(highest));
+        assert_eq!(bases[index], Some(lowest));
+    }
+
+    let trends = result
+        .column(&rma_clouds.get_trend_col())
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_iter()
+        .map(|trend| trend.unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(trends[0], "undefined");
+    assert!(trends.iter().any(|trend| trend.ends_with("_bull")));
+    assert!(trends.iter().any(|trend| trend.ends_with("_bear")));
+}
+
+#[test]
+fn test_tsi_matches_golden_values() {
+    let df = read_csv(EMA_CSV);
+    let legacy_df = read_csv(LEGACY_INDICATORS_CSV);
+    let tsi = TrueStrengthIndex::new(get_btcusdt(), 25, 13);
+    let result = append_indicator(&df, &tsi);
+
+    let calculated = get_f64_values(&result, &tsi.get_tsi_col());
+    let minimum_klines = tsi.get_minimum_klines_for_calculation() as usize;
+    assert!(calculated[minimum_klines..].iter().all(Option::is_some));
+    // legacy values are rounded to 4 decimals
+    assert_close_within(
+        &get_f64_values(&legacy_df, "non_shifted_TSI_BTCUSDT_1"),
+        &calculated,
+        0,
+        "tsi",
+        1e-4,
+    );
+}
+
+#[test]
+fn test_stc_matches_golden_values() {
+    let df = read_csv(EMA_CSV);
+    let legacy_df = read_csv(LEGACY_INDICATORS_CSV);
+    let stc = SchaffTrendCycle::new(get_btcusdt(), 10, 23, 50, 0.5);
+    let result = append_indicator(&df, &stc);
+    let calculated = get_f64_values(&result, &stc.get_stc_col());
+
+    let minimum_klines = stc.get_minimum_klines_for_calculation() as usize;
+    assert!(calculated[..stc.slow_span as usize]
+        .iter()
+        .all(Option::is_none));
+    let values: Vec<f64> = calculated[minimum_klines..]
+        .iter()
+        .map(|value| value.expect("stc to be calculated after warm up"))
+        .collect();
+    assert!(values.iter().all(|value| (0.0..=100.0).contains(value)));
+
+    // legacy STC smooths from 0 during the warm up, which fades out by half every kline
+    assert_close(
+        &get_f64_values(&legacy_df, "non_shifted_STC_BTCUSDT_1"),
+        &calculated,
+        minimum_klines + 50,
+        "stc",
+    );
+}
@@ -0,0 +1,176 @@
+//! Regenerates `data/test/legacy_indicators.csv`, the golden RMA, TSI and STC values `indicators.rs` checks
+//! the indicator builders against. The legacy indicators no longer build, so their formulas are ported as they
+//! were written, over the closes of `data/test/BTC_USDT_AGIX_USDT.csv`:
+//!
+//! `cargo test -p strategy --test legacy_indicators -- --ignored`
+use polars::prelude::*;
+
+const KLINES_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/BTC_USDT_AGIX_USDT.csv"
+);
+const LEGACY_INDICATORS_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/legacy_indicators.csv"
+);
+
+/// Non adjusted exponential moving average, as legacy indicators set their `EWMOptions`
+fn get_ewm(values: Vec<Option<f64>>, span: u32) -> Vec<Option<f64>> {
+    let opts = EWMOptions {
+        alpha: 2.0 / (span as f64 + 1.0),
+        adjust: false,
+        bias: false,
+        min_periods: span as usize,
+        ignore_nulls: true,
+    };
+    df!("values" => values)
+        .unwrap()
+        .lazy()
+        .select([col("values").ewm_mean(opts)])
+        .collect()
+        .unwrap()
+        .column("values")
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+fn get_rolling_max_min(values: &[Option<f64>], index: usize, length: usize) -> Option<(f64, f64)> {
+    if index + 1 < length {
+        return None;
+    }
+    let window = values[index + 1 - length..=index]
+        .iter()
+        .copied()
+        .collect::<Option<Vec<f64>>>()?;
+    let max = window.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let min = window.iter().copied().fold(f64::INFINITY, f64::min);
+    Some((max, min))
+}
+
+/// Smooths `values` by `weight` from `first`, restarting from the value itself after a missing one
+fn get_weighted(values: &[Option<f64>], first: Option<f64>, weight: f64) -> Vec<Option<f64>> {
+    let mut weighted = vec![first];
+    for value in &values[1..] {
+        let smoothed = match weighted.last().unwrap() {
+            Some(previous) => Some(previous + weight * (value.unwrap_or_default() - previous)),
+            None => *value,
+        };
+        weighted.push(smoothed);
+    }
+    weighted
+}
+
+/// `legacy/indicators/fast_slow_cloud_rmas.rs`, whose RMAs are seeded at the first close
+fn get_legacy_rma(closes: &[f64], period: u32) -> Vec<Option<f64>> {
+    let alpha = 1.0 / period as f64;
+    let mut rma = vec![closes[0]];
+    for close in &closes[1..] {
+        rma.push(alpha * close + (1.0 - alpha) * rma.last().unwrap());
+    }
+    rma.into_iter().map(Some).collect()
+}
+
+/// `legacy/indicators/tsi.rs`, rounded to 4 decimals
+fn get_legacy_tsi(closes: &[f64], long_span: u32, short_span: u32) -> Vec<Option<f64>> {
+    let price_changes: Vec<Option<f64>> = (0..closes.len())
+        .map(|index| match index {
+            0 => Some(0.0),
+            _ => Some(closes[index] - closes[index - 1]),
+        })
+        .collect();
+    let absolute_price_changes = price_changes
+        .iter()
+        .map(|change| change.map(f64::abs))
+        .collect();
+    let double_smoothed = get_ewm(get_ewm(price_changes, long_span), short_span);
+    let absolute_double_smoothed = get_ewm(get_ewm(absolute_price_changes, long_span), short_span);
+    double_smoothed
+        .iter()
+        .zip(&absolute_double_smoothed)
+        .map(|(value, absolute)| {
+            let tsi = 100.0 * (*value)? / (*absolute)?;
+            tsi.is_finite().then(|| (tsi * 10000.0).round() / 10000.0)
+        })
+        .collect()
+}
+
+/// `legacy/indicators/stc.rs`, whose smoothing starts from 0 during the warm up
+fn get_legacy_stc(
+    closes: &[f64],
+    length: usize,
+    short_span: u32,
+    long_span: u32,
+    weight: f64,
+) -> Vec<Option<f64>> {
+    let closes: Vec<Option<f64>> = closes.iter().copied().map(Some).collect();
+    let short_ema = get_ewm(closes.clone(), short_span);
+    let long_ema = get_ewm(closes.clone(), long_span);
+    let macd: Vec<Option<f64>> = short_ema
+        .iter()
+        .zip(&long_ema)
+        .map(|(short, long)| Some((*short)? - (*long)?))
+        .collect();
+    let macd_k: Vec<Option<f64>> = (0..closes.len())
+        .map(|index| {
+            let (max, min) = get_rolling_max_min(&macd, index, length)?;
+            let macd = macd[index]?;
+            (max - min > 0.0).then(|| 100.0 * (macd - min) / (max - min))
+        })
+        .collect();
+    let weighted_k = get_weighted(&macd_k, macd_k[0], weight);
+    let stochastic: Vec<Option<f64>> = (0..closes.len())
+        .map(|index| {
+            let computed =
+                get_rolling_max_min(&weighted_k, index, length).and_then(|(max, min)| {
+                    let weighted = weighted_k[index]?;
+                    (max - min > 0.0).then(|| 100.0 * (weighted - min) / (max - min))
+                });
+            match (computed, index) {
+                (Some(value), _) => Some(value),
+                (None, 0) => None,
+                (None, _) => weighted_k[index - 1],
+            }
+        })
+        .collect();
+    get_weighted(&stochastic, Some(stochastic[0].unwrap_or_default()), weight)
+}
+
+#[test]
+#[ignore = "regenerates the golden legacy indicators csv"]
+fn generate_legacy_indicators_csv() {
+    let klines_df = CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(None)
+        .try_into_reader_with_file_path(Some(KLINES_CSV.into()))
+        .expect("test csv to exist")
+        .finish()
+        .expect("test csv to be parsed");
+    let closes: Vec<f64> = klines_df
+        .column("BTCUSDT_close")
+        .unwrap()
+        .cast(&DataType::Float64)
+        .unwrap()
+        .f64()
+        .unwrap()
+        .into_no_null_iter()
+        .collect();
+
+    let mut legacy_indicators_df = df!(
+        "start_time" => klines_df.column("start_time").unwrap().as_materialized_series().clone(),
+        "BTCUSDT_rma_fast" => get_legacy_rma(&closes, 9),
+        "BTCUSDT_rma_slow" => get_legacy_rma(&closes, 21),
+        "non_shifted_TSI_BTCUSDT_1" => get_legacy_tsi(&closes, 25, 13),
+        "non_shifted_STC_BTCUSDT_1" => get_legacy_stc(&closes, 10, 23, 50, 0.5),
+    )
+    .unwrap();
+
+    let mut file = std::fs::File::create(LEGACY_INDICATORS_CSV).unwrap();
+    CsvWriter::new(&mut file)
+        .with_float_precision(Some(6))
+        .with_datetime_format(Some("%Y-%m-%dT%H:%M:%S%.3f".to_owned()))
+        .finish(&mut legacy_indicators_df)
+        .unwrap();
+}
@@ -0,0 +1,20 @@
+use strategy::params::{NumberParamConfig, Param};
+
+#[test]
+fn test_params_reject_numbers_out_of_their_type_range() {
+    let unbounded_param = Param::UInt32(14, NumberParamConfig::new(14, None, None));
+    let signed_param = Param::Int64(0, NumberParamConfig::new(0, None, None));
+
+    assert!(unbounded_param.with_number(-1.0).is_err());
+    assert!(unbounded_param.with_number(14.5).is_err());
+    assert!(unbounded_param.with_number(u32::MAX as f64 + 1.0).is_err());
+    assert_eq!(
+        unbounded_param
+            .with_number(u32::MAX as f64)
+            .unwrap()
+            .number_value(),
+        Some(u32::MAX as f64)
+    );
+    assert!(signed_param.with_number(i64::MAX as f64).is_err());
+    assert!(signed_param.with_number(i64::MIN as f64).is_ok());
+}
@@ -0,0 +1,202 @@
+use common::{
+    enums::{signal_category::SignalCategory, symbol_id::SymbolId},
+    structs::SymbolsPair,
+};
+use polars::prelude::*;
+use strategy::{params::ParamId, r#static::STRATEGIES_IDS, Strategy, StrategyId};
+
+const KLINES_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/BTC_USDT_AGIX_USDT.csv"
+);
+
+fn read_klines() -> DataFrame {
+    CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(None)
+        .try_into_reader_with_file_path(Some(KLINES_CSV.into()))
+        .expect("test csv to exist")
+        .finish()
+        .expect("test csv to be parsed")
+        .select([
+            "start_time",
+            "BTCUSDT_open",
+            "BTCUSDT_high",
+            "BTCUSDT_low",
+            "BTCUSDT_close",
+        ])
+        .expect("klines columns to exist")
+}
+
+#[test]
+fn test_strategies_append_declared_columns() {
+    let klines_df = read_klines();
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+
+    for strategy_id in STRATEGIES_IDS {
+        let strategy = Strategy::new(strategy_id, symbols_pair).unwrap();
+        let lf = strategy
+            .append_indicators_to_lf(klines_df.clone().lazy())
+            .unwrap();
+        let strategy_df = strategy
+            .append_signals_to_lf(lf)
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        // data feed stacks new klines on top of this frame, so nothing but the declared columns can be added
+        let mut expected_columns: Vec<String> = klines_df
+            .get_column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let declared_columns = [
+            strategy.get_indicators_columns().unwrap(),
+            strategy.get_signals_columns(),
+        ]
+        .concat();
+        expected_columns.extend(declared_columns.iter().map(|(name, _)| name.clone()));
+        expected_columns.sort();
+        let mut columns: Vec<String> = strategy_df
+            .get_column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        columns.sort();
+        assert_eq!(columns, expected_columns, "{:?} columns", strategy_id);
+
+        for (name, dtype) in declared_columns {
+            assert_eq!(
+                strategy_df.column(&name).unwrap().dtype(),
+                &dtype,
+                "{:?} {} dtype",
+                strategy_id,
+                name
+            );
+        }
+
+        let minimum_klines = strategy.get_minimum_klines_for_calculation().unwrap() as usize;
+        assert!(minimum_klines > 0 && minimum_klines < strategy_df.height());
+
+        for signal in [
+            SignalCategory::GoShort,
+            SignalCategory::GoLong,
+            SignalCategory::CloseShort,
+            SignalCategory::CloseLong,
+        ] {
+            let values = strategy_df
+                .column(signal.get_column())
+                .unwrap()
+                .cast(&DataType::Int32)
+                .unwrap();
+            let values = values.i32().unwrap();
+            assert_eq!(values.null_count(), 0, "{:?} null signals", strategy_id);
+            assert!(
+                values.into_iter().all(|value| matches!(value, Some(0 | 1))),
+                "{:?} {:?} signals must be 0 or 1",
+                strategy_id,
+                signal
+            );
+            // signals can't fire before indicators are calculated,
+            // but simple trend EMAs are defined from the first kline
+            if strategy_id == StrategyId::SimpleTrend {
+                continue;
+            }
+            let warm_up_signals: i32 = values
+                .slice(0, minimum_klines.saturating_sub(1))
+                .into_iter()
+                .flatten()
+                .sum();
+            assert_eq!(warm_up_signals, 0, "{:?} {:?} warm up", strategy_id, signal);
+        }
+    }
+}
+
+/// Signals of the legacy `strategy/src/legacy/signals` strategies over the klines csv,
+/// computed at a 1 minute window from their indicators before rounding
+const LEGACY_SIGNALS_CSV: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../data/test/legacy_signals.csv"
+);
+
+#[test]
+fn test_strategies_match_legacy_signals() {
+    let klines_df = read_klines();
+    let legacy_signals_df = CsvReadOptions::default()
+        .with_has_header(true)
+        .try_into_reader_with_file_path(Some(LEGACY_SIGNALS_CSV.into()))
+        .expect("legacy signals csv to exist")
+        .finish()
+        .expect("legacy signals csv to be parsed");
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+
+    // legacy RMAs and STC are seeded differently, so only compare once their seeds have faded out
+    for (strategy_id, prefix, from) in [
+        (StrategyId::CloudMomentum, "cloud_momentum", 800),
+        (StrategyId::TsiStc, "tsi_stc", 200),
+        (
+            StrategyId::MultipleStochasticWithThreshold,
+            "multiple_stochastic_with_threshold",
+            200,
+        ),
+        (StrategyId::RmaClouds, "rma_clouds", 800),
+    ] {
+        let strategy = Strategy::new(strategy_id, symbols_pair).unwrap();
+        let lf = strategy
+            .append_indicators_to_lf(klines_df.clone().lazy())
+            .unwrap();
+        let strategy_df = strategy
+            .append_signals_to_lf(lf)
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        for signal in [
+            SignalCategory::GoShort,
+            SignalCategory::GoLong,
+            SignalCategory::CloseShort,
+            SignalCategory::CloseLong,
+        ] {
+            let get_values = |df: &DataFrame, column: &str| -> Vec<i32> {
+                df.column(column)
+                    .unwrap()
+                    .cast(&DataType::Int32)
+                    .unwrap()
+                    .i32()
+                    .unwrap()
+                    .into_no_null_iter()
+                    .skip(from)
+                    .collect()
+            };
+            let legacy_column = format!("{}_{}", prefix, signal.get_column());
+            let expected = get_values(&legacy_signals_df, &legacy_column);
+            let calculated = get_values(&strategy_df, signal.get_column());
+
+            let mismatches: Vec<usize> = (0..expected.len())
+                .filter(|index| expected[*index] != calculated[*index])
+                .map(|index| index + from)
+                .collect();
+            assert!(
+                mismatches.is_empty(),
+                "{} at rows {:?}",
+                legacy_column,
+                mismatches
+            );
+        }
+    }
+}
+
+#[test]
+fn test_missing_param_returns_error() {
+    let klines_df = read_klines();
+    let symbols_pair = SymbolsPair::new(&SymbolId::Bitcoin, &SymbolId::Bitcoin);
+    let mut strategy = Strategy::new(StrategyId::TsiStc, symbols_pair).unwrap();
+    strategy.params.remove(&ParamId::TrendPeriod);
+
+    let error = strategy
+        .get_minimum_klines_for_calculation()
+        .expect_err("missing param to be an error");
+    assert!(error.description.contains("TrendPeriod"), "{:?}", error);
+    assert!(strategy.get_indicators_columns().is_ok());
+    assert!(strategy.append_signals_to_lf(klines_df.lazy()).is_err());
+}